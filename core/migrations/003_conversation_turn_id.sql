@@ -0,0 +1,12 @@
+-- SQLite Migration: Conversation Turn IDs
+-- Version: 003
+-- Date: 2026-10-17
+-- Description: Links conversation rows to the voice pipeline turn they came from
+
+-- Turn id minted at input capture and carried through ASR, teacher and TTS in
+-- dora metadata (`question_id`); TTS cache files are keyed by the same id.
+-- NULL for rows written before turn ids existed.
+ALTER TABLE conversations ADD COLUMN turn_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_conversations_turn
+ON conversations(turn_id);
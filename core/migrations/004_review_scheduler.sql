@@ -0,0 +1,30 @@
+-- SQLite Migration: Review Scheduler State
+-- Version: 004
+-- Date: 2026-10-17
+-- Description: Per-word SM-2 state replacing the fixed 1→2→4→7→14→30 day ladder
+
+-- Interval growth factor (2.5 for new words, never below 1.3)
+ALTER TABLE issue_words ADD COLUMN ease_factor REAL NOT NULL DEFAULT 2.5;
+-- Consecutive successful reviews
+ALTER TABLE issue_words ADD COLUMN review_reps INTEGER NOT NULL DEFAULT 0;
+-- Failed reviews (success_level below 3)
+ALTER TABLE issue_words ADD COLUMN lapses INTEGER NOT NULL DEFAULT 0;
+
+-- Initialise from the old ladder: the position on the ladder is the number of
+-- successes since the last failure, difficulty 1–5 maps onto ease 2.5–1.3.
+-- review_interval_days and next_review_at are kept, so due dates don't move.
+UPDATE issue_words SET
+    review_reps = CASE
+        WHEN pick_count = 0 THEN 0
+        WHEN review_interval_days <= 1 THEN 0
+        WHEN review_interval_days = 2 THEN 1
+        WHEN review_interval_days = 4 THEN 2
+        WHEN review_interval_days = 7 THEN 3
+        WHEN review_interval_days = 14 THEN 4
+        ELSE 5
+    END,
+    ease_factor = 2.5 - 0.3 * (COALESCE(difficulty_level, 1) - 1),
+    lapses = (
+        SELECT COUNT(*) FROM word_practice_log
+        WHERE word_id = issue_words.id AND success_level < 3
+    );
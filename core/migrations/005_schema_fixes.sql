@@ -0,0 +1,69 @@
+-- SQLite Migration: Schema Fixes
+-- Version: 005
+-- Date: 2026-10-17
+-- Description: Reconciles the schema with the queries that run against it
+
+-- ============================================================================
+-- conversations.use_lang: allow 'mix'
+-- ============================================================================
+
+-- The teacher reports mixed English/Chinese input as `mix` (UseLang::Mix),
+-- which the CHECK constraint from 001 rejects. SQLite can't change a CHECK
+-- constraint in place, so the table is rebuilt. Dropping it cascades to
+-- conversation_annotations, which are kept aside and restored.
+
+CREATE TABLE conversation_annotations_backup AS
+SELECT * FROM conversation_annotations;
+
+CREATE TABLE conversations_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id TEXT NOT NULL, -- UUID for grouping related conversations
+    speaker TEXT NOT NULL CHECK(speaker IN ('user', 'teacher')),
+    use_lang TEXT NOT NULL CHECK(use_lang IN ('en', 'zh', 'mix')),
+    content_en TEXT NOT NULL,
+    content_zh TEXT NOT NULL,
+    audio_path TEXT, -- Path to audio file if available
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    duration_ms INTEGER, -- Audio duration in milliseconds
+
+    -- Performance metrics
+    words_per_minute REAL,
+    pause_count INTEGER,
+    hesitation_count INTEGER,
+
+    turn_id TEXT -- Voice pipeline turn (dora `question_id`), see 003
+);
+
+INSERT INTO conversations_new (
+    id, session_id, speaker, use_lang, content_en, content_zh, audio_path, created_at,
+    duration_ms, words_per_minute, pause_count, hesitation_count, turn_id
+)
+SELECT
+    id, session_id, speaker, use_lang, content_en, content_zh, audio_path, created_at,
+    duration_ms, words_per_minute, pause_count, hesitation_count, turn_id
+FROM conversations;
+
+DROP TABLE conversations;
+ALTER TABLE conversations_new RENAME TO conversations;
+
+CREATE INDEX IF NOT EXISTS idx_conversations_session
+ON conversations(session_id, created_at);
+
+CREATE INDEX IF NOT EXISTS idx_conversations_created
+ON conversations(created_at);
+
+CREATE INDEX IF NOT EXISTS idx_conversations_turn
+ON conversations(turn_id);
+
+INSERT INTO conversation_annotations
+SELECT * FROM conversation_annotations_backup;
+
+DROP TABLE conversation_annotations_backup;
+
+-- ============================================================================
+-- learning_sessions.target_words
+-- ============================================================================
+
+-- learning-db-reader records the words it picked for a review session.
+-- JSON array of words; NULL for sessions without target words.
+ALTER TABLE learning_sessions ADD COLUMN target_words TEXT;
@@ -0,0 +1,320 @@
+//! Achievements awarded by declarative rules
+//!
+//! [`ACHIEVEMENTS`] lists every achievement together with the [`Rule`] that
+//! earns it. [`evaluate`] runs after each session: it measures the learner's
+//! [`Progress`] from the learning tables and records every achievement whose
+//! rule is met in `user_achievements`. The table's
+//! `UNIQUE(user_id, achievement_type, achievement_name)` keeps evaluation
+//! idempotent, so only achievements earned for the first time come back.
+
+use std::sync::mpsc::Sender;
+
+use chrono::{Local, NaiveDate};
+
+use crate::asset_api::Scene;
+use crate::daily_stats;
+use crate::db::Database;
+use crate::models::{Achievement, AnnotationType, LearningSession, SessionType};
+
+/// What has to happen to earn an achievement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    /// Study on this many consecutive days
+    Streak { days: i64 },
+    /// Have this many words mastered at once
+    WordsMastered { count: i64 },
+    /// Complete this many sessions of a type
+    SessionsCompleted {
+        session_type: SessionType,
+        count: i64,
+    },
+    /// Speak this many turns in one conversation without a grammar error
+    CleanConversation { turns: i64 },
+}
+
+impl Rule {
+    /// The learner's progress towards the rule's target
+    pub fn value(&self, progress: &Progress) -> i64 {
+        match self {
+            Rule::Streak { .. } => progress.longest_streak,
+            Rule::WordsMastered { .. } => progress.mastered_words,
+            Rule::SessionsCompleted { session_type, .. } => progress.completed(session_type),
+            Rule::CleanConversation { .. } => progress.clean_conversation_turns,
+        }
+    }
+
+    pub fn target(&self) -> i64 {
+        match self {
+            Rule::Streak { days } => *days,
+            Rule::WordsMastered { count } => *count,
+            Rule::SessionsCompleted { count, .. } => *count,
+            Rule::CleanConversation { turns } => *turns,
+        }
+    }
+
+    pub fn is_met(&self, progress: &Progress) -> bool {
+        self.value(progress) >= self.target()
+    }
+}
+
+/// An achievement and the rule that earns it
+#[derive(Debug, Clone)]
+pub struct AchievementRule {
+    pub achievement_type: &'static str,
+    pub achievement_name: &'static str,
+    pub description_en: &'static str,
+    pub description_zh: &'static str,
+    pub rule: Rule,
+}
+
+impl AchievementRule {
+    /// The `user_achievements` row recording this achievement
+    fn earned(&self, progress: &Progress, earned_at: i64) -> Achievement {
+        Achievement {
+            achievement_type: self.achievement_type.to_string(),
+            achievement_name: self.achievement_name.to_string(),
+            description_en: Some(self.description_en.to_string()),
+            description_zh: Some(self.description_zh.to_string()),
+            earned_at,
+            metadata: Some(
+                serde_json::json!({
+                    "value": self.rule.value(progress),
+                    "target": self.rule.target(),
+                })
+                .to_string(),
+            ),
+        }
+    }
+}
+
+/// Every achievement that can be earned
+pub const ACHIEVEMENTS: &[AchievementRule] = &[
+    AchievementRule {
+        achievement_type: "streak",
+        achievement_name: "streak_3",
+        description_en: "Studied 3 days in a row",
+        description_zh: "连续学习 3 天",
+        rule: Rule::Streak { days: 3 },
+    },
+    AchievementRule {
+        achievement_type: "streak",
+        achievement_name: "streak_7",
+        description_en: "Studied 7 days in a row",
+        description_zh: "连续学习 7 天",
+        rule: Rule::Streak { days: 7 },
+    },
+    AchievementRule {
+        achievement_type: "streak",
+        achievement_name: "streak_30",
+        description_en: "Studied 30 days in a row",
+        description_zh: "连续学习 30 天",
+        rule: Rule::Streak { days: 30 },
+    },
+    AchievementRule {
+        achievement_type: "vocabulary",
+        achievement_name: "mastered_10",
+        description_en: "Mastered 10 words",
+        description_zh: "掌握 10 个单词",
+        rule: Rule::WordsMastered { count: 10 },
+    },
+    AchievementRule {
+        achievement_type: "vocabulary",
+        achievement_name: "mastered_100",
+        description_en: "Mastered 100 words",
+        description_zh: "掌握 100 个单词",
+        rule: Rule::WordsMastered { count: 100 },
+    },
+    AchievementRule {
+        achievement_type: "review",
+        achievement_name: "first_review",
+        description_en: "Finished a first review session",
+        description_zh: "完成第一次复习",
+        rule: Rule::SessionsCompleted {
+            session_type: SessionType::Review,
+            count: 1,
+        },
+    },
+    AchievementRule {
+        achievement_type: "scene",
+        achievement_name: "first_scene",
+        description_en: "Completed a first scene",
+        description_zh: "完成第一个场景",
+        rule: Rule::SessionsCompleted {
+            session_type: SessionType::Scenario,
+            count: 1,
+        },
+    },
+    AchievementRule {
+        achievement_type: "conversation",
+        achievement_name: "clean_10_turns",
+        description_en: "10 turns in one conversation without a grammar error",
+        description_zh: "一次对话中连续 10 轮零语法错误",
+        rule: Rule::CleanConversation { turns: 10 },
+    },
+];
+
+/// The learner's progress as measured from the learning tables
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    pub longest_streak: i64,
+    pub mastered_words: i64,
+    /// Ended sessions per session type
+    pub completed_sessions: Vec<(SessionType, i64)>,
+    /// Most learner turns in a conversation without a grammar error
+    pub clean_conversation_turns: i64,
+}
+
+impl Progress {
+    /// Measure the progress as of `today`, catching `daily_stats` up first
+    pub async fn measure(db: &Database, today: NaiveDate) -> Result<Self, sqlx::Error> {
+        let streaks = daily_stats::update(db, today).await?;
+        Ok(Self {
+            longest_streak: streaks.longest,
+            mastered_words: db.count_mastered_words().await?,
+            completed_sessions: db.count_completed_sessions().await?,
+            clean_conversation_turns: db
+                .get_longest_clean_conversation(AnnotationType::GrammarError)
+                .await?,
+        })
+    }
+
+    /// Ended sessions of `session_type`
+    pub fn completed(&self, session_type: &SessionType) -> i64 {
+        self.completed_sessions
+            .iter()
+            .find(|(t, _)| t == session_type)
+            .map_or(0, |(_, count)| *count)
+    }
+}
+
+/// Award every achievement whose rule is met; returns the ones earned for
+/// the first time
+pub async fn evaluate(db: &Database, today: NaiveDate) -> Result<Vec<Achievement>, sqlx::Error> {
+    let progress = Progress::measure(db, today).await?;
+    let now = Local::now().timestamp();
+
+    let mut earned = Vec::new();
+    for achievement in ACHIEVEMENTS.iter().filter(|a| a.rule.is_met(&progress)) {
+        let achievement = achievement.earned(&progress, now);
+        if db.award_achievement(&achievement).await? {
+            earned.push(achievement);
+        }
+    }
+    Ok(earned)
+}
+
+/// Open a scenario session for `scene`; [`end_conversation`] closes it
+pub async fn start_scene(db: &Database, scene: &Scene) -> Result<(), sqlx::Error> {
+    let sceneid = db.save_scene(scene).await?;
+    db.create_session(&LearningSession {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        session_type: Some(SessionType::Scenario),
+        sceneid: Some(sceneid),
+        scene_dialogue_id: None,
+        classic_clip_id: None,
+        target_words: None,
+        started_at: Local::now().timestamp(),
+        ended_at: None,
+        duration_seconds: None,
+        total_words_spoken: 0,
+        average_wpm: None,
+        error_count: 0,
+        correction_count: 0,
+        notes: None,
+        ai_summary_en: None,
+        ai_summary_zh: None,
+    })
+    .await
+}
+
+/// [`start_scene`] on a worker thread; failures are logged
+pub fn spawn_start_scene(scene: Scene) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(async {
+            let db = Database::open_default().await?;
+            start_scene(&db, &scene).await
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to start scene {}: {}", scene.name_en, e);
+        }
+    });
+}
+
+/// Close the conversation's sessions, free talk or scenario, then
+/// [`evaluate`] what it earned
+pub async fn end_conversation(
+    db: &Database,
+    today: NaiveDate,
+) -> Result<Vec<Achievement>, sqlx::Error> {
+    db.end_open_sessions(&SessionType::FreeTalk).await?;
+    db.end_open_sessions(&SessionType::Scenario).await?;
+    evaluate(db, today).await
+}
+
+/// [`end_conversation`] on a worker thread; newly earned achievements are
+/// sent to `tx`, failures are logged
+pub fn spawn_end_conversation(tx: Sender<Vec<Achievement>>) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(async {
+            let db = Database::open_default().await?;
+            end_conversation(&db, Local::now().date_naive()).await
+        });
+        match result {
+            Ok(earned) if !earned.is_empty() => {
+                let _ = tx.send(earned);
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to end conversation: {}", e),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        let progress = Progress {
+            longest_streak: 7,
+            mastered_words: 99,
+            completed_sessions: vec![(SessionType::Review, 2)],
+            clean_conversation_turns: 10,
+        };
+
+        assert!(Rule::Streak { days: 7 }.is_met(&progress));
+        assert!(!Rule::Streak { days: 30 }.is_met(&progress));
+        assert!(!Rule::WordsMastered { count: 100 }.is_met(&progress));
+        assert!(
+            Rule::SessionsCompleted {
+                session_type: SessionType::Review,
+                count: 2
+            }
+            .is_met(&progress)
+        );
+        assert!(
+            !Rule::SessionsCompleted {
+                session_type: SessionType::Scenario,
+                count: 1
+            }
+            .is_met(&progress)
+        );
+        assert!(Rule::CleanConversation { turns: 10 }.is_met(&progress));
+    }
+
+    #[test]
+    fn test_achievement_names_are_unique() {
+        for (i, a) in ACHIEVEMENTS.iter().enumerate() {
+            assert!(
+                ACHIEVEMENTS[i + 1..].iter().all(|b| {
+                    (a.achievement_type, a.achievement_name)
+                        != (b.achievement_type, b.achievement_name)
+                }),
+                "duplicate achievement {}",
+                a.achievement_name
+            );
+        }
+    }
+}
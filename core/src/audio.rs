@@ -1,4 +1,4 @@
-//! Audio device management and mic level monitoring
+//! Audio device management, mic level monitoring and utterance capture
 
 use std::sync::Arc;
 
@@ -7,6 +7,8 @@ use cpal::{Device, Host, Stream, StreamConfig};
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use parking_lot::Mutex;
 
+use crate::vad::{SpeechChunk, Utterance, VadConfig, VoiceActivityDetector};
+
 /// Audio device info
 #[derive(Clone, Debug)]
 pub struct AudioDeviceInfo {
@@ -46,6 +48,10 @@ pub struct AudioManager {
     /// Channel for sending captured audio chunks
     audio_tx: Option<Sender<AudioChunk>>,
     audio_rx: Option<Receiver<AudioChunk>>,
+    /// Groups captured chunks into utterances
+    vad: VoiceActivityDetector,
+    /// Utterances closed by `stop_mic_monitoring`, returned by the next poll
+    pending_utterances: Vec<Utterance>,
 }
 
 impl AudioManager {
@@ -60,6 +66,8 @@ impl AudioManager {
             current_output_device: None,
             audio_tx: Some(audio_tx),
             audio_rx: Some(audio_rx),
+            vad: VoiceActivityDetector::default(),
+            pending_utterances: Vec::new(),
         }
     }
 
@@ -259,6 +267,14 @@ impl AudioManager {
     /// Stop mic monitoring
     pub fn stop_mic_monitoring(&mut self) {
         self.input_stream = None;
+        // Keep whatever the user was saying when the mic was turned off
+        for chunk in self.poll_audio_chunks() {
+            let utterances = self.vad.push(&chunk);
+            self.pending_utterances.extend(utterances);
+        }
+        if let Some(utterance) = self.vad.flush() {
+            self.pending_utterances.push(utterance);
+        }
         let mut state = self.mic_level.lock();
         state.level = 0.0;
         state.peak = 0.0;
@@ -301,6 +317,37 @@ impl AudioManager {
         }
         chunks
     }
+
+    /// Poll for complete utterances (non-blocking)
+    /// Captured chunks are run through the VAD; only endpointed speech is returned
+    pub fn poll_utterances(&mut self) -> Vec<Utterance> {
+        let mut utterances = std::mem::take(&mut self.pending_utterances);
+        for chunk in self.poll_audio_chunks() {
+            utterances.extend(self.vad.push(&chunk));
+        }
+        utterances
+    }
+
+    /// Take the speech streamed since the last poll
+    /// Call after `poll_utterances`, which feeds the captured chunks to the VAD
+    pub fn poll_speech_chunks(&mut self) -> Vec<SpeechChunk> {
+        self.vad.take_chunks()
+    }
+
+    /// Whether the VAD currently detects the user speaking
+    pub fn is_speaking(&self) -> bool {
+        self.vad.is_speaking()
+    }
+
+    /// Get current VAD settings
+    pub fn vad_config(&self) -> &VadConfig {
+        self.vad.config()
+    }
+
+    /// Update VAD thresholds (drops any utterance in progress)
+    pub fn set_vad_config(&mut self, config: VadConfig) {
+        self.vad.set_config(config);
+    }
 }
 
 impl Default for AudioManager {
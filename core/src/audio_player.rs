@@ -4,12 +4,17 @@
 
 use std::collections::VecDeque;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use crossbeam_channel::{Receiver, Sender, unbounded};
 use parking_lot::Mutex;
 
+/// Participant index of the learner's own audio, see [`AudioPlayer::current_participant_idx`]
+pub const MYSELF_PARTICIPANT_IDX: usize = 0;
+/// Participant index of the teacher's audio
+pub const TEACHER_PARTICIPANT_IDX: usize = 1;
+
 /// Segment tracking for knowing which participant and question owns audio in the buffer
 #[derive(Clone, Debug)]
 struct AudioSegment {
@@ -23,6 +28,8 @@ enum AudioCommand {
     Write(Vec<f32>, Option<String>, Option<String>), // samples, participant_id, question_id
     Reset,
     SmartReset(String), // Keep only segments with this question_id
+    Flush(String),      // Drop segments with this question_id (barge-in)
+    SetGain(f32),
     Pause,
     Resume,
     Stop,
@@ -37,6 +44,7 @@ struct CircularAudioBuffer {
     buffer_size: usize,
     segments: VecDeque<AudioSegment>,
     current_playing_participant: Option<String>,
+    current_playing_question: Option<String>,
 }
 
 impl CircularAudioBuffer {
@@ -50,6 +58,7 @@ impl CircularAudioBuffer {
             buffer_size,
             segments: VecDeque::new(),
             current_playing_participant: None,
+            current_playing_question: None,
         }
     }
 
@@ -118,6 +127,7 @@ impl CircularAudioBuffer {
 
                 if let Some(front) = self.segments.front_mut() {
                     self.current_playing_participant = front.participant_id.clone();
+                    self.current_playing_question = front.question_id.clone();
                     if front.samples_remaining > 0 {
                         front.samples_remaining -= 1;
                     }
@@ -136,6 +146,10 @@ impl CircularAudioBuffer {
         self.current_playing_participant.clone()
     }
 
+    fn current_question(&self) -> Option<String> {
+        self.current_playing_question.clone()
+    }
+
     fn fill_percentage(&self) -> f64 {
         (self.available_samples as f64 / self.buffer_size as f64) * 100.0
     }
@@ -150,45 +164,79 @@ impl CircularAudioBuffer {
         self.available_samples = 0;
         self.segments.clear();
         self.current_playing_participant = None;
+        self.current_playing_question = None;
     }
 
     /// Smart reset - only keep segments with the specified question_id
     /// This prevents playing stale audio from previous questions after a reset
     fn smart_reset(&mut self, active_question_id: &str) {
-        // Count samples to discard (segments with wrong question_id)
+        // Segments without question_id are discarded
+        let discarded = self.retain_segments(|qid| qid == Some(active_question_id));
+
+        if discarded > 0 {
+            log::info!(
+                "Smart reset: discarding {} samples from stale questions, keeping {} segments for question_id={}",
+                discarded,
+                self.segments.len(),
+                active_question_id
+            );
+        }
+    }
+
+    /// Flush - drop the segments of one question (the reply being interrupted)
+    fn flush_question(&mut self, question_id: &str) {
+        let discarded = self.retain_segments(|qid| qid != Some(question_id));
+
+        if discarded > 0 {
+            log::info!(
+                "Flush: discarding {} samples for question_id={}",
+                discarded,
+                question_id
+            );
+        }
+    }
+
+    /// Keep only segments whose question_id passes `keep`, returning the number of
+    /// samples discarded
+    ///
+    /// Kept segments are compacted to the front of the read position so playback
+    /// continues with them straight away.
+    fn retain_segments(&mut self, keep: impl Fn(Option<&str>) -> bool) -> usize {
         let mut samples_to_discard = 0;
+        let mut kept = Vec::new();
         let mut new_segments = VecDeque::new();
+        let mut pos = self.read_pos;
 
         for segment in &self.segments {
-            if let Some(ref qid) = segment.question_id {
-                if qid == active_question_id {
-                    new_segments.push_back(segment.clone());
-                } else {
-                    samples_to_discard += segment.samples_remaining;
+            if keep(segment.question_id.as_deref()) {
+                for i in 0..segment.samples_remaining {
+                    kept.push(self.buffer[(pos + i) % self.buffer_size]);
                 }
+                new_segments.push_back(segment.clone());
             } else {
-                // Segments without question_id are discarded
                 samples_to_discard += segment.samples_remaining;
             }
+            pos = (pos + segment.samples_remaining) % self.buffer_size;
         }
 
-        if samples_to_discard > 0 {
-            log::info!(
-                "Smart reset: discarding {} samples from stale questions, keeping {} segments for question_id={}",
-                samples_to_discard,
-                new_segments.len(),
-                active_question_id
-            );
-
-            // Advance read position past discarded samples
-            self.read_pos = (self.read_pos + samples_to_discard) % self.buffer_size;
-            self.available_samples = self.available_samples.saturating_sub(samples_to_discard);
-            self.segments = new_segments;
+        if samples_to_discard == 0 {
+            return 0;
+        }
 
-            // Update current participant from remaining segments
-            self.current_playing_participant =
-                self.segments.front().and_then(|s| s.participant_id.clone());
+        // Rewrite the kept samples contiguously from the read position
+        for (i, sample) in kept.iter().enumerate() {
+            self.buffer[(self.read_pos + i) % self.buffer_size] = *sample;
         }
+        self.available_samples = kept.len();
+        self.write_pos = (self.read_pos + kept.len()) % self.buffer_size;
+        self.segments = new_segments;
+
+        // Update current participant from remaining segments
+        self.current_playing_participant =
+            self.segments.front().and_then(|s| s.participant_id.clone());
+        self.current_playing_question = self.segments.front().and_then(|s| s.question_id.clone());
+
+        samples_to_discard
     }
 
     fn available(&self) -> usize {
@@ -202,6 +250,7 @@ struct SharedAudioState {
     buffer_seconds: f64,
     is_playing: bool,
     current_participant: Option<String>,
+    current_question_id: Option<String>,
     output_waveform: Vec<f32>, // Samples currently being played (for visualization)
 }
 
@@ -223,6 +272,7 @@ impl AudioPlayer {
             buffer_seconds: 0.0,
             is_playing: false,
             current_participant: None,
+            current_question_id: None,
             output_waveform: vec![0.0; 512],
         }));
 
@@ -316,7 +366,26 @@ impl AudioPlayer {
         self.state.lock().output_waveform.clone()
     }
 
-    /// Get current participant index (0=myself, 1=teacher)
+    /// Get question_id of the audio currently being played
+    pub fn current_question_id(&self) -> Option<String> {
+        self.state.lock().current_question_id.clone()
+    }
+
+    /// Set playback gain (0.0 - 1.0), ramped to avoid clicks
+    /// Used to duck the teacher while the user starts talking over it
+    pub fn set_gain(&self, gain: f32) {
+        let _ = self.command_tx.send(AudioCommand::SetGain(gain));
+    }
+
+    /// Flush - drop buffered audio for the specified question_id
+    /// Use this when the user interrupts the reply being played
+    pub fn flush_question(&self, question_id: &str) {
+        let _ = self
+            .command_tx
+            .send(AudioCommand::Flush(question_id.to_string()));
+    }
+
+    /// Get current participant index ([`MYSELF_PARTICIPANT_IDX`] or [`TEACHER_PARTICIPANT_IDX`])
     /// Matches conference-dashboard's interface for consistent behavior
     pub fn current_participant_idx(&self) -> Option<usize> {
         self.state
@@ -324,8 +393,8 @@ impl AudioPlayer {
             .current_participant
             .as_ref()
             .and_then(|p| match p.as_str() {
-                "myself" => Some(0),
-                "teacher" => Some(1),
+                "myself" => Some(MYSELF_PARTICIPANT_IDX),
+                "teacher" => Some(TEACHER_PARTICIPANT_IDX),
                 _ => None,
             })
     }
@@ -349,6 +418,8 @@ fn run_audio_thread(
         sample_rate,
     )));
     let is_playing = Arc::new(AtomicBool::new(false));
+    // Target gain as f32 bits; the callback ramps towards it
+    let gain = Arc::new(AtomicU32::new(1.0f32.to_bits()));
 
     let host = cpal::default_host();
     let device = host
@@ -391,7 +462,11 @@ fn run_audio_thread(
 
     let buffer_clone = Arc::clone(&buffer);
     let is_playing_clone = Arc::clone(&is_playing);
+    let gain_clone = Arc::clone(&gain);
     let state_for_callback = Arc::clone(&state);
+    // ~20ms ramp at 48kHz
+    let gain_step = 1.0 / 1000.0;
+    let mut current_gain = 1.0f32;
 
     let channels = config.channels as usize;
     let stream = device
@@ -420,6 +495,20 @@ fn run_audio_thread(
                     let current_participant = buf.current_participant();
                     drop(buf);
 
+                    let target_gain = f32::from_bits(gain_clone.load(Ordering::Relaxed));
+                    if current_gain != 1.0 || target_gain != 1.0 {
+                        for frame in data.chunks_mut(channels) {
+                            if current_gain < target_gain {
+                                current_gain = (current_gain + gain_step).min(target_gain);
+                            } else if current_gain > target_gain {
+                                current_gain = (current_gain - gain_step).max(target_gain);
+                            }
+                            for sample in frame.iter_mut() {
+                                *sample *= current_gain;
+                            }
+                        }
+                    }
+
                     if let Some(mut s) = state_for_callback.try_lock() {
                         s.current_participant = current_participant;
 
@@ -478,6 +567,13 @@ fn run_audio_thread(
                 buffer.lock().smart_reset(&question_id);
                 log::info!("Audio buffer smart reset for question_id={}", question_id);
             }
+            Ok(AudioCommand::Flush(question_id)) => {
+                buffer.lock().flush_question(&question_id);
+                log::info!("Audio buffer flushed for question_id={}", question_id);
+            }
+            Ok(AudioCommand::SetGain(value)) => {
+                gain.store(value.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+            }
             Ok(AudioCommand::Pause) => {
                 is_playing.store(false, Ordering::Relaxed);
             }
@@ -503,6 +599,7 @@ fn run_audio_thread(
             s.buffer_seconds = buf.available_seconds(sample_rate);
             s.is_playing = is_playing.load(Ordering::Relaxed);
             s.current_participant = buf.current_participant();
+            s.current_question_id = buf.current_question();
         }
 
         std::thread::sleep(std::time::Duration::from_millis(5));
@@ -515,3 +612,49 @@ fn run_audio_thread(
 pub fn create_audio_player(sample_rate: u32) -> Result<Arc<AudioPlayer>, String> {
     AudioPlayer::new(sample_rate).map(Arc::new)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(buf: &mut CircularAudioBuffer, value: f32, len: usize, qid: &str) {
+        buf.write_with_participant(
+            &vec![value; len],
+            Some("teacher".to_string()),
+            Some(qid.to_string()),
+        );
+    }
+
+    #[test]
+    fn test_flush_question_keeps_other_replies() {
+        let mut buf = CircularAudioBuffer::new(1.0, 100);
+        write(&mut buf, 1.0, 10, "q1");
+        write(&mut buf, 2.0, 10, "q2");
+        write(&mut buf, 3.0, 10, "q1");
+
+        buf.flush_question("q1");
+
+        assert_eq!(buf.available(), 10);
+        let mut out = vec![0.0; 12];
+        assert_eq!(buf.read(&mut out), 10);
+        assert!(out[..10].iter().all(|&s| s == 2.0));
+        assert_eq!(buf.current_question().as_deref(), Some("q2"));
+    }
+
+    #[test]
+    fn test_smart_reset_keeps_active_question() {
+        let mut buf = CircularAudioBuffer::new(1.0, 100);
+        write(&mut buf, 1.0, 95, "old");
+        let mut out = vec![0.0; 90];
+        buf.read(&mut out);
+        // Wraps around the end of the ring
+        write(&mut buf, 2.0, 20, "new");
+
+        buf.smart_reset("new");
+
+        assert_eq!(buf.available(), 20);
+        let mut out = vec![0.0; 20];
+        buf.read(&mut out);
+        assert!(out.iter().all(|&s| s == 2.0));
+    }
+}
@@ -0,0 +1,168 @@
+//! Barge-in detection: the learner talking over the teacher
+//!
+//! Driven from the UI audio timer with the VAD speaking state and whether
+//! teacher audio is playing. Playback is ducked as soon as speech starts; if
+//! the speech lasts long enough the reply is interrupted, otherwise the
+//! volume is restored (coughs and short backchannels don't cut the teacher
+//! off).
+
+use std::time::{Duration, Instant};
+
+/// Barge-in tuning parameters
+#[derive(Clone, Debug)]
+pub struct BargeInConfig {
+    pub enabled: bool,
+    /// Playback gain while the learner may be barging in
+    pub duck_gain: f32,
+    /// Speech needed over the teacher before the reply is interrupted
+    pub confirm_ms: u32,
+}
+
+impl Default for BargeInConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            duck_gain: 0.25,
+            confirm_ms: 300,
+        }
+    }
+}
+
+/// What the caller should do with playback
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BargeInAction {
+    /// Lower the playback gain to `duck_gain`
+    Duck,
+    /// Speech stopped before confirming; restore full gain
+    Restore,
+    /// Flush the current reply and cancel its TTS/LLM work
+    Interrupt,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BargeInState {
+    Idle,
+    Ducked {
+        since: Instant,
+    },
+    /// Interrupted; re-armed once the learner stops talking
+    Interrupted,
+}
+
+pub struct BargeInDetector {
+    config: BargeInConfig,
+    state: BargeInState,
+}
+
+impl BargeInDetector {
+    pub fn new(config: BargeInConfig) -> Self {
+        Self {
+            config,
+            state: BargeInState::Idle,
+        }
+    }
+
+    pub fn config(&self) -> &BargeInConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: BargeInConfig) {
+        self.config = config;
+        self.state = BargeInState::Idle;
+    }
+
+    /// Advance with the current speaking/playing state
+    pub fn update(
+        &mut self,
+        speaking: bool,
+        teacher_playing: bool,
+        now: Instant,
+    ) -> Option<BargeInAction> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        match self.state {
+            BargeInState::Idle => {
+                if speaking && teacher_playing {
+                    self.state = BargeInState::Ducked { since: now };
+                    Some(BargeInAction::Duck)
+                } else {
+                    None
+                }
+            }
+            BargeInState::Ducked { since } => {
+                if !speaking || !teacher_playing {
+                    self.state = BargeInState::Idle;
+                    Some(BargeInAction::Restore)
+                } else if now.duration_since(since)
+                    >= Duration::from_millis(self.config.confirm_ms as u64)
+                {
+                    self.state = BargeInState::Interrupted;
+                    Some(BargeInAction::Interrupt)
+                } else {
+                    None
+                }
+            }
+            BargeInState::Interrupted => {
+                if !speaking {
+                    self.state = BargeInState::Idle;
+                }
+                None
+            }
+        }
+    }
+}
+
+impl Default for BargeInDetector {
+    fn default() -> Self {
+        Self::new(BargeInConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(start: Instant, ms: u64) -> Instant {
+        start + Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_sustained_speech_interrupts() {
+        let mut detector = BargeInDetector::default();
+        let t0 = Instant::now();
+
+        assert_eq!(detector.update(true, true, t0), Some(BargeInAction::Duck));
+        assert_eq!(detector.update(true, true, ms(t0, 100)), None);
+        assert_eq!(
+            detector.update(true, true, ms(t0, 300)),
+            Some(BargeInAction::Interrupt)
+        );
+        // No repeated interrupts while the learner keeps talking
+        assert_eq!(detector.update(true, true, ms(t0, 400)), None);
+        assert_eq!(detector.update(false, false, ms(t0, 500)), None);
+        assert_eq!(
+            detector.update(true, true, ms(t0, 600)),
+            Some(BargeInAction::Duck)
+        );
+    }
+
+    #[test]
+    fn test_short_speech_restores() {
+        let mut detector = BargeInDetector::default();
+        let t0 = Instant::now();
+
+        detector.update(true, true, t0);
+        assert_eq!(
+            detector.update(false, true, ms(t0, 100)),
+            Some(BargeInAction::Restore)
+        );
+    }
+
+    #[test]
+    fn test_speech_without_playback_is_ignored() {
+        let mut detector = BargeInDetector::default();
+        assert_eq!(detector.update(true, false, Instant::now()), None);
+    }
+}
@@ -0,0 +1,166 @@
+//! Daily statistics aggregation and learning streaks
+//!
+//! `daily_stats` is derived data: [`Database::recompute_daily_stats`] rebuilds
+//! any date range from sessions, conversations, review grades and reading
+//! attempts. [`refresh`] catches the table up to today before the Home and
+//! Stats screens read it, and [`upload`] mirrors the days that changed to the
+//! backend's `/learn/daily-stats`.
+
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone};
+
+use crate::db::Database;
+use crate::learn_api::{LearnApiClient, UpsertDailyStatRequest, get_learn_api};
+use crate::models::{DailyStat, Streaks};
+
+/// Recompute `daily_stats` from the last stored day (which may have been
+/// partial) through `today`; returns the days whose stats changed
+///
+/// Sessions that are still open keep accruing minutes on the day they
+/// started, so the range also reaches back to the earliest of them. The
+/// first run covers everything since the earliest recorded activity.
+pub async fn refresh(db: &Database, today: NaiveDate) -> Result<Vec<DailyStat>, sqlx::Error> {
+    let from = match db.get_last_stat_date().await? {
+        Some(date) => date,
+        None => db
+            .get_first_activity_at()
+            .await?
+            .and_then(local_date)
+            .unwrap_or(today),
+    };
+    let from = db
+        .get_first_open_session_at()
+        .await?
+        .and_then(local_date)
+        .map_or(from, |open| open.min(from));
+    db.recompute_daily_stats(from.min(today), today).await
+}
+
+fn local_date(timestamp: i64) -> Option<NaiveDate> {
+    Local
+        .timestamp_opt(timestamp, 0)
+        .earliest()
+        .map(|t| t.date_naive())
+}
+
+/// [`refresh`] the stats, [`upload`] the days that changed and return the
+/// streaks as of `today`; a failed upload is logged, not returned
+pub async fn update(db: &Database, today: NaiveDate) -> Result<Streaks, sqlx::Error> {
+    let changed = refresh(db, today).await?;
+    if let Err(e) = upload(&changed).await {
+        log::warn!("Failed to upload daily stats: {}", e);
+    }
+    streaks(db, today).await
+}
+
+/// Current and longest streaks from the stored daily stats
+pub async fn streaks(db: &Database, today: NaiveDate) -> Result<Streaks, sqlx::Error> {
+    let dates = db.get_study_dates().await?;
+    Ok(compute_streaks(&dates, today))
+}
+
+/// Streaks over `dates` (ascending, without duplicates) as of `today`
+pub fn compute_streaks(dates: &[NaiveDate], today: NaiveDate) -> Streaks {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for &date in dates.iter().filter(|&&date| date <= today) {
+        run = match previous {
+            Some(prev) if date - prev == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(date);
+    }
+
+    // Today doesn't break the streak until it's over
+    let current = match previous {
+        Some(last) if today - last <= Duration::days(1) => run,
+        _ => 0,
+    };
+    Streaks { current, longest }
+}
+
+/// Monday of the week containing `date`
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+/// Unix timestamp of local midnight at the start of `date`
+pub fn local_timestamp(date: NaiveDate) -> i64 {
+    let midnight = date.and_hms_opt(0, 0, 0).unwrap();
+    Local
+        .from_local_datetime(&midnight)
+        .earliest()
+        .map(|t| t.timestamp())
+        .unwrap_or_else(|| midnight.and_utc().timestamp())
+}
+
+/// Push changed days to the backend when signed in
+pub async fn upload(stats: &[DailyStat]) -> Result<(), String> {
+    let Some(client) = get_learn_api()
+        .and_then(|api| api.read().ok().map(|client| client.clone()))
+        .filter(LearnApiClient::is_authenticated)
+    else {
+        return Ok(());
+    };
+    for stat in stats {
+        client.upsert_daily_stat(stat.into()).await?;
+    }
+    Ok(())
+}
+
+impl From<&DailyStat> for UpsertDailyStatRequest {
+    fn from(stat: &DailyStat) -> Self {
+        Self {
+            stat_date: stat.stat_date.clone(),
+            minutes_studied: Some(stat.minutes_studied as i32),
+            words_practiced: Some(stat.words_practiced as i32),
+            sessions_completed: Some(stat.sessions_completed as i32),
+            errors_corrected: Some(stat.errors_corrected as i32),
+            new_words_learned: Some(stat.new_words_learned as i32),
+            review_words_count: Some(stat.review_words_count as i32),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+    }
+
+    #[test]
+    fn test_compute_streaks() {
+        let dates = [date(1), date(2), date(3), date(4), date(10), date(11)];
+
+        assert_eq!(
+            compute_streaks(&dates, date(11)),
+            Streaks {
+                current: 2,
+                longest: 4
+            }
+        );
+        // Not studied yet today
+        assert_eq!(compute_streaks(&dates, date(12)).current, 2);
+        assert_eq!(compute_streaks(&dates, date(13)).current, 0);
+        // Dates after `today` are ignored
+        assert_eq!(
+            compute_streaks(&dates, date(3)),
+            Streaks {
+                current: 3,
+                longest: 3
+            }
+        );
+        assert_eq!(compute_streaks(&[], date(1)), Streaks::default());
+    }
+
+    #[test]
+    fn test_week_start() {
+        // 2026-10-15 is a Thursday
+        assert_eq!(week_start(date(15)), date(12));
+        assert_eq!(week_start(date(12)), date(12));
+        assert_eq!(week_start(date(18)), date(12));
+    }
+}
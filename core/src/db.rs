@@ -1,13 +1,28 @@
 // Database models and operations for English Learning Companion
+//
+// Queries are checked against the migrated schema at compile time
+// (`sqlx::query!`). Without a `DATABASE_URL` the macros use the offline query
+// data in `.sqlx/` at the workspace root; after changing a query or a
+// migration, regenerate it with `cargo sqlx prepare --workspace`.
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use sqlx::Row;
-use sqlx::sqlite::SqlitePool;
+use chrono::NaiveDate;
+use sqlx::sqlite::{SqliteExecutor, SqlitePool};
 
+use crate::asset_api::Scene;
 use crate::models::{
-    Conversation, ConversationAnnotation, IssueWord, LearningSession, WordPracticeLog,
+    Achievement, AnnotationType, Conversation, ConversationAnnotation, DailyStat, IssueType,
+    IssueWord, LearningSession, MistakeSummary, ReviewStats, SessionType, VocabularyWord,
+    WordPracticeLog,
 };
+use crate::scheduler::{Grade, ReviewState, Scheduler, fuzz_seed};
+
+/// File name of the learning database
+pub const DATABASE_FILE: &str = "learning_companion.db";
 
 /// Database manager for English Learning Companion
 pub struct Database {
@@ -21,6 +36,31 @@ impl Database {
         Ok(Self { pool })
     }
 
+    /// Path of the app's learning database, under the configured data location
+    pub fn default_path() -> PathBuf {
+        crate::screens::settings::data_location_dir().join(DATABASE_FILE)
+    }
+
+    /// URL of the app's learning database; `DATABASE_URL` when set, otherwise
+    /// [`Self::default_path`]. The same URL is passed to the dataflow so the
+    /// db reader/writer nodes and the app share one database.
+    pub fn default_url() -> String {
+        std::env::var("DATABASE_URL").unwrap_or_else(|_| {
+            let path = Self::default_path();
+            if let Some(dir) = path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            format!("sqlite://{}?mode=rwc", path.display())
+        })
+    }
+
+    /// Open the app's learning database and bring its schema up to date
+    pub async fn open_default() -> Result<Self, sqlx::Error> {
+        let db = Self::new(&Self::default_url()).await?;
+        db.migrate().await?;
+        Ok(db)
+    }
+
     /// Run migrations
     pub async fn migrate(&self) -> Result<(), sqlx::Error> {
         sqlx::migrate!("./migrations").run(&self.pool).await?;
@@ -37,55 +77,78 @@ impl Database {
 
     // ============ IssueWord Operations ============
 
-    /// Insert a new issue word
+    /// Insert a new issue word, or refresh the descriptions of a known one;
+    /// returns the word id
     pub async fn insert_issue_word(&self, word: &IssueWord) -> Result<i64, sqlx::Error> {
-        let result = sqlx::query(
+        Self::upsert_issue_word(&self.pool, word).await
+    }
+
+    /// [`Self::insert_issue_word`] for each of `words`, all or none
+    pub async fn insert_issue_words(&self, words: &[IssueWord]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for word in words {
+            Self::upsert_issue_word(&mut *tx, word).await?;
+        }
+        tx.commit().await
+    }
+
+    async fn upsert_issue_word(
+        executor: impl SqliteExecutor<'_>,
+        word: &IssueWord,
+    ) -> Result<i64, sqlx::Error> {
+        let issue_type = word.issue_type.to_string();
+        sqlx::query_scalar!(
             r#"
             INSERT INTO issue_words (
                 word, issue_type, description_en, description_zh, created_at, pick_count,
-                review_interval_days, difficulty_level, context, audio_timestamp
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                review_interval_days, difficulty_level, context, audio_timestamp,
+                ease_factor, review_reps, lapses
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(word, issue_type) DO UPDATE SET
                 description_en = excluded.description_en,
                 description_zh = excluded.description_zh,
                 context = excluded.context,
                 audio_timestamp = excluded.audio_timestamp
+            RETURNING id
             "#,
+            word.word,
+            issue_type,
+            word.description_en,
+            word.description_zh,
+            word.created_at,
+            word.pick_count,
+            word.review_interval_days,
+            word.difficulty_level,
+            word.context,
+            word.audio_timestamp,
+            word.ease_factor,
+            word.review_reps,
+            word.lapses,
         )
-        .bind(&word.word)
-        .bind(word.issue_type.to_string())
-        .bind(&word.description_en)
-        .bind(&word.description_zh)
-        .bind(word.created_at)
-        .bind(word.pick_count)
-        .bind(word.review_interval_days)
-        .bind(word.difficulty_level)
-        .bind(&word.context)
-        .bind(word.audio_timestamp)
-        .execute(&self.pool)
-        .await?;
-
-        Ok(result.last_insert_rowid())
+        .fetch_one(executor)
+        .await
     }
 
     /// Get words due for review (max 30, respecting daily frequency limit)
     pub async fn get_words_for_review(&self, limit: i64) -> Result<Vec<IssueWord>, sqlx::Error> {
-        let rows = sqlx::query(
+        let now = Self::now();
+        let day_ago = now - 86400; // 24 hours ago
+        let rows = sqlx::query_as!(
+            IssueWordRow,
             r#"
             SELECT 
                 w.id, w.word, w.issue_type, w.description_en, w.description_zh, w.last_picked_at,
                 w.created_at, w.pick_count, w.next_review_at, w.review_interval_days,
                 w.difficulty_level, w.context, w.audio_timestamp,
-                COALESCE(
-                    (SELECT COUNT(*) FROM word_practice_log 
-                     WHERE word_id = w.id 
-                     AND practiced_at >= ?),
-                    0
-                ) as today_count
+                w.ease_factor, w.review_reps, w.lapses
             FROM issue_words w
             WHERE 
                 (w.next_review_at IS NULL OR w.next_review_at <= ?)
-            HAVING today_count < 5
+                AND (
+                    SELECT COUNT(*) FROM word_practice_log 
+                    WHERE word_id = w.id 
+                    AND practiced_at >= ?
+                ) < 5
             ORDER BY 
                 CASE WHEN w.next_review_at IS NULL THEN 0 ELSE 1 END,
                 w.next_review_at ASC,
@@ -93,93 +156,177 @@ impl Database {
                 w.created_at ASC
             LIMIT ?
             "#,
+            now,
+            day_ago,
+            limit,
         )
-        .bind(Self::now() - 86400) // 24 hours ago
-        .bind(Self::now())
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut words = Vec::new();
-        for row in rows {
-            words.push(IssueWord {
-                id: row.get("id"),
-                word: row.get("word"),
-                issue_type: row.get::<String, _>("issue_type").parse().unwrap(),
-                description_en: row.get("description_en"),
-                description_zh: row.get("description_zh"),
-                last_picked_at: row.get("last_picked_at"),
-                created_at: row.get("created_at"),
-                pick_count: row.get("pick_count"),
-                next_review_at: row.get("next_review_at"),
-                review_interval_days: row.get("review_interval_days"),
-                difficulty_level: row.get("difficulty_level"),
-                context: row.get("context"),
-                audio_timestamp: row.get("audio_timestamp"),
-            });
-        }
+        rows.into_iter().map(IssueWord::try_from).collect()
+    }
 
-        Ok(words)
+    /// Get words that are due now or become due before `until`, soonest first
+    ///
+    /// Unlike [`Self::get_words_for_review`] this ignores the daily practice
+    /// limit; it is the queue shown on the review screen.
+    pub async fn get_upcoming_words(
+        &self,
+        until: i64,
+        limit: i64,
+    ) -> Result<Vec<IssueWord>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            IssueWordRow,
+            r#"
+            SELECT 
+                w.id, w.word, w.issue_type, w.description_en, w.description_zh, w.last_picked_at,
+                w.created_at, w.pick_count, w.next_review_at, w.review_interval_days,
+                w.difficulty_level, w.context, w.audio_timestamp,
+                w.ease_factor, w.review_reps, w.lapses
+            FROM issue_words w
+            WHERE w.next_review_at IS NULL OR w.next_review_at <= ?
+            ORDER BY 
+                CASE WHEN w.next_review_at IS NULL THEN 0 ELSE 1 END,
+                w.next_review_at ASC,
+                w.difficulty_level DESC,
+                w.created_at ASC
+            LIMIT ?
+            "#,
+            until,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(IssueWord::try_from).collect()
+    }
+
+    /// Get mastered words (see [`Scheduler::is_mastered`]), longest interval first
+    pub async fn get_mastered_words(&self, limit: i64) -> Result<Vec<IssueWord>, sqlx::Error> {
+        let mastered_interval = Scheduler::default().config().mastered_interval_days;
+        let rows = sqlx::query_as!(
+            IssueWordRow,
+            r#"
+            SELECT 
+                w.id, w.word, w.issue_type, w.description_en, w.description_zh, w.last_picked_at,
+                w.created_at, w.pick_count, w.next_review_at, w.review_interval_days,
+                w.difficulty_level, w.context, w.audio_timestamp,
+                w.ease_factor, w.review_reps, w.lapses
+            FROM issue_words w
+            WHERE w.review_interval_days >= ?
+            ORDER BY w.review_interval_days DESC, w.last_picked_at DESC
+            LIMIT ?
+            "#,
+            mastered_interval,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(IssueWord::try_from).collect()
+    }
+
+    /// Get a word by its `(word, issue_type)` key
+    pub async fn get_issue_word(
+        &self,
+        word: &str,
+        issue_type: &IssueType,
+    ) -> Result<Option<IssueWord>, sqlx::Error> {
+        let issue_type = issue_type.to_string();
+        let row = sqlx::query_as!(
+            IssueWordRow,
+            r#"
+            SELECT
+                w.id AS "id!", w.word, w.issue_type, w.description_en, w.description_zh,
+                w.last_picked_at, w.created_at, w.pick_count, w.next_review_at,
+                w.review_interval_days, w.difficulty_level, w.context, w.audio_timestamp,
+                w.ease_factor, w.review_reps, w.lapses
+            FROM issue_words w
+            WHERE w.word = ? AND w.issue_type = ?
+            "#,
+            word,
+            issue_type,
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(IssueWord::try_from).transpose()
+    }
+
+    /// Get every word in the review queue, oldest first
+    pub async fn get_all_issue_words(&self) -> Result<Vec<IssueWord>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            IssueWordRow,
+            r#"
+            SELECT
+                w.id AS "id!", w.word, w.issue_type, w.description_en, w.description_zh,
+                w.last_picked_at, w.created_at, w.pick_count, w.next_review_at,
+                w.review_interval_days, w.difficulty_level, w.context, w.audio_timestamp,
+                w.ease_factor, w.review_reps, w.lapses
+            FROM issue_words w
+            ORDER BY w.created_at, w.id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(IssueWord::try_from).collect()
     }
 
     /// Update word after practice (implements spaced repetition)
+    ///
+    /// Schedules the next review with [`Scheduler`] from the word's stored
+    /// ease, interval and repetition count.
     pub async fn update_word_after_practice(
         &self,
         word_id: i64,
-        success: bool,
+        grade: Grade,
     ) -> Result<(), sqlx::Error> {
-        let (new_interval, new_difficulty) = if success {
-            // Increase interval on success (1 -> 2 -> 4 -> 7 -> 14 -> 30 days)
-            let row = sqlx::query(
-                "SELECT review_interval_days, difficulty_level FROM issue_words WHERE id = ?",
-            )
-            .bind(word_id)
-            .fetch_one(&self.pool)
-            .await?;
-
-            let interval: i64 = row.get("review_interval_days");
-            let diff: i64 = row.get("difficulty_level");
-
-            let new_interval = match interval {
-                1 => 2,
-                2 => 4,
-                4 => 7,
-                7 => 14,
-                14 => 30,
-                _ => 30,
-            };
-            let new_diff = (diff - 1).max(1);
-            (new_interval, new_diff)
-        } else {
-            // Reset interval on failure, increase difficulty
-            let diff = sqlx::query_scalar::<_, i64>(
-                "SELECT difficulty_level FROM issue_words WHERE id = ?",
-            )
-            .bind(word_id)
-            .fetch_one(&self.pool)
-            .await?;
+        let row = sqlx::query!(
+            r#"
+            SELECT ease_factor, review_interval_days, review_reps, lapses, pick_count
+            FROM issue_words WHERE id = ?
+            "#,
+            word_id,
+        )
+        .fetch_one(&self.pool)
+        .await?;
 
-            (1, (diff + 1).min(5))
+        let state = ReviewState {
+            ease: row.ease_factor,
+            interval_days: row.review_interval_days.unwrap_or(1),
+            reps: row.review_reps,
+            lapses: row.lapses,
         };
 
-        let next_review = Self::now() + (new_interval * 86400);
+        let scheduler = Scheduler::default();
+        let next = scheduler.review(&state, grade);
+        let now = Self::now();
+        let next_review = scheduler.due_at(&next, now, fuzz_seed(word_id, row.pick_count + 1));
+        let difficulty = scheduler.difficulty_level(&next);
 
-        sqlx::query(
+        sqlx::query!(
             r#"
             UPDATE issue_words 
             SET last_picked_at = ?,
                 pick_count = pick_count + 1,
                 next_review_at = ?,
                 review_interval_days = ?,
-                difficulty_level = ?
+                difficulty_level = ?,
+                ease_factor = ?,
+                review_reps = ?,
+                lapses = ?
             WHERE id = ?
             "#,
+            now,
+            next_review,
+            next.interval_days,
+            difficulty,
+            next.ease,
+            next.reps,
+            next.lapses,
+            word_id,
         )
-        .bind(Self::now())
-        .bind(next_review)
-        .bind(new_interval)
-        .bind(new_difficulty)
-        .bind(word_id)
         .execute(&self.pool)
         .await?;
 
@@ -190,25 +337,28 @@ impl Database {
 
     /// Insert a new conversation entry
     pub async fn insert_conversation(&self, conv: &Conversation) -> Result<i64, sqlx::Error> {
-        let result = sqlx::query(
+        let speaker = conv.speaker.to_string();
+        let use_lang = conv.use_lang.to_string();
+        let result = sqlx::query!(
             r#"
             INSERT INTO conversations (
-                session_id, speaker, use_lang, content_en, content_zh, audio_path, created_at,
-                duration_ms, words_per_minute, pause_count, hesitation_count
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                session_id, turn_id, speaker, use_lang, content_en, content_zh, audio_path,
+                created_at, duration_ms, words_per_minute, pause_count, hesitation_count
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
+            conv.session_id,
+            conv.turn_id,
+            speaker,
+            use_lang,
+            conv.content_en,
+            conv.content_zh,
+            conv.audio_path,
+            conv.created_at,
+            conv.duration_ms,
+            conv.words_per_minute,
+            conv.pause_count,
+            conv.hesitation_count,
         )
-        .bind(&conv.session_id)
-        .bind(conv.speaker.to_string())
-        .bind(conv.use_lang.to_string())
-        .bind(&conv.content_en)
-        .bind(&conv.content_zh)
-        .bind(&conv.audio_path)
-        .bind(conv.created_at)
-        .bind(conv.duration_ms)
-        .bind(conv.words_per_minute)
-        .bind(conv.pause_count)
-        .bind(conv.hesitation_count)
         .execute(&self.pool)
         .await?;
 
@@ -221,38 +371,41 @@ impl Database {
         session_id: &str,
         limit: i64,
     ) -> Result<Vec<Conversation>, sqlx::Error> {
-        let rows = sqlx::query(
+        let rows = sqlx::query_as!(
+            ConversationRow,
             r#"
             SELECT * FROM conversations 
             WHERE session_id = ?
-            ORDER BY created_at DESC
+            ORDER BY created_at DESC, id DESC
             LIMIT ?
             "#,
+            session_id,
+            limit,
         )
-        .bind(session_id)
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut conversations = Vec::new();
-        for row in rows {
-            conversations.push(Conversation {
-                id: row.get("id"),
-                session_id: row.get("session_id"),
-                speaker: row.get::<String, _>("speaker").parse().unwrap(),
-                use_lang: row.get::<String, _>("use_lang").parse().unwrap(),
-                content_en: row.get("content_en"),
-                content_zh: row.get("content_zh"),
-                audio_path: row.get("audio_path"),
-                created_at: row.get("created_at"),
-                duration_ms: row.get("duration_ms"),
-                words_per_minute: row.get("words_per_minute"),
-                pause_count: row.get("pause_count"),
-                hesitation_count: row.get("hesitation_count"),
-            });
-        }
+        rows.into_iter().map(Conversation::try_from).collect()
+    }
 
-        Ok(conversations)
+    /// Get the user message and teacher reply of one voice turn
+    pub async fn get_turn_conversations(
+        &self,
+        turn_id: &str,
+    ) -> Result<Vec<Conversation>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ConversationRow,
+            r#"
+            SELECT * FROM conversations
+            WHERE turn_id = ?
+            ORDER BY id
+            "#,
+            turn_id,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(Conversation::try_from).collect()
     }
 
     // ============ Annotation Operations ============
@@ -262,24 +415,26 @@ impl Database {
         &self,
         annotation: &ConversationAnnotation,
     ) -> Result<i64, sqlx::Error> {
-        let result = sqlx::query(
+        let annotation_type = annotation.annotation_type.to_string();
+        let severity = annotation.severity.to_string();
+        let result = sqlx::query!(
             r#"
             INSERT INTO conversation_annotations (
                 conversation_id, annotation_type, start_position, end_position,
                 original_text, suggested_text, description_en, description_zh, severity, created_at
             ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
+            annotation.conversation_id,
+            annotation_type,
+            annotation.start_position,
+            annotation.end_position,
+            annotation.original_text,
+            annotation.suggested_text,
+            annotation.description_en,
+            annotation.description_zh,
+            severity,
+            annotation.created_at,
         )
-        .bind(annotation.conversation_id)
-        .bind(annotation.annotation_type.to_string())
-        .bind(annotation.start_position)
-        .bind(annotation.end_position)
-        .bind(&annotation.original_text)
-        .bind(&annotation.suggested_text)
-        .bind(&annotation.description_en)
-        .bind(&annotation.description_zh)
-        .bind(annotation.severity.to_string())
-        .bind(annotation.created_at)
         .execute(&self.pool)
         .await?;
 
@@ -291,96 +446,788 @@ impl Database {
         &self,
         conversation_id: i64,
     ) -> Result<Vec<ConversationAnnotation>, sqlx::Error> {
-        let rows = sqlx::query("SELECT * FROM conversation_annotations WHERE conversation_id = ?")
-            .bind(conversation_id)
-            .fetch_all(&self.pool)
-            .await?;
+        let rows = sqlx::query!(
+            "SELECT * FROM conversation_annotations WHERE conversation_id = ?",
+            conversation_id,
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
         let mut annotations = Vec::new();
         for row in rows {
             annotations.push(ConversationAnnotation {
-                id: row.get("id"),
-                conversation_id: row.get("conversation_id"),
-                annotation_type: row.get::<String, _>("annotation_type").parse().unwrap(),
-                start_position: row.get("start_position"),
-                end_position: row.get("end_position"),
-                original_text: row.get("original_text"),
-                suggested_text: row.get("suggested_text"),
-                description_en: row.get("description_en"),
-                description_zh: row.get("description_zh"),
-                severity: row.get::<String, _>("severity").parse().unwrap(),
-                created_at: row.get("created_at"),
+                id: row.id,
+                conversation_id: row.conversation_id,
+                annotation_type: decode(&row.annotation_type)?,
+                start_position: row.start_position,
+                end_position: row.end_position,
+                original_text: row.original_text,
+                suggested_text: row.suggested_text,
+                description_en: row.description_en,
+                description_zh: row.description_zh,
+                severity: decode(row.severity.as_deref().unwrap_or("medium"))?,
+                created_at: row.created_at,
             });
         }
 
         Ok(annotations)
     }
 
+    // ============ Scene Operations ============
+
+    /// Keep a local copy of a scene from the asset API, matched by its
+    /// English name, so sessions can reference it; returns the local id
+    pub async fn save_scene(&self, scene: &Scene) -> Result<i64, sqlx::Error> {
+        // Unknown levels would fail the column's CHECK; store them as unset
+        let difficulty_level = scene
+            .difficulty_level
+            .as_deref()
+            .filter(|level| matches!(*level, "beginner" | "intermediate" | "advanced"));
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO scenes (
+                name_en, name_zh, description_en, description_zh, icon_emoji,
+                difficulty_level, category, display_order
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(name_en) DO UPDATE SET
+                name_zh = excluded.name_zh,
+                description_en = excluded.description_en,
+                description_zh = excluded.description_zh,
+                icon_emoji = excluded.icon_emoji,
+                difficulty_level = excluded.difficulty_level,
+                category = excluded.category,
+                display_order = excluded.display_order
+            RETURNING id
+            "#,
+            scene.name_en,
+            scene.name_zh,
+            scene.description_en,
+            scene.description_zh,
+            scene.icon_emoji,
+            difficulty_level,
+            scene.category,
+            scene.display_order,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
     // ============ Learning Session Operations ============
 
     /// Create a new learning session
-    pub async fn create_session(&self, session: &LearningSession) -> Result<i64, sqlx::Error> {
-        let result = sqlx::query(
+    pub async fn create_session(&self, session: &LearningSession) -> Result<(), sqlx::Error> {
+        let session_type = session.session_type.as_ref().map(|t| t.to_string());
+        sqlx::query!(
             r#"
             INSERT INTO learning_sessions (
-                session_id, topic, target_words, started_at, total_exchanges
-            ) VALUES (?, ?, ?, ?, ?)
+                session_id, session_type, sceneid, scene_dialogue_id, classic_clip_id,
+                target_words, started_at, total_words_spoken, average_wpm, error_count,
+                correction_count, notes
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
+            session.session_id,
+            session_type,
+            session.sceneid,
+            session.scene_dialogue_id,
+            session.classic_clip_id,
+            session.target_words,
+            session.started_at,
+            session.total_words_spoken,
+            session.average_wpm,
+            session.error_count,
+            session.correction_count,
+            session.notes,
         )
-        .bind(&session.session_id)
-        .bind(&session.topic)
-        .bind(&session.target_words)
-        .bind(session.started_at)
-        .bind(session.total_exchanges)
         .execute(&self.pool)
         .await?;
 
-        Ok(result.last_insert_rowid())
+        Ok(())
     }
 
     /// Update session on completion
     pub async fn end_session(
         &self,
         session_id: &str,
-        satisfaction: Option<i64>,
         notes: Option<String>,
     ) -> Result<(), sqlx::Error> {
-        sqlx::query(
+        let now = Self::now();
+        sqlx::query!(
             r#"
             UPDATE learning_sessions 
-            SET ended_at = ?, user_satisfaction = ?, notes = ?
-            WHERE session_id = ?
+            SET ended_at = ?1, duration_seconds = ?1 - started_at, notes = COALESCE(?2, notes)
+            WHERE session_id = ?3
             "#,
+            now,
+            notes,
+            session_id,
         )
-        .bind(Self::now())
-        .bind(satisfaction)
-        .bind(notes)
-        .bind(session_id)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
+    /// End every open session of `session_type`; returns how many were ended
+    pub async fn end_open_sessions(&self, session_type: &SessionType) -> Result<u64, sqlx::Error> {
+        let now = Self::now();
+        let session_type = session_type.to_string();
+        let result = sqlx::query!(
+            r#"
+            UPDATE learning_sessions
+            SET ended_at = ?1, duration_seconds = ?1 - started_at
+            WHERE ended_at IS NULL AND session_type = ?2
+            "#,
+            now,
+            session_type,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Get the most recently started sessions
+    pub async fn get_recent_sessions(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<LearningSession>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                session_id AS "session_id!", session_type, sceneid, scene_dialogue_id,
+                classic_clip_id, target_words, started_at, ended_at, duration_seconds,
+                total_words_spoken, average_wpm, error_count, correction_count, notes,
+                ai_summary_en, ai_summary_zh
+            FROM learning_sessions
+            ORDER BY started_at DESC, rowid DESC
+            LIMIT ?
+            "#,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut sessions = Vec::new();
+        for row in rows {
+            sessions.push(LearningSession {
+                session_id: row.session_id,
+                session_type: row.session_type.as_deref().map(decode).transpose()?,
+                sceneid: row.sceneid,
+                scene_dialogue_id: row.scene_dialogue_id,
+                classic_clip_id: row.classic_clip_id,
+                target_words: row.target_words,
+                started_at: row.started_at,
+                ended_at: row.ended_at,
+                duration_seconds: row.duration_seconds,
+                total_words_spoken: row.total_words_spoken.unwrap_or_default(),
+                average_wpm: row.average_wpm,
+                error_count: row.error_count.unwrap_or_default(),
+                correction_count: row.correction_count.unwrap_or_default(),
+                notes: row.notes,
+                ai_summary_en: row.ai_summary_en,
+                ai_summary_zh: row.ai_summary_zh,
+            });
+        }
+
+        Ok(sessions)
+    }
+
+    // ============ Vocabulary Operations ============
+
+    /// Get the learner's vocabulary, alphabetically
+    pub async fn get_vocabulary(&self) -> Result<Vec<VocabularyWord>, sqlx::Error> {
+        sqlx::query_as!(
+            VocabularyWord,
+            r#"
+            SELECT
+                word, word_zh,
+                COALESCE(mastery_level, 1) AS "mastery_level!: i64",
+                first_seen_at, last_practiced_at,
+                COALESCE(practice_count, 0) AS "practice_count!: i64",
+                COALESCE(correct_count, 0) AS "correct_count!: i64",
+                next_review_at
+            FROM user_vocabulary
+            ORDER BY word COLLATE NOCASE
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
     // ============ Word Practice Log Operations ============
 
     /// Log a word practice
     pub async fn log_word_practice(&self, log: &WordPracticeLog) -> Result<i64, sqlx::Error> {
-        let result = sqlx::query(
+        let result = sqlx::query!(
             r#"
             INSERT INTO word_practice_log (
                 word_id, session_id, practiced_at, success_level, notes
             ) VALUES (?, ?, ?, ?, ?)
             "#,
+            log.word_id,
+            log.session_id,
+            log.practiced_at,
+            log.success_level,
+            log.notes,
         )
-        .bind(log.word_id)
-        .bind(&log.session_id)
-        .bind(log.practiced_at)
-        .bind(log.success_level)
-        .bind(&log.notes)
         .execute(&self.pool)
         .await?;
 
         Ok(result.last_insert_rowid())
     }
+
+    // ============ Review Screen Queries ============
+
+    /// Get the corrections made most often, grouped by the corrected text
+    pub async fn get_frequent_mistakes(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<MistakeSummary>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                original_text AS "original_text!",
+                suggested_text,
+                annotation_type AS "annotation_type!",
+                MAX(description_en) AS "description_en?: String",
+                MAX(description_zh) AS "description_zh?: String",
+                COUNT(*) AS "occurrences!: i64",
+                MAX(created_at) AS "last_seen_at!: i64"
+            FROM conversation_annotations
+            WHERE original_text IS NOT NULL AND original_text != ''
+            GROUP BY lower(original_text), suggested_text
+            ORDER BY COUNT(*) DESC, MAX(created_at) DESC
+            LIMIT ?
+            "#,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut mistakes = Vec::new();
+        for row in rows {
+            mistakes.push(MistakeSummary {
+                original_text: row.original_text,
+                suggested_text: row.suggested_text,
+                annotation_type: decode(&row.annotation_type)?,
+                description_en: row.description_en,
+                description_zh: row.description_zh,
+                occurrences: row.occurrences,
+                last_seen_at: row.last_seen_at,
+            });
+        }
+
+        Ok(mistakes)
+    }
+
+    /// Get the review screen totals; `week_start` is the start of the week
+    /// (unix seconds) that [`ReviewStats::week_reviews`] counts from
+    pub async fn get_review_stats(&self, week_start: i64) -> Result<ReviewStats, sqlx::Error> {
+        let now = Self::now();
+        let mastered_interval = Scheduler::default().config().mastered_interval_days;
+
+        let words = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "total_words!: i64",
+                COALESCE(SUM(next_review_at IS NULL OR next_review_at <= ?1), 0) AS "due_words!: i64",
+                COALESCE(SUM(review_interval_days >= ?2), 0) AS "mastered_words!: i64",
+                COALESCE(AVG(MIN(MAX(review_interval_days, 0), ?2) * 1.0 / ?2), 0.0)
+                    AS "average_mastery!: f64"
+            FROM issue_words
+            "#,
+            now,
+            mastered_interval,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let mistakes = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "mistakes!: i64" FROM (
+                SELECT 1 FROM conversation_annotations
+                WHERE original_text IS NOT NULL AND original_text != ''
+                GROUP BY lower(original_text), suggested_text
+            )
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let practice = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "reviews!: i64",
+                COALESCE(SUM(success_level >= 3), 0) AS "passed_reviews!: i64",
+                COUNT(DISTINCT date(practiced_at, 'unixepoch', 'localtime')) AS "study_days!: i64"
+            FROM word_practice_log
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let week = sqlx::query!(
+            r#"
+            SELECT (practiced_at - ?1) / 86400 AS "day!: i64", COUNT(*) AS "reviews!: i64"
+            FROM word_practice_log
+            WHERE practiced_at >= ?1 AND practiced_at < ?1 + 7 * 86400
+            GROUP BY 1
+            "#,
+            week_start,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut week_reviews = [0; 7];
+        for row in week {
+            if let Some(count) = week_reviews.get_mut(row.day as usize) {
+                *count = row.reviews;
+            }
+        }
+
+        Ok(ReviewStats {
+            total_words: words.total_words,
+            due_words: words.due_words,
+            mastered_words: words.mastered_words,
+            mistakes,
+            reviews: practice.reviews,
+            passed_reviews: practice.passed_reviews,
+            study_days: practice.study_days,
+            average_mastery: words.average_mastery,
+            week_reviews,
+        })
+    }
+
+    // ============ Daily Stats Operations ============
+
+    /// Rebuild `daily_stats` for the local dates `from..=to` from the
+    /// learning tables; returns the days whose stats changed, oldest first
+    ///
+    /// Unchanged days are not rewritten.
+    pub async fn recompute_daily_stats(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyStat>, sqlx::Error> {
+        let stored = self.get_daily_stats(from, to).await?;
+        let from = from.to_string();
+        let to = to.to_string();
+        let mut days: BTreeMap<String, DailyStat> = BTreeMap::new();
+
+        // Open sessions count up to their last recorded activity
+        let sessions = sqlx::query!(
+            r#"
+            SELECT
+                date(s.started_at, 'unixepoch', 'localtime') AS "day!: String",
+                COALESCE(SUM(MAX(COALESCE(
+                    s.duration_seconds,
+                    (
+                        SELECT MAX(t) FROM (
+                            SELECT created_at AS t FROM conversations
+                            WHERE session_id = s.session_id
+                            UNION ALL
+                            SELECT practiced_at FROM word_practice_log
+                            WHERE session_id = s.session_id
+                            UNION ALL
+                            SELECT attempted_at FROM reading_practice_attempts
+                            WHERE session_id = s.session_id
+                        )
+                    ) - s.started_at,
+                    0
+                ), 0)), 0) AS "seconds!: i64",
+                COALESCE(SUM(s.ended_at IS NOT NULL), 0) AS "completed!: i64"
+            FROM learning_sessions s
+            WHERE date(s.started_at, 'unixepoch', 'localtime') BETWEEN ?1 AND ?2
+            GROUP BY 1
+            "#,
+            from,
+            to,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        for row in sessions {
+            let stat = day_stat(&mut days, row.day);
+            stat.minutes_studied = (row.seconds + 30) / 60;
+            stat.sessions_completed = row.completed;
+        }
+
+        let practice = sqlx::query!(
+            r#"
+            SELECT
+                date(practiced_at, 'unixepoch', 'localtime') AS "day!: String",
+                COUNT(DISTINCT word_id) AS "words!: i64",
+                COUNT(*) AS "reviews!: i64"
+            FROM word_practice_log
+            WHERE date(practiced_at, 'unixepoch', 'localtime') BETWEEN ?1 AND ?2
+            GROUP BY 1
+            "#,
+            from,
+            to,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        for row in practice {
+            let stat = day_stat(&mut days, row.day);
+            stat.words_practiced = row.words;
+            stat.review_words_count = row.reviews;
+        }
+
+        let new_words = sqlx::query!(
+            r#"
+            SELECT
+                date(created_at, 'unixepoch', 'localtime') AS "day!: String",
+                COUNT(*) AS "words!: i64"
+            FROM issue_words
+            WHERE date(created_at, 'unixepoch', 'localtime') BETWEEN ?1 AND ?2
+            GROUP BY 1
+            "#,
+            from,
+            to,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        for row in new_words {
+            day_stat(&mut days, row.day).new_words_learned = row.words;
+        }
+
+        let corrections = sqlx::query!(
+            r#"
+            SELECT
+                date(a.created_at, 'unixepoch', 'localtime') AS "day!: String",
+                COUNT(*) AS "errors!: i64"
+            FROM conversation_annotations a
+            JOIN conversations c ON c.id = a.conversation_id
+            WHERE c.speaker = 'user'
+              AND date(a.created_at, 'unixepoch', 'localtime') BETWEEN ?1 AND ?2
+            GROUP BY 1
+            "#,
+            from,
+            to,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        for row in corrections {
+            day_stat(&mut days, row.day).errors_corrected = row.errors;
+        }
+
+        let stats: Vec<DailyStat> = days.into_values().filter(DailyStat::is_active).collect();
+        let changed: Vec<DailyStat> = stats
+            .iter()
+            .filter(|stat| !stored.contains(stat))
+            .cloned()
+            .collect();
+        let outdated: Vec<&String> = stored
+            .iter()
+            .filter(|old| !stats.contains(old))
+            .map(|old| &old.stat_date)
+            .collect();
+        if outdated.is_empty() && changed.is_empty() {
+            return Ok(changed);
+        }
+
+        let mut tx = self.pool.begin().await?;
+        for stat_date in outdated {
+            sqlx::query!("DELETE FROM daily_stats WHERE stat_date = ?", stat_date)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for stat in &changed {
+            sqlx::query!(
+                r#"
+                INSERT INTO daily_stats (
+                    stat_date, minutes_studied, words_practiced, sessions_completed,
+                    errors_corrected, new_words_learned, review_words_count
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+                stat.stat_date,
+                stat.minutes_studied,
+                stat.words_practiced,
+                stat.sessions_completed,
+                stat.errors_corrected,
+                stat.new_words_learned,
+                stat.review_words_count,
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(changed)
+    }
+
+    /// Get the stored daily stats for the local dates `from..=to`, oldest first
+    pub async fn get_daily_stats(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<DailyStat>, sqlx::Error> {
+        let from = from.to_string();
+        let to = to.to_string();
+        sqlx::query_as!(
+            DailyStat,
+            r#"
+            SELECT
+                stat_date,
+                COALESCE(minutes_studied, 0) AS "minutes_studied!: i64",
+                COALESCE(words_practiced, 0) AS "words_practiced!: i64",
+                COALESCE(sessions_completed, 0) AS "sessions_completed!: i64",
+                COALESCE(errors_corrected, 0) AS "errors_corrected!: i64",
+                COALESCE(new_words_learned, 0) AS "new_words_learned!: i64",
+                COALESCE(review_words_count, 0) AS "review_words_count!: i64"
+            FROM daily_stats
+            WHERE stat_date BETWEEN ?1 AND ?2
+            ORDER BY stat_date
+            "#,
+            from,
+            to,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Get every date with stored daily stats, oldest first
+    pub async fn get_study_dates(&self) -> Result<Vec<NaiveDate>, sqlx::Error> {
+        let dates = sqlx::query_scalar!("SELECT stat_date FROM daily_stats ORDER BY stat_date")
+            .fetch_all(&self.pool)
+            .await?;
+        Ok(dates.iter().filter_map(|date| date.parse().ok()).collect())
+    }
+
+    /// Get the latest date with stored daily stats
+    pub async fn get_last_stat_date(&self) -> Result<Option<NaiveDate>, sqlx::Error> {
+        let date = sqlx::query_scalar!("SELECT MAX(stat_date) FROM daily_stats")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(date.and_then(|date| date.parse().ok()))
+    }
+
+    /// Get the Unix timestamp of the earliest session that hasn't ended
+    pub async fn get_first_open_session_at(&self) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT MIN(started_at) AS "started_at: i64" FROM learning_sessions
+            WHERE ended_at IS NULL
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Get the Unix timestamp of the earliest recorded learning activity
+    pub async fn get_first_activity_at(&self) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT MIN(t) AS "first_activity_at: i64" FROM (
+                SELECT MIN(started_at) AS t FROM learning_sessions
+                UNION ALL SELECT MIN(practiced_at) FROM word_practice_log
+                UNION ALL SELECT MIN(created_at) FROM issue_words
+                UNION ALL SELECT MIN(created_at) FROM conversation_annotations
+            )
+            "#
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    // ============ Achievement Operations ============
+
+    /// Count the words whose review interval has reached mastery
+    pub async fn count_mastered_words(&self) -> Result<i64, sqlx::Error> {
+        let mastered_interval = Scheduler::default().config().mastered_interval_days;
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "mastered_words!: i64" FROM issue_words
+            WHERE review_interval_days >= ?
+            "#,
+            mastered_interval,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Count the ended sessions of each session type
+    pub async fn count_completed_sessions(&self) -> Result<Vec<(SessionType, i64)>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT session_type AS "session_type!", COUNT(*) AS "sessions!: i64"
+            FROM learning_sessions
+            WHERE ended_at IS NOT NULL AND session_type IS NOT NULL
+            GROUP BY session_type
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| Some((row.session_type.parse().ok()?, row.sessions)))
+            .collect())
+    }
+
+    /// Get the most learner turns in one conversation session without any
+    /// annotation of `annotation_type`
+    pub async fn get_longest_clean_conversation(
+        &self,
+        annotation_type: AnnotationType,
+    ) -> Result<i64, sqlx::Error> {
+        let annotation_type = annotation_type.to_string();
+        sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(MAX(turns), 0) AS "turns!: i64" FROM (
+                SELECT COUNT(*) AS turns
+                FROM conversations
+                WHERE speaker = 'user' AND session_id NOT IN (
+                    SELECT c.session_id
+                    FROM conversation_annotations a
+                    JOIN conversations c ON c.id = a.conversation_id
+                    WHERE a.annotation_type = ?
+                )
+                GROUP BY session_id
+            )
+            "#,
+            annotation_type,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Record an earned achievement; returns `false` when it was already
+    /// earned (`UNIQUE(user_id, achievement_type, achievement_name)`)
+    pub async fn award_achievement(&self, achievement: &Achievement) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO user_achievements (
+                achievement_type, achievement_name, description_en, description_zh,
+                earned_at, metadata
+            ) VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT(user_id, achievement_type, achievement_name) DO NOTHING
+            "#,
+            achievement.achievement_type,
+            achievement.achievement_name,
+            achievement.description_en,
+            achievement.description_zh,
+            achievement.earned_at,
+            achievement.metadata,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the earned achievements, newest first
+    pub async fn get_achievements(&self) -> Result<Vec<Achievement>, sqlx::Error> {
+        sqlx::query_as!(
+            Achievement,
+            r#"
+            SELECT
+                achievement_type, achievement_name, description_en, description_zh,
+                earned_at, metadata
+            FROM user_achievements
+            ORDER BY earned_at DESC, id DESC
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+/// The stats entry for `date`, added empty if missing
+fn day_stat(days: &mut BTreeMap<String, DailyStat>, date: String) -> &mut DailyStat {
+    days.entry(date.clone()).or_insert_with(|| DailyStat {
+        stat_date: date,
+        ..Default::default()
+    })
+}
+
+/// Parse a stored enum column; an unknown value fails the query instead of
+/// panicking
+fn decode<T: FromStr<Err = String>>(value: &str) -> Result<T, sqlx::Error> {
+    value
+        .parse()
+        .map_err(|e: String| sqlx::Error::Decode(e.into()))
+}
+
+/// An `issue_words` row as stored
+struct IssueWordRow {
+    id: i64,
+    word: String,
+    issue_type: String,
+    description_en: Option<String>,
+    description_zh: Option<String>,
+    last_picked_at: Option<i64>,
+    created_at: i64,
+    pick_count: i64,
+    next_review_at: Option<i64>,
+    review_interval_days: Option<i64>,
+    difficulty_level: Option<i64>,
+    context: Option<String>,
+    audio_timestamp: Option<i64>,
+    ease_factor: f64,
+    review_reps: i64,
+    lapses: i64,
+}
+
+impl TryFrom<IssueWordRow> for IssueWord {
+    type Error = sqlx::Error;
+
+    fn try_from(row: IssueWordRow) -> Result<Self, Self::Error> {
+        Ok(IssueWord {
+            id: Some(row.id),
+            word: row.word,
+            issue_type: decode(&row.issue_type)?,
+            description_en: row.description_en,
+            description_zh: row.description_zh,
+            last_picked_at: row.last_picked_at,
+            created_at: row.created_at,
+            pick_count: row.pick_count,
+            next_review_at: row.next_review_at,
+            review_interval_days: row.review_interval_days.unwrap_or(1),
+            difficulty_level: row.difficulty_level.unwrap_or(1),
+            context: row.context,
+            audio_timestamp: row.audio_timestamp,
+            ease_factor: row.ease_factor,
+            review_reps: row.review_reps,
+            lapses: row.lapses,
+        })
+    }
+}
+
+/// A `conversations` row as stored
+struct ConversationRow {
+    id: Option<i64>,
+    session_id: String,
+    speaker: String,
+    use_lang: String,
+    content_en: String,
+    content_zh: String,
+    audio_path: Option<String>,
+    created_at: i64,
+    duration_ms: Option<i64>,
+    words_per_minute: Option<f64>,
+    pause_count: Option<i64>,
+    hesitation_count: Option<i64>,
+    turn_id: Option<String>,
+}
+
+impl TryFrom<ConversationRow> for Conversation {
+    type Error = sqlx::Error;
+
+    fn try_from(row: ConversationRow) -> Result<Self, Self::Error> {
+        Ok(Conversation {
+            id: row.id,
+            session_id: row.session_id,
+            turn_id: row.turn_id,
+            speaker: decode(&row.speaker)?,
+            use_lang: decode(&row.use_lang)?,
+            content_en: row.content_en,
+            content_zh: row.content_zh,
+            audio_path: row.audio_path,
+            created_at: row.created_at,
+            duration_ms: row.duration_ms,
+            words_per_minute: row.words_per_minute,
+            pause_count: row.pause_count,
+            hesitation_count: row.hesitation_count,
+        })
+    }
 }
@@ -3,7 +3,8 @@
 //! Manages the lifecycle of dora bridges and routes data between
 //! the dora dataflow and MoFA widgets.
 
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::thread;
 
@@ -11,6 +12,7 @@ use crossbeam_channel::{Receiver, Sender, bounded};
 use dora_bridge::controller::DataflowController;
 use dora_bridge::data::{AudioData, ChatMessage, LogEntry};
 use dora_bridge::dispatcher::DynamicNodeDispatcher;
+use dora_bridge::health::{HealthMonitor, NodeHealth, NodeState, RestartPolicy};
 use parking_lot::RwLock;
 
 // NOTE: ParticipantAudioData removed - LED visualization is calculated in screen.rs
@@ -43,6 +45,8 @@ pub enum DoraCommand {
         dataflow_path: PathBuf,
         env_vars: std::collections::HashMap<String, String>,
     },
+    /// Set how crashed nodes are restarted (applies to the running dataflow too)
+    SetRestartPolicy { policy: RestartPolicy },
     /// Stop the dataflow gracefully (default 15s grace period)
     StopDataflow,
     /// Stop the dataflow with custom grace duration (in seconds)
@@ -53,9 +57,28 @@ pub enum DoraCommand {
     /// Send text input to prompt-input node
     SendText { message: String },
     /// Send audio data to audio-input node (f32 samples, -1.0 to 1.0)
-    SendAudio { data: Vec<f32>, sample_rate: u32 },
+    SendAudio {
+        data: Vec<f32>,
+        sample_rate: u32,
+        /// Utterance start/end in ms since capture start (from VAD)
+        span_ms: Option<(u64, u64)>,
+        /// Turn id minted by the VAD (the bridge mints one when `None`)
+        question_id: Option<String>,
+    },
+    /// Stream speech of the utterance in progress to streaming ASR
+    SendAudioChunk {
+        data: Vec<f32>,
+        sample_rate: u32,
+        question_id: String,
+        /// Last chunk of the utterance
+        last: bool,
+    },
     /// Send a control command
     SendControl { command: String },
+    /// Barge-in: cancel the teacher turn being played
+    Interrupt { question_id: Option<String> },
+    /// Replay a teacher reply from the TTS cache (latest when `None`)
+    Replay { question_id: Option<String> },
     /// Update buffer status
     UpdateBufferStatus { fill_percentage: f64 },
 }
@@ -79,6 +102,8 @@ pub enum DoraEvent {
     ChatReceived { message: ChatMessage },
     /// Log entry received
     LogReceived { entry: LogEntry },
+    /// A dataflow node changed health state (exited, restarting, running again)
+    NodeStatusChanged { node_id: String, state: NodeState },
     /// Error occurred
     Error { message: String },
 }
@@ -151,6 +176,11 @@ impl DoraIntegration {
         })
     }
 
+    /// Set the policy for restarting the dataflow after a node crashes
+    pub fn set_restart_policy(&self, policy: RestartPolicy) -> bool {
+        self.send_command(DoraCommand::SetRestartPolicy { policy })
+    }
+
     /// Stop the current dataflow gracefully (default 15s grace period)
     pub fn stop_dataflow(&self) -> bool {
         self.send_command(DoraCommand::StopDataflow)
@@ -179,7 +209,32 @@ impl DoraIntegration {
 
     /// Send audio data to the dataflow (f32 samples, -1.0 to 1.0)
     pub fn send_audio(&self, data: Vec<f32>, sample_rate: u32) -> bool {
-        self.send_command(DoraCommand::SendAudio { data, sample_rate })
+        self.send_command(DoraCommand::SendAudio {
+            data,
+            sample_rate,
+            span_ms: None,
+            question_id: None,
+        })
+    }
+
+    /// Send a VAD-endpointed utterance to the dataflow
+    pub fn send_utterance(&self, utterance: crate::vad::Utterance) -> bool {
+        self.send_command(DoraCommand::SendAudio {
+            data: utterance.samples,
+            sample_rate: utterance.sample_rate,
+            span_ms: Some((utterance.start_ms, utterance.end_ms)),
+            question_id: Some(utterance.turn_id),
+        })
+    }
+
+    /// Stream a chunk of the utterance in progress to the dataflow
+    pub fn send_speech_chunk(&self, chunk: crate::vad::SpeechChunk) -> bool {
+        self.send_command(DoraCommand::SendAudioChunk {
+            data: chunk.samples,
+            sample_rate: chunk.sample_rate,
+            question_id: chunk.turn_id,
+            last: chunk.last,
+        })
     }
 
     /// Send a control command (e.g., "reset", "cancel")
@@ -189,6 +244,16 @@ impl DoraIntegration {
         })
     }
 
+    /// Interrupt the teacher (barge-in), cancelling TTS/LLM work for the turn
+    pub fn send_interrupt(&self, question_id: Option<String>) -> bool {
+        self.send_command(DoraCommand::Interrupt { question_id })
+    }
+
+    /// Replay the cached audio of a teacher reply (latest when `None`)
+    pub fn send_replay(&self, question_id: Option<String>) -> bool {
+        self.send_command(DoraCommand::Replay { question_id })
+    }
+
     /// Poll for events (non-blocking)
     pub fn poll_events(&self) -> Vec<DoraEvent> {
         let mut events = Vec::new();
@@ -216,7 +281,13 @@ impl DoraIntegration {
         let mut last_status_check = std::time::Instant::now();
         let status_check_interval = std::time::Duration::from_secs(2);
         let mut dataflow_start_time: Option<std::time::Instant> = None;
-        let startup_grace_period = std::time::Duration::from_secs(10); // Don't check status during startup
+        // Node exits are reported by the health monitor as they happen; the status
+        // check only catches the dataflow stopping as a whole
+        let startup_grace_period = std::time::Duration::from_secs(5); // Don't check status during startup
+        let mut restart_policy = RestartPolicy::default();
+        let mut health: Option<HealthMonitor> = None;
+        // Path and env of the running dataflow, to restart it after a node crash
+        let mut last_start: Option<(PathBuf, HashMap<String, String>)> = None;
 
         loop {
             // Check for stop signal
@@ -242,41 +313,34 @@ impl DoraIntegration {
                             }
                         }
 
-                        match DataflowController::new(&dataflow_path) {
-                            Ok(mut controller) => {
-                                // Pass env vars to controller so they're explicitly added to dora
-                                // start command
-                                controller.set_envs(env_vars.clone());
-
-                                let mut disp = DynamicNodeDispatcher::new(controller);
-
-                                match disp.start() {
-                                    Ok(dataflow_id) => {
-                                        log::info!("Dataflow started: {}", dataflow_id);
-                                        state.write().dataflow_running = true;
-                                        state.write().dataflow_id = Some(dataflow_id.clone());
-                                        dataflow_start_time = Some(std::time::Instant::now());
-                                        let _ = event_tx
-                                            .send(DoraEvent::DataflowStarted { dataflow_id });
-                                        dispatcher = Some(disp);
-                                    }
-                                    Err(e) => {
-                                        log::error!("Failed to start dataflow: {}", e);
-                                        let _ = event_tx.send(DoraEvent::Error {
-                                            message: format!("Failed to start dataflow: {}", e),
-                                        });
-                                    }
-                                }
+                        match Self::launch(&dataflow_path, &env_vars) {
+                            Ok((disp, dataflow_id)) => {
+                                state.write().dataflow_running = true;
+                                state.write().dataflow_id = Some(dataflow_id.clone());
+                                dataflow_start_time = Some(std::time::Instant::now());
+                                health = disp
+                                    .controller()
+                                    .read()
+                                    .parsed()
+                                    .map(|parsed| HealthMonitor::new(parsed, restart_policy));
+                                last_start = Some((dataflow_path, env_vars));
+                                let _ = event_tx.send(DoraEvent::DataflowStarted { dataflow_id });
+                                dispatcher = Some(disp);
                             }
-                            Err(e) => {
-                                log::error!("Failed to create controller: {}", e);
-                                let _ = event_tx.send(DoraEvent::Error {
-                                    message: format!("Failed to create controller: {}", e),
-                                });
+                            Err(message) => {
+                                let _ = event_tx.send(DoraEvent::Error { message });
                             }
                         }
                     }
 
+                    DoraCommand::SetRestartPolicy { policy } => {
+                        log::info!("Restart policy: {:?}", policy);
+                        restart_policy = policy;
+                        if let Some(ref mut monitor) = health {
+                            monitor.set_policy(policy);
+                        }
+                    }
+
                     DoraCommand::StopDataflow => {
                         log::info!("Stopping dataflow (graceful)");
                         if let Some(mut disp) = dispatcher.take() {
@@ -287,6 +351,8 @@ impl DoraIntegration {
                         state.write().dataflow_running = false;
                         state.write().dataflow_id = None;
                         dataflow_start_time = None;
+                        health = None;
+                        last_start = None;
                         let _ = event_tx.send(DoraEvent::DataflowStopped);
                     }
 
@@ -301,6 +367,8 @@ impl DoraIntegration {
                         state.write().dataflow_running = false;
                         state.write().dataflow_id = None;
                         dataflow_start_time = None;
+                        health = None;
+                        last_start = None;
                         let _ = event_tx.send(DoraEvent::DataflowStopped);
                     }
 
@@ -314,6 +382,8 @@ impl DoraIntegration {
                         state.write().dataflow_running = false;
                         state.write().dataflow_id = None;
                         dataflow_start_time = None;
+                        health = None;
+                        last_start = None;
                         let _ = event_tx.send(DoraEvent::DataflowStopped);
                     }
 
@@ -332,7 +402,12 @@ impl DoraIntegration {
                         }
                     }
 
-                    DoraCommand::SendAudio { data, sample_rate } => {
+                    DoraCommand::SendAudio {
+                        data,
+                        sample_rate,
+                        span_ms,
+                        question_id,
+                    } => {
                         if let Some(ref disp) = dispatcher {
                             if let Some(bridge) = disp.get_bridge("mofa-mic-input") {
                                 log::debug!(
@@ -345,7 +420,9 @@ impl DoraIntegration {
                                     sample_rate,
                                     channels: 1,
                                     participant_id: None,
-                                    question_id: None,
+                                    question_id,
+                                    span_ms,
+                                    last_chunk: false,
                                 };
                                 if let Err(e) =
                                     bridge.send("audio", dora_bridge::DoraData::Audio(audio_data))
@@ -358,6 +435,32 @@ impl DoraIntegration {
                         }
                     }
 
+                    DoraCommand::SendAudioChunk {
+                        data,
+                        sample_rate,
+                        question_id,
+                        last,
+                    } => {
+                        if let Some(ref disp) = dispatcher {
+                            if let Some(bridge) = disp.get_bridge("mofa-mic-input") {
+                                let audio_data = dora_bridge::data::AudioData {
+                                    samples: data,
+                                    sample_rate,
+                                    channels: 1,
+                                    participant_id: None,
+                                    question_id: Some(question_id),
+                                    span_ms: None,
+                                    last_chunk: last,
+                                };
+                                if let Err(e) = bridge
+                                    .send("audio_stream", dora_bridge::DoraData::Audio(audio_data))
+                                {
+                                    log::error!("Failed to stream audio: {}", e);
+                                }
+                            }
+                        }
+                    }
+
                     DoraCommand::SendControl { command } => {
                         if let Some(ref disp) = dispatcher {
                             if let Some(bridge) = disp.get_bridge("mofa-text-input") {
@@ -374,6 +477,46 @@ impl DoraIntegration {
                         }
                     }
 
+                    DoraCommand::Interrupt { question_id } => {
+                        if let Some(ref disp) = dispatcher {
+                            if let Some(bridge) = disp.get_bridge("mofa-text-input") {
+                                log::info!("Sending interrupt for question_id={:?}", question_id);
+                                let mut ctrl =
+                                    dora_bridge::ControlCommand::new(dora_messages::INTERRUPT);
+                                if let Some(qid) = question_id {
+                                    ctrl = ctrl.with_param("question_id", qid);
+                                }
+                                if let Err(e) =
+                                    bridge.send("control", dora_bridge::DoraData::Control(ctrl))
+                                {
+                                    log::error!("Failed to send interrupt: {}", e);
+                                }
+                            } else {
+                                log::warn!("mofa-text-input bridge not found for interrupt");
+                            }
+                        }
+                    }
+
+                    DoraCommand::Replay { question_id } => {
+                        if let Some(ref disp) = dispatcher {
+                            if let Some(bridge) = disp.get_bridge("mofa-text-input") {
+                                log::info!("Sending replay for question_id={:?}", question_id);
+                                let mut ctrl =
+                                    dora_bridge::ControlCommand::new(dora_messages::REPLAY);
+                                if let Some(qid) = question_id {
+                                    ctrl = ctrl.with_param("question_id", qid);
+                                }
+                                if let Err(e) =
+                                    bridge.send("control", dora_bridge::DoraData::Control(ctrl))
+                                {
+                                    log::error!("Failed to send replay: {}", e);
+                                }
+                            } else {
+                                log::warn!("mofa-text-input bridge not found for replay");
+                            }
+                        }
+                    }
+
                     DoraCommand::UpdateBufferStatus { fill_percentage } => {
                         state.write().buffer_fill = fill_percentage;
                         // Forward to audio player bridge for backpressure signaling to dora
@@ -406,13 +549,20 @@ impl DoraIntegration {
                         Ok(status) => {
                             let was_running = state.read().dataflow_running;
                             let is_running = status.state.is_running();
+                            // A pending restart brings the dataflow back up
+                            let restarting = health.as_ref().is_some_and(|h| h.restart_pending());
 
-                            if was_running && !is_running {
+                            if let dora_bridge::DataflowState::Error { message } = &status.state {
+                                log::warn!("{}", message);
+                            }
+                            if was_running && !is_running && !restarting {
                                 // Dataflow stopped unexpectedly
                                 log::warn!("Dataflow stopped unexpectedly");
                                 state.write().dataflow_running = false;
                                 state.write().dataflow_id = None;
                                 dataflow_start_time = None;
+                                health = None;
+                                last_start = None;
                                 let _ = event_tx.send(DoraEvent::DataflowStopped);
                             }
                         }
@@ -426,6 +576,12 @@ impl DoraIntegration {
             // Poll bridge events
             if let Some(ref disp) = dispatcher {
                 for (node_id, bridge_event) in disp.poll_events() {
+                    if let Some(ref mut monitor) = health {
+                        for node in monitor.observe(&node_id, &bridge_event) {
+                            Self::report_node(&event_tx, node);
+                        }
+                    }
+
                     match bridge_event {
                         dora_bridge::BridgeEvent::Connected => {
                             log::info!("Bridge connected: {}", node_id);
@@ -454,9 +610,21 @@ impl DoraIntegration {
                                 _ => {}
                             }
                         }
-                        dora_bridge::BridgeEvent::DataReceived { input_id, data, .. } => {
+                        dora_bridge::BridgeEvent::DataReceived {
+                            input_id,
+                            data,
+                            metadata,
+                        } => {
                             match data {
                                 dora_bridge::DoraData::Audio(audio) => {
+                                    // Teacher audio carries the stage stamps of its turn
+                                    // (the user's own audio has the turn id but no stamps)
+                                    let stamps = metadata.stage_stamps();
+                                    if let (Some(turn_id), false) =
+                                        (metadata.question_id(), stamps.is_empty())
+                                    {
+                                        crate::metrics::record_stamps(turn_id, stamps);
+                                    }
                                     let _ = event_tx.send(DoraEvent::AudioReceived { data: audio });
                                 }
                                 dora_bridge::DoraData::Chat(chat) => {
@@ -490,6 +658,46 @@ impl DoraIntegration {
                 }
             }
 
+            // Relaunch the whole dataflow once a crashed node's backoff has elapsed
+            let restart_due = health
+                .as_mut()
+                .is_some_and(|h| h.restart_due(std::time::Instant::now()));
+            if let (true, Some((path, env_vars))) = (restart_due, last_start.as_ref()) {
+                let attempt = health.as_ref().map(|h| h.restarts()).unwrap_or_default();
+                log::warn!(
+                    "Restarting dataflow after node failure (attempt {})",
+                    attempt
+                );
+                if let Some(mut disp) = dispatcher.take() {
+                    if let Err(e) = disp.force_stop() {
+                        log::warn!("Failed to stop crashed dataflow: {}", e);
+                    }
+                }
+
+                match Self::launch(path, env_vars) {
+                    Ok((disp, dataflow_id)) => {
+                        state.write().dataflow_id = Some(dataflow_id.clone());
+                        dataflow_start_time = Some(std::time::Instant::now());
+                        if let Some(ref mut monitor) = health {
+                            for node in monitor.restarted() {
+                                Self::report_node(&event_tx, node);
+                            }
+                        }
+                        let _ = event_tx.send(DoraEvent::DataflowStarted { dataflow_id });
+                        dispatcher = Some(disp);
+                    }
+                    Err(message) => {
+                        state.write().dataflow_running = false;
+                        state.write().dataflow_id = None;
+                        dataflow_start_time = None;
+                        health = None;
+                        last_start = None;
+                        let _ = event_tx.send(DoraEvent::Error { message });
+                        let _ = event_tx.send(DoraEvent::DataflowStopped);
+                    }
+                }
+            }
+
             // Small sleep to avoid busy-waiting
             std::thread::sleep(std::time::Duration::from_millis(10));
         }
@@ -501,6 +709,49 @@ impl DoraIntegration {
 
         log::info!("Dora integration worker stopped");
     }
+
+    /// Create a controller and dispatcher for `dataflow_path` and start them
+    fn launch(
+        dataflow_path: &Path,
+        env_vars: &HashMap<String, String>,
+    ) -> Result<(DynamicNodeDispatcher, String), String> {
+        let mut controller = DataflowController::new(dataflow_path).map_err(|e| {
+            log::error!("Failed to create controller: {}", e);
+            format!("Failed to create controller: {}", e)
+        })?;
+        // Pass env vars to controller so they're explicitly added to dora start command
+        controller.set_envs(env_vars.clone());
+
+        let mut disp = DynamicNodeDispatcher::new(controller);
+        match disp.start() {
+            Ok(dataflow_id) => {
+                log::info!("Dataflow started: {}", dataflow_id);
+                Ok((disp, dataflow_id))
+            }
+            Err(e) => {
+                log::error!("Failed to start dataflow: {}", e);
+                Err(format!("Failed to start dataflow: {}", e))
+            }
+        }
+    }
+
+    /// Forward a node health change to the UI
+    fn report_node(event_tx: &Sender<DoraEvent>, node: NodeHealth) {
+        match &node.state {
+            NodeState::Running => log::info!("Node {} running", node.node_id),
+            NodeState::Failed { reason } => log::error!("Node {} failed: {}", node.node_id, reason),
+            NodeState::DataflowRestarting { attempt, delay } => log::warn!(
+                "Node {} exited, dataflow restart {} in {:?}",
+                node.node_id,
+                attempt,
+                delay
+            ),
+        }
+        let _ = event_tx.send(DoraEvent::NodeStatusChanged {
+            node_id: node.node_id,
+            state: node.state,
+        });
+    }
 }
 
 impl Drop for DoraIntegration {
@@ -522,3 +773,18 @@ impl Default for DoraIntegration {
         Self::new()
     }
 }
+
+/// The learning dataflow shipped with the app, looked up from the current
+/// working directory
+pub fn default_dataflow_path() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    [
+        // apps/colang/dataflow/learning.yml (when running from workspace root)
+        cwd.join("apps").join("colang").join("dataflow"),
+        // dataflow/learning.yml (when running from app directory)
+        cwd.join("dataflow"),
+    ]
+    .into_iter()
+    .map(|dir| dir.join("learning.yml"))
+    .find(|path| path.exists())
+}
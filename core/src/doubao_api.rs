@@ -3,6 +3,7 @@
 
 use std::error::Error;
 
+pub use dora_messages::{TextIssue, WordTiming};
 use futures::stream::StreamExt;
 use reqwest::{Client, header};
 use serde::{Deserialize, Serialize};
@@ -34,14 +35,6 @@ pub struct AsrResponse {
     pub words: Vec<WordTiming>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct WordTiming {
-    pub word: String,
-    pub start_time: f64,
-    pub end_time: f64,
-    pub confidence: f32,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TtsRequest {
     pub text: String,
@@ -483,7 +476,7 @@ impl DoubaoClient {
         let messages = vec![
             ChatMessage {
                 role: "system".to_string(),
-                content: "You are an English language expert. Analyze the user's English text and identify issues including: grammar errors, word choice problems, better alternatives, and suggest improvements. Return your analysis in JSON format as an array of issues, each with: {\"type\": \"grammar|word_choice|suggestion\", \"original\": \"text\", \"suggested\": \"better text\", \"description\": \"explanation\", \"severity\": \"low|medium|high\"}".to_string(),
+                content: "You are an English language expert. Analyze the user's English text and identify issues including: grammar errors, word choice problems, better alternatives, and suggest improvements. Return your analysis in JSON format as an array of issues, each with: {\"type\": \"grammar|word_choice|suggestion\", \"original\": \"text\", \"suggested\": \"better text\", \"description_en\": \"explanation in English\", \"description_zh\": \"explanation in Chinese\", \"severity\": \"low|medium|high\"}".to_string(),
             },
             ChatMessage {
                 role: "user".to_string(),
@@ -520,16 +513,6 @@ impl DoubaoClient {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TextIssue {
-    #[serde(rename = "type")]
-    pub issue_type: String,
-    pub original: String,
-    pub suggested: String,
-    pub description: String,
-    pub severity: String,
-}
-
 // Base64 encoding/decoding utilities
 mod base64 {
     use std::io::{Error, ErrorKind};
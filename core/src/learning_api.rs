@@ -1,6 +1,8 @@
 //! Learning content API client
 //!
 //! This module provides a client for fetching learning content from the backend server.
+//! The learner's own data (issue words, sessions, conversations) goes through
+//! [`crate::store::LearningStore`] instead.
 
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -136,127 +138,6 @@ pub struct KeyPhrase {
     pub created_at: String,
 }
 
-// ============================================================================
-// API Response Types (User-specific)
-// ============================================================================
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct IssueWord {
-    pub id: i64,
-    pub user_id: i64,
-    pub word: String,
-    pub issue_type: String,
-    pub description_en: Option<String>,
-    pub description_zh: Option<String>,
-    pub last_picked_at: Option<String>,
-    pub pick_count: i32,
-    pub next_review_at: Option<String>,
-    pub review_interval_days: Option<i32>,
-    pub difficulty_level: Option<i32>,
-    pub context: Option<String>,
-    pub audio_timestamp: Option<i32>,
-    pub created_at: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct LearningSession {
-    pub id: i64,
-    pub session_id: String,
-    pub user_id: i64,
-    pub session_type: Option<String>,
-    pub sceneid: Option<i64>,
-    pub scene_dialogue_id: Option<i64>,
-    pub classic_clip_id: Option<i64>,
-    pub started_at: String,
-    pub ended_at: Option<String>,
-    pub duration_seconds: Option<i32>,
-    pub total_words_spoken: Option<i32>,
-    pub average_wpm: Option<f32>,
-    pub error_count: Option<i32>,
-    pub correction_count: Option<i32>,
-    pub notes: Option<String>,
-    pub ai_summary_en: Option<String>,
-    pub ai_summary_zh: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserVocabulary {
-    pub id: i64,
-    pub user_id: i64,
-    pub word: String,
-    pub word_zh: Option<String>,
-    pub mastery_level: Option<i32>,
-    pub first_seen_at: String,
-    pub last_practiced_at: Option<String>,
-    pub practice_count: Option<i32>,
-    pub correct_count: Option<i32>,
-    pub next_review_at: Option<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DailyStat {
-    pub id: i64,
-    pub user_id: i64,
-    pub stat_date: String,
-    pub minutes_studied: Option<i32>,
-    pub words_practiced: Option<i32>,
-    pub sessions_completed: Option<i32>,
-    pub errors_corrected: Option<i32>,
-    pub new_words_learned: Option<i32>,
-    pub review_words_count: Option<i32>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct UserAchievement {
-    pub id: i64,
-    pub user_id: i64,
-    pub achievement_type: String,
-    pub achievement_name: String,
-    pub description_en: Option<String>,
-    pub description_zh: Option<String>,
-    pub metadata: Option<Value>,
-    pub earned_at: String,
-}
-
-// ============================================================================
-// Request Types
-// ============================================================================
-
-#[derive(Debug, Serialize)]
-pub struct CreateIssueWordRequest {
-    pub word: String,
-    pub issue_type: String,
-    pub description_en: Option<String>,
-    pub description_zh: Option<String>,
-    pub context: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct CreateSessionRequest {
-    pub session_id: String,
-    pub session_type: Option<String>,
-    pub sceneid: Option<i64>,
-    pub scene_dialogue_id: Option<i64>,
-    pub classic_clip_id: Option<i64>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct CreateVocabularyRequest {
-    pub word: String,
-    pub word_zh: Option<String>,
-}
-
-#[derive(Debug, Serialize)]
-pub struct UpsertDailyStatRequest {
-    pub stat_date: String,
-    pub minutes_studied: Option<i32>,
-    pub words_practiced: Option<i32>,
-    pub sessions_completed: Option<i32>,
-    pub errors_corrected: Option<i32>,
-    pub new_words_learned: Option<i32>,
-    pub review_words_count: Option<i32>,
-}
-
 // ============================================================================
 // Client Implementation
 // ============================================================================
@@ -274,10 +155,6 @@ impl LearningApiClient {
         self.auth_token = token;
     }
 
-    fn auth_header(&self) -> Option<String> {
-        self.auth_token.as_ref().map(|t| format!("Bearer {}", t))
-    }
-
     // ========================================================================
     // Shared Content APIs (no auth required)
     // ========================================================================
@@ -562,280 +439,6 @@ impl LearningApiClient {
             .await
             .map_err(|e| format!("Parse error: {}", e))
     }
-
-    // ========================================================================
-    // User-specific APIs (auth required)
-    // ========================================================================
-
-    pub async fn list_issue_words(
-        &self,
-        due_only: bool,
-        limit: Option<i64>,
-    ) -> Result<Vec<IssueWord>, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-
-        let mut url = format!("{}/learning/issue-words", self.base_url);
-        let mut params = vec![];
-
-        if due_only {
-            params.push("due_only=true".to_string());
-        }
-        if let Some(lim) = limit {
-            params.push(format!("limit={}", lim));
-        }
-
-        if !params.is_empty() {
-            url = format!("{}?{}", url, params.join("&"));
-        }
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn create_issue_word(&self, req: CreateIssueWordRequest) -> Result<IssueWord, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-        let url = format!("{}/learning/issue-words", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn list_sessions(
-        &self,
-        session_type: Option<&str>,
-        limit: Option<i64>,
-    ) -> Result<Vec<LearningSession>, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-
-        let mut url = format!("{}/learning/sessions", self.base_url);
-        let mut params = vec![];
-
-        if let Some(st) = session_type {
-            params.push(format!("type={}", st));
-        }
-        if let Some(lim) = limit {
-            params.push(format!("limit={}", lim));
-        }
-
-        if !params.is_empty() {
-            url = format!("{}?{}", url, params.join("&"));
-        }
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn create_session(&self, req: CreateSessionRequest) -> Result<LearningSession, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-        let url = format!("{}/learning/sessions", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn list_vocabulary(
-        &self,
-        due_only: bool,
-        limit: Option<i64>,
-    ) -> Result<Vec<UserVocabulary>, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-
-        let mut url = format!("{}/learning/vocabulary", self.base_url);
-        let mut params = vec![];
-
-        if due_only {
-            params.push("due_only=true".to_string());
-        }
-        if let Some(lim) = limit {
-            params.push(format!("limit={}", lim));
-        }
-
-        if !params.is_empty() {
-            url = format!("{}?{}", url, params.join("&"));
-        }
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn create_vocabulary(&self, req: CreateVocabularyRequest) -> Result<UserVocabulary, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-        let url = format!("{}/learning/vocabulary", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn list_daily_stats(&self, limit: Option<i64>) -> Result<Vec<DailyStat>, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-
-        let mut url = format!("{}/learning/daily-stats", self.base_url);
-        if let Some(lim) = limit {
-            url = format!("{}?limit={}", url, lim);
-        }
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn upsert_daily_stat(&self, req: UpsertDailyStatRequest) -> Result<DailyStat, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-        let url = format!("{}/learning/daily-stats", self.base_url);
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .header("Content-Type", "application/json")
-            .json(&req)
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
-
-    pub async fn list_achievements(&self) -> Result<Vec<UserAchievement>, String> {
-        let auth = self.auth_header().ok_or("Not authenticated")?;
-        let url = format!("{}/learning/achievements", self.base_url);
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| format!("Request failed: {}", e))?;
-
-        if !response.status().is_success() {
-            return Err(format!("API error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .map_err(|e| format!("Parse error: {}", e))
-    }
 }
 
 // ============================================================================
@@ -1,12 +1,20 @@
+pub mod achievements;
 pub mod asset_api;
 pub mod audio;
 pub mod audio_player;
+pub mod barge_in;
+pub mod daily_stats;
 pub mod db;
 pub mod dict_api;
 pub mod dora_integration;
 pub mod doubao_api;
 pub mod learn_api;
 pub mod log_bridge;
+pub mod metrics;
 pub mod models;
+pub mod notebook;
 pub mod routes;
+pub mod scheduler;
 pub mod screens;
+pub mod store;
+pub mod vad;
@@ -0,0 +1,468 @@
+//! Turn Metrics - end-to-end latency of voice turns
+//!
+//! Dataflow nodes stamp stage times into dora metadata (see
+//! `dora_messages::timing`); the stamps reach the app with the teacher audio,
+//! keyed by the turn's `question_id`. This module joins them with the playback
+//! start, computes per-stage latencies for each turn, keeps rolling p50/p95
+//! over recent turns and appends every finished turn to a JSON-lines file.
+//!
+//! The dora worker records stamps, the chat screen records playback start and
+//! the debug panel polls [`take_report`].
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use dora_messages::timing::{self, Stage};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+/// Finished turns kept for the rolling percentiles
+const WINDOW: usize = 50;
+
+/// Open turns without new stamps for this long are finished as they are
+const IDLE_TIMEOUT: Duration = Duration::from_secs(15);
+
+static METRICS: Lazy<Mutex<TurnMetrics>> =
+    Lazy::new(|| Mutex::new(TurnMetrics::new(Some(metrics_path()))));
+
+/// Metrics file: `~/.colang/dashboard/turn_metrics.jsonl`
+pub fn metrics_path() -> PathBuf {
+    crate::models::Preferences::get_preferences_path().with_file_name("turn_metrics.jsonl")
+}
+
+/// Record the stage stamps (unix ms) of one message of a turn
+pub fn record_stamps(turn_id: &str, stamps: impl IntoIterator<Item = (Stage, i64)>) {
+    METRICS.lock().record(turn_id, stamps);
+}
+
+/// Record that playback of a turn's reply started now
+pub fn record_playback_start(turn_id: &str) {
+    METRICS
+        .lock()
+        .record(turn_id, [(Stage::PlaybackStart, timing::now_ms())]);
+}
+
+/// Latest report, if any turn finished since the last call
+pub fn take_report() -> Option<LatencyReport> {
+    let mut metrics = METRICS.lock();
+    metrics.expire(Instant::now());
+    metrics.take_report()
+}
+
+/// A latency measured between two stages of a turn
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Span {
+    Asr,
+    LlmFirstToken,
+    LlmComplete,
+    TtsFirstChunk,
+    TtsComplete,
+    Playback,
+    EndToEnd,
+}
+
+impl Span {
+    pub const ALL: [Span; 7] = [
+        Span::Asr,
+        Span::LlmFirstToken,
+        Span::LlmComplete,
+        Span::TtsFirstChunk,
+        Span::TtsComplete,
+        Span::Playback,
+        Span::EndToEnd,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Span::Asr => "ASR",
+            Span::LlmFirstToken => "LLM first token",
+            Span::LlmComplete => "LLM complete",
+            Span::TtsFirstChunk => "TTS first chunk",
+            Span::TtsComplete => "TTS complete",
+            Span::Playback => "Playback start",
+            Span::EndToEnd => "End to end",
+        }
+    }
+
+    /// Key in the metrics file
+    pub fn key(self) -> &'static str {
+        match self {
+            Span::Asr => "asr",
+            Span::LlmFirstToken => "llm_first_token",
+            Span::LlmComplete => "llm_complete",
+            Span::TtsFirstChunk => "tts_first_chunk",
+            Span::TtsComplete => "tts_complete",
+            Span::Playback => "playback",
+            Span::EndToEnd => "end_to_end",
+        }
+    }
+
+    /// Stages the span is measured between (from, to)
+    fn stages(self) -> (Stage, Stage) {
+        match self {
+            Span::Asr => (Stage::AudioReceived, Stage::AsrDone),
+            Span::LlmFirstToken => (Stage::AsrDone, Stage::LlmFirstToken),
+            Span::LlmComplete => (Stage::AsrDone, Stage::LlmComplete),
+            Span::TtsFirstChunk => (Stage::LlmFirstToken, Stage::TtsFirstChunk),
+            Span::TtsComplete => (Stage::LlmComplete, Stage::TtsComplete),
+            Span::Playback => (Stage::TtsFirstChunk, Stage::PlaybackStart),
+            Span::EndToEnd => (Stage::AudioReceived, Stage::PlaybackStart),
+        }
+    }
+}
+
+/// Latencies of one finished turn
+#[derive(Debug, Clone)]
+pub struct TurnLatency {
+    pub turn_id: String,
+    /// Stage stamps (unix ms)
+    pub stamps: BTreeMap<Stage, i64>,
+    /// Span latencies (ms); spans missing a stamp are left out
+    pub spans: BTreeMap<Span, u64>,
+}
+
+impl TurnLatency {
+    fn new(turn_id: String, stamps: BTreeMap<Stage, i64>) -> Self {
+        let spans = Span::ALL
+            .into_iter()
+            .filter_map(|span| {
+                let (from, to) = span.stages();
+                let elapsed = stamps.get(&to)? - stamps.get(&from)?;
+                Some((span, elapsed.max(0) as u64))
+            })
+            .collect();
+        Self {
+            turn_id,
+            stamps,
+            spans,
+        }
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let stamps: serde_json::Map<_, _> = self
+            .stamps
+            .iter()
+            .map(|(stage, at)| (stage.key().to_string(), (*at).into()))
+            .collect();
+        let spans: serde_json::Map<_, _> = self
+            .spans
+            .iter()
+            .map(|(span, ms)| (span.key().to_string(), (*ms).into()))
+            .collect();
+        serde_json::json!({
+            "turn_id": self.turn_id,
+            "stamps": stamps,
+            "latency_ms": spans,
+        })
+    }
+
+    fn summary(&self) -> String {
+        self.spans
+            .iter()
+            .map(|(span, ms)| format!("{} {}ms", span.key(), ms))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Latency of one span: last turn and rolling percentiles
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanStats {
+    pub span: Span,
+    pub last_ms: Option<u64>,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+}
+
+/// Per-turn and rolling latencies for display
+#[derive(Debug, Clone)]
+pub struct LatencyReport {
+    pub last_turn: Option<TurnLatency>,
+    /// Turns in the rolling window
+    pub turns: usize,
+    pub spans: Vec<SpanStats>,
+}
+
+impl LatencyReport {
+    /// Markdown table for the debug panel
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!(
+            "**Turn latency** (last / p50 / p95 over {} turns)\n\n",
+            self.turns
+        );
+        out.push_str("| Stage | Last | p50 | p95 |\n|---|---|---|---|\n");
+        for stats in &self.spans {
+            let last = stats
+                .last_ms
+                .map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(|| "-".to_string());
+            out.push_str(&format!(
+                "| {} | {} | {}ms | {}ms |\n",
+                stats.span.label(),
+                last,
+                stats.p50_ms,
+                stats.p95_ms
+            ));
+        }
+        out
+    }
+}
+
+struct OpenTurn {
+    stamps: BTreeMap<Stage, i64>,
+    updated: Instant,
+}
+
+/// Joins stage stamps into turns and aggregates finished turns
+pub struct TurnMetrics {
+    open: HashMap<String, OpenTurn>,
+    finished: VecDeque<TurnLatency>,
+    /// JSON-lines file finished turns are appended to
+    path: Option<PathBuf>,
+    changed: bool,
+}
+
+impl TurnMetrics {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            open: HashMap::new(),
+            finished: VecDeque::new(),
+            path,
+            changed: false,
+        }
+    }
+
+    /// Record the stamps of one message; finishes the turn once its reply is
+    /// playing and fully synthesized
+    pub fn record(&mut self, turn_id: &str, stamps: impl IntoIterator<Item = (Stage, i64)>) {
+        // Late audio of a turn that already finished
+        if self.finished.iter().any(|turn| turn.turn_id == turn_id) {
+            return;
+        }
+
+        let turn = self
+            .open
+            .entry(turn_id.to_string())
+            .or_insert_with(|| OpenTurn {
+                stamps: BTreeMap::new(),
+                updated: Instant::now(),
+            });
+        turn.updated = Instant::now();
+        let mut playback_start = None;
+        for (stage, at_ms) in stamps {
+            if stage == Stage::PlaybackStart {
+                playback_start = Some(at_ms);
+            }
+            turn.stamps
+                .entry(stage)
+                .and_modify(|existing| {
+                    *existing = if stage.keeps_latest() {
+                        (*existing).max(at_ms)
+                    } else {
+                        (*existing).min(at_ms)
+                    }
+                })
+                .or_insert(at_ms);
+        }
+
+        let complete = [Stage::PlaybackStart, Stage::LlmComplete, Stage::TtsComplete]
+            .iter()
+            .all(|stage| turn.stamps.contains_key(stage));
+        if complete {
+            self.finish(turn_id);
+        }
+
+        // A newer reply started playing: earlier turns won't get more audio
+        if let Some(at_ms) = playback_start {
+            let earlier: Vec<String> = self
+                .open
+                .iter()
+                .filter(|(id, turn)| {
+                    id.as_str() != turn_id
+                        && turn
+                            .stamps
+                            .get(&Stage::PlaybackStart)
+                            .is_some_and(|started| *started <= at_ms)
+                })
+                .map(|(id, _)| id.clone())
+                .collect();
+            for id in earlier {
+                self.finish(&id);
+            }
+        }
+    }
+
+    /// Finish turns that stopped receiving stamps (interrupted, empty last segment)
+    pub fn expire(&mut self, now: Instant) {
+        let idle: Vec<String> = self
+            .open
+            .iter()
+            .filter(|(_, turn)| now.duration_since(turn.updated) >= IDLE_TIMEOUT)
+            .map(|(id, _)| id.clone())
+            .collect();
+        for id in idle {
+            self.finish(&id);
+        }
+    }
+
+    /// Report for display, if a turn finished since the last call
+    pub fn take_report(&mut self) -> Option<LatencyReport> {
+        if !std::mem::take(&mut self.changed) {
+            return None;
+        }
+        Some(self.report())
+    }
+
+    pub fn report(&self) -> LatencyReport {
+        let last_turn = self.finished.back().cloned();
+        let spans = Span::ALL
+            .into_iter()
+            .filter_map(|span| {
+                let mut values: Vec<u64> = self
+                    .finished
+                    .iter()
+                    .filter_map(|turn| turn.spans.get(&span).copied())
+                    .collect();
+                if values.is_empty() {
+                    return None;
+                }
+                values.sort_unstable();
+                Some(SpanStats {
+                    span,
+                    last_ms: last_turn
+                        .as_ref()
+                        .and_then(|turn| turn.spans.get(&span).copied()),
+                    p50_ms: percentile(&values, 50),
+                    p95_ms: percentile(&values, 95),
+                })
+            })
+            .collect();
+        LatencyReport {
+            last_turn,
+            turns: self.finished.len(),
+            spans,
+        }
+    }
+
+    fn finish(&mut self, turn_id: &str) {
+        let Some(turn) = self.open.remove(turn_id) else {
+            return;
+        };
+        let latency = TurnLatency::new(turn_id.to_string(), turn.stamps);
+        // Replays and text-only stamps have nothing to measure
+        if latency.spans.is_empty() {
+            return;
+        }
+
+        ::log::info!("Turn {} latency: {}", latency.turn_id, latency.summary());
+        let written = self
+            .path
+            .as_deref()
+            .map(|path| append_line(path, &latency.to_json()));
+        if let Some(Err(e)) = written {
+            ::log::warn!("Failed to write turn metrics to {:?}: {}", self.path, e);
+        }
+
+        self.finished.push_back(latency);
+        if self.finished.len() > WINDOW {
+            self.finished.pop_front();
+        }
+        self.changed = true;
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], p: usize) -> u64 {
+    let rank = (p * sorted.len()).div_ceil(100).max(1);
+    sorted[rank.min(sorted.len()) - 1]
+}
+
+fn append_line(path: &Path, json: &serde_json::Value) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A voice turn whose reply has two segments
+    fn voice_turn(metrics: &mut TurnMetrics, turn_id: &str, start: i64, asr_ms: i64) {
+        let asr_done = start + asr_ms;
+        let first_token = asr_done + 400;
+        let segment = |llm_complete: Option<i64>, synthesized: i64| {
+            let mut stamps = vec![
+                (Stage::AudioReceived, start),
+                (Stage::AsrDone, asr_done),
+                (Stage::LlmFirstToken, first_token),
+                (Stage::TtsFirstChunk, synthesized),
+                (Stage::TtsComplete, synthesized),
+            ];
+            stamps.extend(llm_complete.map(|at| (Stage::LlmComplete, at)));
+            stamps
+        };
+        metrics.record(turn_id, segment(None, asr_done + 700));
+        metrics.record(turn_id, [(Stage::PlaybackStart, asr_done + 750)]);
+        // First-of stamps keep the earliest value, completion stamps the latest
+        metrics.record(turn_id, segment(Some(asr_done + 1200), asr_done + 1500));
+    }
+
+    #[test]
+    fn test_turn_latency() {
+        let mut metrics = TurnMetrics::new(None);
+        voice_turn(&mut metrics, "q1", 1_000, 300);
+        assert!(metrics.open.is_empty());
+        // Late audio of a finished turn doesn't reopen it
+        metrics.record("q1", [(Stage::TtsComplete, 9_999)]);
+        assert!(metrics.open.is_empty());
+
+        let report = metrics.take_report().unwrap();
+        assert!(metrics.take_report().is_none());
+        let turn = report.last_turn.unwrap();
+        assert_eq!(turn.spans[&Span::Asr], 300);
+        assert_eq!(turn.spans[&Span::LlmFirstToken], 400);
+        assert_eq!(turn.spans[&Span::TtsFirstChunk], 300);
+        assert_eq!(turn.spans[&Span::TtsComplete], 300);
+        assert_eq!(turn.spans[&Span::Playback], 50);
+        assert_eq!(turn.spans[&Span::EndToEnd], 1050);
+    }
+
+    #[test]
+    fn test_rolling_percentiles() {
+        let mut metrics = TurnMetrics::new(None);
+        for (i, asr_ms) in (1..=20).map(|n| n * 100).enumerate() {
+            voice_turn(&mut metrics, &format!("q{}", i), i as i64 * 10_000, asr_ms);
+        }
+        // An interrupted turn never plays; it expires without a complete set
+        metrics.record(
+            "q-cut",
+            [(Stage::AudioReceived, 500_000), (Stage::AsrDone, 500_200)],
+        );
+        metrics.expire(Instant::now() + IDLE_TIMEOUT);
+
+        let report = metrics.report();
+        assert_eq!(report.turns, 21);
+        let asr = report.spans.iter().find(|s| s.span == Span::Asr).unwrap();
+        assert_eq!(asr.last_ms, Some(200));
+        assert_eq!(asr.p50_ms, 1000);
+        assert_eq!(asr.p95_ms, 1900);
+        assert!(
+            report
+                .to_markdown()
+                .contains("| ASR | 200ms | 1000ms | 1900ms |")
+        );
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[5], 95), 5);
+        assert_eq!(percentile(&[1, 2, 3, 4], 50), 2);
+        assert_eq!(percentile(&[1, 2, 3, 4], 95), 4);
+    }
+}
@@ -2,12 +2,16 @@
 
 mod preferences;
 mod providers;
+mod vault;
 
 pub use preferences::*;
 pub use providers::*;
+pub use vault::{KeySource, PASSPHRASE_ENV, Vault, VaultError};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
+use crate::scheduler::ReviewState;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct IssueWord {
     pub id: Option<i64>,
@@ -23,6 +27,22 @@ pub struct IssueWord {
     pub difficulty_level: i64,
     pub context: Option<String>,
     pub audio_timestamp: Option<i64>,
+    /// Scheduler state, see [`crate::scheduler`]
+    pub ease_factor: f64,
+    pub review_reps: i64,
+    pub lapses: i64,
+}
+
+impl IssueWord {
+    /// Scheduler state of the word
+    pub fn review_state(&self) -> ReviewState {
+        ReviewState {
+            ease: self.ease_factor,
+            interval_days: self.review_interval_days,
+            reps: self.review_reps,
+            lapses: self.lapses,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -63,6 +83,8 @@ impl std::str::FromStr for IssueType {
 pub struct Conversation {
     pub id: Option<i64>,
     pub session_id: String,
+    /// Voice pipeline turn (dora `question_id`) the message belongs to
+    pub turn_id: Option<String>,
     pub speaker: Speaker,
     pub use_lang: UseLang,
     pub content_en: String,
@@ -222,15 +244,63 @@ impl std::str::FromStr for Severity {
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct LearningSession {
-    pub id: Option<i64>,
     pub session_id: String,
-    pub topic: String,
+    pub session_type: Option<SessionType>,
+    pub sceneid: Option<i64>,
+    pub scene_dialogue_id: Option<i64>,
+    pub classic_clip_id: Option<i64>,
     pub target_words: Option<String>, // JSON array
     pub started_at: i64,
     pub ended_at: Option<i64>,
-    pub total_exchanges: i64,
-    pub user_satisfaction: Option<i64>,
+    pub duration_seconds: Option<i64>,
+    pub total_words_spoken: i64,
+    pub average_wpm: Option<f64>,
+    pub error_count: i64,
+    pub correction_count: i64,
     pub notes: Option<String>,
+    pub ai_summary_en: Option<String>,
+    pub ai_summary_zh: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionType {
+    FreeTalk,
+    Scenario,
+    ClassicDialogue,
+    Reading,
+    Review,
+    Assistant,
+}
+
+impl std::fmt::Display for SessionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SessionType::FreeTalk => "free_talk",
+            SessionType::Scenario => "scenario",
+            SessionType::ClassicDialogue => "classic_dialogue",
+            SessionType::Reading => "reading",
+            SessionType::Review => "review",
+            SessionType::Assistant => "assistant",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for SessionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "free_talk" => Ok(SessionType::FreeTalk),
+            "scenario" => Ok(SessionType::Scenario),
+            "classic_dialogue" => Ok(SessionType::ClassicDialogue),
+            "reading" => Ok(SessionType::Reading),
+            "review" => Ok(SessionType::Review),
+            "assistant" => Ok(SessionType::Assistant),
+            _ => Err(format!("Invalid session type: {}", s)),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -242,3 +312,99 @@ pub struct WordPracticeLog {
     pub success_level: Option<i64>,
     pub notes: Option<String>,
 }
+
+/// A correction that keeps coming up in conversation annotations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MistakeSummary {
+    pub original_text: String,
+    pub suggested_text: Option<String>,
+    pub annotation_type: AnnotationType,
+    pub description_en: Option<String>,
+    pub description_zh: Option<String>,
+    /// Times the correction was made
+    pub occurrences: i64,
+    pub last_seen_at: i64,
+}
+
+/// Totals shown on the review screens
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReviewStats {
+    pub total_words: i64,
+    pub due_words: i64,
+    pub mastered_words: i64,
+    /// Distinct corrections in the annotation history
+    pub mistakes: i64,
+    pub reviews: i64,
+    /// Reviews graded as remembered (`success_level` >= 3)
+    pub passed_reviews: i64,
+    /// Days with at least one review
+    pub study_days: i64,
+    /// Average mastery (0.0–1.0) over all words
+    pub average_mastery: f64,
+    /// Reviews per day of the current week, Monday first
+    pub week_reviews: [i64; 7],
+}
+
+/// One day of learning activity, as stored in `daily_stats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyStat {
+    /// Local date, `YYYY-MM-DD`
+    pub stat_date: String,
+    /// Time spent in sessions started that day
+    pub minutes_studied: i64,
+    /// Distinct words reviewed
+    pub words_practiced: i64,
+    /// Sessions started that day that have ended
+    pub sessions_completed: i64,
+    /// Corrections annotated on the learner's turns
+    pub errors_corrected: i64,
+    /// Words added to the review queue
+    pub new_words_learned: i64,
+    /// Review grades recorded
+    pub review_words_count: i64,
+}
+
+impl DailyStat {
+    /// Whether anything was studied that day
+    pub fn is_active(&self) -> bool {
+        self.minutes_studied > 0
+            || self.words_practiced > 0
+            || self.sessions_completed > 0
+            || self.errors_corrected > 0
+            || self.new_words_learned > 0
+            || self.review_words_count > 0
+    }
+}
+
+/// Consecutive days with learning activity
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Streaks {
+    /// Run ending today, or yesterday if nothing was studied yet today
+    pub current: i64,
+    pub longest: i64,
+}
+
+/// An achievement the learner has earned, as stored in `user_achievements`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Achievement {
+    pub achievement_type: String,
+    pub achievement_name: String,
+    pub description_en: Option<String>,
+    pub description_zh: Option<String>,
+    pub earned_at: i64,
+    pub metadata: Option<String>, // JSON
+}
+
+/// A word in the learner's vocabulary, as stored in `user_vocabulary`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VocabularyWord {
+    pub word: String,
+    pub word_zh: Option<String>,
+    /// 1 (new) to 5 (mastered)
+    pub mastery_level: i64,
+    pub first_seen_at: i64,
+    pub last_practiced_at: Option<i64>,
+    pub practice_count: i64,
+    pub correct_count: i64,
+    pub next_review_at: Option<i64>,
+}
@@ -1,11 +1,22 @@
 //! User preferences storage
+//!
+//! Secrets (provider API keys, access tokens, auth token) are never written to
+//! `preferences.json`; they live in the encrypted vault (see [`super::vault`]).
+//! Plaintext values found in older preference files are moved into the vault
+//! on first load.
 
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use dora_bridge::RestartPolicy;
 use serde::{Deserialize, Serialize};
 
 use super::providers::{Provider, ProviderId, get_supported_providers};
+use super::vault::{KeySource, Vault, VaultError};
+
+const AUTH_TOKEN_SECRET: &str = "auth_token";
 
 /// User preferences for the dashboard
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -20,8 +31,45 @@ pub struct Preferences {
     /// Custom data storage location
     #[serde(default)]
     pub data_location: Option<String>,
+    /// Automatic restart of the dataflow after a node crashes
     #[serde(default)]
-    pub auth_token: Option<String>,
+    pub restart: RestartPreferences,
+    /// Stored in the vault, see [`Preferences::auth_token`]
+    #[serde(default, skip_serializing)]
+    auth_token: Option<String>,
+}
+
+/// Stored form of the dataflow [`RestartPolicy`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RestartPreferences {
+    /// Restarts allowed per session (0 disables restarting)
+    pub max_restarts: u32,
+    /// Delay before the first restart, doubled for each further one
+    pub initial_backoff_secs: u64,
+    /// Upper bound for the restart delay
+    pub max_backoff_secs: u64,
+}
+
+impl Default for RestartPreferences {
+    fn default() -> Self {
+        let policy = RestartPolicy::default();
+        Self {
+            max_restarts: policy.max_restarts,
+            initial_backoff_secs: policy.initial_backoff.as_secs(),
+            max_backoff_secs: policy.max_backoff.as_secs(),
+        }
+    }
+}
+
+impl RestartPreferences {
+    pub fn policy(&self) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts: self.max_restarts,
+            initial_backoff: Duration::from_secs(self.initial_backoff_secs),
+            max_backoff: Duration::from_secs(self.max_backoff_secs),
+        }
+    }
 }
 
 impl Preferences {
@@ -33,17 +81,31 @@ impl Preferences {
             .join("preferences.json")
     }
 
+    /// Get the encrypted secrets vault path
+    pub fn get_vault_path() -> PathBuf {
+        Self::get_preferences_path().with_file_name("secrets.vault")
+    }
+
+    fn open_vault() -> Result<Vault, VaultError> {
+        let path = Self::get_vault_path();
+        let key_source = KeySource::from_env(path.parent().unwrap_or(&path));
+        Vault::open(path, key_source)
+    }
+
     /// Load preferences from disk, or create defaults if not found
     pub fn load() -> Self {
-        let path = Self::get_preferences_path();
+        Self::load_from(&Self::get_preferences_path(), &Self::open_vault)
+    }
 
+    fn load_from(path: &Path, open_vault: &dyn Fn() -> Result<Vault, VaultError>) -> Self {
         if path.exists() {
-            match fs::read_to_string(&path) {
+            match fs::read_to_string(path) {
                 Ok(content) => {
                     match serde_json::from_str::<Preferences>(&content) {
                         Ok(mut prefs) => {
                             // Merge with supported providers to ensure all are present
                             prefs.merge_with_supported_providers();
+                            prefs.load_secrets(path, open_vault);
                             return prefs;
                         }
                         Err(e) => {
@@ -60,24 +122,100 @@ impl Preferences {
         // Return defaults with supported providers
         let mut prefs = Self::default();
         prefs.providers = get_supported_providers();
+        prefs.load_secrets(path, open_vault);
         prefs
     }
 
-    /// Save preferences to disk
+    /// Save preferences to disk, secrets to the vault
     pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let path = Self::get_preferences_path();
+        self.save_to(&Self::get_preferences_path(), Self::open_vault())
+    }
+
+    /// Store the secrets in `vault`, then write `path` without them. A vault
+    /// that can't be unlocked is never overwritten, and `path` keeps any
+    /// plaintext secrets until they are safely in the vault.
+    fn save_to(
+        &self,
+        path: &Path,
+        vault: Result<Vault, VaultError>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut vault = vault?;
+        vault.replace_all(self.collect_secrets());
+        vault.save()?;
 
         // Ensure directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
+        // Secret fields are skipped by serde
         let content = serde_json::to_string_pretty(self)?;
-        fs::write(&path, content)?;
+        fs::write(path, content)?;
 
         Ok(())
     }
 
+    /// Fill secret fields from the vault, migrating plaintext values
+    fn load_secrets(&mut self, path: &Path, open_vault: &dyn Fn() -> Result<Vault, VaultError>) {
+        let vault = match open_vault() {
+            Ok(vault) => vault,
+            Err(e) => {
+                log::warn!("Failed to open secrets vault: {}", e);
+                return;
+            }
+        };
+
+        // Plaintext secrets read from an older preferences.json
+        let plaintext = self.collect_secrets();
+
+        for provider in &mut self.providers {
+            if let Some(key) = vault.get(&api_key_secret(&provider.id)) {
+                provider.api_key = Some(key.to_string());
+            }
+            if let Some(volcano) = &mut provider.volcano {
+                if let Some(token) = vault.get(&access_token_secret(&provider.id)) {
+                    volcano.access_token = token.to_string();
+                }
+            }
+        }
+        if let Some(token) = vault.get(AUTH_TOKEN_SECRET) {
+            self.auth_token = Some(token.to_string());
+        }
+
+        let needs_migration = plaintext
+            .iter()
+            .any(|(name, value)| vault.get(name) != Some(value.as_str()));
+        if needs_migration {
+            // Rewrites preferences.json without secrets and stores them in the vault
+            match self.save_to(path, open_vault()) {
+                Ok(()) => log::info!("Moved plaintext secrets into the vault"),
+                Err(e) => log::error!("Failed to migrate secrets to the vault: {}", e),
+            }
+        }
+    }
+
+    /// All non-empty secrets, keyed by vault name
+    fn collect_secrets(&self) -> BTreeMap<String, String> {
+        let mut secrets = BTreeMap::new();
+        for provider in &self.providers {
+            if let Some(key) = provider.api_key.as_ref().filter(|k| !k.is_empty()) {
+                secrets.insert(api_key_secret(&provider.id), key.clone());
+            }
+            if let Some(volcano) = &provider.volcano {
+                if !volcano.access_token.is_empty() {
+                    secrets.insert(
+                        access_token_secret(&provider.id),
+                        volcano.access_token.clone(),
+                    );
+                }
+            }
+        }
+        if let Some(token) = self.auth_token.as_ref().filter(|t| !t.is_empty()) {
+            secrets.insert(AUTH_TOKEN_SECRET.to_string(), token.clone());
+        }
+        secrets
+    }
+
     /// Merge loaded preferences with supported providers
     fn merge_with_supported_providers(&mut self) {
         let supported = get_supported_providers();
@@ -127,9 +265,148 @@ impl Preferences {
         self.providers.iter().filter(|p| p.enabled).collect()
     }
 
+    /// API key of a provider, if set
+    pub fn api_key(&self, provider_id: &str) -> Option<&str> {
+        self.get_provider(provider_id)?
+            .api_key
+            .as_deref()
+            .filter(|key| !key.is_empty())
+    }
+
+    /// Set a provider's API key (stored in the vault on [`Preferences::save`])
+    pub fn set_api_key(&mut self, provider_id: &str, api_key: Option<String>) {
+        if let Some(provider) = self.get_provider_mut(provider_id) {
+            provider.api_key = api_key.filter(|key| !key.is_empty());
+        }
+    }
+
+    pub fn auth_token(&self) -> Option<&str> {
+        self.auth_token.as_deref()
+    }
+
+    /// Set the login token (stored in the vault on [`Preferences::save`])
+    pub fn set_auth_token(&mut self, token: Option<String>) {
+        self.auth_token = token;
+    }
+
     pub fn authorization_header_value(&self) -> Option<String> {
         self.auth_token
             .as_ref()
             .map(|token| format!("Bearer {}", token))
     }
 }
+
+fn api_key_secret(provider_id: &str) -> String {
+    format!("provider.{}.api_key", provider_id)
+}
+
+fn access_token_secret(provider_id: &str) -> String {
+    format!("provider.{}.access_token", provider_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAINTEXT_JSON: &str = r#"{
+        "providers": [{
+            "id": "openai", "name": "OpenAI", "url": "https://api.openai.com/v1",
+            "api_key": "sk-old", "provider_type": "OpenAi", "enabled": true,
+            "models": [], "is_custom": false
+        }],
+        "default_chat_provider": null,
+        "default_tts_provider": null,
+        "default_asr_provider": null,
+        "auth_token": "token-old"
+    }"#;
+
+    #[test]
+    fn test_secrets_are_not_serialized() {
+        let mut prefs = Preferences {
+            providers: get_supported_providers(),
+            ..Default::default()
+        };
+        prefs.set_api_key("openai", Some("sk-secret".to_string()));
+        prefs.set_auth_token(Some("token-secret".to_string()));
+        if let Some(volcano) = prefs
+            .get_provider_mut("volcano")
+            .and_then(|p| p.volcano.as_mut())
+        {
+            volcano.access_token = "volc-secret".to_string();
+            volcano.app_id = "12345".to_string();
+        }
+
+        let json = serde_json::to_string(&prefs).unwrap();
+        assert!(!json.contains("sk-secret"));
+        assert!(!json.contains("token-secret"));
+        assert!(!json.contains("volc-secret"));
+        assert!(json.contains("12345"));
+
+        let secrets = prefs.collect_secrets();
+        assert_eq!(secrets["provider.openai.api_key"], "sk-secret");
+        assert_eq!(secrets["provider.volcano.access_token"], "volc-secret");
+        assert_eq!(secrets[AUTH_TOKEN_SECRET], "token-secret");
+    }
+
+    #[test]
+    fn test_restart_policy_is_persisted() {
+        // Files written before the setting existed get the default policy
+        let prefs: Preferences = serde_json::from_str(PLAINTEXT_JSON).unwrap();
+        assert_eq!(prefs.restart.policy(), RestartPolicy::default());
+
+        let mut prefs = prefs;
+        prefs.restart.max_restarts = 0;
+        prefs.restart.initial_backoff_secs = 5;
+        let json = serde_json::to_string(&prefs).unwrap();
+        let loaded: Preferences = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.restart, prefs.restart);
+        assert_eq!(
+            loaded.restart.policy().initial_backoff,
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn test_plaintext_secrets_are_read_for_migration() {
+        let prefs: Preferences = serde_json::from_str(PLAINTEXT_JSON).unwrap();
+        assert_eq!(prefs.api_key("openai"), Some("sk-old"));
+        assert_eq!(prefs.auth_token(), Some("token-old"));
+    }
+
+    #[test]
+    fn test_secrets_stay_in_json_until_vault_unlocks() {
+        let dir = std::env::temp_dir().join(format!("colang-prefs-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("preferences.json");
+        let vault_path = dir.join("secrets.vault");
+        fs::write(&path, PLAINTEXT_JSON).unwrap();
+
+        // A passphrase-protected vault doesn't open with the key file
+        let passphrase = || KeySource::Passphrase("correct".to_string());
+        Vault::open(&vault_path, passphrase())
+            .unwrap()
+            .save()
+            .unwrap();
+        let locked = || Vault::open(&vault_path, KeySource::KeyFile(dir.join("vault.key")));
+
+        let prefs = Preferences::load_from(&path, &locked);
+        assert_eq!(prefs.api_key("openai"), Some("sk-old"));
+        assert!(prefs.save_to(&path, locked()).is_err());
+        let json = fs::read_to_string(&path).unwrap();
+        assert!(json.contains("sk-old"));
+        assert!(json.contains("token-old"));
+
+        // Once the vault unlocks, loading migrates the secrets out of the JSON
+        let unlocked = || Vault::open(&vault_path, passphrase());
+        let prefs = Preferences::load_from(&path, &unlocked);
+        assert_eq!(prefs.api_key("openai"), Some("sk-old"));
+        let json = fs::read_to_string(&path).unwrap();
+        assert!(!json.contains("sk-old"));
+        assert!(!json.contains("token-old"));
+        let vault = unlocked().unwrap();
+        assert_eq!(vault.get("provider.openai.api_key"), Some("sk-old"));
+        assert_eq!(vault.get(AUTH_TOKEN_SECRET), Some("token-old"));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
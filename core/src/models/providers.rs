@@ -1,5 +1,7 @@
 //! Provider data models
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 /// Unique identifier for a provider
@@ -12,6 +14,10 @@ pub enum ProviderType {
     OpenAi,
     DeepSeek,
     AlibabaCloud,
+    /// Volcano Engine (Doubao): LLM via Ark plus speech (ASR/TTS) credentials
+    Volcano,
+    /// ZhipuAI BigModel: speech (ASR/TTS) for the bigmodel-* nodes
+    BigModel,
     Custom,
 }
 
@@ -21,6 +27,8 @@ impl ProviderType {
             ProviderType::OpenAi => "OpenAI",
             ProviderType::DeepSeek => "DeepSeek",
             ProviderType::AlibabaCloud => "Alibaba Cloud",
+            ProviderType::Volcano => "Volcano Engine",
+            ProviderType::BigModel => "ZhipuAI BigModel",
             ProviderType::Custom => "Custom",
         }
     }
@@ -51,17 +59,67 @@ impl ProviderConnectionStatus {
     }
 }
 
+/// Speech credentials for the Volcano Engine (Doubao) provider
+///
+/// The Ark LLM key is the provider's `api_key`; these are the extra values the
+/// doubao-asr / doubao-tts nodes need.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VolcanoSettings {
+    pub app_id: String,
+    /// Stored in the vault, never serialized
+    #[serde(skip_serializing)]
+    pub access_token: String,
+    pub tts_resource_id: String,
+    pub asr_resource_id: String,
+    pub voice_type: String,
+}
+
+impl Default for VolcanoSettings {
+    fn default() -> Self {
+        Self {
+            app_id: String::new(),
+            access_token: String::new(),
+            tts_resource_id: "seed-tts-2.0".to_string(),
+            asr_resource_id: "volc.bigasr.sauc.duration".to_string(),
+            voice_type: "zh_female_vv_uranus_bigtts".to_string(),
+        }
+    }
+}
+
+impl VolcanoSettings {
+    /// Dataflow environment variables for the configured (non-empty) values
+    pub fn env_vars(&self) -> HashMap<String, String> {
+        [
+            ("DOUBAO_APP_ID", &self.app_id),
+            ("DOUBAO_ACCESS_TOKEN", &self.access_token),
+            ("DOUBAO_RESOURCE_ID", &self.tts_resource_id),
+            ("DOUBAO_ASR_RESOURCE_ID", &self.asr_resource_id),
+            ("VOICE_TYPE", &self.voice_type),
+        ]
+        .into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .map(|(key, value)| (key.to_string(), value.trim().to_string()))
+        .collect()
+    }
+}
+
 /// A configured AI provider
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Provider {
     pub id: ProviderId,
     pub name: String,
     pub url: String,
+    /// Stored in the vault, never serialized
+    #[serde(default, skip_serializing)]
     pub api_key: Option<String>,
     pub provider_type: ProviderType,
     pub enabled: bool,
     pub models: Vec<String>,
     pub is_custom: bool,
+    /// Speech credentials, only for [`ProviderType::Volcano`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volcano: Option<VolcanoSettings>,
     #[serde(skip)]
     pub connection_status: ProviderConnectionStatus,
 }
@@ -77,6 +135,7 @@ impl Default for Provider {
             enabled: false,
             models: Vec::new(),
             is_custom: false,
+            volcano: None,
             connection_status: ProviderConnectionStatus::Disconnected,
         }
     }
@@ -94,6 +153,7 @@ impl Provider {
             enabled: false,
             models: Vec::new(),
             is_custom: true,
+            volcano: None,
             connection_status: ProviderConnectionStatus::Disconnected,
         }
     }
@@ -135,6 +195,7 @@ pub fn get_supported_providers() -> Vec<Provider> {
                 "o1-mini".to_string(),
             ],
             is_custom: false,
+            volcano: None,
             connection_status: ProviderConnectionStatus::Disconnected,
         },
         Provider {
@@ -146,6 +207,7 @@ pub fn get_supported_providers() -> Vec<Provider> {
             enabled: false,
             models: vec!["deepseek-chat".to_string(), "deepseek-reasoner".to_string()],
             is_custom: false,
+            volcano: None,
             connection_status: ProviderConnectionStatus::Disconnected,
         },
         Provider {
@@ -161,6 +223,31 @@ pub fn get_supported_providers() -> Vec<Provider> {
                 "qwen-max".to_string(),
             ],
             is_custom: false,
+            volcano: None,
+            connection_status: ProviderConnectionStatus::Disconnected,
+        },
+        Provider {
+            id: "volcano".to_string(),
+            name: "Volcano Engine (Doubao)".to_string(),
+            url: "https://ark.cn-beijing.volces.com/api/v3".to_string(),
+            api_key: None,
+            provider_type: ProviderType::Volcano,
+            enabled: false,
+            models: vec!["doubao-seed-1-8-251228".to_string()],
+            is_custom: false,
+            volcano: Some(VolcanoSettings::default()),
+            connection_status: ProviderConnectionStatus::Disconnected,
+        },
+        Provider {
+            id: "bigmodel".to_string(),
+            name: "ZhipuAI BigModel".to_string(),
+            url: "https://open.bigmodel.cn/api/paas/v4".to_string(),
+            api_key: None,
+            provider_type: ProviderType::BigModel,
+            enabled: false,
+            models: vec!["glm-4-flash".to_string()],
+            is_custom: false,
+            volcano: None,
             connection_status: ProviderConnectionStatus::Disconnected,
         },
     ]
@@ -0,0 +1,376 @@
+//! Encrypted secret storage
+//!
+//! Provider API keys, speech access tokens and the auth token are kept out of
+//! `preferences.json` and stored in `secrets.vault` next to it, encrypted with
+//! XChaCha20-Poly1305.
+//!
+//! The encryption key is either
+//! - derived with Argon2id from the passphrase in `COLANG_VAULT_PASSPHRASE`, or
+//! - read from a machine-local key file (`vault.key`, created on first use).
+//!
+//! A key-file vault opened with a passphrase is re-encrypted with the
+//! passphrase, so setting `COLANG_VAULT_PASSPHRASE` later protects the
+//! existing secrets.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Environment variable holding the vault passphrase
+pub const PASSPHRASE_ENV: &str = "COLANG_VAULT_PASSPHRASE";
+
+/// Key file name, next to the vault
+const KEY_FILE: &str = "vault.key";
+
+const VAULT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+
+/// Passphrase and salt an Argon2id key is derived from
+type KdfInput = (String, Vec<u8>);
+
+/// Argon2id keys derived in this process. Deriving is deliberately slow and
+/// preferences are loaded from UI code.
+static DERIVED_KEYS: Mutex<BTreeMap<KdfInput, [u8; KEY_LEN]>> = Mutex::new(BTreeMap::new());
+
+#[derive(Error, Debug)]
+pub enum VaultError {
+    #[error("Vault I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid vault file: {0}")]
+    Format(String),
+
+    #[error("Vault is passphrase protected, set {PASSPHRASE_ENV} to unlock it")]
+    PassphraseRequired,
+
+    #[error("Failed to decrypt vault (wrong key or corrupted file)")]
+    Decrypt,
+
+    #[error("Failed to encrypt vault")]
+    Encrypt,
+
+    #[error("Key derivation failed: {0}")]
+    Kdf(String),
+}
+
+/// Where the vault encryption key comes from
+#[derive(Clone, Debug)]
+pub enum KeySource {
+    /// Argon2id-derived from a user passphrase
+    Passphrase(String),
+    /// Raw key in a machine-local file
+    KeyFile(PathBuf),
+}
+
+impl KeySource {
+    /// `COLANG_VAULT_PASSPHRASE` if set, otherwise the key file in `dir`
+    pub fn from_env(dir: &Path) -> Self {
+        match std::env::var(PASSPHRASE_ENV) {
+            Ok(passphrase) if !passphrase.is_empty() => KeySource::Passphrase(passphrase),
+            _ => KeySource::KeyFile(dir.join(KEY_FILE)),
+        }
+    }
+
+    fn kdf_name(&self) -> &'static str {
+        match self {
+            KeySource::Passphrase(_) => "argon2id",
+            KeySource::KeyFile(_) => "key_file",
+        }
+    }
+
+    /// Encryption key for this source; `salt` is only used for passphrases.
+    /// A missing key file is generated only when `create` is set (on save).
+    fn key(&self, salt: &[u8], create: bool) -> Result<[u8; KEY_LEN], VaultError> {
+        let mut key = [0u8; KEY_LEN];
+        match self {
+            KeySource::Passphrase(passphrase) => {
+                let cache_key = (passphrase.clone(), salt.to_vec());
+                if let Some(key) = DERIVED_KEYS.lock().unwrap().get(&cache_key) {
+                    return Ok(*key);
+                }
+                argon2::Argon2::default()
+                    .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+                    .map_err(|e| VaultError::Kdf(e.to_string()))?;
+                DERIVED_KEYS.lock().unwrap().insert(cache_key, key);
+            }
+            KeySource::KeyFile(path) => {
+                if !path.exists() && create {
+                    OsRng.fill_bytes(&mut key);
+                    write_private(path, &key)?;
+                    return Ok(key);
+                }
+                let bytes = fs::read(path)?;
+                if bytes.len() != KEY_LEN {
+                    return Err(VaultError::Format(format!(
+                        "key file {} must be {} bytes",
+                        path.display(),
+                        KEY_LEN
+                    )));
+                }
+                key.copy_from_slice(&bytes);
+            }
+        }
+        Ok(key)
+    }
+}
+
+/// On-disk format of `secrets.vault`
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    kdf: String,
+    #[serde(default)]
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Decrypted secrets, keyed by name (e.g. `provider.openai.api_key`)
+pub struct Vault {
+    path: PathBuf,
+    key_source: KeySource,
+    secrets: BTreeMap<String, String>,
+}
+
+impl Vault {
+    /// Open the vault at `path`; a missing file is an empty vault.
+    /// A key-file vault opened with a passphrase is re-keyed to it.
+    pub fn open(path: impl Into<PathBuf>, key_source: KeySource) -> Result<Self, VaultError> {
+        let path = path.into();
+        if !path.exists() {
+            return Ok(Self {
+                path,
+                key_source,
+                secrets: BTreeMap::new(),
+            });
+        }
+
+        let file: VaultFile = serde_json::from_str(&fs::read_to_string(&path)?)
+            .map_err(|e| VaultError::Format(e.to_string()))?;
+        let key_path = path.with_file_name(KEY_FILE);
+        let key_file = KeySource::KeyFile(key_path.clone());
+        let rekey =
+            matches!(key_source, KeySource::Passphrase(_)) && file.kdf == key_file.kdf_name();
+        let secrets = Self::decrypt(&file, if rekey { &key_file } else { &key_source })?;
+
+        let vault = Self {
+            path,
+            key_source,
+            secrets,
+        };
+        if rekey {
+            vault.save()?;
+            log::info!("Re-encrypted the secrets vault with {}", PASSPHRASE_ENV);
+            // The vault no longer needs the key file
+            fs::remove_file(key_path).ok();
+        }
+        Ok(vault)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.secrets.get(name).map(String::as_str)
+    }
+
+    /// Replace all secrets (empty values are dropped)
+    pub fn replace_all(&mut self, secrets: BTreeMap<String, String>) {
+        self.secrets = secrets
+            .into_iter()
+            .filter(|(_, value)| !value.is_empty())
+            .collect();
+    }
+
+    pub fn secrets(&self) -> &BTreeMap<String, String> {
+        &self.secrets
+    }
+
+    /// Encrypt and write the vault file
+    pub fn save(&self) -> Result<(), VaultError> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let key = self.key_source.key(&salt, true)?;
+
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext =
+            serde_json::to_vec(&self.secrets).map_err(|e| VaultError::Format(e.to_string()))?;
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_ref())
+            .map_err(|_| VaultError::Encrypt)?;
+
+        let file = VaultFile {
+            version: VAULT_VERSION,
+            kdf: self.key_source.kdf_name().to_string(),
+            salt: match self.key_source {
+                KeySource::Passphrase(_) => BASE64.encode(salt),
+                KeySource::KeyFile(_) => String::new(),
+            },
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        let content =
+            serde_json::to_string_pretty(&file).map_err(|e| VaultError::Format(e.to_string()))?;
+        write_private(&self.path, content.as_bytes())?;
+        Ok(())
+    }
+
+    fn decrypt(
+        file: &VaultFile,
+        key_source: &KeySource,
+    ) -> Result<BTreeMap<String, String>, VaultError> {
+        if file.version != VAULT_VERSION {
+            return Err(VaultError::Format(format!(
+                "unsupported version {}",
+                file.version
+            )));
+        }
+        if file.kdf != key_source.kdf_name() {
+            return match file.kdf.as_str() {
+                "argon2id" => Err(VaultError::PassphraseRequired),
+                other => Err(VaultError::Format(format!("vault uses kdf {}", other))),
+            };
+        }
+
+        let decode = |field: &str, value: &str| {
+            BASE64
+                .decode(value)
+                .map_err(|e| VaultError::Format(format!("{}: {}", field, e)))
+        };
+        let salt = decode("salt", &file.salt)?;
+        let nonce = decode("nonce", &file.nonce)?;
+        let ciphertext = decode("ciphertext", &file.ciphertext)?;
+        if nonce.len() != 24 {
+            return Err(VaultError::Format("nonce must be 24 bytes".to_string()));
+        }
+
+        let key = key_source.key(&salt, false)?;
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&key));
+        let plaintext = cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| VaultError::Decrypt)?;
+        serde_json::from_slice(&plaintext).map_err(|e| VaultError::Format(e.to_string()))
+    }
+}
+
+/// Write a file readable only by the current user
+fn write_private(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, content)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o600))?;
+    }
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("colang-vault-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn sample() -> BTreeMap<String, String> {
+        BTreeMap::from([
+            ("auth_token".to_string(), "token".to_string()),
+            ("provider.openai.api_key".to_string(), "sk-test".to_string()),
+        ])
+    }
+
+    #[test]
+    fn test_key_file_round_trip() {
+        let dir = temp_dir("key-file");
+        let key_source = KeySource::KeyFile(dir.join("vault.key"));
+
+        let mut vault = Vault::open(dir.join("secrets.vault"), key_source.clone()).unwrap();
+        vault.replace_all(sample());
+        vault.save().unwrap();
+
+        let content = fs::read_to_string(dir.join("secrets.vault")).unwrap();
+        assert!(!content.contains("sk-test"));
+
+        let vault = Vault::open(dir.join("secrets.vault"), key_source).unwrap();
+        assert_eq!(vault.get("provider.openai.api_key"), Some("sk-test"));
+        assert_eq!(vault.secrets(), &sample());
+
+        // A different key file can't open it
+        let other = KeySource::KeyFile(dir.join("other.key"));
+        other.key(&[], true).unwrap();
+        assert!(matches!(
+            Vault::open(dir.join("secrets.vault"), other),
+            Err(VaultError::Decrypt)
+        ));
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_passphrase_round_trip() {
+        let dir = temp_dir("passphrase");
+        let path = dir.join("secrets.vault");
+
+        let mut vault = Vault::open(&path, KeySource::Passphrase("correct".to_string())).unwrap();
+        vault.replace_all(sample());
+        vault.save().unwrap();
+
+        let vault = Vault::open(&path, KeySource::Passphrase("correct".to_string())).unwrap();
+        assert_eq!(vault.get("auth_token"), Some("token"));
+
+        assert!(matches!(
+            Vault::open(&path, KeySource::Passphrase("wrong".to_string())),
+            Err(VaultError::Decrypt)
+        ));
+        assert!(matches!(
+            Vault::open(&path, KeySource::KeyFile(dir.join("vault.key"))),
+            Err(VaultError::PassphraseRequired)
+        ));
+
+        fs::remove_dir_all(dir).ok();
+    }
+    #[test]
+    fn test_key_file_vault_is_rekeyed_to_passphrase() {
+        let dir = temp_dir("rekey");
+        let path = dir.join("secrets.vault");
+        let key_file = KeySource::KeyFile(dir.join(KEY_FILE));
+
+        let mut vault = Vault::open(&path, key_file.clone()).unwrap();
+        vault.replace_all(sample());
+        vault.save().unwrap();
+
+        // Setting a passphrase later keeps the secrets and protects them
+        let passphrase = || KeySource::Passphrase("correct".to_string());
+        let vault = Vault::open(&path, passphrase()).unwrap();
+        assert_eq!(vault.secrets(), &sample());
+        assert!(fs::read_to_string(&path).unwrap().contains("argon2id"));
+        assert!(!dir.join(KEY_FILE).exists());
+
+        assert_eq!(
+            Vault::open(&path, passphrase()).unwrap().secrets(),
+            &sample()
+        );
+        assert!(matches!(
+            Vault::open(&path, key_file),
+            Err(VaultError::PassphraseRequired)
+        ));
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -0,0 +1,249 @@
+//! Export and import of the mistake notebook
+//!
+//! The notebook is the review queue in `issue_words` plus the learner's
+//! vocabulary in `user_vocabulary`. It leaves the app as an Anki package
+//! ([`export_apkg`]) or as CSV/TSV ([`export_text`]). CSV/TSV files come back
+//! in through [`import_text`], which merges rows into `issue_words` and can
+//! first run dry to report what would change.
+
+mod anki;
+mod csv;
+
+use std::path::{Path, PathBuf};
+
+use dora_messages::tts::cache_key;
+use thiserror::Error;
+
+use crate::db::Database;
+use crate::models::{IssueType, IssueWord, Preferences, VocabularyWord, VolcanoSettings};
+
+pub use anki::export_apkg;
+pub use csv::{ImportAction, ImportReport, ImportRow, SkippedRow, export_text, import_text};
+
+#[derive(Error, Debug)]
+pub enum NotebookError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] ::csv::Error),
+
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("Missing column: {0}")]
+    MissingColumn(&'static str),
+}
+
+/// Delimited text layout of exported and imported files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextFormat {
+    Csv,
+    Tsv,
+}
+
+impl TextFormat {
+    /// TSV for `.tsv` and `.txt` files, CSV otherwise
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("tsv") || ext.eq_ignore_ascii_case("txt") => {
+                TextFormat::Tsv
+            }
+            _ => TextFormat::Csv,
+        }
+    }
+
+    fn delimiter(self) -> u8 {
+        match self {
+            TextFormat::Csv => b',',
+            TextFormat::Tsv => b'\t',
+        }
+    }
+}
+
+/// Part of the notebook a card comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Deck {
+    /// `issue_words`
+    Mistakes,
+    /// `user_vocabulary`
+    Vocabulary,
+}
+
+impl Deck {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Deck::Mistakes => "mistakes",
+            Deck::Vocabulary => "vocabulary",
+        }
+    }
+
+    /// Deck name shown in Anki
+    pub fn title(self) -> &'static str {
+        match self {
+            Deck::Mistakes => "开朗英语::错题本",
+            Deck::Vocabulary => "开朗英语::生词本",
+        }
+    }
+}
+
+/// One exported card: a mistake or a vocabulary word
+#[derive(Debug, Clone, PartialEq)]
+pub struct Card {
+    pub deck: Deck,
+    pub word: String,
+    /// Issue type of a mistake
+    pub issue_type: Option<IssueType>,
+    pub description_en: Option<String>,
+    pub description_zh: Option<String>,
+    /// Sentence the word came up in
+    pub context: Option<String>,
+}
+
+impl From<&IssueWord> for Card {
+    fn from(word: &IssueWord) -> Self {
+        Self {
+            deck: Deck::Mistakes,
+            word: word.word.clone(),
+            issue_type: Some(word.issue_type.clone()),
+            description_en: word.description_en.clone(),
+            description_zh: word.description_zh.clone(),
+            context: word.context.clone(),
+        }
+    }
+}
+
+impl From<&VocabularyWord> for Card {
+    fn from(word: &VocabularyWord) -> Self {
+        Self {
+            deck: Deck::Vocabulary,
+            word: word.word.clone(),
+            issue_type: None,
+            description_en: None,
+            description_zh: word.word_zh.clone(),
+            context: None,
+        }
+    }
+}
+
+/// The whole notebook as stored
+#[derive(Debug, Clone, Default)]
+pub struct Notebook {
+    pub issue_words: Vec<IssueWord>,
+    pub vocabulary: Vec<VocabularyWord>,
+}
+
+impl Notebook {
+    pub async fn load(db: &Database) -> Result<Self, sqlx::Error> {
+        Ok(Self {
+            issue_words: db.get_all_issue_words().await?,
+            vocabulary: db.get_vocabulary().await?,
+        })
+    }
+
+    /// Mistakes first, then vocabulary
+    pub fn cards(&self) -> Vec<Card> {
+        self.issue_words
+            .iter()
+            .map(Card::from)
+            .chain(self.vocabulary.iter().map(Card::from))
+            .collect()
+    }
+}
+
+/// What an export wrote
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub cards: usize,
+    /// Cached TTS clips packed with the cards
+    pub audio_clips: usize,
+}
+
+/// Speech rate of the dataflow's TTS node (`SPEED_RATIO` 1.0)
+const SPEECH_RATE: i32 = 0;
+
+/// Cached TTS clips, looked up by text
+///
+/// The cache is content-addressed by [`cache_key`], so only text the TTS node
+/// synthesized on its own (a reply sentence, or a word read out alone) with
+/// the same voice is found.
+#[derive(Debug, Clone)]
+pub struct TtsAudio {
+    dir: PathBuf,
+    voice: String,
+    resource_id: String,
+}
+
+impl TtsAudio {
+    pub fn new(dir: impl Into<PathBuf>, voice: &str, resource_id: &str) -> Self {
+        Self {
+            dir: dir.into(),
+            voice: voice.to_string(),
+            resource_id: resource_id.to_string(),
+        }
+    }
+
+    /// The cache the Doubao TTS node fills, with the configured voice
+    pub fn from_preferences() -> Self {
+        let defaults = VolcanoSettings::default();
+        let settings = Preferences::load()
+            .get_provider("volcano")
+            .and_then(|provider| provider.volcano.clone())
+            .unwrap_or_default();
+        let or_default = |value: &str, default: &str| {
+            let value = value.trim();
+            if value.is_empty() { default } else { value }.to_string()
+        };
+        Self {
+            dir: crate::screens::settings::tts_cache_dir(),
+            voice: or_default(&settings.voice_type, &defaults.voice_type),
+            resource_id: or_default(&settings.tts_resource_id, &defaults.tts_resource_id),
+        }
+    }
+
+    /// File name and path of the cached clip for `text`
+    pub fn find(&self, text: &str) -> Option<(String, PathBuf)> {
+        if text.trim().is_empty() {
+            return None;
+        }
+        let name = format!(
+            "{}.mp3",
+            cache_key(text, &self.voice, SPEECH_RATE, &self.resource_id)
+        );
+        let path = self.dir.join(&name);
+        path.is_file().then_some((name, path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_format_from_path() {
+        assert_eq!(TextFormat::from_path(Path::new("a.csv")), TextFormat::Csv);
+        assert_eq!(TextFormat::from_path(Path::new("a.TSV")), TextFormat::Tsv);
+        assert_eq!(TextFormat::from_path(Path::new("a.txt")), TextFormat::Tsv);
+        assert_eq!(TextFormat::from_path(Path::new("a")), TextFormat::Csv);
+    }
+
+    #[test]
+    fn test_tts_audio_find() {
+        let dir = std::env::temp_dir().join(format!("colang-tts-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let audio = TtsAudio::new(&dir, "voice", "seed-tts-2.0");
+        let key = cache_key("schedule", "voice", SPEECH_RATE, "seed-tts-2.0");
+        std::fs::write(dir.join(format!("{key}.mp3")), b"mp3").unwrap();
+
+        let (name, path) = audio.find(" schedule ").unwrap();
+        assert_eq!(name, format!("{key}.mp3"));
+        assert!(path.is_file());
+        assert!(audio.find("either").is_none());
+        assert!(audio.find("").is_none());
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
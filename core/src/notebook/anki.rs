@@ -0,0 +1,413 @@
+//! Anki package (`.apkg`) export
+//!
+//! An `.apkg` is a zip of `collection.anki2`, an SQLite collection in Anki's
+//! schema 11, plus a `media` JSON map from numbered zip entries to file
+//! names. Cards go to one deck per [`Deck`] under a single note type. Deck
+//! and note type ids and each note's guid are stable; note ids are not (they
+//! are timestamps of the export), but Anki matches notes by guid, so
+//! importing a newer export updates the earlier notes instead of duplicating
+//! them. All cards start out new in Anki.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+use chrono::Local;
+use serde_json::{Value, json};
+use sha1::{Digest, Sha1};
+use sqlx::sqlite::SqlitePool;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::{Card, Deck, ExportSummary, Notebook, NotebookError, TtsAudio};
+
+/// Id of the note type
+const MODEL_ID: i64 = 1_760_000_000_000;
+
+/// Field separator within `notes.flds`
+const FIELD_SEPARATOR: &str = "\u{1f}";
+
+const FIELDS: [&str; 5] = ["Word", "Meaning", "Explanation", "Context", "Audio"];
+
+const FRONT: &str = r#"<div class="word">{{Word}}</div>
+{{#Context}}<div class="context">{{Context}}</div>{{/Context}}
+{{Audio}}"#;
+
+const BACK: &str = r#"{{FrontSide}}
+<hr id="answer">
+<div class="meaning">{{Meaning}}</div>
+{{#Explanation}}<div class="explanation">{{Explanation}}</div>{{/Explanation}}"#;
+
+const CSS: &str = ".card { font-family: sans-serif; font-size: 20px; text-align: center; }
+.word { font-size: 32px; font-weight: bold; }
+.context { margin-top: 12px; color: #64748b; font-style: italic; }
+.meaning { font-size: 24px; }
+.explanation { margin-top: 8px; color: #475569; font-size: 16px; }";
+
+/// Schema 11 of an Anki collection
+const SCHEMA: &str = "
+CREATE TABLE col (
+    id integer primary key, crt integer not null, mod integer not null,
+    scm integer not null, ver integer not null, dty integer not null,
+    usn integer not null, ls integer not null, conf text not null,
+    models text not null, decks text not null, dconf text not null, tags text not null
+);
+CREATE TABLE notes (
+    id integer primary key, guid text not null, mid integer not null,
+    mod integer not null, usn integer not null, tags text not null,
+    flds text not null, sfld integer not null, csum integer not null,
+    flags integer not null, data text not null
+);
+CREATE TABLE cards (
+    id integer primary key, nid integer not null, did integer not null,
+    ord integer not null, mod integer not null, usn integer not null,
+    type integer not null, queue integer not null, due integer not null,
+    ivl integer not null, factor integer not null, reps integer not null,
+    lapses integer not null, left integer not null, odue integer not null,
+    odid integer not null, flags integer not null, data text not null
+);
+CREATE TABLE revlog (
+    id integer primary key, cid integer not null, usn integer not null,
+    ease integer not null, ivl integer not null, lastIvl integer not null,
+    factor integer not null, time integer not null, type integer not null
+);
+CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null);
+CREATE INDEX ix_notes_usn ON notes (usn);
+CREATE INDEX ix_cards_usn ON cards (usn);
+CREATE INDEX ix_revlog_usn ON revlog (usn);
+CREATE INDEX ix_cards_nid ON cards (nid);
+CREATE INDEX ix_cards_sched ON cards (did, queue, due);
+CREATE INDEX ix_revlog_cid ON revlog (cid);
+CREATE INDEX ix_notes_csum ON notes (csum);
+";
+
+fn deck_id(deck: Deck) -> i64 {
+    match deck {
+        Deck::Mistakes => MODEL_ID + 1,
+        Deck::Vocabulary => MODEL_ID + 2,
+    }
+}
+
+/// Write the notebook to `path` as an Anki package, with the cached TTS
+/// clips of each card's word and context when `audio` is given
+pub async fn export_apkg(
+    notebook: &Notebook,
+    audio: Option<&TtsAudio>,
+    path: &Path,
+) -> Result<ExportSummary, NotebookError> {
+    let cards = notebook.cards();
+    let collection_path =
+        std::env::temp_dir().join(format!("colang-anki-{}.anki2", uuid::Uuid::new_v4()));
+
+    let result = async {
+        let media = write_collection(&cards, audio, &collection_path).await?;
+        write_package(&collection_path, &media, path)?;
+        Ok(ExportSummary {
+            cards: cards.len(),
+            audio_clips: media.len(),
+        })
+    }
+    .await;
+
+    let _ = std::fs::remove_file(&collection_path);
+    result
+}
+
+/// A media file of the package
+struct Media {
+    name: String,
+    path: std::path::PathBuf,
+}
+
+/// Write the collection database; returns the media the notes refer to
+async fn write_collection(
+    cards: &[Card],
+    audio: Option<&TtsAudio>,
+    path: &Path,
+) -> Result<Vec<Media>, NotebookError> {
+    let now = Local::now();
+    let now_ms = now.timestamp_millis();
+    let now_secs = now.timestamp();
+
+    // The Anki schema isn't ours, so these queries can't be checked by sqlx
+    let pool = SqlitePool::connect(&format!("sqlite://{}?mode=rwc", path.display())).await?;
+    sqlx::raw_sql(SCHEMA).execute(&pool).await?;
+
+    sqlx::query("INSERT INTO col VALUES (1, ?, ?, ?, 11, 0, 0, 0, ?, ?, ?, ?, '{}')")
+        .bind(crate::daily_stats::local_timestamp(now.date_naive()))
+        .bind(now_ms)
+        .bind(now_ms)
+        .bind(collection_conf().to_string())
+        .bind(json!({ MODEL_ID.to_string(): note_type(now_secs) }).to_string())
+        .bind(decks(now_secs).to_string())
+        .bind(json!({ "1": deck_conf() }).to_string())
+        .execute(&pool)
+        .await?;
+
+    let mut media: Vec<Media> = Vec::new();
+    for (position, card) in cards.iter().enumerate() {
+        let mut sounds = String::new();
+        for text in [Some(card.word.as_str()), card.context.as_deref()]
+            .into_iter()
+            .flatten()
+        {
+            if let Some((name, path)) = audio.and_then(|audio| audio.find(text)) {
+                sounds.push_str(&format!("[sound:{}]", name));
+                if !media.iter().any(|m| m.name == name) {
+                    media.push(Media { name, path });
+                }
+            }
+        }
+
+        let fields = [
+            escape(&card.word),
+            escape(card.description_zh.as_deref().unwrap_or_default()),
+            escape(card.description_en.as_deref().unwrap_or_default()),
+            escape(card.context.as_deref().unwrap_or_default()),
+            sounds,
+        ];
+        let mut tags = vec!["colang".to_string(), card.deck.as_str().to_string()];
+        tags.extend(card.issue_type.as_ref().map(|t| t.to_string()));
+
+        let id = now_ms + position as i64;
+        sqlx::query("INSERT INTO notes VALUES (?, ?, ?, ?, -1, ?, ?, ?, ?, 0, '')")
+            .bind(id)
+            .bind(guid(card))
+            .bind(MODEL_ID)
+            .bind(now_secs)
+            .bind(format!(" {} ", tags.join(" ")))
+            .bind(fields.join(FIELD_SEPARATOR))
+            .bind(&card.word)
+            .bind(checksum(&card.word))
+            .execute(&pool)
+            .await?;
+        // A new card, queued in notebook order
+        sqlx::query(
+            "INSERT INTO cards VALUES (?, ?, ?, 0, ?, -1, 0, 0, ?, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+        )
+        .bind(id)
+        .bind(id)
+        .bind(deck_id(card.deck))
+        .bind(now_secs)
+        .bind(position as i64 + 1)
+        .execute(&pool)
+        .await?;
+    }
+
+    pool.close().await;
+    Ok(media)
+}
+
+/// Zip the collection and media into the package at `path`
+fn write_package(collection: &Path, media: &[Media], path: &Path) -> Result<(), NotebookError> {
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut zip = ZipWriter::new(File::create(path)?);
+
+    zip.start_file("collection.anki2", options)?;
+    zip.write_all(&std::fs::read(collection)?)?;
+
+    let names: serde_json::Map<String, Value> = media
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (i.to_string(), Value::from(m.name.clone())))
+        .collect();
+    zip.start_file("media", options)?;
+    zip.write_all(Value::Object(names).to_string().as_bytes())?;
+
+    for (i, m) in media.iter().enumerate() {
+        zip.start_file(i.to_string(), options)?;
+        zip.write_all(&std::fs::read(&m.path)?)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+/// Note guid, stable across exports, from what identifies the card
+fn guid(card: &Card) -> String {
+    let issue_type = card
+        .issue_type
+        .as_ref()
+        .map(|t| t.to_string())
+        .unwrap_or_default();
+    let digest = Sha1::digest(format!(
+        "colang\u{1f}{}\u{1f}{}\u{1f}{}",
+        card.deck.as_str(),
+        issue_type,
+        card.word
+    ));
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Anki's duplicate check: the first 8 hex digits of the sha1 of the sort field
+fn checksum(field: &str) -> i64 {
+    let digest = Sha1::digest(field.as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) as i64
+}
+
+/// Field content is HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\n', "<br>")
+}
+
+fn collection_conf() -> Value {
+    json!({
+        "activeDecks": [1],
+        "curDeck": 1,
+        "newSpread": 0,
+        "collapseTime": 1200,
+        "timeLim": 0,
+        "estTimes": true,
+        "dueCounts": true,
+        "curModel": MODEL_ID,
+        "nextPos": 1,
+        "sortType": "noteFld",
+        "sortBackwards": false,
+        "addToCur": true
+    })
+}
+
+fn note_type(now_secs: i64) -> Value {
+    let fields: Vec<Value> = FIELDS
+        .iter()
+        .enumerate()
+        .map(|(ord, name)| {
+            json!({
+                "name": name,
+                "ord": ord,
+                "sticky": false,
+                "rtl": false,
+                "font": "Arial",
+                "size": 20,
+                "media": []
+            })
+        })
+        .collect();
+    json!({
+        "id": MODEL_ID,
+        "name": "开朗英语",
+        "type": 0,
+        "mod": now_secs,
+        "usn": -1,
+        "sortf": 0,
+        "did": deck_id(Deck::Mistakes),
+        "tmpls": [{
+            "name": "Card 1",
+            "ord": 0,
+            "qfmt": FRONT,
+            "afmt": BACK,
+            "did": null,
+            "bqfmt": "",
+            "bafmt": ""
+        }],
+        "flds": fields,
+        "css": CSS,
+        "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+        "latexPost": "\\end{document}",
+        "latexsvg": false,
+        // The card needs the Word field
+        "req": [[0, "any", [0]]],
+        "tags": [],
+        "vers": []
+    })
+}
+
+fn decks(now_secs: i64) -> Value {
+    let deck = |id: i64, name: &str| {
+        json!({
+            "id": id,
+            "name": name,
+            "mod": now_secs,
+            "usn": -1,
+            "lrnToday": [0, 0],
+            "revToday": [0, 0],
+            "newToday": [0, 0],
+            "timeToday": [0, 0],
+            "collapsed": false,
+            "desc": "",
+            "dyn": 0,
+            "conf": 1,
+            "extendNew": 10,
+            "extendRev": 50
+        })
+    };
+    let mut decks = serde_json::Map::new();
+    decks.insert("1".to_string(), deck(1, "Default"));
+    for d in [Deck::Mistakes, Deck::Vocabulary] {
+        decks.insert(deck_id(d).to_string(), deck(deck_id(d), d.title()));
+    }
+    Value::Object(decks)
+}
+
+/// Anki's default deck options
+fn deck_conf() -> Value {
+    json!({
+        "id": 1,
+        "name": "Default",
+        "mod": 0,
+        "usn": 0,
+        "maxTaken": 60,
+        "autoplay": true,
+        "timer": 0,
+        "replayq": true,
+        "dyn": false,
+        "new": {
+            "delays": [1, 10],
+            "ints": [1, 4, 7],
+            "initialFactor": 2500,
+            "order": 1,
+            "perDay": 20,
+            "bury": true,
+            "separate": true
+        },
+        "rev": {
+            "perDay": 100,
+            "ease4": 1.3,
+            "fuzz": 0.05,
+            "ivlFct": 1,
+            "maxIvl": 36500,
+            "minSpace": 1,
+            "bury": true
+        },
+        "lapse": {
+            "delays": [10],
+            "mult": 0,
+            "minInt": 1,
+            "leechFails": 8,
+            "leechAction": 0
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::IssueType;
+
+    #[test]
+    fn test_note_ids() {
+        let card = Card {
+            deck: Deck::Mistakes,
+            word: "schedule".to_string(),
+            issue_type: Some(IssueType::Pronunciation),
+            description_en: None,
+            description_zh: None,
+            context: None,
+        };
+        let vocabulary = Card {
+            deck: Deck::Vocabulary,
+            issue_type: None,
+            ..card.clone()
+        };
+        assert_eq!(guid(&card).len(), 16);
+        assert_eq!(guid(&card), guid(&card.clone()));
+        assert_ne!(guid(&card), guid(&vocabulary));
+
+        // sha1("schedule") = 11e9ba26...
+        assert_eq!(checksum("schedule"), 0x11e9_ba26);
+        assert_eq!(escape("a < b & c\nd"), "a &lt; b &amp; c<br>d");
+    }
+}
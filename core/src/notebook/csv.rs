@@ -0,0 +1,360 @@
+//! CSV/TSV export and import
+//!
+//! Files have a header row; columns are matched by name, case-insensitively,
+//! and unknown columns are ignored. `word` and `issue_type` are required on
+//! import. Rows whose `deck` is `vocabulary` are skipped: vocabulary only
+//! leaves the app.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use chrono::Local;
+
+use super::{Card, Deck, ExportSummary, Notebook, NotebookError, TextFormat};
+use crate::db::Database;
+use crate::models::{IssueType, IssueWord};
+use crate::scheduler::Scheduler;
+
+const HEADER: [&str; 6] = [
+    "deck",
+    "word",
+    "issue_type",
+    "description_zh",
+    "description_en",
+    "context",
+];
+
+/// Write the notebook to `path` as CSV or TSV, chosen by its extension
+pub fn export_text(notebook: &Notebook, path: &Path) -> Result<ExportSummary, NotebookError> {
+    let cards = notebook.cards();
+    write_cards(&cards, File::create(path)?, TextFormat::from_path(path))?;
+    Ok(ExportSummary {
+        cards: cards.len(),
+        audio_clips: 0,
+    })
+}
+
+fn write_cards(cards: &[Card], out: impl Write, format: TextFormat) -> Result<(), NotebookError> {
+    let mut writer = ::csv::WriterBuilder::new()
+        .delimiter(format.delimiter())
+        .from_writer(out);
+    writer.write_record(HEADER)?;
+    for card in cards {
+        let issue_type = card.issue_type.as_ref().map(|t| t.to_string());
+        writer.write_record([
+            card.deck.as_str(),
+            &card.word,
+            issue_type.as_deref().unwrap_or_default(),
+            card.description_zh.as_deref().unwrap_or_default(),
+            card.description_en.as_deref().unwrap_or_default(),
+            card.context.as_deref().unwrap_or_default(),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// What importing a row does to `issue_words`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportAction {
+    Add,
+    /// Changes the named columns of a known word
+    Update(Vec<&'static str>),
+    Unchanged,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRow {
+    /// 1-based line in the file
+    pub line: u64,
+    pub word: String,
+    pub issue_type: IssueType,
+    pub action: ImportAction,
+}
+
+/// A row that can't be imported
+#[derive(Debug, Clone, PartialEq)]
+pub struct SkippedRow {
+    pub line: u64,
+    pub reason: String,
+}
+
+/// Outcome of [`import_text`], or what it would be on a dry run
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    pub rows: Vec<ImportRow>,
+    pub skipped: Vec<SkippedRow>,
+}
+
+impl ImportReport {
+    pub fn added(&self) -> usize {
+        self.count(|action| *action == ImportAction::Add)
+    }
+
+    pub fn updated(&self) -> usize {
+        self.count(|action| matches!(action, ImportAction::Update(_)))
+    }
+
+    pub fn unchanged(&self) -> usize {
+        self.count(|action| *action == ImportAction::Unchanged)
+    }
+
+    fn count(&self, f: impl Fn(&ImportAction) -> bool) -> usize {
+        self.rows.iter().filter(|row| f(&row.action)).count()
+    }
+}
+
+/// Merge the CSV or TSV file at `path` into `issue_words`
+///
+/// Non-empty cells replace the stored descriptions and context of a known
+/// `(word, issue_type)`; its review schedule is kept. The changes are
+/// written in one transaction, so a failed import leaves `issue_words` as it
+/// was. With `dry_run` nothing is written and the report says what would
+/// change.
+pub async fn import_text(
+    db: &Database,
+    path: &Path,
+    dry_run: bool,
+) -> Result<ImportReport, NotebookError> {
+    import(db, File::open(path)?, TextFormat::from_path(path), dry_run).await
+}
+
+pub(super) async fn import(
+    db: &Database,
+    input: impl Read,
+    format: TextFormat,
+    dry_run: bool,
+) -> Result<ImportReport, NotebookError> {
+    let mut report = ImportReport {
+        dry_run,
+        ..Default::default()
+    };
+    // Words as they stand after the rows so far, so that a dry run reports
+    // repeated rows the way a real import would apply them
+    let mut merged: HashMap<(String, String), IssueWord> = HashMap::new();
+    let mut changed = Vec::new();
+
+    for row in read_rows(input, format)? {
+        let (line, fields) = match row {
+            Ok(row) => row,
+            Err(skipped) => {
+                report.skipped.push(skipped);
+                continue;
+            }
+        };
+        let key = (fields.word.clone(), fields.issue_type.to_string());
+        let known = match merged.remove(&key) {
+            Some(word) => Some(word),
+            None => db.get_issue_word(&fields.word, &fields.issue_type).await?,
+        };
+
+        let (word, action) = match known {
+            Some(mut word) => {
+                let changed = fields.apply(&mut word);
+                let action = if changed.is_empty() {
+                    ImportAction::Unchanged
+                } else {
+                    ImportAction::Update(changed)
+                };
+                (word, action)
+            }
+            None => (fields.new_word(), ImportAction::Add),
+        };
+
+        if action != ImportAction::Unchanged {
+            changed.push(word.clone());
+        }
+        report.rows.push(ImportRow {
+            line,
+            word: word.word.clone(),
+            issue_type: word.issue_type.clone(),
+            action,
+        });
+        merged.insert(key, word);
+    }
+
+    if !dry_run {
+        db.insert_issue_words(&changed).await?;
+    }
+    Ok(report)
+}
+
+/// The importable cells of a row; empty cells are `None`
+#[derive(Debug)]
+struct RowFields {
+    word: String,
+    issue_type: IssueType,
+    description_en: Option<String>,
+    description_zh: Option<String>,
+    context: Option<String>,
+}
+
+impl RowFields {
+    /// Overwrite `word` with the non-empty cells; returns the changed columns
+    fn apply(&self, word: &mut IssueWord) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        for (column, cell, stored) in [
+            ("description_en", &self.description_en, &mut word.description_en),
+            ("description_zh", &self.description_zh, &mut word.description_zh),
+            ("context", &self.context, &mut word.context),
+        ] {
+            if cell.is_some() && cell != stored {
+                stored.clone_from(cell);
+                changed.push(column);
+            }
+        }
+        changed
+    }
+
+    fn new_word(&self) -> IssueWord {
+        let state = Scheduler::default().new_state();
+        IssueWord {
+            id: None,
+            word: self.word.clone(),
+            issue_type: self.issue_type.clone(),
+            description_en: self.description_en.clone(),
+            description_zh: self.description_zh.clone(),
+            last_picked_at: None,
+            created_at: Local::now().timestamp(),
+            pick_count: 0,
+            next_review_at: None,
+            review_interval_days: 1,
+            difficulty_level: 1,
+            context: self.context.clone(),
+            audio_timestamp: None,
+            ease_factor: state.ease,
+            review_reps: state.reps,
+            lapses: state.lapses,
+        }
+    }
+}
+
+type ParsedRow = Result<(u64, RowFields), SkippedRow>;
+
+/// Parse every data row; malformed rows come back as [`SkippedRow`]s
+fn read_rows(input: impl Read, format: TextFormat) -> Result<Vec<ParsedRow>, NotebookError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(format.delimiter())
+        .flexible(true)
+        .trim(::csv::Trim::All)
+        .from_reader(input);
+
+    let headers = reader.headers()?.clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim_start_matches('\u{feff}').eq_ignore_ascii_case(name))
+    };
+    let word_column = column("word").ok_or(NotebookError::MissingColumn("word"))?;
+    let type_column = column("issue_type").ok_or(NotebookError::MissingColumn("issue_type"))?;
+    let deck_column = column("deck");
+    let en_column = column("description_en");
+    let zh_column = column("description_zh");
+    let context_column = column("context");
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(0, |p| p.line());
+                rows.push(Err(SkippedRow {
+                    line,
+                    reason: e.to_string(),
+                }));
+                continue;
+            }
+        };
+        let line = record.position().map_or(0, |p| p.line());
+        let cell = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let skip = |reason: String| Err(SkippedRow { line, reason });
+
+        if cell(deck_column).as_deref() == Some(Deck::Vocabulary.as_str()) {
+            rows.push(skip("Vocabulary words are not imported".to_string()));
+            continue;
+        }
+        let Some(word) = cell(Some(word_column)) else {
+            rows.push(skip("Empty word".to_string()));
+            continue;
+        };
+        let issue_type = match cell(Some(type_column)).unwrap_or_default().parse() {
+            Ok(issue_type) => issue_type,
+            Err(e) => {
+                rows.push(skip(e));
+                continue;
+            }
+        };
+
+        rows.push(Ok((
+            line,
+            RowFields {
+                word,
+                issue_type,
+                description_en: cell(en_column),
+                description_zh: cell(zh_column),
+                context: cell(context_column),
+            },
+        )));
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(deck: Deck, word: &str) -> Card {
+        Card {
+            deck,
+            word: word.to_string(),
+            issue_type: (deck == Deck::Mistakes).then_some(IssueType::Usage),
+            description_en: None,
+            description_zh: Some("日程, 安排".to_string()),
+            context: Some("Let's check the schedule, then \"decide\".".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_export_reads_back() {
+        let cards = [card(Deck::Mistakes, "schedule"), card(Deck::Vocabulary, "either")];
+        for format in [TextFormat::Csv, TextFormat::Tsv] {
+            let mut out = Vec::new();
+            write_cards(&cards, &mut out, format).unwrap();
+            let rows = read_rows(out.as_slice(), format).unwrap();
+
+            assert_eq!(rows.len(), 2);
+            let (line, fields) = rows[0].as_ref().unwrap();
+            assert_eq!(*line, 2);
+            assert_eq!(fields.word, "schedule");
+            assert_eq!(fields.issue_type, IssueType::Usage);
+            assert_eq!(fields.description_en, None);
+            assert_eq!(fields.context, cards[0].context);
+            assert_eq!(rows[1].as_ref().unwrap_err().line, 3);
+        }
+    }
+
+    #[test]
+    fn test_read_rows_reports_bad_rows() {
+        let input = "Word,Issue_Type,Notes\nschedule,usage,x\n,grammar\neither,spelling\n";
+        let rows = read_rows(input.as_bytes(), TextFormat::Csv).unwrap();
+        assert!(rows[0].is_ok());
+        assert_eq!(rows[1].as_ref().unwrap_err().reason, "Empty word");
+        assert_eq!(
+            rows[2].as_ref().unwrap_err().reason,
+            "Invalid issue type: spelling"
+        );
+
+        assert!(matches!(
+            read_rows("word\nschedule\n".as_bytes(), TextFormat::Csv),
+            Err(NotebookError::MissingColumn("issue_type"))
+        ));
+    }
+}
@@ -0,0 +1,343 @@
+//! Spaced-repetition scheduling for issue words
+//!
+//! An SM-2 scheduler: every word keeps an ease factor, its number of
+//! consecutive successful reviews and a lapse count. Reviews are graded 1–5
+//! (the range of `word_practice_log.success_level`). Grades below 3 are
+//! lapses that send the word back to a one-day interval; passing grades grow
+//! the interval by the ease factor, and the ease itself moves with the grade.
+//!
+//! Due dates are fuzzed by a few percent so words learned together don't keep
+//! coming due on the same day. The fuzz is derived from the word id and its
+//! review count, so the same review always gets the same due date.
+
+const DAY_SECS: i64 = 86_400;
+
+/// How well a word was recalled in a review
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Grade {
+    /// Not recalled at all
+    Forgot = 1,
+    /// Recalled wrongly, but the answer looked familiar
+    Wrong = 2,
+    /// Recalled with serious difficulty
+    Hard = 3,
+    /// Recalled after some hesitation
+    Good = 4,
+    /// Recalled immediately
+    Easy = 5,
+}
+
+impl Grade {
+    /// Grade from a `success_level` (1–5)
+    pub fn from_level(level: i64) -> Option<Self> {
+        match level {
+            1 => Some(Grade::Forgot),
+            2 => Some(Grade::Wrong),
+            3 => Some(Grade::Hard),
+            4 => Some(Grade::Good),
+            5 => Some(Grade::Easy),
+            _ => None,
+        }
+    }
+
+    /// `success_level` of the grade
+    pub fn level(self) -> i64 {
+        self as i64
+    }
+
+    /// Whether the word counts as remembered
+    pub fn is_pass(self) -> bool {
+        self >= Grade::Hard
+    }
+}
+
+/// Scheduler tuning parameters
+#[derive(Clone, Debug)]
+pub struct SchedulerConfig {
+    /// Ease of a word that was never reviewed
+    pub initial_ease: f64,
+    /// Ease never drops below this
+    pub min_ease: f64,
+    /// Interval after the first successful review (and after a lapse)
+    pub first_interval_days: i64,
+    /// Interval after the second successful review in a row
+    pub second_interval_days: i64,
+    /// Upper bound for intervals
+    pub max_interval_days: i64,
+    /// Interval from which a word counts as mastered
+    pub mastered_interval_days: i64,
+    /// Relative spread of due dates (0 disables fuzzing)
+    pub fuzz: f64,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            initial_ease: 2.5,
+            min_ease: 1.3,
+            first_interval_days: 1,
+            second_interval_days: 6,
+            max_interval_days: 365,
+            mastered_interval_days: 21,
+            fuzz: 0.05,
+        }
+    }
+}
+
+/// Scheduling state of one word
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReviewState {
+    /// Interval growth factor
+    pub ease: f64,
+    /// Current interval (0 for words never reviewed)
+    pub interval_days: i64,
+    /// Consecutive successful reviews
+    pub reps: i64,
+    /// Failed reviews
+    pub lapses: i64,
+}
+
+pub struct Scheduler {
+    config: SchedulerConfig,
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &SchedulerConfig {
+        &self.config
+    }
+
+    /// State of a word that was never reviewed
+    pub fn new_state(&self) -> ReviewState {
+        ReviewState {
+            ease: self.config.initial_ease,
+            interval_days: 0,
+            reps: 0,
+            lapses: 0,
+        }
+    }
+
+    /// State after reviewing a word with `grade`
+    pub fn review(&self, state: &ReviewState, grade: Grade) -> ReviewState {
+        let penalty = (Grade::Easy.level() - grade.level()) as f64;
+        let ease = (state.ease + 0.1 - penalty * (0.08 + penalty * 0.02)).max(self.config.min_ease);
+
+        if !grade.is_pass() {
+            return ReviewState {
+                ease,
+                interval_days: self.config.first_interval_days,
+                reps: 0,
+                lapses: state.lapses + 1,
+            };
+        }
+
+        let interval = match state.reps {
+            0 => self.config.first_interval_days,
+            1 => self.config.second_interval_days,
+            // A passing review always grows the interval
+            _ => ((state.interval_days as f64 * state.ease).round() as i64)
+                .max(state.interval_days + 1),
+        };
+        let interval = interval.min(self.config.max_interval_days);
+
+        ReviewState {
+            ease,
+            interval_days: interval,
+            reps: state.reps + 1,
+            lapses: state.lapses,
+        }
+    }
+
+    /// Due time (unix seconds) of a word reviewed at `now`
+    ///
+    /// `seed` picks the fuzz; see [`fuzz_seed`].
+    pub fn due_at(&self, state: &ReviewState, now: i64, seed: u64) -> i64 {
+        now + self.fuzzed_interval(state.interval_days, seed) * DAY_SECS
+    }
+
+    /// Difficulty level (1–5) shown for a word, from its ease
+    pub fn difficulty_level(&self, state: &ReviewState) -> i64 {
+        let range = self.config.initial_ease - self.config.min_ease;
+        if range <= 0.0 {
+            return 1;
+        }
+        let hardness = (self.config.initial_ease - state.ease) / range;
+        (1 + (hardness * 4.0).round() as i64).clamp(1, 5)
+    }
+
+    /// Progress towards mastery (0.0–1.0), from the word's interval
+    pub fn mastery(&self, state: &ReviewState) -> f64 {
+        if self.config.mastered_interval_days <= 0 {
+            return 1.0;
+        }
+        (state.interval_days as f64 / self.config.mastered_interval_days as f64).clamp(0.0, 1.0)
+    }
+
+    /// Whether the word's interval has reached the mastered interval
+    pub fn is_mastered(&self, state: &ReviewState) -> bool {
+        state.interval_days >= self.config.mastered_interval_days
+    }
+
+    /// Spread an interval by up to ±`fuzz` (at least a day once it is 3+ days)
+    fn fuzzed_interval(&self, days: i64, seed: u64) -> i64 {
+        if days < 3 || self.config.fuzz <= 0.0 {
+            return days;
+        }
+        let spread = ((days as f64 * self.config.fuzz).round() as i64).max(1);
+        let offset = (mix(seed) % (2 * spread as u64 + 1)) as i64 - spread;
+        (days + offset).clamp(1, self.config.max_interval_days)
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new(SchedulerConfig::default())
+    }
+}
+
+/// Fuzz seed for the `review_count`-th review of a word
+pub fn fuzz_seed(word_id: i64, review_count: i64) -> u64 {
+    ((word_id as u64) << 32) ^ review_count as u64
+}
+
+/// SplitMix64 finalizer: spreads nearby seeds over the whole range
+fn mix(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulate(scheduler: &Scheduler, grades: &[Grade]) -> Vec<ReviewState> {
+        let mut state = scheduler.new_state();
+        grades
+            .iter()
+            .map(|&grade| {
+                state = scheduler.review(&state, grade);
+                state
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_good_reviews_grow_interval() {
+        let scheduler = Scheduler::default();
+        let history = simulate(&scheduler, &[Grade::Good; 8]);
+        let intervals: Vec<i64> = history.iter().map(|s| s.interval_days).collect();
+        assert_eq!(intervals, vec![1, 6, 15, 38, 95, 238, 365, 365]);
+        // "Good" keeps the ease where it is
+        assert!(history.iter().all(|s| (s.ease - 2.5).abs() < 1e-9));
+        assert_eq!(history.last().unwrap().reps, 8);
+    }
+
+    #[test]
+    fn test_lapse_resets_interval_and_lowers_ease() {
+        let scheduler = Scheduler::default();
+        let grades = [
+            Grade::Good,
+            Grade::Good,
+            Grade::Good,
+            Grade::Forgot,
+            Grade::Good,
+            Grade::Good,
+            Grade::Good,
+        ];
+        let history = simulate(&scheduler, &grades);
+
+        let lapse = history[3];
+        assert_eq!(lapse.interval_days, 1);
+        assert_eq!(lapse.reps, 0);
+        assert_eq!(lapse.lapses, 1);
+        assert!((lapse.ease - 1.96).abs() < 1e-9);
+
+        // Relearning starts over with the lower ease: 1, 6, 6 * 1.96
+        let intervals: Vec<i64> = history[4..].iter().map(|s| s.interval_days).collect();
+        assert_eq!(intervals, vec![1, 6, 12]);
+        assert_eq!(history.last().unwrap().lapses, 1);
+    }
+
+    #[test]
+    fn test_hard_reviews_floor_ease() {
+        let scheduler = Scheduler::default();
+        let history = simulate(&scheduler, &[Grade::Hard; 12]);
+        assert!((history.last().unwrap().ease - 1.3).abs() < 1e-9);
+        assert_eq!(scheduler.difficulty_level(history.last().unwrap()), 5);
+        assert!(
+            history
+                .windows(2)
+                .all(|w| w[1].interval_days >= w[0].interval_days)
+        );
+
+        let easy = simulate(&scheduler, &[Grade::Easy; 3]);
+        assert!(easy[2].ease > 2.5);
+        assert_eq!(scheduler.difficulty_level(&easy[2]), 1);
+    }
+
+    #[test]
+    fn test_due_dates_are_fuzzed() {
+        let scheduler = Scheduler::default();
+        let state = ReviewState {
+            ease: 2.5,
+            interval_days: 100,
+            reps: 4,
+            lapses: 0,
+        };
+        let days: Vec<i64> = (0..50)
+            .map(|word_id| scheduler.due_at(&state, 0, fuzz_seed(word_id, 5)) / DAY_SECS)
+            .collect();
+        assert!(days.iter().all(|d| (95..=105).contains(d)));
+        // Words reviewed together spread over several days
+        let mut distinct = days.clone();
+        distinct.sort();
+        distinct.dedup();
+        assert!(distinct.len() > 3);
+        // The same review always lands on the same day
+        assert_eq!(
+            scheduler.due_at(&state, 0, fuzz_seed(7, 5)) / DAY_SECS,
+            days[7]
+        );
+
+        // Short intervals are not fuzzed
+        let short = ReviewState {
+            interval_days: 1,
+            ..state
+        };
+        assert_eq!(scheduler.due_at(&short, 0, fuzz_seed(1, 1)), DAY_SECS);
+    }
+
+    #[test]
+    fn test_mastery_follows_interval() {
+        let scheduler = Scheduler::default();
+        let history = simulate(&scheduler, &[Grade::Good; 4]);
+        let mastery: Vec<f64> = history.iter().map(|s| scheduler.mastery(s)).collect();
+        // Intervals 1, 6, 15, 38 against the 21-day mastered interval
+        assert!((mastery[1] - 6.0 / 21.0).abs() < 1e-9);
+        assert!(mastery.windows(2).all(|w| w[1] >= w[0]));
+        assert!(!scheduler.is_mastered(&history[2]));
+        assert!(scheduler.is_mastered(&history[3]));
+        assert_eq!(mastery[3], 1.0);
+
+        // A lapse sends the word back to the start
+        let lapse = scheduler.review(&history[3], Grade::Forgot);
+        assert!(!scheduler.is_mastered(&lapse));
+        assert_eq!(scheduler.mastery(&scheduler.new_state()), 0.0);
+    }
+
+    #[test]
+    fn test_grade_levels() {
+        for level in 1..=5 {
+            assert_eq!(Grade::from_level(level).unwrap().level(), level);
+        }
+        assert_eq!(Grade::from_level(0), None);
+        assert_eq!(Grade::from_level(6), None);
+        assert!(!Grade::Wrong.is_pass());
+        assert!(Grade::Hard.is_pass());
+    }
+}
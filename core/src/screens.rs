@@ -12,7 +12,25 @@ pub mod settings;
 // pub use home::{HomeScreen, HomeScreenWidgetRefExt};
 // pub use settings::{SettingsScreen, SettingsScreenWidgetRefExt};
 
-use makepad_widgets::Cx;
+use makepad_widgets::*;
+
+use crate::models::Achievement;
+
+/// Actions any screen may emit to the shell
+#[derive(Clone, Debug, DefaultNone)]
+pub enum AchievementAction {
+    None,
+    /// Achievements earned for the first time, to announce
+    Earned(Vec<Achievement>),
+}
+
+/// A conversation was closed in the database
+#[derive(Clone, Debug, DefaultNone)]
+pub enum SessionAction {
+    None,
+    /// Screens showing learning data should reload it
+    Ended,
+}
 
 pub fn live_design(cx: &mut Cx) {
     println!("==============screens live design");
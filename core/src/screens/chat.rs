@@ -16,7 +16,8 @@ pub struct ChatMessageEntry {
     pub content: String,
     pub timestamp: u64,
     pub is_streaming: bool,
-    pub session_id: Option<String>,
+    /// Voice pipeline turn; streaming updates of one message share it
+    pub turn_id: Option<String>,
 }
 
 impl ChatMessageEntry {
@@ -29,7 +30,7 @@ impl ChatMessageEntry {
                 .map(|d| d.as_millis() as u64)
                 .unwrap_or(0),
             is_streaming: false,
-            session_id: None,
+            turn_id: None,
         }
     }
 }
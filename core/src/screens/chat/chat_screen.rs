@@ -10,13 +10,15 @@ use std::path::PathBuf;
 
 use makepad_widgets::*;
 use colang_widgets::StateChangeListener;
-use colang_widgets::participant_panel::ParticipantPanelWidgetExt;
+use colang_widgets::participant_panel::{ParticipantPanelAction, ParticipantPanelWidgetExt};
 use makepad_component::*;
 
 use super::ChatMessageEntry;
 use super::mofa_hero::{MofaHeroAction, MofaHeroWidgetExt};
+use crate::asset_api::Scene;
 use crate::dora_integration::{DoraCommand, DoraIntegration};
 use crate::log_bridge;
+use crate::models::Achievement;
 
 mod audio_controls;
 mod chat_panel;
@@ -414,6 +416,21 @@ live_design! {
                         }
                     }
 
+                    // Replay teacher replies from the TTS cache
+                    replay_picker = <DropDown> {
+                        width: 160, height: 24
+                        margin: {left: 12}
+                        labels: []
+                        values: []
+                    }
+                    replay_btn = <Button> {
+                        width: Fit, height: 24
+                        padding: {left: 8, right: 8}
+                        text: "重播"
+                        draw_bg: { color: (SLATE_200) border_radius: 6.0 }
+                        draw_text: { color: (TEXT_PRIMARY) text_style: <FONT_MEDIUM>{ font_size: 11.0 } }
+                    }
+
                     // Copy button
                     copy_chat_btn = <Button> {
                         width: 28, height: 24
@@ -805,6 +822,24 @@ pub struct ChatScreen {
     // Participant audio levels for decay animation (matches conference-dashboard)
     #[rust]
     participant_levels: [f64; 2], // 0=myself, 1=teacher
+    // Barge-in: duck and flush teacher playback when the user talks over it
+    #[rust]
+    barge_in: crate::barge_in::BargeInDetector,
+    // Interrupted question_ids; late TTS audio for these is dropped
+    #[rust]
+    interrupted_questions: std::collections::VecDeque<String>,
+    // question_ids of the teacher replies listed in the replay picker
+    #[rust]
+    replay_questions: Vec<String>,
+    // question_id whose playback start was last recorded for latency metrics
+    #[rust]
+    playing_question: Option<String>,
+    // Achievements evaluated when the conversation ended
+    #[rust]
+    achievements_rx: Option<std::sync::mpsc::Receiver<Vec<Achievement>>>,
+    // Scene picked in the scene center; the next dataflow practices it
+    #[rust]
+    scene: Option<Scene>,
 }
 
 impl Widget for ChatScreen {
@@ -816,6 +851,15 @@ impl Widget for ChatScreen {
             // Initialize log bridge to capture Rust logs
             log_bridge::init();
             self.init_audio(cx);
+            // Teacher replies are replayed from the TTS audio cache
+            self.view
+                .participant_panel(ids!(
+                    hidden_compat
+                        .participant_container
+                        .participant_bar
+                        .teacher_panel
+                ))
+                .set_replay_enabled(cx, true);
             self.audio_initialized = true;
         }
 
@@ -833,27 +877,48 @@ impl Widget for ChatScreen {
                 }
             }
 
-            // Poll for captured audio chunks and send to dora
-            if let Some(ref audio_manager) = self.audio_manager {
-                let chunks = audio_manager.poll_audio_chunks();
-                if !chunks.is_empty() {
+            // Run captured audio through the VAD: speech is streamed while the user
+            // talks, complete utterances are sent once endpointed
+            if let Some(ref mut audio_manager) = self.audio_manager {
+                let utterances = audio_manager.poll_utterances();
+                let chunks = audio_manager.poll_speech_chunks();
+                if let Some(ref dora) = self.dora_integration {
+                    for chunk in chunks {
+                        dora.send_speech_chunk(chunk);
+                    }
+                }
+                if !utterances.is_empty() {
                     if let Some(ref dora) = self.dora_integration {
-                        for chunk in chunks {
+                        for utterance in utterances {
                             ::log::debug!(
-                                "Sending mic audio chunk: {} samples at {}Hz",
-                                chunk.samples.len(),
-                                chunk.sample_rate
+                                "Sending utterance: {} samples at {}Hz ({}-{}ms)",
+                                utterance.samples.len(),
+                                utterance.sample_rate,
+                                utterance.start_ms,
+                                utterance.end_ms
                             );
-                            dora.send_audio(chunk.samples, chunk.sample_rate);
+                            dora.send_utterance(utterance);
                         }
                     }
                 }
+
+                let speaking = audio_manager.is_speaking();
+                self.handle_barge_in(speaking);
+                self.view
+                    .participant_panel(ids!(
+                        hidden_compat
+                            .participant_container
+                            .participant_bar
+                            .myself_panel
+                    ))
+                    .set_speaking(cx, speaking);
             }
         }
 
         // Handle dora timer for polling dora events
         if self.dora_timer.is_event(event).is_some() {
             self.poll_dora_events(cx);
+            self.poll_achievements(cx, scope);
         }
 
         // Handle copy chat feedback timer - reset animation
@@ -902,6 +967,28 @@ impl Widget for ChatScreen {
                 }
                 MofaHeroAction::None => {}
             }
+            if let ParticipantPanelAction::ReplayClicked = action.as_widget_action().cast() {
+                self.replay_teacher(cx, None);
+            }
+        }
+
+        // Handle replay controls
+        if self
+            .view
+            .button(ids!(main_layout.left_column.chat_header.replay_btn))
+            .clicked(actions)
+        {
+            self.replay_teacher(cx, None);
+        }
+
+        if let Some(selected) = self
+            .view
+            .drop_down(ids!(main_layout.left_column.chat_header.replay_picker))
+            .selected(actions)
+        {
+            if let Some(question_id) = self.replay_questions.get(selected).cloned() {
+                self.replay_teacher(cx, Some(question_id));
+            }
         }
 
         // Handle log filters
@@ -1064,6 +1151,12 @@ impl ChatScreenRef {
             ::log::debug!("ChatScreen timers started");
         }
     }
+    /// Practice a scene in the next conversation, or free talk with `None`
+    pub fn set_scene(&self, scene: Option<Scene>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.scene = scene;
+        }
+    }
 }
 
 impl StateChangeListener for ChatScreenRef {
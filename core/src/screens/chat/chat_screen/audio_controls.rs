@@ -2,10 +2,12 @@
 //!
 //! Handles audio device selection, mic monitoring, and level visualization.
 
-use makepad_widgets::*;
 use makepad_component::*;
+use makepad_widgets::*;
 
 use super::ChatScreen;
+use crate::audio_player::TEACHER_PARTICIPANT_IDX;
+use crate::barge_in::BargeInAction;
 
 impl ChatScreen {
     /// Initialize audio manager and timers (UI controls removed)
@@ -59,6 +61,48 @@ impl ChatScreen {
         }
     }
 
+    /// Duck, then interrupt teacher playback while the user talks over it
+    pub(super) fn handle_barge_in(&mut self, speaking: bool) {
+        let Some(player) = self.audio_player.clone() else {
+            return;
+        };
+        let teacher_playing = player.buffer_seconds() > 0.0
+            && player.current_participant_idx() == Some(TEACHER_PARTICIPANT_IDX);
+
+        let action = self
+            .barge_in
+            .update(speaking, teacher_playing, std::time::Instant::now());
+        match action {
+            Some(BargeInAction::Duck) => {
+                player.set_gain(self.barge_in.config().duck_gain);
+            }
+            Some(BargeInAction::Restore) => {
+                player.set_gain(1.0);
+            }
+            Some(BargeInAction::Interrupt) => {
+                let question_id = player.current_question_id();
+                ::log::info!("Barge-in: interrupting question_id={:?}", question_id);
+
+                match question_id {
+                    Some(ref qid) => {
+                        player.flush_question(qid);
+                        self.interrupted_questions.push_back(qid.clone());
+                        if self.interrupted_questions.len() > 16 {
+                            self.interrupted_questions.pop_front();
+                        }
+                    }
+                    None => player.reset(),
+                }
+                player.set_gain(1.0);
+
+                if let Some(ref dora) = self.dora_integration {
+                    dora.send_interrupt(question_id);
+                }
+            }
+            None => {}
+        }
+    }
+
     /// Select input device for mic monitoring
     pub(super) fn select_input_device(&mut self, _cx: &mut Cx, device_name: &str) {
         if let Some(ref mut audio_manager) = self.audio_manager {
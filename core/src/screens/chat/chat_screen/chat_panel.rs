@@ -7,6 +7,9 @@ use makepad_component::*;
 
 use super::{ChatMessageEntry, ChatScreen};
 
+/// Teacher replies listed in the replay picker
+const MAX_REPLAY_ITEMS: usize = 20;
+
 impl ChatScreen {
     /// Send prompt to dora
     pub(super) fn send_prompt(&mut self, cx: &mut Cx) {
@@ -178,9 +181,60 @@ impl ChatScreen {
             self.last_chat_count = chat_count;
         }
 
+        self.refresh_replay_picker(cx);
         self.view.redraw(cx);
     }
 
+    /// Replay a teacher reply from the TTS cache (latest when `None`)
+    pub(super) fn replay_teacher(&mut self, cx: &mut Cx, question_id: Option<String>) {
+        let question_id = question_id.or_else(|| self.replay_questions.first().cloned());
+
+        match self.dora_integration {
+            Some(ref dora) if dora.is_running() => {
+                dora.send_replay(question_id.clone());
+                self.add_log(
+                    cx,
+                    &format!(
+                        "[INFO] [App] Replaying teacher reply {}",
+                        question_id.as_deref().unwrap_or("(latest)")
+                    ),
+                );
+            }
+            _ => {
+                self.add_log(cx, "[WARN] [App] Dataflow not running - replay not sent");
+            }
+        }
+    }
+
+    /// List the latest finalized teacher replies (newest first) in the replay picker
+    fn refresh_replay_picker(&mut self, cx: &mut Cx) {
+        let replies: Vec<&ChatMessageEntry> = self
+            .chat_messages
+            .iter()
+            .rev()
+            .filter(|m| m.sender != "You" && m.sender != "Myself")
+            .filter(|m| m.turn_id.is_some())
+            .take(MAX_REPLAY_ITEMS)
+            .collect();
+
+        let questions: Vec<String> = replies.iter().filter_map(|m| m.turn_id.clone()).collect();
+        if questions == self.replay_questions {
+            return;
+        }
+
+        let labels: Vec<String> = replies
+            .iter()
+            .map(|m| {
+                let preview: String = m.content.chars().take(24).collect();
+                format!("{} {}", Self::format_timestamp(m.timestamp), preview)
+            })
+            .collect();
+        self.view
+            .drop_down(ids!(main_layout.left_column.chat_header.replay_picker))
+            .set_labels(cx, labels);
+        self.replay_questions = questions;
+    }
+
     /// Format Unix timestamp (milliseconds) to readable HH:MM:SS format
     /// Matches conference-dashboard's get_timestamp() format
     pub(super) fn format_timestamp(timestamp_ms: u64) -> String {
@@ -3,15 +3,29 @@
 //! Handles dataflow control, event processing, and participant panel updates.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::TryRecvError;
 
-use makepad_widgets::*;
+use dora_bridge::{
+    AsrBackend, DataflowParser, DataflowProfile, NodeState, TtsBackend, teacher_models,
+};
 use makepad_component::*;
+use makepad_widgets::*;
 
 use super::{ChatMessageEntry, ChatScreen};
-use crate::dora_integration::{DoraEvent, DoraIntegration};
+use crate::achievements;
+use crate::audio_player::TEACHER_PARTICIPANT_IDX;
+use crate::db::Database;
+use crate::dora_integration::{DoraEvent, DoraIntegration, default_dataflow_path};
 use crate::models::Preferences;
 use crate::screens::chat::mofa_hero::{ConnectionStatus, MofaHeroWidgetExt};
+use crate::screens::{AchievementAction, SessionAction};
+
+/// Dataflow generated from the provider choices, next to learning.yml
+const PROFILE_DATAFLOW_FILE: &str = "learning.profile.yml";
+
+/// english-teacher model routes, next to learning.yml
+const TEACHER_CONFIG_FILE: &str = "teacher_config.toml";
 
 impl ChatScreen {
     // =====================================================
@@ -32,25 +46,7 @@ impl ChatScreen {
         self.dora_timer = cx.start_interval(0.1);
 
         // Look for default dataflow relative to current working directory
-        // Check multiple possible locations
-        let dataflow_path = std::env::current_dir().ok().and_then(|cwd| {
-            // First try: apps/colang/dataflow/learning.yml (when running from workspace root)
-            let app_path = cwd
-                .join("apps")
-                .join("colang")
-                .join("dataflow")
-                .join("learning.yml");
-            if app_path.exists() {
-                return Some(app_path);
-            }
-            // Second try: dataflow/learning.yml (when running from app directory)
-            let local_path = cwd.join("dataflow").join("learning.yml");
-            if local_path.exists() {
-                return Some(local_path);
-            }
-            None
-        });
-        self.dataflow_path = dataflow_path;
+        self.dataflow_path = default_dataflow_path();
 
         ::log::info!(
             "Dora integration initialized, dataflow: {:?}",
@@ -85,6 +81,28 @@ impl ChatScreen {
         }
     }
 
+    /// Announce the achievements earned by the last conversation, and that
+    /// its data is final once they are evaluated
+    pub(super) fn poll_achievements(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        let Some(rx) = &self.achievements_rx else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(earned) => {
+                self.achievements_rx = None;
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    AchievementAction::Earned(earned),
+                );
+            }
+            Err(TryRecvError::Empty) => return,
+            // Nothing new was earned
+            Err(TryRecvError::Disconnected) => self.achievements_rx = None,
+        }
+        cx.widget_action(self.widget_uid(), &scope.path, SessionAction::Ended);
+    }
+
     /// Poll for dora events and update UI
     pub(super) fn poll_dora_events(&mut self, cx: &mut Cx) {
         // Get dora events if integration is running
@@ -102,6 +120,10 @@ impl ChatScreen {
                         cx,
                         &format!("[INFO] [App] Dataflow started2: {}", dataflow_id),
                     );
+                    // A scene conversation is recorded as a scenario session
+                    if let Some(scene) = &self.scene {
+                        achievements::spawn_start_scene(scene.clone());
+                    }
                     self.view
                         .mofa_hero(ids!(left_column.mofa_hero))
                         .set_connection_status(cx, ConnectionStatus::Connected);
@@ -109,6 +131,10 @@ impl ChatScreen {
                 DoraEvent::DataflowStopped => {
                     ::log::info!("Dataflow stopped");
                     self.add_log(cx, "[INFO] [App] Dataflow stopped");
+                    // The conversation is over; close it and see what it earned
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    self.achievements_rx = Some(rx);
+                    achievements::spawn_end_conversation(tx);
                     self.view
                         .mofa_hero(ids!(left_column.mofa_hero))
                         .set_running(cx, false);
@@ -140,52 +166,41 @@ impl ChatScreen {
                 }
                 DoraEvent::ChatReceived { message } => {
                     // Handle streaming message consolidation
-                    // Match by BOTH sender AND session_id to avoid confusion between participants
+                    // Match by BOTH sender AND turn_id: the user transcript and the teacher
+                    // reply of a turn share the turn id
                     let sender = message.sender.clone();
-                    let session_id = message.session_id.clone();
+                    let turn_id = message.turn_id.clone();
 
                     // Debug logging for chat messages
                     ::log::info!(
-                        "[Chat] sender={}, session_id={:?}, is_streaming={}, content_len={}, pending_count={}, finalized_count={}",
+                        "[Chat] sender={}, turn_id={:?}, is_streaming={}, content_len={}, pending_count={}, finalized_count={}",
                         sender,
-                        session_id,
+                        turn_id,
                         message.is_streaming,
                         message.content.len(),
                         self.pending_streaming_messages.len(),
                         self.chat_messages.len()
                     );
 
+                    let entry = ChatMessageEntry {
+                        sender: sender.clone(),
+                        content: message.content.clone(),
+                        timestamp: message.timestamp,
+                        is_streaming: message.is_streaming,
+                        turn_id: turn_id.clone(),
+                    };
+
                     if message.is_streaming {
                         // Update or create pending streaming message
-                        let entry = ChatMessageEntry {
-                            sender: sender.clone(),
-                            content: message.content.clone(),
-                            timestamp: message.timestamp,
-                            is_streaming: true,
-                            session_id: session_id.clone(),
-                        };
-
-                        // Find existing pending message with same sender AND session_id
-                        // (or same sender if session_id is "unknown")
-                        let found = self.pending_streaming_messages.iter_mut()
-                            .find(|m| {
-                                m.sender == sender && (
-                                    // Match by session_id if both have real session_ids
-                                    (m.session_id.as_ref().map(|s| s != "unknown").unwrap_or(false)
-                                        && session_id.as_ref().map(|s| s != "unknown").unwrap_or(false)
-                                        && m.session_id == session_id)
-                                    ||
-                                    // For "unknown" session_ids, just match by sender
-                                    (m.session_id.as_ref().map(|s| s == "unknown").unwrap_or(true)
-                                        && session_id.as_ref().map(|s| s == "unknown").unwrap_or(true))
-                                )
-                            });
+                        let found = self
+                            .pending_streaming_messages
+                            .iter_mut()
+                            .find(|m| m.sender == sender && m.turn_id == turn_id);
 
                         if let Some(pending) = found {
                             // Update existing pending message
                             pending.content = entry.content;
                             pending.timestamp = entry.timestamp;
-                            pending.session_id = entry.session_id;
                         } else {
                             // Add new pending message
                             self.pending_streaming_messages.push(entry);
@@ -194,29 +209,9 @@ impl ChatScreen {
                         // Update display with pending messages (shown but not finalized)
                         self.update_chat_display(cx);
                     } else {
-                        // Streaming complete - finalize the message
-                        let entry = ChatMessageEntry {
-                            sender: sender.clone(),
-                            content: message.content.clone(),
-                            timestamp: message.timestamp,
-                            is_streaming: false,
-                            session_id: session_id.clone(),
-                        };
-
-                        // Remove from pending - match by sender AND session_id
-                        self.pending_streaming_messages.retain(|m| {
-                            !(m.sender == sender
-                                && (m.session_id == session_id
-                                    || (m
-                                        .session_id
-                                        .as_ref()
-                                        .map(|s| s == "unknown")
-                                        .unwrap_or(true)
-                                        && session_id
-                                            .as_ref()
-                                            .map(|s| s == "unknown")
-                                            .unwrap_or(true))))
-                        });
+                        // Streaming complete - remove from pending and finalize the message
+                        self.pending_streaming_messages
+                            .retain(|m| !(m.sender == sender && m.turn_id == turn_id));
 
                         // Add to finalized messages
                         self.chat_messages.push(entry);
@@ -233,7 +228,43 @@ impl ChatScreen {
                     let log_line = format!("[{}] [{}] {}", level_str, entry.node_id, entry.message);
                     self.add_log(cx, &log_line);
                 }
+                DoraEvent::NodeStatusChanged { node_id, state } => {
+                    let status = match state {
+                        NodeState::Running => {
+                            self.add_log(cx, &format!("[INFO] [Dora] {} running", node_id));
+                            ConnectionStatus::Connected
+                        }
+                        NodeState::Failed { reason } => {
+                            self.add_log(
+                                cx,
+                                &format!("[ERROR] [Dora] {} exited: {}", node_id, reason),
+                            );
+                            ConnectionStatus::NodeFailed { node_id, reason }
+                        }
+                        NodeState::DataflowRestarting { attempt, delay } => {
+                            self.add_log(
+                                cx,
+                                &format!(
+                                    "[WARN] [Dora] Restarting dataflow for {} in {:?} (attempt {})",
+                                    node_id, delay, attempt
+                                ),
+                            );
+                            ConnectionStatus::Restarting { node_id, attempt }
+                        }
+                    };
+                    self.view
+                        .mofa_hero(ids!(left_column.mofa_hero))
+                        .set_connection_status(cx, status);
+                }
                 DoraEvent::AudioReceived { data } => {
+                    // Drop audio still arriving for a reply the user interrupted
+                    if data
+                        .question_id
+                        .as_ref()
+                        .is_some_and(|qid| self.interrupted_questions.contains(qid))
+                    {
+                        continue;
+                    }
                     // Forward to audio player for playback with question_id for smart reset
                     if let Some(ref player) = self.audio_player {
                         player.write_audio_with_question(
@@ -266,6 +297,19 @@ impl ChatScreen {
             (false, None, Vec::new())
         };
 
+        // First audible moment of a reply closes its latency measurement
+        // (the user's own audio shares the turn id, so only count the teacher)
+        let playing_question = self
+            .audio_player
+            .as_ref()
+            .filter(|_| is_playing && active_idx == Some(TEACHER_PARTICIPANT_IDX))
+            .and_then(|player| player.current_question_id())
+            .filter(|qid| self.playing_question.as_ref() != Some(qid));
+        if let Some(qid) = playing_question {
+            crate::metrics::record_playback_start(&qid);
+            self.playing_question = Some(qid);
+        }
+
         {
             // Calculate band levels from waveform data (same as conference-dashboard)
             let band_levels: [f32; 8] = if waveform_data.is_empty() {
@@ -387,17 +431,20 @@ impl ChatScreen {
         self.init_dora(cx);
 
         // Load API keys from preferences
-        let env_vars = self.load_api_keys_from_preferences();
+        let mut env_vars = self.load_api_keys_from_preferences();
+        env_vars.extend(Self::data_location_env());
 
         // Log which keys are available
         let has_openai = env_vars.contains_key("OPENAI_API_KEY");
         let has_deepseek = env_vars.contains_key("DEEPSEEK_API_KEY");
+        let has_volcano = env_vars.contains_key("DOUBAO_ACCESS_TOKEN");
         self.add_log(
             cx,
             &format!(
-                "[INFO] [App] API Keys: OpenAI={}, DeepSeek={}",
+                "[INFO] [App] API Keys: OpenAI={}, DeepSeek={}, Volcano={}",
                 if has_openai { "✓" } else { "✗" },
-                if has_deepseek { "✓" } else { "✗" }
+                if has_deepseek { "✓" } else { "✗" },
+                if has_volcano { "✓" } else { "✗" }
             ),
         );
 
@@ -418,6 +465,53 @@ impl ChatScreen {
             return;
         }
 
+        // Generate the dataflow from the default providers in settings,
+        // falling back to the static learning.yml
+        let teacher_config = dataflow_path.with_file_name(TEACHER_CONFIG_FILE);
+        let dataflow_path = match Self::dataflow_profile(&teacher_config)
+            .write(dataflow_path.with_file_name(PROFILE_DATAFLOW_FILE))
+        {
+            Ok(path) => path,
+            Err(e) => {
+                self.add_log(
+                    cx,
+                    &format!("[WARN] [App] Failed to generate dataflow profile: {}", e),
+                );
+                dataflow_path
+            }
+        };
+
+        // Check credentials before starting, so missing ones can be listed
+        match DataflowParser::parse(&dataflow_path) {
+            Ok(parsed) => {
+                let missing = parsed.missing_env_requirements(&env_vars);
+                if !missing.is_empty() {
+                    for req in &missing {
+                        self.add_log(
+                            cx,
+                            &format!(
+                                "[ERROR] [App] Missing {}{} (used by {})",
+                                req.key,
+                                if req.secret { " (credential)" } else { "" },
+                                req.used_by.join(", ")
+                            ),
+                        );
+                    }
+                    self.add_log(
+                        cx,
+                        "[ERROR] [App] Configure the missing values in Settings > Providers",
+                    );
+                    self.view
+                        .mofa_hero(ids!(left_column.mofa_hero))
+                        .set_connection_status(cx, ConnectionStatus::Failed);
+                    return;
+                }
+            }
+            Err(e) => {
+                self.add_log(cx, &format!("[WARN] [App] Failed to parse dataflow: {}", e));
+            }
+        }
+
         self.add_log(
             cx,
             &format!("[INFO] [App] Starting dataflow: {:?}", dataflow_path),
@@ -431,8 +525,10 @@ impl ChatScreen {
             .mofa_hero(ids!(left_column.mofa_hero))
             .set_connection_status(cx, ConnectionStatus::Connecting);
 
-        // Start dataflow with environment variables
+        // Start dataflow with environment variables, restarting it after a node
+        // crash as configured in preferences
         if let Some(ref dora) = self.dora_integration {
+            dora.set_restart_policy(Preferences::load().restart.policy());
             if !dora.start_dataflow_with_env(&dataflow_path, env_vars) {
                 self.add_log(cx, "[ERROR] [App] Failed to send start command");
                 self.view
@@ -465,6 +561,67 @@ impl ChatScreen {
         // to confirm the dataflow actually stopped
     }
 
+    /// Node data directories under the user's data location
+    fn data_location_env() -> HashMap<String, String> {
+        let mut env_vars = HashMap::new();
+        env_vars.insert(
+            "TTS_CACHE_DIR".to_string(),
+            crate::screens::settings::tts_cache_dir()
+                .to_string_lossy()
+                .to_string(),
+        );
+        env_vars.insert("DATABASE_URL".to_string(), Database::default_url());
+        env_vars
+    }
+
+    /// Dataflow profile from the default ASR/TTS/chat providers
+    fn dataflow_profile(teacher_config: &Path) -> DataflowProfile {
+        let prefs = Preferences::load();
+        let mut profile = DataflowProfile::default();
+
+        if let Some(id) = prefs.default_asr_provider.as_deref() {
+            match AsrBackend::from_provider_id(id) {
+                Some(asr) => profile.asr = asr,
+                None => ::log::warn!("No ASR node for provider {}, using default", id),
+            }
+        }
+        if let Some(id) = prefs.default_tts_provider.as_deref() {
+            match TtsBackend::from_provider_id(id) {
+                Some(tts) => profile.tts = tts,
+                None => ::log::warn!("No TTS node for provider {}, using default", id),
+            }
+        }
+
+        // Teacher model: first model of the default chat provider that has a
+        // route in teacher_config.toml, otherwise keep its default_model
+        if let Some(provider) = prefs
+            .default_chat_provider
+            .as_deref()
+            .and_then(|id| prefs.get_provider(id))
+        {
+            let routed = match teacher_models(teacher_config) {
+                Ok(models) => models,
+                Err(e) => {
+                    ::log::warn!("Failed to read teacher models: {}", e);
+                    Vec::new()
+                }
+            };
+            profile.teacher_model = provider
+                .models
+                .iter()
+                .find(|model| routed.contains(model))
+                .cloned();
+            if profile.teacher_model.is_none() {
+                ::log::warn!(
+                    "No teacher route for provider {} models, using default",
+                    provider.id
+                );
+            }
+        }
+
+        profile
+    }
+
     /// Load API keys from preferences
     pub(super) fn load_api_keys_from_preferences(&self) -> HashMap<String, String> {
         let mut env_vars = HashMap::new();
@@ -499,6 +656,23 @@ impl ChatScreen {
             }
         }
 
+        // Get ZhipuAI BigModel API key (bigmodel-asr/tts)
+        if let Some(api_key) = prefs.api_key("bigmodel") {
+            env_vars.insert("BIGMODEL_API_KEY".to_string(), api_key.to_string());
+        }
+
+        // Get Volcano Engine (Doubao) API key and speech credentials
+        if let Some(provider) = prefs.get_provider("volcano") {
+            if let Some(ref api_key) = provider.api_key {
+                if !api_key.is_empty() {
+                    env_vars.insert("DOUBAO_API_KEY".to_string(), api_key.clone());
+                }
+            }
+            if let Some(ref volcano) = provider.volcano {
+                env_vars.extend(volcano.env_vars());
+            }
+        }
+
         env_vars
     }
 }
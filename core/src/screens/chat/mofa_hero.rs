@@ -261,6 +261,16 @@ pub enum ConnectionStatus {
     Stopping,
     Stopped,
     Failed,
+    /// A dataflow node exited and won't be restarted
+    NodeFailed {
+        node_id: String,
+        reason: String,
+    },
+    /// A dataflow node exited; the dataflow is being restarted
+    Restarting {
+        node_id: String,
+        attempt: u32,
+    },
 }
 
 impl Widget for MofaHero {
@@ -308,7 +318,8 @@ impl MofaHero {
     pub fn set_connection_status(&mut self, cx: &mut Cx, status: ConnectionStatus) {
         self.connection_status = status.clone();
 
-        let (text, color, hint) = match status {
+        let node_hint;
+        let (text, color, hint) = match &status {
             ConnectionStatus::Ready => ("Ready", vec4(0.133, 0.773, 0.373, 1.0), "等待启动"),
             ConnectionStatus::Connecting => (
                 "Connecting",
@@ -327,6 +338,18 @@ impl MofaHero {
             ConnectionStatus::Failed => {
                 ("Failed", vec4(0.937, 0.267, 0.267, 1.0), "连接异常，请重试")
             }
+            ConnectionStatus::NodeFailed { node_id, reason } => {
+                node_hint = format!("{} 已退出：{}", node_id, reason);
+                ("Failed", vec4(0.937, 0.267, 0.267, 1.0), node_hint.as_str())
+            }
+            ConnectionStatus::Restarting { node_id, attempt } => {
+                node_hint = format!("{} 已退出，正在重启数据流（第 {} 次）", node_id, attempt);
+                (
+                    "Restarting",
+                    vec4(0.922, 0.533, 0.196, 1.0),
+                    node_hint.as_str(),
+                )
+            }
         };
 
         self.view
@@ -1,3 +1,4 @@
+mod home_data;
 pub mod home_screen;
 
 use makepad_widgets::Cx;
@@ -0,0 +1,64 @@
+//! Learning data behind the Home screen's stats card
+
+use std::sync::mpsc::Sender;
+
+use chrono::{Duration, Local, NaiveDate};
+
+use crate::daily_stats;
+use crate::db::Database;
+use crate::models::{DailyStat, ReviewStats, Streaks};
+
+pub struct HomeData {
+    /// Daily stats of the current week, Monday first
+    pub week: [Option<DailyStat>; 7],
+    pub streaks: Streaks,
+    pub review: ReviewStats,
+}
+
+impl HomeData {
+    /// Minutes studied so far this week
+    pub fn week_minutes(&self) -> i64 {
+        self.week.iter().flatten().map(|s| s.minutes_studied).sum()
+    }
+}
+
+/// Load a [`HomeData`] snapshot in the background, catching `daily_stats` up
+/// to today first
+pub fn spawn_load(tx: Sender<Result<HomeData, String>>) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(load()).map_err(|e| e.to_string());
+        let _ = tx.send(result);
+    });
+}
+
+async fn load() -> Result<HomeData, sqlx::Error> {
+    let db = Database::open_default().await?;
+    let today = Local::now().date_naive();
+    let monday = daily_stats::week_start(today);
+
+    let streaks = daily_stats::update(&db, today).await?;
+
+    let mut week: [Option<DailyStat>; 7] = Default::default();
+    for stat in db
+        .get_daily_stats(monday, monday + Duration::days(6))
+        .await?
+    {
+        let day = stat
+            .stat_date
+            .parse::<NaiveDate>()
+            .map(|date| (date - monday).num_days())
+            .unwrap_or(-1);
+        if let Some(slot) = usize::try_from(day).ok().and_then(|day| week.get_mut(day)) {
+            *slot = Some(stat);
+        }
+    }
+
+    Ok(HomeData {
+        week,
+        streaks,
+        review: db
+            .get_review_stats(daily_stats::local_timestamp(monday))
+            .await?,
+    })
+}
@@ -9,9 +9,13 @@
 //! - AI insights section
 //! - Recommended scenes
 
+use std::sync::mpsc;
+
 use makepad_widgets::*;
 use makepad_component::*;
 
+use super::home_data::{HomeData, spawn_load};
+
 live_design! {
     use link::theme::*;
     use link::shaders::*;
@@ -505,17 +509,17 @@ live_design! {
             spacing: 8
 
             stat_minutes = <StatCard> {
-                stat_value = { text: "47" }
-                stat_label = { text: "本周对话(分钟)" }
+                stat_value = { text: "0" }
+                stat_label = { text: "本周学习(分钟)" }
             }
 
             stat_vocab = <StatCardSuccess> {
-                stat_value = { text: "156" }
+                stat_value = { text: "0" }
                 stat_label = { text: "已掌握词汇" }
             }
 
             stat_review = <StatCardWarning> {
-                stat_value = { text: "23" }
+                stat_value = { text: "0" }
                 stat_label = { text: "待复习" }
             }
 
@@ -525,13 +529,16 @@ live_design! {
             }
         }
 
-        // Weekly chart placeholder
-        chart_placeholder = <PanelBase> {
+        // Study days this week and the current streak
+        week_panel = <PanelBase> {
             width: Fill, height: 40
-            padding: {left: 12}
+            padding: {left: 12, right: 12}
+            flow: Right
             align: {y: 0.5}
 
-            <MutedText> { text: "本周学习时长分布 ▓▓▓░▓▓▓" }
+            week_chart = <MutedText> { text: "本周学习 ░░░░░░░" }
+            <View> { width: Fill }
+            streak_label = <MutedText> { text: "🔥 连续 0 天" }
         }
     }
 
@@ -656,12 +663,38 @@ live_design! {
 pub struct HomeScreen {
     #[deref]
     view: View,
+
+    #[rust]
+    data_loaded: bool,
+
+    #[rust]
+    fetch_rx: Option<mpsc::Receiver<Result<HomeData, String>>>,
 }
 
 impl Widget for HomeScreen {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
         self.widget_match_event(cx, event, scope);
+
+        let mut result = None;
+        if let Some(rx) = &self.fetch_rx {
+            while let Ok(r) = rx.try_recv() {
+                result = Some(r);
+            }
+        }
+        match result {
+            Some(Ok(data)) => self.update_stats(cx, &data),
+            Some(Err(e)) => ::log::warn!("Failed to load home stats: {}", e),
+            None => {}
+        }
+
+        // Trigger initial data load on first draw
+        if let Event::Draw(_) = event {
+            if !self.data_loaded {
+                self.data_loaded = true;
+                self.load_data();
+            }
+        }
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
@@ -735,6 +768,43 @@ impl WidgetMatchEvent for HomeScreen {
 }
 
 impl HomeScreen {
+    /// Load the stats card data from the local learning database
+    fn load_data(&mut self) {
+        let (tx, rx) = mpsc::channel();
+        self.fetch_rx = Some(rx);
+        spawn_load(tx);
+    }
+
+    fn update_stats(&mut self, cx: &mut Cx, data: &HomeData) {
+        self.label(ids!(stats_card.stats_grid.stat_minutes.stat_value))
+            .set_text(cx, &data.week_minutes().to_string());
+        self.label(ids!(stats_card.stats_grid.stat_vocab.stat_value))
+            .set_text(cx, &data.review.mastered_words.to_string());
+        self.label(ids!(stats_card.stats_grid.stat_review.stat_value))
+            .set_text(cx, &data.review.due_words.to_string());
+
+        // One block per day, Monday first
+        let days: String = data
+            .week
+            .iter()
+            .map(|day| match day {
+                Some(stat) if stat.is_active() => '▓',
+                _ => '░',
+            })
+            .collect();
+        self.label(ids!(stats_card.week_panel.week_chart))
+            .set_text(cx, &format!("本周学习 {}", days));
+        self.label(ids!(stats_card.week_panel.streak_label)).set_text(
+            cx,
+            &format!(
+                "🔥 连续 {} 天 · 最长 {} 天",
+                data.streaks.current, data.streaks.longest
+            ),
+        );
+
+        self.view.redraw(cx);
+    }
+
     /// Apply dark mode to all components
     pub fn apply_dark_mode(&mut self, cx: &mut Cx, dark_mode: f64) {
         // Apply to main background
@@ -770,3 +840,14 @@ impl HomeScreen {
             .apply_over(cx, dark_mode_update);
     }
 }
+
+impl HomeScreenRef {
+    /// Reload the stats card, e.g. after a session ended
+    pub fn refresh_data(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.data_loaded = true;
+            inner.load_data();
+            inner.view.redraw(cx);
+        }
+    }
+}
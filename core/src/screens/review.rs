@@ -2,6 +2,7 @@ mod components;
 mod due_screen;
 mod mastered_screen;
 mod mistakes_screen;
+mod review_data;
 pub mod review_screen;
 mod stats_screen;
 
@@ -299,19 +299,19 @@ live_design! {
         flow: Right
         spacing: 12
         stat_due = <StatCardOrange> {
-            stat_value = { text: "23" }
+            stat_value = { text: "0" }
             stat_label = { text: "待复习" }
         }
         stat_mastered = <StatCardGreen> {
-            stat_value = { text: "156" }
+            stat_value = { text: "0" }
             stat_label = { text: "已掌握" }
         }
         stat_mistakes = <StatCardRed> {
-            stat_value = { text: "8" }
+            stat_value = { text: "0" }
             stat_label = { text: "易错点" }
         }
         stat_accuracy = <StatCardBlue> {
-            stat_value = { text: "85%" }
+            stat_value = { text: "—" }
             stat_label = { text: "正确率" }
         }
     }
@@ -425,6 +425,42 @@ live_design! {
         }
     }
 
+    // Flashcard for review practice: word and context up front, answer revealed on demand
+    pub Flashcard = <PanelBase> {
+        width: Fill, height: Fit
+        padding: 24
+        flow: Down
+        align: {x: 0.5}
+        spacing: 12
+        card_word = <Label> {
+            draw_text: {
+                instance dark_mode: 0.0
+                text_style: <FONT_BOLD>{ font_size: 24.0 }
+                fn get_color(self) -> vec4 {
+                    return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+                }
+            }
+        }
+        card_context = <MutedText> {}
+        answer = <View> {
+            visible: false
+            width: Fill, height: Fit
+            flow: Down
+            align: {x: 0.5}
+            spacing: 6
+            answer_zh = <Label> {
+                draw_text: {
+                    instance dark_mode: 0.0
+                    text_style: <FONT_MEDIUM>{ font_size: 14.0 }
+                    fn get_color(self) -> vec4 {
+                        return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+                    }
+                }
+            }
+            answer_en = <MutedText> {}
+        }
+    }
+
     // Color constants for tip banners
     pub TIP_AMBER_BG = #fffbDD
     pub TIP_AMBER_TEXT = #924000
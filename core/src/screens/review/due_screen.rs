@@ -16,7 +16,7 @@ live_design! {
         flow: Down
         spacing: 16
 
-        // Word cards grid - 2 columns
+        // Word card slots - 2 columns, filled from the learning database
         cards_grid = <View> {
             width: Fill, height: Fit
             flow: Right
@@ -27,50 +27,9 @@ live_design! {
                 flow: Down
                 spacing: 16
 
-                card1 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "accommodation" }
-                        due_badge = {
-                            visible: true
-                            draw_bg: { urgent: 1.0 }
-                            due_text = { text: "Now", draw_text: { urgent: 1.0 } }
-                        }
-                    }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.6 } }
-                        progress_label = { text: "60%" }
-                    }
-                }
-
-                card3 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "reservation" }
-                        due_badge = {
-                            visible: true
-                            draw_bg: { urgent: 0.0 }
-                            due_text = { text: "2小时", draw_text: { urgent: 0.0 } }
-                        }
-                    }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.7 } }
-                        progress_label = { text: "70%" }
-                    }
-                }
-
-                card5 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "schedule" }
-                        due_badge = {
-                            visible: true
-                            draw_bg: { urgent: 0.0 }
-                            due_text = { text: "4小时", draw_text: { urgent: 0.0 } }
-                        }
-                    }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.55 } }
-                        progress_label = { text: "55%" }
-                    }
-                }
+                card1 = <WordCard> { visible: false }
+                card3 = <WordCard> { visible: false }
+                card5 = <WordCard> { visible: false }
             }
 
             col2 = <View> {
@@ -78,50 +37,9 @@ live_design! {
                 flow: Down
                 spacing: 16
 
-                card2 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "itinerary" }
-                        due_badge = {
-                            visible: true
-                            draw_bg: { urgent: 1.0 }
-                            due_text = { text: "Now", draw_text: { urgent: 1.0 } }
-                        }
-                    }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.45 } }
-                        progress_label = { text: "45%" }
-                    }
-                }
-
-                card4 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "appointment" }
-                        due_badge = {
-                            visible: true
-                            draw_bg: { urgent: 0.0 }
-                            due_text = { text: "3小时", draw_text: { urgent: 0.0 } }
-                        }
-                    }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.65 } }
-                        progress_label = { text: "65%" }
-                    }
-                }
-
-                card6 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "availability" }
-                        due_badge = {
-                            visible: true
-                            draw_bg: { urgent: 0.0 }
-                            due_text = { text: "明天", draw_text: { urgent: 0.0 } }
-                        }
-                    }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.5 } }
-                        progress_label = { text: "50%" }
-                    }
-                }
+                card2 = <WordCard> { visible: false }
+                card4 = <WordCard> { visible: false }
+                card6 = <WordCard> { visible: false }
             }
         }
 
@@ -131,7 +49,7 @@ live_design! {
             width: Fill, height: Fit
             padding: 32
             align: {x: 0.5}
-            <Label> {
+            empty_label = <Label> {
                 text: "太棒了！暂时没有需要复习的内容"
                 draw_text: {
                     instance dark_mode: 0.0
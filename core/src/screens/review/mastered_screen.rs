@@ -28,7 +28,7 @@ live_design! {
             }
         }
 
-        // Word cards grid - 2 columns
+        // Word card slots - 2 columns, filled from the learning database
         cards_grid = <View> {
             width: Fill, height: Fit
             flow: Right
@@ -39,38 +39,9 @@ live_design! {
                 flow: Down
                 spacing: 16
 
-                card1 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "schedule" }
-                    }
-                    hint_label = { text: "日程安排" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.95 } }
-                        progress_label = { text: "95%" }
-                    }
-                }
-
-                card3 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "confirm" }
-                    }
-                    hint_label = { text: "确认" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.92 } }
-                        progress_label = { text: "92%" }
-                    }
-                }
-
-                card5 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "available" }
-                    }
-                    hint_label = { text: "可用的，有空的" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.88 } }
-                        progress_label = { text: "88%" }
-                    }
-                }
+                card1 = <WordCard> { visible: false }
+                card3 = <WordCard> { visible: false }
+                card5 = <WordCard> { visible: false }
             }
 
             col2 = <View> {
@@ -78,36 +49,25 @@ live_design! {
                 flow: Down
                 spacing: 16
 
-                card2 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "appointment" }
-                    }
-                    hint_label = { text: "预约" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.90 } }
-                        progress_label = { text: "90%" }
-                    }
-                }
-
-                card4 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "cancel" }
-                    }
-                    hint_label = { text: "取消" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.94 } }
-                        progress_label = { text: "94%" }
-                    }
-                }
+                card2 = <WordCard> { visible: false }
+                card4 = <WordCard> { visible: false }
+                card6 = <WordCard> { visible: false }
+            }
+        }
 
-                card6 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "reschedule" }
-                    }
-                    hint_label = { text: "重新安排" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.86 } }
-                        progress_label = { text: "86%" }
+        // Empty state (hidden by default)
+        empty_state = <View> {
+            visible: false
+            width: Fill, height: Fit
+            padding: 32
+            align: {x: 0.5}
+            empty_label = <Label> {
+                text: "还没有掌握的词汇，坚持复习就会出现在这里"
+                draw_text: {
+                    instance dark_mode: 0.0
+                    text_style: <FONT_REGULAR>{ font_size: 14.0 }
+                    fn get_color(self) -> vec4 {
+                        return mix((TEXT_MUTED), (TEXT_SECONDARY_DARK), self.dark_mode);
                     }
                 }
             }
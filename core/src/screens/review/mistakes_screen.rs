@@ -8,6 +8,8 @@ live_design! {
 
     use colang_widgets::theme::*;
 
+    use crate::screens::review::components::PrimaryButton;
+    use crate::screens::review::components::SecondaryButton;
     use crate::screens::review::components::TipBanner;
     use crate::screens::review::components::WordCard;
     use crate::screens::review::components::TIP_AMBER_BG;
@@ -28,7 +30,56 @@ live_design! {
             }
         }
 
-        // Word cards grid - 2 columns
+        // Notebook export/import
+        notebook_bar = <View> {
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: {y: 0.5}
+
+            export_apkg_btn = <SecondaryButton> { text: "导出 Anki" }
+            export_csv_btn = <SecondaryButton> { text: "导出 CSV" }
+            import_csv_btn = <SecondaryButton> { text: "导入 CSV" }
+
+            notebook_status = <Label> {
+                width: Fill
+                text: ""
+                draw_text: {
+                    instance dark_mode: 0.0
+                    wrap: Word
+                    text_style: <FONT_REGULAR>{ font_size: 11.0 }
+                    fn get_color(self) -> vec4 {
+                        return mix((TEXT_MUTED), (TEXT_SECONDARY_DARK), self.dark_mode);
+                    }
+                }
+            }
+        }
+
+        // Dry-run report of a CSV import, waiting for confirmation
+        import_confirm = <View> {
+            visible: false
+            width: Fill, height: Fit
+            flow: Right
+            spacing: 8
+            align: {y: 0.5}
+
+            import_report = <Label> {
+                width: Fill
+                text: ""
+                draw_text: {
+                    instance dark_mode: 0.0
+                    wrap: Word
+                    text_style: <FONT_MEDIUM>{ font_size: 11.0 }
+                    fn get_color(self) -> vec4 {
+                        return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+                    }
+                }
+            }
+            cancel_import_btn = <SecondaryButton> { text: "取消" }
+            confirm_import_btn = <PrimaryButton> { text: "确认导入" }
+        }
+
+        // Word card slots - 2 columns, filled from the learning database
         cards_grid = <View> {
             width: Fill, height: Fit
             flow: Right
@@ -39,27 +90,9 @@ live_design! {
                 flow: Down
                 spacing: 16
 
-                card1 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "affect vs effect" }
-                    }
-                    hint_label = { text: "affect (v.) 影响 / effect (n.) 效果" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.3 } }
-                        progress_label = { text: "30%" }
-                    }
-                }
-
-                card3 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "complement vs compliment" }
-                    }
-                    hint_label = { text: "complement (补充) / compliment (赞美)" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.35 } }
-                        progress_label = { text: "35%" }
-                    }
-                }
+                card1 = <WordCard> { visible: false }
+                card3 = <WordCard> { visible: false }
+                card5 = <WordCard> { visible: false }
             }
 
             col2 = <View> {
@@ -67,25 +100,25 @@ live_design! {
                 flow: Down
                 spacing: 16
 
-                card2 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "their vs there" }
-                    }
-                    hint_label = { text: "their (他们的) / there (那里)" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.4 } }
-                        progress_label = { text: "40%" }
-                    }
-                }
+                card2 = <WordCard> { visible: false }
+                card4 = <WordCard> { visible: false }
+                card6 = <WordCard> { visible: false }
+            }
+        }
 
-                card4 = <WordCard> {
-                    header_row = {
-                        word_label = { text: "principal vs principle" }
-                    }
-                    hint_label = { text: "principal (主要的/校长) / principle (原则)" }
-                    progress_row = {
-                        progress_bar = { draw_bg: { progress: 0.25 } }
-                        progress_label = { text: "25%" }
+        // Empty state (hidden by default)
+        empty_state = <View> {
+            visible: false
+            width: Fill, height: Fit
+            padding: 32
+            align: {x: 0.5}
+            empty_label = <Label> {
+                text: "还没有记录到错误，和老师聊聊天吧"
+                draw_text: {
+                    instance dark_mode: 0.0
+                    text_style: <FONT_REGULAR>{ font_size: 14.0 }
+                    fn get_color(self) -> vec4 {
+                        return mix((TEXT_MUTED), (TEXT_SECONDARY_DARK), self.dark_mode);
                     }
                 }
             }
@@ -0,0 +1,365 @@
+//! Learning data behind the review screens
+//!
+//! Everything is read from the local learning database on a worker thread and
+//! handed back to [`super::review_screen::ReviewScreen`] as [`ReviewMessage`]s.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender};
+
+use chrono::Local;
+
+use crate::achievements;
+use crate::daily_stats;
+use crate::db::Database;
+use crate::models::{
+    Achievement, IssueWord, LearningSession, MistakeSummary, ReviewStats, SessionType, Streaks,
+    WordPracticeLog,
+};
+use crate::notebook::{self, ExportSummary, ImportAction, ImportReport, Notebook, TtsAudio};
+use crate::scheduler::Grade;
+use crate::store::{LearningStore, StoreError};
+
+/// Word cards per tab
+pub const CARD_SLOTS: usize = 6;
+
+/// Words offered in one flashcard session
+const PRACTICE_WORDS: i64 = 20;
+
+/// The due tab shows words coming due within this window
+const UPCOMING_WINDOW_SECS: i64 = 2 * 86_400;
+
+/// Changed rows listed in the import confirmation
+const IMPORT_PREVIEW_ROWS: usize = 8;
+
+/// Snapshot of the learning data shown on the review screens
+pub struct ReviewData {
+    /// Words due now (within the daily practice limit), for flashcards
+    pub due: Vec<IssueWord>,
+    /// Words due now or soon, soonest first
+    pub upcoming: Vec<IssueWord>,
+    pub mistakes: Vec<MistakeSummary>,
+    pub mastered: Vec<IssueWord>,
+    pub stats: ReviewStats,
+    pub streaks: Streaks,
+}
+
+/// Results sent back from the worker threads
+pub enum ReviewMessage {
+    Loaded(Result<Box<ReviewData>, String>),
+    /// A practice session was closed in the database, earning these
+    /// achievements for the first time, or recording it failed
+    PracticeEnded(Result<Vec<Achievement>, String>),
+    NotebookExported(Result<ExportSummary, String>),
+    /// A CSV/TSV import of the file, or its dry run
+    NotebookImported(PathBuf, Result<ImportReport, String>),
+}
+
+/// Load a [`ReviewData`] snapshot in the background
+pub fn spawn_load(tx: Sender<ReviewMessage>) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(load()).map(Box::new).map_err(|e| e.to_string());
+        let _ = tx.send(ReviewMessage::Loaded(result));
+    });
+}
+
+async fn load() -> Result<ReviewData, StoreError> {
+    let db = Database::open_default().await?;
+    let store: &dyn LearningStore = &db;
+    let now = Local::now();
+    let today = now.date_naive();
+
+    let streaks = daily_stats::update(&db, today).await?;
+
+    Ok(ReviewData {
+        due: store.words_for_review(PRACTICE_WORDS).await?,
+        upcoming: db
+            .get_upcoming_words(now.timestamp() + UPCOMING_WINDOW_SECS, CARD_SLOTS as i64)
+            .await?,
+        mistakes: db.get_frequent_mistakes(CARD_SLOTS as i64).await?,
+        mastered: db.get_mastered_words(CARD_SLOTS as i64).await?,
+        stats: db
+            .get_review_stats(daily_stats::local_timestamp(daily_stats::week_start(today)))
+            .await?,
+        streaks,
+    })
+}
+
+/// Badge text for a word due at `next_review_at`, and whether it is due now
+pub fn due_badge(next_review_at: Option<i64>, now: i64) -> (String, bool) {
+    let Some(due) = next_review_at.filter(|&due| due > now) else {
+        return ("现在".to_string(), true);
+    };
+    let secs = due - now;
+    let text = if secs < 3600 {
+        format!("{}分钟", (secs / 60).max(1))
+    } else if secs < 86_400 {
+        format!("{}小时", secs / 3600)
+    } else if secs < 2 * 86_400 {
+        "明天".to_string()
+    } else {
+        format!("{}天", secs / 86_400)
+    };
+    (text, false)
+}
+
+/// A flashcard run over the words due now
+///
+/// Grades go to a worker thread that owns the database connection, so they
+/// are written in order after the session row. Dropping the session closes
+/// it in the database.
+pub struct PracticeSession {
+    words: Vec<IssueWord>,
+    index: usize,
+    passed: usize,
+    /// Whether the answer of the current card is shown
+    pub revealed: bool,
+    grades: Option<Sender<(i64, Grade)>>,
+}
+
+impl PracticeSession {
+    /// Start a session over `words`; `events` receives [`ReviewMessage::PracticeEnded`]
+    /// once the session is closed and achievements are evaluated
+    pub fn start(words: Vec<IssueWord>, events: Sender<ReviewMessage>) -> Self {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let target_words = words.iter().map(|w| w.word.as_str()).collect::<Vec<_>>();
+        let session = LearningSession {
+            session_id: uuid::Uuid::new_v4().to_string(),
+            session_type: Some(SessionType::Review),
+            sceneid: None,
+            scene_dialogue_id: None,
+            classic_clip_id: None,
+            target_words: serde_json::to_string(&target_words).ok(),
+            started_at: Local::now().timestamp(),
+            ended_at: None,
+            duration_seconds: None,
+            total_words_spoken: 0,
+            average_wpm: None,
+            error_count: 0,
+            correction_count: 0,
+            notes: None,
+            ai_summary_en: None,
+            ai_summary_zh: None,
+        };
+
+        std::thread::spawn(move || {
+            let rt = tokio::runtime::Runtime::new().unwrap();
+            let result = rt.block_on(record_practice(session, rx));
+            let _ = events.send(ReviewMessage::PracticeEnded(
+                result.map_err(|e| e.to_string()),
+            ));
+        });
+
+        Self {
+            words,
+            index: 0,
+            passed: 0,
+            revealed: false,
+            grades: Some(tx),
+        }
+    }
+
+    /// Word on the current card, `None` once all words are graded
+    pub fn current(&self) -> Option<&IssueWord> {
+        self.words.get(self.index)
+    }
+
+    /// 1-based position of the current card and the number of cards
+    pub fn position(&self) -> (usize, usize) {
+        ((self.index + 1).min(self.words.len()), self.words.len())
+    }
+
+    /// Words graded so far, and how many of them were remembered
+    pub fn results(&self) -> (usize, usize) {
+        (self.index, self.passed)
+    }
+
+    /// Grade the current card and move on to the next one
+    pub fn grade(&mut self, grade: Grade) {
+        let Some(word) = self.current() else {
+            return;
+        };
+        if let (Some(word_id), Some(grades)) = (word.id, &self.grades) {
+            let _ = grades.send((word_id, grade));
+        }
+        if grade.is_pass() {
+            self.passed += 1;
+        }
+        self.index += 1;
+        self.revealed = false;
+        if self.current().is_none() {
+            self.finish();
+        }
+    }
+
+    /// Close the session; ungraded words stay due
+    pub fn finish(&mut self) {
+        self.grades = None;
+    }
+}
+
+/// Record the session and its grades; returns the achievements it earned
+async fn record_practice(
+    session: LearningSession,
+    grades: Receiver<(i64, Grade)>,
+) -> Result<Vec<Achievement>, StoreError> {
+    let db = Database::open_default().await?;
+    let store: &dyn LearningStore = &db;
+    store.start_session(&session).await?;
+
+    // Ends when the PracticeSession finishes or is dropped
+    while let Ok((word_id, grade)) = grades.recv() {
+        db.log_word_practice(&WordPracticeLog {
+            id: None,
+            word_id,
+            session_id: session.session_id.clone(),
+            practiced_at: Local::now().timestamp(),
+            success_level: Some(grade.level()),
+            notes: None,
+        })
+        .await?;
+        db.update_word_after_practice(word_id, grade).await?;
+    }
+
+    db.end_session(&session.session_id, None).await?;
+    Ok(achievements::evaluate(&db, Local::now().date_naive()).await?)
+}
+
+/// Export the notebook to `path`: an Anki package for `.apkg`, CSV or TSV
+/// otherwise
+pub fn spawn_export(path: PathBuf, tx: Sender<ReviewMessage>) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(async {
+            let db = Database::open_default().await?;
+            let book = Notebook::load(&db).await?;
+            let is_apkg = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("apkg"));
+            if is_apkg {
+                let audio = TtsAudio::from_preferences();
+                notebook::export_apkg(&book, Some(&audio), &path).await
+            } else {
+                notebook::export_text(&book, &path)
+            }
+        });
+        let _ = tx.send(ReviewMessage::NotebookExported(
+            result.map_err(|e| e.to_string()),
+        ));
+    });
+}
+
+/// Import the CSV/TSV file at `path` into the mistakes, or only report what
+/// would change with `dry_run`
+pub fn spawn_import(path: PathBuf, dry_run: bool, tx: Sender<ReviewMessage>) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let result = rt.block_on(async {
+            let db = Database::open_default().await?;
+            notebook::import_text(&db, &path, dry_run).await
+        });
+        let _ = tx.send(ReviewMessage::NotebookImported(
+            path,
+            result.map_err(|e| e.to_string()),
+        ));
+    });
+}
+
+/// One-line summary of an import report
+pub fn import_summary(report: &ImportReport) -> String {
+    format!(
+        "新增 {} · 更新 {} · 未变 {} · 跳过 {}",
+        report.added(),
+        report.updated(),
+        report.unchanged(),
+        report.skipped.len()
+    )
+}
+
+/// One line per row an import adds or updates, with the updated columns
+pub fn import_changes(report: &ImportReport) -> String {
+    let mut lines: Vec<String> = report
+        .rows
+        .iter()
+        .filter_map(|row| {
+            let word = format!("{} ({})", row.word, row.issue_type.to_string());
+            match &row.action {
+                ImportAction::Add => Some(format!("第 {} 行 新增 {}", row.line, word)),
+                ImportAction::Update(columns) => Some(format!(
+                    "第 {} 行 更新 {}: {}",
+                    row.line,
+                    word,
+                    columns.join(", ")
+                )),
+                ImportAction::Unchanged => None,
+            }
+        })
+        .collect();
+    let more = lines.len().saturating_sub(IMPORT_PREVIEW_ROWS);
+    if more > 0 {
+        lines.truncate(IMPORT_PREVIEW_ROWS);
+        lines.push(format!("……另有 {} 行", more));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::IssueType;
+    use crate::notebook::ImportRow;
+
+    #[test]
+    fn test_due_badge() {
+        let now = 1_760_000_000;
+        assert_eq!(due_badge(None, now), ("现在".to_string(), true));
+        assert_eq!(due_badge(Some(now - 10), now), ("现在".to_string(), true));
+        assert_eq!(due_badge(Some(now + 20), now), ("1分钟".to_string(), false));
+        assert_eq!(
+            due_badge(Some(now + 45 * 60), now),
+            ("45分钟".to_string(), false)
+        );
+        assert_eq!(
+            due_badge(Some(now + 3 * 3600), now),
+            ("3小时".to_string(), false)
+        );
+        assert_eq!(
+            due_badge(Some(now + 30 * 3600), now),
+            ("明天".to_string(), false)
+        );
+        assert_eq!(
+            due_badge(Some(now + 5 * 86_400), now),
+            ("5天".to_string(), false)
+        );
+    }
+    #[test]
+    fn test_import_changes() {
+        let row = |line, action| ImportRow {
+            line,
+            word: "either".to_string(),
+            issue_type: IssueType::Pronunciation,
+            action,
+        };
+        let mut report = ImportReport {
+            dry_run: true,
+            rows: vec![
+                row(2, ImportAction::Add),
+                row(3, ImportAction::Unchanged),
+                row(4, ImportAction::Update(vec!["description_zh", "context"])),
+            ],
+            skipped: Vec::new(),
+        };
+        assert_eq!(
+            import_changes(&report),
+            "第 2 行 新增 either (pronunciation)\n\
+             第 4 行 更新 either (pronunciation): description_zh, context"
+        );
+
+        report.rows = (0..10)
+            .map(|line| row(line + 2, ImportAction::Add))
+            .collect();
+        let changes = import_changes(&report);
+        assert_eq!(changes.lines().count(), IMPORT_PREVIEW_ROWS + 1);
+        assert!(changes.ends_with("……另有 2 行"));
+    }
+}
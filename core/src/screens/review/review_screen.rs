@@ -1,6 +1,18 @@
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use chrono::Local;
 use makepad_widgets::*;
 use makepad_component::*;
 
+use super::review_data::{
+    CARD_SLOTS, PracticeSession, ReviewData, ReviewMessage, due_badge, import_changes,
+    import_summary, spawn_export, spawn_import, spawn_load,
+};
+use crate::models::{IssueType, IssueWord};
+use crate::scheduler::{Grade, Scheduler};
+use crate::screens::AchievementAction;
+
 live_design! {
     use link::theme::*;
     use link::shaders::*;
@@ -19,6 +31,9 @@ live_design! {
     use crate::screens::review::components::StatsPanel;
     use crate::screens::review::components::CardBase;
     use crate::screens::review::components::PrimaryButton;
+    use crate::screens::review::components::SecondaryButton;
+    use crate::screens::review::components::ReviewActionButton;
+    use crate::screens::review::components::Flashcard;
     use crate::screens::review::due_screen::DueScreen;
     use crate::screens::review::mistakes_screen::MistakesScreen;
     use crate::screens::review::mastered_screen::MasteredScreen;
@@ -86,6 +101,55 @@ live_design! {
                     stats_panel = <StatsPanel> {}
                 }
 
+                // Flashcard practice over the due words (replaces the tabs while active)
+                practice_card = <CardBase> {
+                    visible: false
+                    width: Fill, height: Fit
+                    padding: 20
+                    flow: Down
+                    spacing: 16
+
+                    practice_header = <View> {
+                        width: Fill, height: Fit
+                        flow: Right
+                        align: {y: 0.5}
+                        practice_progress = <SectionTitle> {}
+                        <View> { width: Fill }
+                        end_practice_btn = <SecondaryButton> { text: "结束复习" }
+                    }
+
+                    flashcard = <Flashcard> {}
+
+                    reveal_row = <View> {
+                        width: Fill, height: Fit
+                        align: {x: 0.5}
+                        reveal_btn = <PrimaryButton> { text: "显示答案" }
+                    }
+
+                    grade_row = <View> {
+                        visible: false
+                        width: Fill, height: Fit
+                        flow: Right
+                        spacing: 8
+                        grade_forgot = <ReviewActionButton> { text: "忘记了" }
+                        grade_wrong = <ReviewActionButton> { text: "记错了" }
+                        grade_hard = <ReviewActionButton> { text: "困难" }
+                        grade_good = <ReviewActionButton> { text: "良好" }
+                        grade_easy = <ReviewActionButton> { text: "简单" }
+                    }
+
+                    summary = <View> {
+                        visible: false
+                        width: Fill, height: Fit
+                        padding: 16
+                        flow: Down
+                        align: {x: 0.5}
+                        spacing: 12
+                        summary_label = <SectionTitle> {}
+                        done_btn = <PrimaryButton> { text: "完成" }
+                    }
+                }
+
                 // Tab area card
                 tab_card = <CardBase> {
                     width: Fill, height: Fit
@@ -154,12 +218,56 @@ pub struct ReviewScreen {
     view: View,
     #[rust]
     tab: ReviewTab,
+
+    #[rust]
+    data_loaded: bool,
+
+    #[rust]
+    data: Option<ReviewData>,
+
+    #[rust]
+    load_error: Option<String>,
+
+    /// Results from the load and practice worker threads
+    #[rust]
+    events: Option<(mpsc::Sender<ReviewMessage>, mpsc::Receiver<ReviewMessage>)>,
+
+    /// Wakes the screen to pick up worker results and refresh due badges
+    #[rust]
+    poll_timer: Timer,
+
+    /// Minute the due badges were last rendered for
+    #[rust]
+    badge_minute: i64,
+
+    #[rust]
+    practice: Option<PracticeSession>,
+
+    /// CSV/TSV file whose dry-run report awaits confirmation
+    #[rust]
+    pending_import: Option<PathBuf>,
 }
 
 impl Widget for ReviewScreen {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
 
+        // Trigger initial data load on first draw
+        if let Event::Draw(_) = event {
+            if !self.data_loaded {
+                self.load_data(cx);
+            }
+        }
+
+        self.poll_events(cx, scope);
+
+        // Keep the due badges current while the screen is open
+        if self.poll_timer.is_event(event).is_some()
+            && Local::now().timestamp() / 60 != self.badge_minute
+        {
+            self.update_due_cards(cx);
+        }
+
         let actions = match event {
             Event::Actions(actions) => actions.as_slice(),
             _ => return,
@@ -197,6 +305,101 @@ impl Widget for ReviewScreen {
             self.tab = ReviewTab::Stats;
             self.apply_tab_state(cx);
         }
+
+        // Practice flow
+        if self
+            .view
+            .button(ids!(content_scroll.content.header_card.title_row.start_review_btn))
+            .clicked(actions)
+        {
+            self.start_practice(cx);
+        }
+        if self
+            .view
+            .button(ids!(content_scroll.content.practice_card.reveal_row.reveal_btn))
+            .clicked(actions)
+        {
+            if let Some(practice) = &mut self.practice {
+                practice.revealed = true;
+            }
+            self.update_practice_card(cx);
+        }
+        for (button_id, grade) in [
+            (ids!(content_scroll.content.practice_card.grade_row.grade_forgot), Grade::Forgot),
+            (ids!(content_scroll.content.practice_card.grade_row.grade_wrong), Grade::Wrong),
+            (ids!(content_scroll.content.practice_card.grade_row.grade_hard), Grade::Hard),
+            (ids!(content_scroll.content.practice_card.grade_row.grade_good), Grade::Good),
+            (ids!(content_scroll.content.practice_card.grade_row.grade_easy), Grade::Easy),
+        ] {
+            if self.view.button(button_id).clicked(actions) {
+                if let Some(practice) = &mut self.practice {
+                    practice.grade(grade);
+                }
+                self.update_practice_card(cx);
+            }
+        }
+        if self
+            .view
+            .button(ids!(content_scroll.content.practice_card.practice_header.end_practice_btn))
+            .clicked(actions)
+            || self
+                .view
+                .button(ids!(content_scroll.content.practice_card.summary.done_btn))
+                .clicked(actions)
+        {
+            // Dropping the session closes it; the reload follows PracticeEnded
+            self.practice = None;
+            self.update_practice_card(cx);
+        }
+
+        // Notebook export/import
+        let mistakes_page = ids!(content_scroll.content.tab_card.pages.mistakes_page);
+        if self
+            .view
+            .view(mistakes_page)
+            .button(ids!(notebook_bar.export_apkg_btn))
+            .clicked(actions)
+        {
+            self.export_notebook(cx, "apkg");
+        }
+        if self
+            .view
+            .view(mistakes_page)
+            .button(ids!(notebook_bar.export_csv_btn))
+            .clicked(actions)
+        {
+            self.export_notebook(cx, "csv");
+        }
+        if self
+            .view
+            .view(mistakes_page)
+            .button(ids!(notebook_bar.import_csv_btn))
+            .clicked(actions)
+        {
+            self.check_import(cx);
+        }
+        if self
+            .view
+            .view(mistakes_page)
+            .button(ids!(import_confirm.confirm_import_btn))
+            .clicked(actions)
+        {
+            if let Some(path) = self.pending_import.take() {
+                self.set_notebook_status(cx, "正在导入…");
+                spawn_import(path, false, self.events_tx());
+            }
+            self.update_import_confirm(cx, None);
+        }
+        if self
+            .view
+            .view(mistakes_page)
+            .button(ids!(import_confirm.cancel_import_btn))
+            .clicked(actions)
+        {
+            self.pending_import = None;
+            self.set_notebook_status(cx, "");
+            self.update_import_confirm(cx, None);
+        }
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
@@ -259,6 +462,478 @@ impl ReviewScreen {
             .page_flip(ids!(content_scroll.content.tab_card.pages))
             .set_active_page(cx, page);
 
+        // A page may only just have been created; fill it in
+        self.update_data(cx);
+    }
+
+    fn events_tx(&mut self) -> mpsc::Sender<ReviewMessage> {
+        self.events.get_or_insert_with(mpsc::channel).0.clone()
+    }
+
+    /// Load learning data from the local database
+    fn load_data(&mut self, cx: &mut Cx) {
+        if !self.data_loaded {
+            self.data_loaded = true;
+            self.poll_timer = cx.start_interval(1.0);
+        }
+        spawn_load(self.events_tx());
+    }
+
+    fn poll_events(&mut self, cx: &mut Cx, scope: &mut Scope) {
+        let mut messages = Vec::new();
+        if let Some((_, rx)) = &self.events {
+            while let Ok(message) = rx.try_recv() {
+                messages.push(message);
+            }
+        }
+
+        for message in messages {
+            match message {
+                ReviewMessage::Loaded(Ok(data)) => {
+                    self.data = Some(*data);
+                    self.load_error = None;
+                    self.update_data(cx);
+                }
+                ReviewMessage::Loaded(Err(e)) => {
+                    ::log::error!("Failed to load review data: {}", e);
+                    self.data = None;
+                    self.load_error = Some(e);
+                    self.update_data(cx);
+                }
+                ReviewMessage::NotebookExported(Ok(summary)) => {
+                    let status = if summary.audio_clips > 0 {
+                        format!(
+                            "已导出 {} 张卡片，含 {} 段语音",
+                            summary.cards, summary.audio_clips
+                        )
+                    } else {
+                        format!("已导出 {} 张卡片", summary.cards)
+                    };
+                    self.set_notebook_status(cx, &status);
+                }
+                ReviewMessage::NotebookExported(Err(e)) => {
+                    ::log::error!("Failed to export notebook: {}", e);
+                    self.set_notebook_status(cx, &format!("导出失败: {}", e));
+                }
+                // Dry run: show what would change and wait for confirmation
+                ReviewMessage::NotebookImported(path, Ok(report)) if report.dry_run => {
+                    let summary = import_summary(&report);
+                    if report.added() + report.updated() == 0 {
+                        self.set_notebook_status(
+                            cx,
+                            &format!("没有需要导入的内容（{}）", summary),
+                        );
+                    } else {
+                        self.pending_import = Some(path);
+                        self.set_notebook_status(cx, "");
+                        let text = format!("将导入: {}\n{}", summary, import_changes(&report));
+                        self.update_import_confirm(cx, Some(&text));
+                    }
+                }
+                ReviewMessage::NotebookImported(_, Ok(report)) => {
+                    let status = format!("已导入: {}", import_summary(&report));
+                    self.set_notebook_status(cx, &status);
+                    self.load_data(cx);
+                }
+                ReviewMessage::NotebookImported(_, Err(e)) => {
+                    ::log::error!("Failed to import notebook: {}", e);
+                    self.set_notebook_status(cx, &format!("导入失败: {}", e));
+                }
+                // Grades are in; pick up the new schedule
+                ReviewMessage::PracticeEnded(Ok(earned)) => {
+                    if !earned.is_empty() {
+                        cx.widget_action(
+                            self.widget_uid(),
+                            &scope.path,
+                            AchievementAction::Earned(earned),
+                        );
+                    }
+                    self.load_data(cx);
+                }
+                // Some grades may have been recorded before the failure
+                ReviewMessage::PracticeEnded(Err(e)) => {
+                    ::log::error!("Failed to record review practice: {}", e);
+                    self.load_data(cx);
+                }
+            }
+        }
+    }
+
+    /// Render the loaded data into every tab
+    fn update_data(&mut self, cx: &mut Cx) {
+        self.update_stats(cx);
+        self.update_due_cards(cx);
+        self.update_mistake_cards(cx);
+        self.update_mastered_cards(cx);
+        self.view.redraw(cx);
+    }
+
+    fn update_stats(&mut self, cx: &mut Cx) {
+        let Some(data) = &self.data else {
+            return;
+        };
+        let stats = &data.stats;
+        let accuracy = if stats.reviews > 0 {
+            format!("{}%", stats.passed_reviews * 100 / stats.reviews)
+        } else {
+            "—".to_string()
+        };
+        let average_mastery = if stats.total_words > 0 {
+            format!("{:.0}%", stats.average_mastery * 100.0)
+        } else {
+            "—".to_string()
+        };
+
+        let panel = self
+            .view
+            .view(ids!(content_scroll.content.header_card.stats_panel));
+        panel
+            .label(ids!(stat_due.stat_value))
+            .set_text(cx, &stats.due_words.to_string());
+        panel
+            .label(ids!(stat_mastered.stat_value))
+            .set_text(cx, &stats.mastered_words.to_string());
+        panel
+            .label(ids!(stat_mistakes.stat_value))
+            .set_text(cx, &stats.mistakes.to_string());
+        panel
+            .label(ids!(stat_accuracy.stat_value))
+            .set_text(cx, &accuracy);
+
+        let page = self
+            .view
+            .view(ids!(content_scroll.content.tab_card.pages.stats_page));
+        page.label(ids!(overview_section.overview_grid.col1.item1.item_value))
+            .set_text(cx, &stats.total_words.to_string());
+        page.label(ids!(overview_section.overview_grid.col2.item2.item_value))
+            .set_text(cx, &stats.study_days.to_string());
+        page.label(ids!(overview_section.overview_grid.col1.item3.item_value))
+            .set_text(cx, &stats.reviews.to_string());
+        page.label(ids!(overview_section.overview_grid.col2.item4.item_value))
+            .set_text(cx, &average_mastery);
+        page.label(ids!(overview_section.overview_grid.col1.item5.item_value))
+            .set_text(cx, &data.streaks.current.to_string());
+        page.label(ids!(overview_section.overview_grid.col2.item6.item_value))
+            .set_text(cx, &data.streaks.longest.to_string());
+
+        let bar_ids = [
+            ids!(chart_section.chart_container.bars.bar1.bar),
+            ids!(chart_section.chart_container.bars.bar2.bar),
+            ids!(chart_section.chart_container.bars.bar3.bar),
+            ids!(chart_section.chart_container.bars.bar4.bar),
+            ids!(chart_section.chart_container.bars.bar5.bar),
+            ids!(chart_section.chart_container.bars.bar6.bar),
+            ids!(chart_section.chart_container.bars.bar7.bar),
+        ];
+        let busiest = stats.week_reviews.iter().copied().max().unwrap_or(0).max(1);
+        for (bar_id, count) in bar_ids.iter().zip(stats.week_reviews) {
+            let height = 4.0 + 92.0 * count as f64 / busiest as f64;
+            page.view(*bar_id).apply_over(cx, live! { height: (height) });
+        }
+    }
+
+    fn update_due_cards(&mut self, cx: &mut Cx) {
+        let now = Local::now().timestamp();
+        self.badge_minute = now / 60;
+
+        let page = self
+            .view
+            .view(ids!(content_scroll.content.tab_card.pages.due_page));
+        let words = self.data.as_ref().map_or(&[][..], |d| &d.upcoming[..]);
+        let scheduler = Scheduler::default();
+
+        for (i, card) in card_slots(&page).iter().enumerate() {
+            let Some(word) = words.get(i) else {
+                card.set_visible(cx, false);
+                continue;
+            };
+            card.set_visible(cx, true);
+            card.label(ids!(header_row.word_label)).set_text(cx, &word.word);
+            card.label(ids!(hint_label)).set_text(cx, word_hint(word));
+
+            let (badge, urgent) = due_badge(word.next_review_at, now);
+            let urgent = if urgent { 1.0 } else { 0.0 };
+            card.view(ids!(header_row.due_badge)).set_visible(cx, true);
+            card.view(ids!(header_row.due_badge))
+                .apply_over(cx, live! { draw_bg: { urgent: (urgent) } });
+            card.label(ids!(header_row.due_badge.due_text)).set_text(cx, &badge);
+            card.label(ids!(header_row.due_badge.due_text))
+                .apply_over(cx, live! { draw_text: { urgent: (urgent) } });
+
+            set_progress(cx, card, scheduler.mastery(&word.review_state()));
+        }
+
+        show_empty_state(
+            cx,
+            &page,
+            words.is_empty(),
+            self.load_error.as_deref(),
+            "太棒了！暂时没有需要复习的内容",
+        );
+        self.view.redraw(cx);
+    }
+
+    fn update_mistake_cards(&mut self, cx: &mut Cx) {
+        let page = self
+            .view
+            .view(ids!(content_scroll.content.tab_card.pages.mistakes_page));
+        let mistakes = self.data.as_ref().map_or(&[][..], |d| &d.mistakes[..]);
+
+        for (i, card) in card_slots(&page).iter().enumerate() {
+            let Some(mistake) = mistakes.get(i) else {
+                card.set_visible(cx, false);
+                continue;
+            };
+            card.set_visible(cx, true);
+            card.label(ids!(header_row.word_label))
+                .set_text(cx, &mistake.original_text);
+
+            let mut hint = Vec::new();
+            if let Some(suggested) = &mistake.suggested_text {
+                hint.push(format!("→ {}", suggested));
+            }
+            if let Some(description) = mistake
+                .description_zh
+                .as_ref()
+                .or(mistake.description_en.as_ref())
+            {
+                hint.push(description.clone());
+            }
+            card.label(ids!(hint_label)).set_text(cx, &hint.join(" · "));
+
+            // Repeated mistakes get the urgent badge
+            let urgent = if mistake.occurrences >= 3 { 1.0 } else { 0.0 };
+            card.view(ids!(header_row.due_badge)).set_visible(cx, true);
+            card.view(ids!(header_row.due_badge))
+                .apply_over(cx, live! { draw_bg: { urgent: (urgent) } });
+            card.label(ids!(header_row.due_badge.due_text))
+                .set_text(cx, &format!("{}次", mistake.occurrences));
+            card.label(ids!(header_row.due_badge.due_text))
+                .apply_over(cx, live! { draw_text: { urgent: (urgent) } });
+
+            card.view(ids!(progress_row)).set_visible(cx, false);
+        }
+
+        show_empty_state(
+            cx,
+            &page,
+            mistakes.is_empty(),
+            self.load_error.as_deref(),
+            "还没有记录到错误，和老师聊聊天吧",
+        );
+    }
+
+    fn update_mastered_cards(&mut self, cx: &mut Cx) {
+        let page = self
+            .view
+            .view(ids!(content_scroll.content.tab_card.pages.mastered_page));
+        let words = self.data.as_ref().map_or(&[][..], |d| &d.mastered[..]);
+        let scheduler = Scheduler::default();
+
+        for (i, card) in card_slots(&page).iter().enumerate() {
+            let Some(word) = words.get(i) else {
+                card.set_visible(cx, false);
+                continue;
+            };
+            card.set_visible(cx, true);
+            card.label(ids!(header_row.word_label)).set_text(cx, &word.word);
+            card.label(ids!(hint_label)).set_text(cx, word_hint(word));
+            set_progress(cx, card, scheduler.mastery(&word.review_state()));
+        }
+
+        show_empty_state(
+            cx,
+            &page,
+            words.is_empty(),
+            self.load_error.as_deref(),
+            "还没有掌握的词汇，坚持复习就会出现在这里",
+        );
+    }
+
+    /// Start flashcards over the words due now
+    fn start_practice(&mut self, cx: &mut Cx) {
+        if self.practice.is_some() {
+            return;
+        }
+        let words = self.data.as_ref().map(|d| d.due.clone()).unwrap_or_default();
+        if words.is_empty() {
+            // Nothing due; the due tab explains why
+            self.tab = ReviewTab::Due;
+            self.apply_tab_state(cx);
+            return;
+        }
+
+        self.practice = Some(PracticeSession::start(words, self.events_tx()));
+        self.update_practice_card(cx);
+    }
+
+    fn update_practice_card(&mut self, cx: &mut Cx) {
+        let card = self.view.view(ids!(content_scroll.content.practice_card));
+        let Some(practice) = &self.practice else {
+            card.set_visible(cx, false);
+            self.view
+                .view(ids!(content_scroll.content.tab_card))
+                .set_visible(cx, true);
+            self.view.redraw(cx);
+            return;
+        };
+        card.set_visible(cx, true);
+        self.view
+            .view(ids!(content_scroll.content.tab_card))
+            .set_visible(cx, false);
+
+        let current = practice.current();
+        let revealed = practice.revealed;
+        card.view(ids!(flashcard)).set_visible(cx, current.is_some());
+        card.view(ids!(reveal_row)).set_visible(cx, current.is_some() && !revealed);
+        card.view(ids!(grade_row)).set_visible(cx, current.is_some() && revealed);
+        card.view(ids!(summary)).set_visible(cx, current.is_none());
+        card.view(ids!(practice_header.end_practice_btn))
+            .set_visible(cx, current.is_some());
+
+        match current {
+            Some(word) => {
+                let (position, total) = practice.position();
+                card.label(ids!(practice_header.practice_progress))
+                    .set_text(cx, &format!("{} / {}", position, total));
+                card.label(ids!(flashcard.card_word)).set_text(cx, &word.word);
+                card.label(ids!(flashcard.card_context))
+                    .set_text(cx, word.context.as_deref().unwrap_or(""));
+                card.view(ids!(flashcard.answer)).set_visible(cx, revealed);
+                card.label(ids!(flashcard.answer.answer_zh)).set_text(
+                    cx,
+                    word.description_zh
+                        .as_deref()
+                        .unwrap_or(issue_label(&word.issue_type)),
+                );
+                card.label(ids!(flashcard.answer.answer_en))
+                    .set_text(cx, word.description_en.as_deref().unwrap_or(""));
+            }
+            None => {
+                let (graded, passed) = practice.results();
+                card.label(ids!(practice_header.practice_progress))
+                    .set_text(cx, "复习完成");
+                card.label(ids!(summary.summary_label)).set_text(
+                    cx,
+                    &format!("本轮复习了 {} 个词，记住了 {} 个", graded, passed),
+                );
+            }
+        }
+
+        self.view.redraw(cx);
+    }
+
+    /// Ask where to save the notebook, then export it as `extension`
+    fn export_notebook(&mut self, cx: &mut Cx, extension: &str) {
+        let file_name = format!("colang-{}.{}", Local::now().format("%Y%m%d"), extension);
+        let dialog = rfd::FileDialog::new()
+            .set_title("导出错题本")
+            .set_file_name(&file_name);
+        let dialog = if extension == "apkg" {
+            dialog.add_filter("Anki", &["apkg"])
+        } else {
+            dialog.add_filter("CSV", &["csv"]).add_filter("TSV", &["tsv", "txt"])
+        };
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+
+        self.set_notebook_status(cx, "正在导出…");
+        spawn_export(path, self.events_tx());
+    }
+
+    /// Pick a CSV/TSV file and dry-run its import
+    fn check_import(&mut self, cx: &mut Cx) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("导入错题本")
+            .add_filter("CSV/TSV", &["csv", "tsv", "txt"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        self.pending_import = None;
+        self.update_import_confirm(cx, None);
+        self.set_notebook_status(cx, "正在检查…");
+        spawn_import(path, true, self.events_tx());
+    }
+
+    fn set_notebook_status(&mut self, cx: &mut Cx, text: &str) {
+        self.view
+            .view(ids!(content_scroll.content.tab_card.pages.mistakes_page))
+            .label(ids!(notebook_bar.notebook_status))
+            .set_text(cx, text);
         self.view.redraw(cx);
     }
+
+    /// Show the dry-run `report` with its confirm button, or hide it
+    fn update_import_confirm(&mut self, cx: &mut Cx, report: Option<&str>) {
+        let confirm = self
+            .view
+            .view(ids!(content_scroll.content.tab_card.pages.mistakes_page))
+            .view(ids!(import_confirm));
+        confirm.set_visible(cx, report.is_some());
+        if let Some(report) = report {
+            confirm.label(ids!(import_report)).set_text(cx, report);
+        }
+        self.view.redraw(cx);
+    }
+}
+
+/// Word card slots of a tab page, in reading order
+fn card_slots(page: &ViewRef) -> Vec<ViewRef> {
+    let slot_ids = [
+        ids!(cards_grid.col1.card1),
+        ids!(cards_grid.col2.card2),
+        ids!(cards_grid.col1.card3),
+        ids!(cards_grid.col2.card4),
+        ids!(cards_grid.col1.card5),
+        ids!(cards_grid.col2.card6),
+    ];
+    debug_assert_eq!(slot_ids.len(), CARD_SLOTS);
+    slot_ids.iter().map(|id| page.view(*id)).collect()
+}
+
+fn set_progress(cx: &mut Cx, card: &ViewRef, mastery: f64) {
+    card.view(ids!(progress_row)).set_visible(cx, true);
+    card.view(ids!(progress_row.progress_bar))
+        .apply_over(cx, live! { draw_bg: { progress: (mastery) } });
+    card.label(ids!(progress_row.progress_label))
+        .set_text(cx, &format!("{:.0}%", mastery * 100.0));
+}
+
+/// Show the page's empty state when it has nothing to list, or the load error
+fn show_empty_state(cx: &mut Cx, page: &ViewRef, empty: bool, error: Option<&str>, text: &str) {
+    let text = match error {
+        Some(e) => format!("加载失败: {}", e),
+        None => text.to_string(),
+    };
+    page.label(ids!(empty_state.empty_label)).set_text(cx, &text);
+    page.view(ids!(empty_state)).set_visible(cx, empty || error.is_some());
+}
+
+fn word_hint(word: &IssueWord) -> &str {
+    word.description_zh
+        .as_deref()
+        .or(word.description_en.as_deref())
+        .or(word.context.as_deref())
+        .unwrap_or(issue_label(&word.issue_type))
+}
+
+fn issue_label(issue_type: &IssueType) -> &'static str {
+    match issue_type {
+        IssueType::Pronunciation => "发音",
+        IssueType::Usage => "用法",
+        IssueType::Unfamiliar => "生词",
+        IssueType::Grammar => "语法",
+    }
+}
+
+impl ReviewScreenRef {
+    /// Reload learning data, e.g. after words were added in a chat
+    pub fn refresh_data(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.load_data(cx);
+        }
+    }
 }
@@ -39,11 +39,15 @@ live_design! {
 
                     item1 = <StatOverviewItem> {
                         item_label = { text: "总学习词汇" }
-                        item_value = { text: "187" }
+                        item_value = { text: "0" }
                     }
                     item3 = <StatOverviewItem> {
                         item_label = { text: "复习次数" }
-                        item_value = { text: "456" }
+                        item_value = { text: "0" }
+                    }
+                    item5 = <StatOverviewItem> {
+                        item_label = { text: "连续学习(天)" }
+                        item_value = { text: "0" }
                     }
                 }
 
@@ -54,11 +58,15 @@ live_design! {
 
                     item2 = <StatOverviewItem> {
                         item_label = { text: "学习天数" }
-                        item_value = { text: "32" }
+                        item_value = { text: "0" }
                     }
                     item4 = <StatOverviewItem> {
                         item_label = { text: "平均掌握度" }
-                        item_value = { text: "78%" }
+                        item_value = { text: "—" }
+                    }
+                    item6 = <StatOverviewItem> {
+                        item_label = { text: "最长连续(天)" }
+                        item_value = { text: "0" }
                     }
                 }
             }
@@ -78,7 +86,7 @@ live_design! {
                 flow: Down
                 spacing: 8
 
-                // Bar chart
+                // Bar chart, scaled to the busiest day of the week
                 bars = <View> {
                     width: Fill, height: 120
                     flow: Right
@@ -86,31 +94,31 @@ live_design! {
                     spacing: 12
 
                     bar1 = <ChartBar> {
-                        bar = { height: 72 }
+                        bar = { height: 4 }
                         day_label = { text: "一" }
                     }
                     bar2 = <ChartBar> {
-                        bar = { height: 96 }
+                        bar = { height: 4 }
                         day_label = { text: "二" }
                     }
                     bar3 = <ChartBar> {
-                        bar = { height: 54 }
+                        bar = { height: 4 }
                         day_label = { text: "三" }
                     }
                     bar4 = <ChartBar> {
-                        bar = { height: 108 }
+                        bar = { height: 4 }
                         day_label = { text: "四" }
                     }
                     bar5 = <ChartBar> {
-                        bar = { height: 84 }
+                        bar = { height: 4 }
                         day_label = { text: "五" }
                     }
                     bar6 = <ChartBar> {
-                        bar = { height: 36 }
+                        bar = { height: 4 }
                         day_label = { text: "六" }
                     }
                     bar7 = <ChartBar> {
-                        bar = { height: 60 }
+                        bar = { height: 4 }
                         day_label = { text: "日" }
                     }
                 }
@@ -665,6 +665,14 @@ live_design! {
     }
 }
 
+/// Actions the scene center sends to the shell
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ScenesAction {
+    None,
+    /// A scene card was clicked; practice that scene in the chat screen
+    Selected(Scene),
+}
+
 /// Data fetch result types
 enum FetchResult {
     Scenes(Result<Vec<Scene>, String>),
@@ -737,6 +745,17 @@ impl Widget for Scenes {
             }
         }
 
+        // Start a scene conversation from its card
+        for (card, scene) in self.scene_cards().iter().zip(&self.filtered_scenes) {
+            if card.finger_up(&actions).is_some() {
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    ScenesAction::Selected(scene.clone()),
+                );
+            }
+        }
+
         // Collect fetch results first to avoid borrow issues
         let mut scenes_result: Option<Result<Vec<Scene>, String>> = None;
         let mut classic_result: Option<Result<Vec<ClassicDialogueSource>, String>> = None;
@@ -866,9 +885,9 @@ impl Scenes {
         }
     }
 
-    /// Update scene cards with filtered data
-    fn update_scene_cards(&mut self, cx: &mut Cx) {
-        let card_ids = [
+    /// Today's scene cards, in `filtered_scenes` order
+    fn scene_cards(&self) -> Vec<ViewRef> {
+        [
             ids!(today_section.today_cards.row1.card0),
             ids!(today_section.today_cards.row1.card1),
             ids!(today_section.today_cards.row1.card2),
@@ -877,11 +896,15 @@ impl Scenes {
             ids!(today_section.today_cards.row2.card5),
             ids!(today_section.today_cards.row2.card6),
             ids!(today_section.today_cards.row2.card7),
-        ];
-
-        for (i, card_id) in card_ids.iter().enumerate() {
-            let card = self.view.view(*card_id);
+        ]
+        .iter()
+        .map(|card_id| self.view.view(*card_id))
+        .collect()
+    }
 
+    /// Update scene cards with filtered data
+    fn update_scene_cards(&mut self, cx: &mut Cx) {
+        for (i, card) in self.scene_cards().into_iter().enumerate() {
             if i < self.filtered_scenes.len() {
                 let scene = &self.filtered_scenes[i];
 
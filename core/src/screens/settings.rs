@@ -31,6 +31,20 @@ pub fn get_default_data_location() -> String {
         .unwrap_or_else(|| "~/Documents/colang".to_string())
 }
 
+/// The configured data location, or the default one
+pub fn data_location_dir() -> std::path::PathBuf {
+    let data_location = crate::models::Preferences::load()
+        .data_location
+        .filter(|location| !location.is_empty())
+        .unwrap_or_else(get_default_data_location);
+    std::path::PathBuf::from(data_location)
+}
+
+/// Directory of the TTS audio cache, under the configured data location
+pub fn tts_cache_dir() -> std::path::PathBuf {
+    data_location_dir().join("tts_cache")
+}
+
 /// Open the data location in the system file explorer
 pub fn open_data_location(data_location: &str) {
     use std::process::Command;
@@ -324,6 +324,7 @@ impl AddProviderModalRef {
                 enabled: true,
                 models: vec![],
                 is_custom: true,
+                volcano: None,
                 connection_status: ProviderConnectionStatus::Disconnected,
             })
         })
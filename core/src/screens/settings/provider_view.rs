@@ -1,9 +1,12 @@
 //! Provider View - Right panel for provider configuration
 
+use dora_bridge::{AsrBackend, TtsBackend};
 use makepad_widgets::*;
 use makepad_component::*;
 
-use crate::models::{Provider, ProviderConnectionStatus, ProviderId};
+use crate::models::{
+    Preferences, Provider, ProviderConnectionStatus, ProviderId, ProviderType, VolcanoSettings,
+};
 
 live_design! {
     use link::theme::*;
@@ -11,6 +14,7 @@ live_design! {
     use link::widgets::*;
 
     use colang_widgets::theme::*;
+    use crate::screens::settings::general_panel::SettingsCheckBox;
 
     // Custom text input style
     SettingsTextInput = <TextInput> {
@@ -322,6 +326,85 @@ live_design! {
                 }
             }
 
+            // Volcano Engine speech credentials (ASR/TTS), only for the Volcano provider
+            volcano_section = <View> {
+                visible: false
+                width: Fill, height: Fit
+                flow: Down
+                spacing: 6
+
+                app_id_label = <SettingsLabel> {
+                    text: "App ID"
+                }
+                app_id_input = <SettingsTextInput> {
+                    empty_text: "DOUBAO_APP_ID"
+                }
+
+                access_token_label = <SettingsLabel> {
+                    text: "Access Token"
+                }
+                access_token_input = <SettingsTextInput> {
+                    empty_text: "DOUBAO_ACCESS_TOKEN"
+                    is_password: true
+                }
+
+                tts_resource_label = <SettingsLabel> {
+                    text: "TTS Resource ID"
+                }
+                tts_resource_input = <SettingsTextInput> {
+                    empty_text: "seed-tts-2.0"
+                }
+
+                asr_resource_label = <SettingsLabel> {
+                    text: "ASR Resource ID"
+                }
+                asr_resource_input = <SettingsTextInput> {
+                    empty_text: "volc.bigasr.sauc.duration"
+                }
+
+                voice_label = <SettingsLabel> {
+                    text: "Voice"
+                }
+                voice_input = <SettingsTextInput> {
+                    empty_text: "zh_female_vv_uranus_bigtts"
+                }
+
+                volcano_hint = <SettingsHint> {
+                    text: "Speech credentials for ASR/TTS, passed to the dataflow when it starts"
+                }
+            }
+
+            // Default providers for the learning dataflow
+            defaults_section = <View> {
+                width: Fill, height: Fit
+                flow: Down
+                spacing: 6
+
+                defaults_label = <SettingsLabel> {
+                    text: "Use For"
+                }
+
+                defaults_row = <View> {
+                    width: Fill, height: Fit
+                    flow: Right
+                    spacing: 16
+
+                    chat_default_checkbox = <SettingsCheckBox> {
+                        text: "Teacher chat"
+                    }
+                    asr_default_checkbox = <SettingsCheckBox> {
+                        text: "Speech recognition"
+                    }
+                    tts_default_checkbox = <SettingsCheckBox> {
+                        text: "Speech synthesis"
+                    }
+                }
+
+                defaults_hint = <SettingsHint> {
+                    text: "Used by the learning dataflow the next time it starts"
+                }
+            }
+
             // Available models with sync button
             models_section = <View> {
                 width: Fill, height: Fit
@@ -570,6 +653,12 @@ impl ProviderViewRef {
             // Show models if available
             Self::display_models_internal(&mut inner, cx, &provider.models);
 
+            // Speech credentials for the Volcano provider
+            inner
+                .view
+                .view(ids!(volcano_section))
+                .set_visible(cx, provider.provider_type == ProviderType::Volcano);
+
             // Show/hide remove button for custom providers
             inner
                 .view
@@ -673,6 +762,101 @@ impl ProviderViewRef {
             .and_then(|inner| inner.current_provider_id.clone())
     }
 
+    /// Show the Volcano speech fields and fill them from `settings`
+    pub fn load_volcano_settings(&self, cx: &mut Cx, settings: Option<&VolcanoSettings>) {
+        if let Some(inner) = self.borrow() {
+            inner
+                .view
+                .view(ids!(volcano_section))
+                .set_visible(cx, settings.is_some());
+
+            let settings = settings.cloned().unwrap_or_default();
+            inner
+                .view
+                .text_input(ids!(app_id_input))
+                .set_text(cx, &settings.app_id);
+            inner
+                .view
+                .text_input(ids!(access_token_input))
+                .set_text(cx, &settings.access_token);
+            inner
+                .view
+                .text_input(ids!(tts_resource_input))
+                .set_text(cx, &settings.tts_resource_id);
+            inner
+                .view
+                .text_input(ids!(asr_resource_input))
+                .set_text(cx, &settings.asr_resource_id);
+            inner
+                .view
+                .text_input(ids!(voice_input))
+                .set_text(cx, &settings.voice_type);
+        }
+    }
+
+    /// Get the Volcano speech fields
+    pub fn volcano_settings(&self) -> Option<VolcanoSettings> {
+        self.borrow().map(|inner| VolcanoSettings {
+            app_id: inner.view.text_input(ids!(app_id_input)).text(),
+            access_token: inner.view.text_input(ids!(access_token_input)).text(),
+            tts_resource_id: inner.view.text_input(ids!(tts_resource_input)).text(),
+            asr_resource_id: inner.view.text_input(ids!(asr_resource_input)).text(),
+            voice_type: inner.view.text_input(ids!(voice_input)).text(),
+        })
+    }
+
+    /// Check the roles this provider is the default for; speech roles are
+    /// only shown for providers with an ASR/TTS node
+    pub fn load_defaults(&self, cx: &mut Cx, provider_id: &str, prefs: &Preferences) {
+        if let Some(inner) = self.borrow() {
+            let is_default = |default: &Option<ProviderId>| default.as_deref() == Some(provider_id);
+
+            inner
+                .view
+                .check_box(ids!(chat_default_checkbox))
+                .set_active(cx, is_default(&prefs.default_chat_provider));
+
+            let asr = inner.view.check_box(ids!(asr_default_checkbox));
+            asr.set_visible(cx, AsrBackend::from_provider_id(provider_id).is_some());
+            asr.set_active(cx, is_default(&prefs.default_asr_provider));
+
+            let tts = inner.view.check_box(ids!(tts_default_checkbox));
+            tts.set_visible(cx, TtsBackend::from_provider_id(provider_id).is_some());
+            tts.set_active(cx, is_default(&prefs.default_tts_provider));
+        }
+    }
+
+    /// Write the checked roles to `prefs`; unchecking a role clears it only
+    /// if this provider was its default
+    pub fn apply_defaults(&self, cx: &Cx, provider_id: &str, prefs: &mut Preferences) {
+        if let Some(inner) = self.borrow() {
+            let apply = |default: &mut Option<ProviderId>, checked: bool| {
+                if checked {
+                    *default = Some(provider_id.to_string());
+                } else if default.as_deref() == Some(provider_id) {
+                    *default = None;
+                }
+            };
+
+            apply(
+                &mut prefs.default_chat_provider,
+                inner.view.check_box(ids!(chat_default_checkbox)).active(cx),
+            );
+            if AsrBackend::from_provider_id(provider_id).is_some() {
+                apply(
+                    &mut prefs.default_asr_provider,
+                    inner.view.check_box(ids!(asr_default_checkbox)).active(cx),
+                );
+            }
+            if TtsBackend::from_provider_id(provider_id).is_some() {
+                apply(
+                    &mut prefs.default_tts_provider,
+                    inner.view.check_box(ids!(tts_default_checkbox)).active(cx),
+                );
+            }
+        }
+    }
+
     /// Get the current form values
     pub fn get_form_values(&self) -> Option<(String, Option<String>)> {
         self.borrow().map(|inner| {
@@ -753,6 +937,25 @@ impl ProviderViewRef {
                     },
                 );
 
+            // Volcano and defaults sections
+            for label in [
+                ids!(content.volcano_section.app_id_label),
+                ids!(content.volcano_section.access_token_label),
+                ids!(content.volcano_section.tts_resource_label),
+                ids!(content.volcano_section.asr_resource_label),
+                ids!(content.volcano_section.voice_label),
+                ids!(content.volcano_section.volcano_hint),
+                ids!(content.defaults_section.defaults_label),
+                ids!(content.defaults_section.defaults_hint),
+            ] {
+                inner.view.label(label).apply_over(
+                    cx,
+                    live! {
+                        draw_text: { dark_mode: (dark_mode) }
+                    },
+                );
+            }
+
             // Models section
             inner
                 .view
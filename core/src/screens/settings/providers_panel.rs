@@ -170,6 +170,19 @@ live_design! {
                     text: "Alibaba Cloud (Qwen)"
                 }
             }
+
+            volcano_item = <ProviderItem> {
+                <Icon> {
+                    draw_icon: {
+                        svg_file: (ICO_DEEPSEEK)
+                        fn get_color(self) -> vec4 { return #3370FF; }
+                    }
+                    icon_walk: {width: 20, height: 20, margin: {right: 10}}
+                }
+                volcano_label = <ProviderLabel> {
+                    text: "Volcano Engine (Doubao)"
+                }
+            }
         }
 
         // Spacer
@@ -209,6 +222,7 @@ impl Widget for ProvidersPanel {
             ids!(list_container.openai_item),
             ids!(list_container.deepseek_item),
             ids!(list_container.alibaba_item),
+            ids!(list_container.volcano_item),
         ];
 
         // Handle hover effects using FingerHover events
@@ -222,6 +236,7 @@ impl Widget for ProvidersPanel {
                         Some("openai") => item_id == &ids!(list_container.openai_item),
                         Some("deepseek") => item_id == &ids!(list_container.deepseek_item),
                         Some("alibaba_cloud") => item_id == &ids!(list_container.alibaba_item),
+                        Some("volcano") => item_id == &ids!(list_container.volcano_item),
                         _ => false,
                     };
                     if !is_selected {
@@ -252,6 +267,7 @@ impl Widget for ProvidersPanel {
                         Some("openai") => item_id == &ids!(list_container.openai_item),
                         Some("deepseek") => item_id == &ids!(list_container.deepseek_item),
                         Some("alibaba_cloud") => item_id == &ids!(list_container.alibaba_item),
+                        Some("volcano") => item_id == &ids!(list_container.volcano_item),
                         _ => false,
                     };
                     if !is_selected {
@@ -312,6 +328,14 @@ impl Widget for ProvidersPanel {
         {
             new_selection = Some(ProviderId::from("alibaba_cloud"));
         }
+        if self
+            .view
+            .view(ids!(list_container.volcano_item))
+            .finger_up(actions)
+            .is_some()
+        {
+            new_selection = Some(ProviderId::from("volcano"));
+        }
 
         if let Some(id) = new_selection {
             // Only process if different from current selection
@@ -400,6 +424,27 @@ impl Widget for ProvidersPanel {
                                 );
                         }
                     }
+                    "volcano" => {
+                        if self.dark_mode {
+                            self.view
+                                .view(ids!(list_container.volcano_item))
+                                .apply_over(
+                                    cx,
+                                    live! {
+                                        draw_bg: { color: (vec4(0.122, 0.227, 0.541, 1.0)) }
+                                    },
+                                );
+                        } else {
+                            self.view
+                                .view(ids!(list_container.volcano_item))
+                                .apply_over(
+                                    cx,
+                                    live! {
+                                        draw_bg: { color: (vec4(0.859, 0.918, 0.996, 1.0)) }
+                                    },
+                                );
+                        }
+                    }
                     _ => {}
                 }
                 self.selected_provider_id = Some(id.clone());
@@ -435,6 +480,7 @@ impl ProvidersPanelRef {
             "openai_item" => Some(ProviderId::from("openai")),
             "deepseek_item" => Some(ProviderId::from("deepseek")),
             "alibaba_item" => Some(ProviderId::from("alibaba_cloud")),
+            "volcano_item" => Some(ProviderId::from("volcano")),
             _ => None,
         }
     }
@@ -553,6 +599,30 @@ impl ProvidersPanelRef {
                     .apply_over(cx, live! { draw_bg: { color: (light_normal) } });
             }
 
+            // Volcano item
+            let is_volcano_selected = selected == Some("volcano");
+            if is_volcano_selected && is_dark {
+                inner
+                    .view
+                    .view(ids!(list_container.volcano_item))
+                    .apply_over(cx, live! { draw_bg: { color: (dark_selected) } });
+            } else if is_volcano_selected {
+                inner
+                    .view
+                    .view(ids!(list_container.volcano_item))
+                    .apply_over(cx, live! { draw_bg: { color: (light_selected) } });
+            } else if is_dark {
+                inner
+                    .view
+                    .view(ids!(list_container.volcano_item))
+                    .apply_over(cx, live! { draw_bg: { color: (dark_normal) } });
+            } else {
+                inner
+                    .view
+                    .view(ids!(list_container.volcano_item))
+                    .apply_over(cx, live! { draw_bg: { color: (light_normal) } });
+            }
+
             // Provider labels - update text colors
             if is_dark {
                 inner
@@ -567,6 +637,10 @@ impl ProvidersPanelRef {
                     .view
                     .label(ids!(list_container.alibaba_item.alibaba_label))
                     .apply_over(cx, live! { draw_text: { color: (dark_text) } });
+                inner
+                    .view
+                    .label(ids!(list_container.volcano_item.volcano_label))
+                    .apply_over(cx, live! { draw_text: { color: (dark_text) } });
             } else {
                 inner
                     .view
@@ -580,6 +654,10 @@ impl ProvidersPanelRef {
                     .view
                     .label(ids!(list_container.alibaba_item.alibaba_label))
                     .apply_over(cx, live! { draw_text: { color: (light_text) } });
+                inner
+                    .view
+                    .label(ids!(list_container.volcano_item.volcano_label))
+                    .apply_over(cx, live! { draw_text: { color: (light_text) } });
             }
 
             // Add button
@@ -7,7 +7,7 @@ use makepad_widgets::*;
 use super::provider_view::ProviderViewWidgetExt;
 use super::providers_panel::{ProvidersPanelAction, ProvidersPanelWidgetExt};
 use super::release_notes_modal::ReleaseNotesModalWidgetExt;
-use crate::models::{Preferences, Provider, ProviderId};
+use crate::models::{Preferences, Provider, ProviderId, ProviderType};
 
 live_design! {
     use link::theme::*;
@@ -414,6 +414,7 @@ impl Widget for SettingsScreen {
                     enabled: true,
                     models: vec![],
                     is_custom: true,
+                    volcano: None,
                     connection_status: crate::models::ProviderConnectionStatus::Disconnected,
                 };
 
@@ -818,7 +819,7 @@ impl SettingsScreen {
         }
 
         // Find the provider and clone the data we need
-        let (provider_name, provider_url, api_key, saved_models, has_api_key, is_custom) = {
+        let (provider_name, provider_url, api_key, saved_models, has_api_key, is_custom, volcano) = {
             if let Some(prefs) = &self.preferences {
                 if let Some(provider) = prefs.providers.iter().find(|p| &p.id == provider_id) {
                     (
@@ -832,18 +833,22 @@ impl SettingsScreen {
                             .map(|k| !k.is_empty())
                             .unwrap_or(false),
                         provider.is_custom,
+                        (provider.provider_type == ProviderType::Volcano)
+                            .then(|| provider.volcano.clone().unwrap_or_default()),
                     )
                 } else {
                     let name = match provider_id.as_str() {
                         "openai" => "OpenAI",
                         "deepseek" => "DeepSeek",
                         "alibaba_cloud" => "Alibaba Cloud (Qwen)",
+                        "volcano" => "Volcano Engine (Doubao)",
                         _ => provider_id.as_str(),
                     };
                     let url = match provider_id.as_str() {
                         "openai" => "https://api.openai.com/v1",
                         "deepseek" => "https://api.deepseek.com",
                         "alibaba_cloud" => "https://dashscope.aliyuncs.com/compatible-mode/v1",
+                        "volcano" => "https://ark.cn-beijing.volces.com/api/v3",
                         _ => "",
                     };
                     (
@@ -853,6 +858,7 @@ impl SettingsScreen {
                         Vec::new(),
                         false,
                         false,
+                        None,
                     )
                 }
             } else {
@@ -860,12 +866,14 @@ impl SettingsScreen {
                     "openai" => "OpenAI",
                     "deepseek" => "DeepSeek",
                     "alibaba_cloud" => "Alibaba Cloud (Qwen)",
+                    "volcano" => "Volcano Engine (Doubao)",
                     _ => provider_id.as_str(),
                 };
                 let url = match provider_id.as_str() {
                     "openai" => "https://api.openai.com/v1",
                     "deepseek" => "https://api.deepseek.com",
                     "alibaba_cloud" => "https://dashscope.aliyuncs.com/compatible-mode/v1",
+                    "volcano" => "https://ark.cn-beijing.volces.com/api/v3",
                     _ => "",
                 };
                 (
@@ -875,6 +883,7 @@ impl SettingsScreen {
                     Vec::new(),
                     false,
                     false,
+                    None,
                 )
             }
         };
@@ -895,6 +904,14 @@ impl SettingsScreen {
                 content.pages.providers_page.provider_view.api_key_input
             ))
             .set_text(cx, &api_key);
+        self.view
+            .provider_view(ids!(content.pages.providers_page.provider_view))
+            .load_volcano_settings(cx, volcano.as_ref());
+        if let Some(prefs) = &self.preferences {
+            self.view
+                .provider_view(ids!(content.pages.providers_page.provider_view))
+                .load_defaults(cx, provider_id, prefs);
+        }
 
         // Update status labels
         self.view
@@ -944,7 +961,7 @@ impl SettingsScreen {
         self.view.redraw(cx);
     }
 
-    fn save_current_provider(&mut self, _cx: &mut Cx) {
+    fn save_current_provider(&mut self, cx: &mut Cx) {
         if let Some(provider_id) = &self.selected_provider_id {
             let api_host = self
                 .view
@@ -969,6 +986,15 @@ impl SettingsScreen {
                 if let Some(provider) = prefs.providers.iter_mut().find(|p| &p.id == provider_id) {
                     provider.url = api_host;
                     provider.api_key = api_key;
+                    if provider.provider_type == ProviderType::Volcano {
+                        provider.volcano = self
+                            .view
+                            .provider_view(ids!(content.pages.providers_page.provider_view))
+                            .volcano_settings();
+                    }
+                    self.view
+                        .provider_view(ids!(content.pages.providers_page.provider_view))
+                        .apply_defaults(cx, provider_id, prefs);
                     if let Err(e) = prefs.save() {
                         eprintln!("Failed to save preferences: {}", e);
                     } else {
@@ -1060,6 +1086,11 @@ impl SettingsScreen {
                     "gpt-3.5-turbo".to_string(),
                 ],
                 "deepseek" => vec!["deepseek-chat".to_string(), "deepseek-coder".to_string()],
+                "volcano" => vec![
+                    "doubao-seed-1-8-251228".to_string(),
+                    "doubao-pro-128k".to_string(),
+                    "doubao-lite-32k".to_string(),
+                ],
                 "alibaba_cloud" => vec![
                     "qwen-turbo".to_string(),
                     "qwen-plus".to_string(),
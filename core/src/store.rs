@@ -0,0 +1,61 @@
+//! Storage-agnostic access to learning data
+//!
+//! [`LearningStore`] covers issue words, learning sessions and conversations.
+//! It is implemented by the local SQLite [`Database`](crate::db::Database)
+//! and by [`LearnApiClient`](crate::learn_api::LearnApiClient) for the
+//! backend's `/learn` API, so screens and nodes take a `&dyn LearningStore`
+//! and work against either.
+//!
+//! Both implementations speak the [`crate::models`] types; the remote store
+//! converts from the API's JSON shapes (RFC 3339 timestamps, string enums) at
+//! the edge. Fields the API doesn't carry (turn ids, scheduler state) come
+//! back as their defaults.
+
+mod local;
+mod remote;
+
+#[cfg(test)]
+mod conformance;
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+use crate::models::{Conversation, IssueWord, LearningSession};
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Learn API error: {0}")]
+    Remote(String),
+
+    #[error("Invalid {field}: {value}")]
+    Invalid { field: &'static str, value: String },
+}
+
+#[async_trait]
+pub trait LearningStore: Send + Sync {
+    /// Add an issue word (or refresh the descriptions of a known one);
+    /// returns its id
+    async fn add_issue_word(&self, word: &IssueWord) -> Result<i64, StoreError>;
+
+    /// Words due for review, never-reviewed words first
+    async fn words_for_review(&self, limit: i64) -> Result<Vec<IssueWord>, StoreError>;
+
+    /// Record the start of a session
+    async fn start_session(&self, session: &LearningSession) -> Result<(), StoreError>;
+
+    /// Sessions, most recently started first
+    async fn recent_sessions(&self, limit: i64) -> Result<Vec<LearningSession>, StoreError>;
+
+    /// Add a conversation message; returns its id
+    async fn add_conversation(&self, conv: &Conversation) -> Result<i64, StoreError>;
+
+    /// Messages of a session, newest first
+    async fn conversation_history(
+        &self,
+        session_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Conversation>, StoreError>;
+}
@@ -0,0 +1,390 @@
+//! Conformance suite for [`LearningStore`] implementations
+//!
+//! The same checks run against a freshly migrated SQLite database and against
+//! [`LearnApiClient`] talking to a stand-in `/learn` server on localhost.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::{LearningStore, StoreError};
+use crate::db::Database;
+use crate::learn_api::LearnApiClient;
+use crate::models::{
+    Conversation, IssueType, IssueWord, LearningSession, SessionType, Speaker, UseLang,
+};
+use crate::scheduler::Scheduler;
+
+fn now() -> i64 {
+    Utc::now().timestamp()
+}
+
+fn issue_word(word: &str, issue_type: IssueType, description: &str) -> IssueWord {
+    let state = Scheduler::default().new_state();
+    IssueWord {
+        id: None,
+        word: word.to_string(),
+        issue_type,
+        description_en: Some(description.to_string()),
+        description_zh: None,
+        last_picked_at: None,
+        created_at: now(),
+        pick_count: 0,
+        next_review_at: None,
+        review_interval_days: 1,
+        difficulty_level: 1,
+        context: Some(format!("I said {word} wrong")),
+        audio_timestamp: None,
+        ease_factor: state.ease,
+        review_reps: state.reps,
+        lapses: state.lapses,
+    }
+}
+
+fn session(session_id: &str, session_type: SessionType) -> LearningSession {
+    LearningSession {
+        session_id: session_id.to_string(),
+        session_type: Some(session_type),
+        sceneid: None,
+        scene_dialogue_id: None,
+        classic_clip_id: None,
+        target_words: None,
+        started_at: now(),
+        ended_at: None,
+        duration_seconds: None,
+        total_words_spoken: 0,
+        average_wpm: None,
+        error_count: 0,
+        correction_count: 0,
+        notes: None,
+        ai_summary_en: None,
+        ai_summary_zh: None,
+    }
+}
+
+fn conversation(session_id: &str, speaker: Speaker, content: &str) -> Conversation {
+    Conversation {
+        id: None,
+        session_id: session_id.to_string(),
+        turn_id: None,
+        speaker,
+        use_lang: UseLang::En,
+        content_en: content.to_string(),
+        content_zh: String::new(),
+        audio_path: None,
+        created_at: now(),
+        duration_ms: Some(1200),
+        words_per_minute: None,
+        pause_count: None,
+        hesitation_count: None,
+    }
+}
+
+async fn check_store(store: &dyn LearningStore) {
+    check_issue_words(store).await;
+    check_sessions(store).await;
+    check_conversations(store).await;
+}
+
+async fn check_issue_words(store: &dyn LearningStore) {
+    let first = store
+        .add_issue_word(&issue_word("schedule", IssueType::Pronunciation, "stress"))
+        .await
+        .unwrap();
+    let second = store
+        .add_issue_word(&issue_word("schedule", IssueType::Usage, "make a schedule"))
+        .await
+        .unwrap();
+    assert_ne!(first, second);
+
+    // Re-adding a word keeps its id and refreshes its description
+    let again = store
+        .add_issue_word(&issue_word(
+            "schedule",
+            IssueType::Pronunciation,
+            "SHED-jool",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(again, first);
+
+    let due = store.words_for_review(10).await.unwrap();
+    assert_eq!(due.len(), 2);
+    let word = due.iter().find(|w| w.id == Some(first)).unwrap();
+    assert_eq!(word.word, "schedule");
+    assert_eq!(word.issue_type, IssueType::Pronunciation);
+    assert_eq!(word.description_en.as_deref(), Some("SHED-jool"));
+    assert_eq!(word.context.as_deref(), Some("I said schedule wrong"));
+    assert_eq!(word.next_review_at, None);
+
+    assert_eq!(store.words_for_review(1).await.unwrap().len(), 1);
+}
+
+async fn check_sessions(store: &dyn LearningStore) {
+    store
+        .start_session(&session("session-a", SessionType::FreeTalk))
+        .await
+        .unwrap();
+    store
+        .start_session(&session("session-b", SessionType::Review))
+        .await
+        .unwrap();
+
+    let sessions = store.recent_sessions(10).await.unwrap();
+    assert_eq!(sessions.len(), 2);
+    let review = sessions
+        .iter()
+        .find(|s| s.session_id == "session-b")
+        .unwrap();
+    assert_eq!(review.session_type, Some(SessionType::Review));
+    assert_eq!(review.ended_at, None);
+    assert!(review.started_at > 0);
+
+    assert_eq!(store.recent_sessions(1).await.unwrap().len(), 1);
+}
+
+async fn check_conversations(store: &dyn LearningStore) {
+    let question = store
+        .add_conversation(&conversation("session-a", Speaker::User, "Hi there"))
+        .await
+        .unwrap();
+    let reply = store
+        .add_conversation(&conversation("session-a", Speaker::Teacher, "Hello!"))
+        .await
+        .unwrap();
+    store
+        .add_conversation(&conversation("session-b", Speaker::User, "Other session"))
+        .await
+        .unwrap();
+
+    let history = store.conversation_history("session-a", 10).await.unwrap();
+    let ids: Vec<Option<i64>> = history.iter().map(|c| c.id).collect();
+    assert_eq!(ids, vec![Some(reply), Some(question)]);
+    assert_eq!(history[0].speaker, Speaker::Teacher);
+    assert_eq!(history[0].content_en, "Hello!");
+    assert_eq!(history[1].use_lang, UseLang::En);
+    assert_eq!(history[1].duration_ms, Some(1200));
+
+    let latest = store.conversation_history("session-a", 1).await.unwrap();
+    assert_eq!(latest.len(), 1);
+    assert_eq!(latest[0].id, Some(reply));
+
+    assert!(
+        store
+            .conversation_history("missing", 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_sqlite_store() {
+    let path = std::env::temp_dir().join(format!("colang-store-{}.db", uuid::Uuid::new_v4()));
+    let db = Database::new(&format!("sqlite://{}?mode=rwc", path.display()))
+        .await
+        .unwrap();
+    db.migrate().await.unwrap();
+
+    check_store(&db).await;
+
+    let _ = std::fs::remove_file(path);
+}
+
+#[tokio::test]
+async fn test_remote_store() {
+    let base_url = serve_stand_in().await;
+    let client = LearnApiClient::new(&base_url, Some("token".to_string()));
+
+    check_store(&client).await;
+
+    let anonymous = LearnApiClient::new(&base_url, None);
+    assert!(matches!(
+        anonymous.words_for_review(10).await,
+        Err(StoreError::Remote(_))
+    ));
+}
+
+// ============ Stand-in /learn server ============
+
+/// In-memory backend answering the `/learn` routes the remote store uses
+#[derive(Default)]
+struct StandIn {
+    issue_words: Vec<Value>,
+    sessions: Vec<Value>,
+    conversations: Vec<Value>,
+}
+
+impl StandIn {
+    fn handle(
+        &mut self,
+        method: &str,
+        path: &str,
+        query: &HashMap<&str, &str>,
+        body: Value,
+    ) -> (u16, Value) {
+        let limit = query
+            .get("limit")
+            .and_then(|l| l.parse().ok())
+            .unwrap_or(usize::MAX);
+        let now = Utc::now().to_rfc3339();
+
+        match (method, path) {
+            ("GET", "/learn/issue-words") => {
+                let due_only = query.get("due_only") == Some(&"true");
+                let words = self
+                    .issue_words
+                    .iter()
+                    .filter(|w| {
+                        !due_only
+                            || w["next_review_at"]
+                                .as_str()
+                                .is_none_or(|t| t <= now.as_str())
+                    })
+                    .take(limit)
+                    .cloned()
+                    .collect();
+                (200, Value::Array(words))
+            }
+            ("POST", "/learn/issue-words") => {
+                let existing = self
+                    .issue_words
+                    .iter_mut()
+                    .find(|w| w["word"] == body["word"] && w["issue_type"] == body["issue_type"]);
+                if let Some(word) = existing {
+                    for key in ["description_en", "description_zh", "context"] {
+                        word[key] = body[key].clone();
+                    }
+                    return (200, word.clone());
+                }
+                let mut word = body;
+                word["id"] = json!(self.issue_words.len() + 1);
+                word["user_id"] = json!(1);
+                word["pick_count"] = json!(0);
+                word["review_interval_days"] = json!(1);
+                word["difficulty_level"] = json!(1);
+                word["created_at"] = json!(now);
+                self.issue_words.push(word.clone());
+                (201, word)
+            }
+            ("GET", "/learn/sessions") => {
+                let sessions = self.sessions.iter().rev().take(limit).cloned().collect();
+                (200, Value::Array(sessions))
+            }
+            ("POST", "/learn/sessions") => {
+                let mut session = body;
+                session["id"] = json!(self.sessions.len() + 1);
+                session["user_id"] = json!(1);
+                session["started_at"] = json!(now);
+                self.sessions.push(session.clone());
+                (201, session)
+            }
+            ("GET", "/learn/conversations") => {
+                let convs = self
+                    .conversations
+                    .iter()
+                    .rev()
+                    .filter(|c| {
+                        query
+                            .get("session_id")
+                            .is_none_or(|s| c["session_id"] == *s)
+                    })
+                    .take(limit)
+                    .cloned()
+                    .collect();
+                (200, Value::Array(convs))
+            }
+            ("POST", "/learn/conversations") => {
+                let mut conv = body;
+                conv["id"] = json!(self.conversations.len() + 1);
+                conv["user_id"] = json!(1);
+                conv["created_at"] = json!(now);
+                self.conversations.push(conv.clone());
+                (201, conv)
+            }
+            _ => (404, json!({ "detail": "Not found" })),
+        }
+    }
+}
+
+/// Start the stand-in server; returns its base URL
+async fn serve_stand_in() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let state = Arc::new(Mutex::new(StandIn::default()));
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let state = state.clone();
+            tokio::spawn(async move {
+                let _ = handle_connection(stream, state).await;
+            });
+        }
+    });
+
+    format!("http://{}", addr)
+}
+
+/// Answer one HTTP/1.1 request, then close the connection
+async fn handle_connection(
+    mut stream: TcpStream,
+    state: Arc<Mutex<StandIn>>,
+) -> std::io::Result<()> {
+    let (read, mut write) = stream.split();
+    let mut reader = BufReader::new(read);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let target = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut authorized = false;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "content-length" => content_length = value.trim().parse().unwrap_or(0),
+            "authorization" => authorized = value.trim().starts_with("Bearer "),
+            _ => {}
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    let body = serde_json::from_slice(&body).unwrap_or(Value::Null);
+
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    let query: HashMap<&str, &str> = query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .collect();
+
+    let (status, response) = if authorized {
+        state.lock().unwrap().handle(&method, path, &query, body)
+    } else {
+        (401, json!({ "detail": "Not authenticated" }))
+    };
+
+    let response = response.to_string();
+    let head = format!(
+        "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        response.len()
+    );
+    write.write_all(head.as_bytes()).await?;
+    write.write_all(response.as_bytes()).await?;
+    write.shutdown().await
+}
@@ -0,0 +1,38 @@
+//! [`LearningStore`] over the local SQLite database
+
+use async_trait::async_trait;
+
+use super::{LearningStore, StoreError};
+use crate::db::Database;
+use crate::models::{Conversation, IssueWord, LearningSession};
+
+#[async_trait]
+impl LearningStore for Database {
+    async fn add_issue_word(&self, word: &IssueWord) -> Result<i64, StoreError> {
+        Ok(self.insert_issue_word(word).await?)
+    }
+
+    async fn words_for_review(&self, limit: i64) -> Result<Vec<IssueWord>, StoreError> {
+        Ok(self.get_words_for_review(limit).await?)
+    }
+
+    async fn start_session(&self, session: &LearningSession) -> Result<(), StoreError> {
+        Ok(self.create_session(session).await?)
+    }
+
+    async fn recent_sessions(&self, limit: i64) -> Result<Vec<LearningSession>, StoreError> {
+        Ok(self.get_recent_sessions(limit).await?)
+    }
+
+    async fn add_conversation(&self, conv: &Conversation) -> Result<i64, StoreError> {
+        Ok(self.insert_conversation(conv).await?)
+    }
+
+    async fn conversation_history(
+        &self,
+        session_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Conversation>, StoreError> {
+        Ok(self.get_conversation_history(session_id, limit).await?)
+    }
+}
@@ -0,0 +1,215 @@
+//! [`LearningStore`] over the backend's `/learn` API
+
+use std::cmp::Reverse;
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDateTime};
+
+use super::{LearningStore, StoreError};
+use crate::learn_api::{self, LearnApiClient};
+use crate::models::{Conversation, IssueWord, LearningSession};
+use crate::scheduler::Scheduler;
+
+#[async_trait]
+impl LearningStore for LearnApiClient {
+    async fn add_issue_word(&self, word: &IssueWord) -> Result<i64, StoreError> {
+        let req = learn_api::CreateIssueWordRequest {
+            word: word.word.clone(),
+            issue_type: word.issue_type.to_string(),
+            description_en: word.description_en.clone(),
+            description_zh: word.description_zh.clone(),
+            context: word.context.clone(),
+        };
+        let created = self
+            .create_issue_word(req)
+            .await
+            .map_err(StoreError::Remote)?;
+        Ok(created.id)
+    }
+
+    async fn words_for_review(&self, limit: i64) -> Result<Vec<IssueWord>, StoreError> {
+        self.list_issue_words(true, Some(limit))
+            .await
+            .map_err(StoreError::Remote)?
+            .into_iter()
+            .map(issue_word)
+            .collect()
+    }
+
+    async fn start_session(&self, session: &LearningSession) -> Result<(), StoreError> {
+        let req = learn_api::CreateSessionRequest {
+            session_id: session.session_id.clone(),
+            session_type: session.session_type.as_ref().map(|t| t.to_string()),
+            scene_id: session.sceneid,
+            dialogue_id: session.scene_dialogue_id,
+            classic_clip_id: session.classic_clip_id,
+        };
+        self.create_session(req).await.map_err(StoreError::Remote)?;
+        Ok(())
+    }
+
+    async fn recent_sessions(&self, limit: i64) -> Result<Vec<LearningSession>, StoreError> {
+        let mut sessions = self
+            .list_sessions(None, Some(limit))
+            .await
+            .map_err(StoreError::Remote)?
+            .into_iter()
+            .map(learning_session)
+            .collect::<Result<Vec<_>, _>>()?;
+        sessions.sort_by_key(|s| Reverse(s.started_at));
+        Ok(sessions)
+    }
+
+    async fn add_conversation(&self, conv: &Conversation) -> Result<i64, StoreError> {
+        let req = learn_api::CreateConversationRequest {
+            session_id: conv.session_id.clone(),
+            speaker: conv.speaker.to_string(),
+            use_lang: conv.use_lang.to_string(),
+            content_en: conv.content_en.clone(),
+            content_zh: conv.content_zh.clone(),
+            audio_path: conv.audio_path.clone(),
+            duration_ms: conv.duration_ms.map(|v| v as i32),
+            words_per_minute: conv.words_per_minute.map(|v| v as f32),
+            pause_count: conv.pause_count.map(|v| v as i32),
+            hesitation_count: conv.hesitation_count.map(|v| v as i32),
+        };
+        let created = self
+            .create_conversation(req)
+            .await
+            .map_err(StoreError::Remote)?;
+        Ok(created.id)
+    }
+
+    async fn conversation_history(
+        &self,
+        session_id: &str,
+        limit: i64,
+    ) -> Result<Vec<Conversation>, StoreError> {
+        let mut convs = self
+            .list_conversations(Some(session_id), Some(limit))
+            .await
+            .map_err(StoreError::Remote)?
+            .into_iter()
+            .map(conversation)
+            .collect::<Result<Vec<_>, _>>()?;
+        convs.sort_by_key(|c| Reverse((c.created_at, c.id)));
+        Ok(convs)
+    }
+}
+
+fn issue_word(word: learn_api::IssueWord) -> Result<IssueWord, StoreError> {
+    // The API doesn't keep scheduler state; remote words start out fresh
+    let state = Scheduler::default().new_state();
+    Ok(IssueWord {
+        id: Some(word.id),
+        issue_type: parse("issue_type", &word.issue_type)?,
+        word: word.word,
+        description_en: word.description_en,
+        description_zh: word.description_zh,
+        last_picked_at: parse_opt_time("last_picked_at", word.last_picked_at)?,
+        created_at: parse_time("created_at", &word.created_at)?,
+        pick_count: word.pick_count as i64,
+        next_review_at: parse_opt_time("next_review_at", word.next_review_at)?,
+        review_interval_days: word.review_interval_days.unwrap_or(1) as i64,
+        difficulty_level: word.difficulty_level.unwrap_or(1) as i64,
+        context: word.context,
+        audio_timestamp: word.audio_timestamp.map(|v| v as i64),
+        ease_factor: state.ease,
+        review_reps: state.reps,
+        lapses: state.lapses,
+    })
+}
+
+fn learning_session(session: learn_api::LearningSession) -> Result<LearningSession, StoreError> {
+    Ok(LearningSession {
+        session_type: session
+            .session_type
+            .as_deref()
+            .map(|t| parse("session_type", t))
+            .transpose()?,
+        started_at: parse_time("started_at", &session.started_at)?,
+        ended_at: parse_opt_time("ended_at", session.ended_at)?,
+        session_id: session.session_id,
+        sceneid: session.scene_id,
+        scene_dialogue_id: session.dialogue_id,
+        classic_clip_id: session.classic_clip_id,
+        target_words: None,
+        duration_seconds: session.duration_seconds.map(|v| v as i64),
+        total_words_spoken: session.total_words_spoken.unwrap_or_default() as i64,
+        average_wpm: session.average_wpm.map(|v| v as f64),
+        error_count: session.error_count.unwrap_or_default() as i64,
+        correction_count: session.correction_count.unwrap_or_default() as i64,
+        notes: session.notes,
+        ai_summary_en: session.ai_summary_en,
+        ai_summary_zh: session.ai_summary_zh,
+    })
+}
+
+fn conversation(conv: learn_api::Conversation) -> Result<Conversation, StoreError> {
+    Ok(Conversation {
+        id: Some(conv.id),
+        turn_id: None,
+        speaker: parse("speaker", &conv.speaker)?,
+        use_lang: parse("use_lang", &conv.use_lang)?,
+        created_at: parse_time("created_at", &conv.created_at)?,
+        session_id: conv.session_id,
+        content_en: conv.content_en,
+        content_zh: conv.content_zh,
+        audio_path: conv.audio_path,
+        duration_ms: conv.duration_ms.map(|v| v as i64),
+        words_per_minute: conv.words_per_minute.map(|v| v as f64),
+        pause_count: conv.pause_count.map(|v| v as i64),
+        hesitation_count: conv.hesitation_count.map(|v| v as i64),
+    })
+}
+
+fn parse<T: std::str::FromStr>(field: &'static str, value: &str) -> Result<T, StoreError> {
+    value.parse().map_err(|_| StoreError::Invalid {
+        field,
+        value: value.to_string(),
+    })
+}
+
+/// Unix seconds of an API timestamp (RFC 3339, or naive UTC)
+fn parse_time(field: &'static str, value: &str) -> Result<i64, StoreError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.timestamp())
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f")
+                .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+                .map(|t| t.and_utc().timestamp())
+        })
+        .map_err(|_| StoreError::Invalid {
+            field,
+            value: value.to_string(),
+        })
+}
+
+fn parse_opt_time(field: &'static str, value: Option<String>) -> Result<Option<i64>, StoreError> {
+    value.map(|v| parse_time(field, &v)).transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        let expected = 1_760_659_200; // 2025-10-17 00:00:00 UTC
+        for value in [
+            "2025-10-17T00:00:00Z",
+            "2025-10-17T08:00:00+08:00",
+            "2025-10-17T00:00:00.123456",
+            "2025-10-17 00:00:00",
+        ] {
+            assert_eq!(parse_time("t", value).unwrap(), expected, "{value}");
+        }
+        assert!(matches!(
+            parse_time("created_at", "yesterday"),
+            Err(StoreError::Invalid {
+                field: "created_at",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,462 @@
+//! Voice activity detection and utterance endpointing for mic capture
+//!
+//! Mic chunks are split into short frames and classified as speech when their
+//! RMS energy is above a threshold and their zero-crossing rate is below a
+//! ceiling (broadband noise crosses zero far more often than voiced speech).
+//! Speech frames are grouped into utterances; an utterance ends after a
+//! hangover of silence and is dropped if it holds too little speech.
+//!
+//! Once an utterance holds enough speech to be kept, its audio is also handed
+//! out as [`SpeechChunk`]s while the user is still talking, so streaming ASR
+//! can recognize it before it is endpointed.
+
+use std::collections::VecDeque;
+
+use crate::audio::AudioChunk;
+
+/// VAD tuning parameters
+#[derive(Clone, Debug)]
+pub struct VadConfig {
+    /// Minimum frame RMS (0.0 - 1.0) to count as speech
+    pub energy_threshold: f32,
+    /// Maximum zero-crossing rate (crossings per sample) to count as speech
+    pub max_zero_crossing_rate: f32,
+    /// Analysis frame length
+    pub frame_ms: u32,
+    /// Silence needed after speech before the utterance is closed
+    pub hangover_ms: u32,
+    /// Utterances with less speech than this are discarded
+    pub min_speech_ms: u32,
+    /// Audio kept before the first speech frame so onsets are not clipped
+    pub pre_roll_ms: u32,
+    /// Utterances are force-closed at this length
+    pub max_utterance_ms: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            energy_threshold: 0.02,
+            max_zero_crossing_rate: 0.35,
+            frame_ms: 20,
+            hangover_ms: 700,
+            min_speech_ms: 250,
+            pre_roll_ms: 200,
+            max_utterance_ms: 30_000,
+        }
+    }
+}
+
+/// A complete utterance ready for ASR
+#[derive(Clone, Debug)]
+pub struct Utterance {
+    /// Turn id, shared with the utterance's speech chunks
+    pub turn_id: String,
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    /// Start time in ms since capture started
+    pub start_ms: u64,
+    /// End time in ms since capture started
+    pub end_ms: u64,
+}
+
+/// Audio of the utterance in progress, streamed while the user speaks
+#[derive(Clone, Debug)]
+pub struct SpeechChunk {
+    /// Turn id of the utterance this chunk belongs to
+    pub turn_id: String,
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    /// The utterance ended with this chunk
+    pub last: bool,
+}
+
+enum VadState {
+    Silence,
+    Speech {
+        turn_id: String,
+        start_sample: u64,
+        speech_samples: usize,
+        silence_samples: usize,
+    },
+}
+
+/// Frame-based VAD that turns a chunk stream into utterances
+pub struct VoiceActivityDetector {
+    config: VadConfig,
+    state: VadState,
+    sample_rate: u32,
+    /// Samples seen since capture started
+    position: u64,
+    /// Samples not yet forming a full frame
+    pending: Vec<f32>,
+    pre_roll: VecDeque<f32>,
+    utterance: Vec<f32>,
+    /// Samples of the current utterance already handed out as chunks
+    streamed: usize,
+    chunks: Vec<SpeechChunk>,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(config: VadConfig) -> Self {
+        Self {
+            config,
+            state: VadState::Silence,
+            sample_rate: 0,
+            position: 0,
+            pending: Vec::new(),
+            pre_roll: VecDeque::new(),
+            utterance: Vec::new(),
+            streamed: 0,
+            chunks: Vec::new(),
+        }
+    }
+
+    pub fn config(&self) -> &VadConfig {
+        &self.config
+    }
+
+    /// Replace the config; an utterance in progress is discarded as by [`Self::reset`]
+    pub fn set_config(&mut self, config: VadConfig) {
+        self.config = config;
+        self.reset();
+    }
+
+    /// Whether the user is currently speaking
+    pub fn is_speaking(&self) -> bool {
+        matches!(self.state, VadState::Speech { .. })
+    }
+
+    /// Drop all buffered audio and restart the capture clock
+    ///
+    /// An utterance that was already streamed gets an empty `last` chunk, so
+    /// the ASR request it opened is closed; it yields no [`Utterance`].
+    pub fn reset(&mut self) {
+        match std::mem::replace(&mut self.state, VadState::Silence) {
+            VadState::Speech { turn_id, .. } if self.streamed > 0 => {
+                self.chunks.push(SpeechChunk {
+                    turn_id,
+                    samples: Vec::new(),
+                    sample_rate: self.sample_rate,
+                    last: true,
+                });
+            }
+            _ => {}
+        }
+        self.position = 0;
+        self.pending.clear();
+        self.pre_roll.clear();
+        self.utterance.clear();
+        self.streamed = 0;
+    }
+
+    /// Feed a mic chunk, returning any utterances it completed
+    pub fn push(&mut self, chunk: &AudioChunk) -> Vec<Utterance> {
+        if chunk.sample_rate != self.sample_rate {
+            self.reset();
+            self.sample_rate = chunk.sample_rate;
+        }
+
+        let frame_len = self.ms_to_samples(self.config.frame_ms).max(1);
+        let mut utterances = Vec::new();
+
+        self.pending.extend_from_slice(&chunk.samples);
+        let mut offset = 0;
+        while self.pending.len() - offset >= frame_len {
+            let frame: Vec<f32> = self.pending[offset..offset + frame_len].to_vec();
+            offset += frame_len;
+            if let Some(utterance) = self.process_frame(&frame) {
+                utterances.push(utterance);
+            }
+        }
+        self.pending.drain(..offset);
+        self.stream_speech();
+
+        utterances
+    }
+
+    /// Take the speech chunks produced since the last call
+    ///
+    /// An utterance's chunks always end with a `last` chunk, emitted by the
+    /// same `push`/`flush` that returns the utterance, or by the `reset` that
+    /// drops it.
+    pub fn take_chunks(&mut self) -> Vec<SpeechChunk> {
+        std::mem::take(&mut self.chunks)
+    }
+
+    /// Close the current utterance (e.g. when the mic is stopped)
+    pub fn flush(&mut self) -> Option<Utterance> {
+        let utterance = match self.state {
+            VadState::Speech { .. } => self.finish_utterance(0),
+            VadState::Silence => None,
+        };
+        self.pending.clear();
+        utterance
+    }
+
+    /// Hand out the new audio of an utterance that holds enough speech to be kept
+    fn stream_speech(&mut self) {
+        let VadState::Speech {
+            turn_id,
+            speech_samples,
+            ..
+        } = &self.state
+        else {
+            return;
+        };
+        if *speech_samples < self.ms_to_samples(self.config.min_speech_ms)
+            || self.utterance.len() <= self.streamed
+        {
+            return;
+        }
+
+        self.chunks.push(SpeechChunk {
+            turn_id: turn_id.clone(),
+            samples: self.utterance[self.streamed..].to_vec(),
+            sample_rate: self.sample_rate,
+            last: false,
+        });
+        self.streamed = self.utterance.len();
+    }
+
+    fn process_frame(&mut self, frame: &[f32]) -> Option<Utterance> {
+        let is_speech = self.is_speech_frame(frame);
+        let hangover = self.ms_to_samples(self.config.hangover_ms);
+        let max_len = self.ms_to_samples(self.config.max_utterance_ms);
+
+        let result = match &mut self.state {
+            VadState::Silence => {
+                if is_speech {
+                    let start_sample = self.position - self.pre_roll.len() as u64;
+                    self.utterance.clear();
+                    self.utterance.extend(self.pre_roll.drain(..));
+                    self.utterance.extend_from_slice(frame);
+                    self.state = VadState::Speech {
+                        turn_id: uuid::Uuid::new_v4().to_string(),
+                        start_sample,
+                        speech_samples: frame.len(),
+                        silence_samples: 0,
+                    };
+                } else {
+                    self.pre_roll.extend(frame);
+                    let keep = self.ms_to_samples(self.config.pre_roll_ms);
+                    while self.pre_roll.len() > keep {
+                        self.pre_roll.pop_front();
+                    }
+                }
+                None
+            }
+            VadState::Speech {
+                speech_samples,
+                silence_samples,
+                ..
+            } => {
+                self.utterance.extend_from_slice(frame);
+                if is_speech {
+                    *speech_samples += frame.len();
+                    *silence_samples = 0;
+                } else {
+                    *silence_samples += frame.len();
+                }
+
+                if *silence_samples >= hangover {
+                    // Trailing silence is not part of the utterance
+                    let trailing = *silence_samples;
+                    self.finish_utterance(trailing)
+                } else if self.utterance.len() >= max_len {
+                    self.finish_utterance(0)
+                } else {
+                    None
+                }
+            }
+        };
+
+        self.position += frame.len() as u64;
+        result
+    }
+
+    fn finish_utterance(&mut self, trailing_silence: usize) -> Option<Utterance> {
+        let VadState::Speech {
+            turn_id,
+            start_sample,
+            speech_samples,
+            ..
+        } = std::mem::replace(&mut self.state, VadState::Silence)
+        else {
+            return None;
+        };
+
+        let mut samples = std::mem::take(&mut self.utterance);
+        samples.truncate(samples.len().saturating_sub(trailing_silence));
+        let streamed = std::mem::take(&mut self.streamed);
+
+        if speech_samples < self.ms_to_samples(self.config.min_speech_ms) || samples.is_empty() {
+            return None;
+        }
+
+        // Trailing silence may already have been streamed
+        self.chunks.push(SpeechChunk {
+            turn_id: turn_id.clone(),
+            samples: samples[streamed.min(samples.len())..].to_vec(),
+            sample_rate: self.sample_rate,
+            last: true,
+        });
+
+        let end_sample = start_sample + samples.len() as u64;
+        Some(Utterance {
+            turn_id,
+            start_ms: self.samples_to_ms(start_sample),
+            end_ms: self.samples_to_ms(end_sample),
+            samples,
+            sample_rate: self.sample_rate,
+        })
+    }
+
+    fn is_speech_frame(&self, frame: &[f32]) -> bool {
+        if frame.is_empty() {
+            return false;
+        }
+        let energy = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+        let rms = energy.sqrt();
+        let crossings = frame
+            .windows(2)
+            .filter(|w| (w[0] >= 0.0) != (w[1] >= 0.0))
+            .count();
+        let zcr = crossings as f32 / frame.len() as f32;
+
+        rms >= self.config.energy_threshold && zcr <= self.config.max_zero_crossing_rate
+    }
+
+    fn ms_to_samples(&self, ms: u32) -> usize {
+        (self.sample_rate as u64 * ms as u64 / 1000) as usize
+    }
+
+    fn samples_to_ms(&self, samples: u64) -> u64 {
+        if self.sample_rate == 0 {
+            0
+        } else {
+            samples * 1000 / self.sample_rate as u64
+        }
+    }
+}
+
+impl Default for VoiceActivityDetector {
+    fn default() -> Self {
+        Self::new(VadConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn tone(ms: u32) -> Vec<f32> {
+        let n = (RATE * ms / 1000) as usize;
+        (0..n)
+            .map(|i| 0.3 * (i as f32 * 2.0 * std::f32::consts::PI * 200.0 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn silence(ms: u32) -> Vec<f32> {
+        vec![0.0; (RATE * ms / 1000) as usize]
+    }
+
+    fn chunk(samples: Vec<f32>) -> AudioChunk {
+        AudioChunk {
+            samples,
+            sample_rate: RATE,
+        }
+    }
+
+    #[test]
+    fn test_utterance_is_endpointed() {
+        let mut vad = VoiceActivityDetector::default();
+        assert!(vad.push(&chunk(silence(500))).is_empty());
+        assert!(vad.push(&chunk(tone(1000))).is_empty());
+        assert!(vad.is_speaking());
+
+        let utterances = vad.push(&chunk(silence(1000)));
+        assert_eq!(utterances.len(), 1);
+        assert!(!vad.is_speaking());
+
+        let utterance = &utterances[0];
+        // Pre-roll of 200ms before the 500ms speech onset
+        assert_eq!(utterance.start_ms, 300);
+        assert_eq!(utterance.end_ms, 1500);
+        assert_eq!(utterance.samples.len(), (RATE * 1200 / 1000) as usize);
+    }
+
+    #[test]
+    fn test_short_blip_is_dropped() {
+        let mut vad = VoiceActivityDetector::default();
+        vad.push(&chunk(tone(100)));
+        assert!(vad.push(&chunk(silence(1000))).is_empty());
+    }
+
+    #[test]
+    fn test_noise_is_not_speech() {
+        let mut vad = VoiceActivityDetector::default();
+        // Alternating samples: high energy but zero-crossing every sample
+        let noise: Vec<f32> = (0..RATE)
+            .map(|i| if i % 2 == 0 { 0.3 } else { -0.3 })
+            .collect();
+        vad.push(&chunk(noise));
+        assert!(!vad.is_speaking());
+    }
+
+    #[test]
+    fn test_flush_closes_open_utterance() {
+        let mut vad = VoiceActivityDetector::default();
+        vad.push(&chunk(tone(600)));
+        let utterance = vad.flush().expect("utterance");
+        assert_eq!(utterance.start_ms, 0);
+        assert!(!vad.is_speaking());
+    }
+
+    #[test]
+    fn test_speech_is_streamed_before_endpoint() {
+        let mut vad = VoiceActivityDetector::default();
+        vad.push(&chunk(silence(500)));
+        // Below min_speech_ms nothing is streamed yet
+        vad.push(&chunk(tone(100)));
+        assert!(vad.take_chunks().is_empty());
+
+        vad.push(&chunk(tone(900)));
+        let chunks = vad.take_chunks();
+        assert_eq!(chunks.len(), 1);
+        assert!(!chunks[0].last);
+        let streamed = chunks[0].samples.len();
+        assert_eq!(streamed, (RATE * 1200 / 1000) as usize);
+
+        let utterances = vad.push(&chunk(silence(1000)));
+        let chunks = vad.take_chunks();
+        let last = chunks.last().expect("last chunk");
+        assert!(last.last);
+        assert_eq!(last.turn_id, utterances[0].turn_id);
+        // Everything after the stream start is trailing silence
+        assert!(last.samples.is_empty());
+        assert_eq!(utterances[0].samples.len(), streamed);
+    }
+    #[test]
+    fn test_reset_closes_streamed_utterance() {
+        let mut vad = VoiceActivityDetector::default();
+        vad.push(&chunk(tone(600)));
+        let streamed = vad.take_chunks();
+        assert_eq!(streamed.len(), 1);
+
+        vad.set_config(VadConfig::default());
+        assert!(!vad.is_speaking());
+        let chunks = vad.take_chunks();
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].last);
+        assert!(chunks[0].samples.is_empty());
+        assert_eq!(chunks[0].turn_id, streamed[0].turn_id);
+
+        // Nothing was streamed yet: there is no request to close
+        vad.push(&chunk(tone(100)));
+        vad.reset();
+        assert!(vad.take_chunks().is_empty());
+    }
+}
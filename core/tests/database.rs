@@ -0,0 +1,833 @@
+//! Exercise every `Database` method against a freshly migrated SQLite file
+
+use std::path::PathBuf;
+
+use chrono::{Duration, Local, NaiveDate, TimeZone};
+use colang_core::achievements;
+use colang_core::asset_api::Scene;
+use colang_core::daily_stats;
+use colang_core::db::Database;
+use colang_core::models::{
+    AnnotationType, Conversation, ConversationAnnotation, IssueType, IssueWord, LearningSession,
+    SessionType, Severity, Speaker, Streaks, UseLang, WordPracticeLog,
+};
+use colang_core::notebook::{self, ImportAction, Notebook, TtsAudio};
+use colang_core::scheduler::{Grade, Scheduler};
+
+/// A migrated database in a temp file, removed on drop
+struct TestDb {
+    db: Database,
+    path: PathBuf,
+}
+
+impl TestDb {
+    async fn new() -> Self {
+        let path = std::env::temp_dir().join(format!("colang-db-{}.db", uuid::Uuid::new_v4()));
+        let db = Database::new(&format!("sqlite://{}?mode=rwc", path.display()))
+            .await
+            .unwrap();
+        db.migrate().await.unwrap();
+        Self { db, path }
+    }
+}
+
+impl Drop for TestDb {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Unix timestamp of `hour`:00 local time on `date`
+fn at(date: NaiveDate, hour: u32) -> i64 {
+    Local
+        .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+        .earliest()
+        .unwrap()
+        .timestamp()
+}
+
+fn issue_word(word: &str, issue_type: IssueType) -> IssueWord {
+    let state = Scheduler::default().new_state();
+    IssueWord {
+        id: None,
+        word: word.to_string(),
+        issue_type,
+        description_en: Some(format!("{word} needs work")),
+        description_zh: None,
+        last_picked_at: None,
+        created_at: now(),
+        pick_count: 0,
+        next_review_at: None,
+        review_interval_days: 1,
+        difficulty_level: 1,
+        context: None,
+        audio_timestamp: None,
+        ease_factor: state.ease,
+        review_reps: state.reps,
+        lapses: state.lapses,
+    }
+}
+
+fn session(session_id: &str) -> LearningSession {
+    LearningSession {
+        session_id: session_id.to_string(),
+        session_type: Some(SessionType::Review),
+        sceneid: None,
+        scene_dialogue_id: None,
+        classic_clip_id: None,
+        target_words: Some(r#"["schedule","either"]"#.to_string()),
+        started_at: now(),
+        ended_at: None,
+        duration_seconds: None,
+        total_words_spoken: 0,
+        average_wpm: None,
+        error_count: 0,
+        correction_count: 0,
+        notes: None,
+        ai_summary_en: None,
+        ai_summary_zh: None,
+    }
+}
+
+/// A scene as the asset API lists it
+fn scene(id: i64, name_en: &str) -> Scene {
+    Scene {
+        id,
+        name_en: name_en.to_string(),
+        name_zh: name_en.to_string(),
+        description_en: None,
+        description_zh: None,
+        icon_emoji: None,
+        difficulty_level: Some("beginner".to_string()),
+        category: Some("travel".to_string()),
+        display_order: None,
+        is_active: Some(true),
+        created_at: String::new(),
+    }
+}
+
+fn conversation(session_id: &str, turn_id: &str, speaker: Speaker, lang: UseLang) -> Conversation {
+    Conversation {
+        id: None,
+        session_id: session_id.to_string(),
+        turn_id: Some(turn_id.to_string()),
+        speaker,
+        use_lang: lang,
+        content_en: "I want go to school".to_string(),
+        content_zh: "我想去学校".to_string(),
+        audio_path: None,
+        created_at: now(),
+        duration_ms: Some(1500),
+        words_per_minute: Some(120.0),
+        pause_count: Some(1),
+        hesitation_count: None,
+    }
+}
+
+fn practice(word_id: i64, session_id: &str) -> WordPracticeLog {
+    WordPracticeLog {
+        id: None,
+        word_id,
+        session_id: session_id.to_string(),
+        practiced_at: now(),
+        success_level: Some(4),
+        notes: None,
+    }
+}
+
+#[tokio::test]
+async fn test_issue_words_and_review() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+
+    let schedule = db
+        .insert_issue_word(&issue_word("schedule", IssueType::Pronunciation))
+        .await
+        .unwrap();
+    let either = db
+        .insert_issue_word(&issue_word("either", IssueType::Usage))
+        .await
+        .unwrap();
+    assert_eq!(
+        db.insert_issue_word(&issue_word("schedule", IssueType::Pronunciation))
+            .await
+            .unwrap(),
+        schedule
+    );
+
+    let due = db.get_words_for_review(10).await.unwrap();
+    assert_eq!(due.len(), 2);
+    assert!(due.iter().all(|w| w.next_review_at.is_none()));
+
+    // A good review schedules the word into the future
+    db.update_word_after_practice(schedule, Grade::Good)
+        .await
+        .unwrap();
+    let due = db.get_words_for_review(10).await.unwrap();
+    let ids: Vec<Option<i64>> = due.iter().map(|w| w.id).collect();
+    assert_eq!(ids, vec![Some(either)]);
+
+    // Words practiced five times today are held back
+    db.create_session(&session("practice")).await.unwrap();
+    for _ in 0..5 {
+        db.log_word_practice(&practice(either, "practice"))
+            .await
+            .unwrap();
+    }
+    assert!(db.get_words_for_review(10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_forgotten_word_stays_due_soon() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+
+    let id = db
+        .insert_issue_word(&issue_word("thorough", IssueType::Pronunciation))
+        .await
+        .unwrap();
+    db.update_word_after_practice(id, Grade::Forgot)
+        .await
+        .unwrap();
+
+    // Due again tomorrow, not now
+    assert!(db.get_words_for_review(10).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_review_queue_and_stats() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+
+    let fresh = db
+        .insert_issue_word(&issue_word("fresh", IssueType::Unfamiliar))
+        .await
+        .unwrap();
+    let known = db
+        .insert_issue_word(&issue_word("known", IssueType::Unfamiliar))
+        .await
+        .unwrap();
+
+    db.create_session(&session("review")).await.unwrap();
+    let mut log = practice(known, "review");
+    for _ in 0..4 {
+        db.update_word_after_practice(known, Grade::Good)
+            .await
+            .unwrap();
+        db.log_word_practice(&log).await.unwrap();
+    }
+    log.success_level = Some(Grade::Forgot.level());
+    log.word_id = fresh;
+    db.log_word_practice(&log).await.unwrap();
+
+    // Four good reviews push the interval past the mastered threshold
+    let upcoming = db.get_upcoming_words(now(), 10).await.unwrap();
+    let ids: Vec<Option<i64>> = upcoming.iter().map(|w| w.id).collect();
+    assert_eq!(ids, vec![Some(fresh)]);
+    let upcoming = db.get_upcoming_words(now() + 60 * 86400, 10).await.unwrap();
+    let ids: Vec<Option<i64>> = upcoming.iter().map(|w| w.id).collect();
+    assert_eq!(ids, vec![Some(fresh), Some(known)]);
+
+    let mastered = db.get_mastered_words(10).await.unwrap();
+    assert_eq!(mastered.len(), 1);
+    assert_eq!(mastered[0].id, Some(known));
+    assert!(Scheduler::default().is_mastered(&mastered[0].review_state()));
+
+    let week_start = log.practiced_at - 2 * 86400;
+    let stats = db.get_review_stats(week_start).await.unwrap();
+    assert_eq!(stats.total_words, 2);
+    assert_eq!(stats.due_words, 1);
+    assert_eq!(stats.mastered_words, 1);
+    assert_eq!(stats.reviews, 5);
+    assert_eq!(stats.passed_reviews, 4);
+    assert_eq!(stats.study_days, 1);
+    assert_eq!(stats.week_reviews, [0, 0, 5, 0, 0, 0, 0]);
+    // One mastered word, one at a 1-day interval
+    assert!((stats.average_mastery - (1.0 + 1.0 / 21.0) / 2.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_frequent_mistakes() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+
+    db.create_session(&session("chat")).await.unwrap();
+    let conv = db
+        .insert_conversation(&conversation("chat", "turn-1", Speaker::User, UseLang::En))
+        .await
+        .unwrap();
+
+    let annotation = |original: &str, suggested: &str| ConversationAnnotation {
+        id: None,
+        conversation_id: conv,
+        annotation_type: AnnotationType::WordChoice,
+        start_position: None,
+        end_position: None,
+        original_text: Some(original.to_string()),
+        suggested_text: Some(suggested.to_string()),
+        description_en: None,
+        description_zh: Some("用词不当".to_string()),
+        severity: Severity::Medium,
+        created_at: now(),
+    };
+    for (original, suggested) in [
+        ("affect", "effect"),
+        ("Affect", "effect"),
+        ("their", "there"),
+    ] {
+        db.insert_annotation(&annotation(original, suggested))
+            .await
+            .unwrap();
+    }
+
+    let mistakes = db.get_frequent_mistakes(10).await.unwrap();
+    assert_eq!(mistakes.len(), 2);
+    assert_eq!(mistakes[0].original_text.to_lowercase(), "affect");
+    assert_eq!(mistakes[0].suggested_text.as_deref(), Some("effect"));
+    assert_eq!(mistakes[0].occurrences, 2);
+    assert_eq!(mistakes[0].annotation_type, AnnotationType::WordChoice);
+    assert_eq!(mistakes[0].description_zh.as_deref(), Some("用词不当"));
+    assert_eq!(mistakes[1].occurrences, 1);
+
+    let stats = db.get_review_stats(now()).await.unwrap();
+    assert_eq!(stats.mistakes, 2);
+    assert_eq!(stats.reviews, 0);
+}
+
+#[tokio::test]
+async fn test_daily_stats_and_streaks() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+    let today = Local::now().date_naive();
+    let days_ago = |n| today - Duration::days(n);
+
+    // Four days ago: a word was added
+    let mut old = issue_word("either", IssueType::Usage);
+    old.created_at = at(days_ago(4), 9);
+    db.insert_issue_word(&old).await.unwrap();
+
+    // Yesterday: a new word, reviewed twice in a session that never ended
+    let mut word = issue_word("schedule", IssueType::Pronunciation);
+    word.created_at = at(days_ago(1), 9);
+    let word_id = db.insert_issue_word(&word).await.unwrap();
+    let mut open = session("open");
+    open.started_at = at(days_ago(1), 10);
+    db.create_session(&open).await.unwrap();
+    let mut log = practice(word_id, "open");
+    for minutes in [20, 25] {
+        log.practiced_at = open.started_at + minutes * 60;
+        db.log_word_practice(&log).await.unwrap();
+    }
+
+    // Today: a finished chat session with one correction
+    db.create_session(&session("chat")).await.unwrap();
+    let conv = db
+        .insert_conversation(&conversation("chat", "turn-1", Speaker::User, UseLang::En))
+        .await
+        .unwrap();
+    db.insert_annotation(&ConversationAnnotation {
+        id: None,
+        conversation_id: conv,
+        annotation_type: AnnotationType::GrammarError,
+        start_position: None,
+        end_position: None,
+        original_text: Some("want go".to_string()),
+        suggested_text: Some("want to go".to_string()),
+        description_en: None,
+        description_zh: None,
+        severity: Severity::Medium,
+        created_at: now(),
+    })
+    .await
+    .unwrap();
+    db.end_session("chat", None).await.unwrap();
+
+    // The first refresh covers everything since the earliest activity
+    let stats = daily_stats::refresh(db, today).await.unwrap();
+    let dates: Vec<String> = stats.iter().map(|s| s.stat_date.clone()).collect();
+    assert_eq!(
+        dates,
+        vec![
+            days_ago(4).to_string(),
+            days_ago(1).to_string(),
+            today.to_string()
+        ]
+    );
+
+    let yesterday = &stats[1];
+    assert_eq!(yesterday.minutes_studied, 25);
+    assert_eq!(yesterday.words_practiced, 1);
+    assert_eq!(yesterday.review_words_count, 2);
+    assert_eq!(yesterday.new_words_learned, 1);
+    assert_eq!(yesterday.sessions_completed, 0);
+    assert_eq!(stats[2].sessions_completed, 1);
+    assert_eq!(stats[2].errors_corrected, 1);
+
+    assert_eq!(db.get_daily_stats(days_ago(6), today).await.unwrap(), stats);
+    assert_eq!(
+        daily_stats::streaks(db, today).await.unwrap(),
+        Streaks {
+            current: 2,
+            longest: 2
+        }
+    );
+
+    // Nothing changed, so nothing is rewritten or uploaded
+    assert!(daily_stats::refresh(db, today).await.unwrap().is_empty());
+
+    // Yesterday's session is still open, so its late activity is picked up
+    // and only that day changes
+    log.practiced_at = open.started_at + 40 * 60;
+    db.log_word_practice(&log).await.unwrap();
+    let changed = daily_stats::refresh(db, today).await.unwrap();
+    assert_eq!(changed.len(), 1);
+    assert_eq!(changed[0].stat_date, days_ago(1).to_string());
+    assert_eq!(changed[0].minutes_studied, 40);
+    assert_eq!(changed[0].review_words_count, 3);
+    assert_eq!(
+        db.get_daily_stats(days_ago(6), today).await.unwrap().len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_sessions() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+
+    db.create_session(&session("first")).await.unwrap();
+    db.create_session(&session("second")).await.unwrap();
+    db.end_session("first", Some("went well".to_string()))
+        .await
+        .unwrap();
+
+    let sessions = db.get_recent_sessions(10).await.unwrap();
+    let ids: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+    assert_eq!(ids, vec!["second", "first"]);
+
+    let first = &sessions[1];
+    assert_eq!(first.session_type, Some(SessionType::Review));
+    assert_eq!(
+        first.target_words.as_deref(),
+        Some(r#"["schedule","either"]"#)
+    );
+    assert_eq!(first.notes.as_deref(), Some("went well"));
+    assert!(first.ended_at.is_some());
+    assert!(first.duration_seconds.unwrap() >= 0);
+    assert_eq!(sessions[0].ended_at, None);
+
+    assert_eq!(db.get_recent_sessions(1).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_achievements() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+    let today = Local::now().date_naive();
+
+    assert!(achievements::evaluate(db, today).await.unwrap().is_empty());
+
+    db.create_session(&session("review")).await.unwrap();
+    db.end_session("review", None).await.unwrap();
+
+    // Ten clean turns in one conversation, twelve with a grammar error in another
+    for turn in 0..12 {
+        let turn_id = format!("turn-{turn}");
+        for session_id in ["clean", "messy"] {
+            if session_id == "clean" && turn >= 10 {
+                continue;
+            }
+            let id = db
+                .insert_conversation(&conversation(
+                    session_id,
+                    &turn_id,
+                    Speaker::User,
+                    UseLang::En,
+                ))
+                .await
+                .unwrap();
+            if session_id == "messy" && turn == 5 {
+                db.insert_annotation(&ConversationAnnotation {
+                    id: None,
+                    conversation_id: id,
+                    annotation_type: AnnotationType::GrammarError,
+                    start_position: None,
+                    end_position: None,
+                    original_text: Some("he go".to_string()),
+                    suggested_text: Some("he goes".to_string()),
+                    description_en: None,
+                    description_zh: None,
+                    severity: Severity::Medium,
+                    created_at: now(),
+                })
+                .await
+                .unwrap();
+            }
+        }
+    }
+    assert_eq!(
+        db.get_longest_clean_conversation(AnnotationType::GrammarError)
+            .await
+            .unwrap(),
+        10
+    );
+
+    let earned = achievements::evaluate(db, today).await.unwrap();
+    let mut names: Vec<&str> = earned.iter().map(|a| a.achievement_name.as_str()).collect();
+    names.sort();
+    assert_eq!(names, vec!["clean_10_turns", "first_review"]);
+    assert_eq!(
+        earned[0].metadata.as_deref(),
+        Some(r#"{"target":1,"value":1}"#)
+    );
+
+    // Already earned achievements aren't awarded again
+    assert!(achievements::evaluate(db, today).await.unwrap().is_empty());
+    assert!(!db.award_achievement(&earned[0]).await.unwrap());
+    assert_eq!(db.get_achievements().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_end_conversation() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+    let today = Local::now().date_naive();
+
+    // The db reader opens the conversation's session; the app closes it
+    let conversation = LearningSession {
+        session_type: Some(SessionType::FreeTalk),
+        ..session("conversation")
+    };
+    db.create_session(&conversation).await.unwrap();
+    db.create_session(&session("review")).await.unwrap();
+
+    assert!(
+        achievements::end_conversation(db, today)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        db.count_completed_sessions().await.unwrap(),
+        vec![(SessionType::FreeTalk, 1)]
+    );
+
+    // A review still in progress is left open
+    let sessions = db.get_recent_sessions(10).await.unwrap();
+    let review = sessions.iter().find(|s| s.session_id == "review").unwrap();
+    assert_eq!(review.ended_at, None);
+    assert_eq!(
+        db.end_open_sessions(&SessionType::FreeTalk).await.unwrap(),
+        0
+    );
+}
+
+#[tokio::test]
+async fn test_first_scene() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+    let today = Local::now().date_naive();
+
+    achievements::start_scene(db, &scene(7, "Airport"))
+        .await
+        .unwrap();
+    let sessions = db.get_recent_sessions(10).await.unwrap();
+    let sceneid = sessions[0].sceneid.unwrap();
+    assert_eq!(sessions[0].session_type, Some(SessionType::Scenario));
+    assert_eq!(sessions[0].ended_at, None);
+
+    let earned = achievements::end_conversation(db, today).await.unwrap();
+    let names: Vec<&str> = earned.iter().map(|a| a.achievement_name.as_str()).collect();
+    assert_eq!(names, vec!["first_scene"]);
+
+    // The same scene again reuses its local copy and earns nothing new
+    achievements::start_scene(db, &scene(7, "Airport"))
+        .await
+        .unwrap();
+    assert!(
+        achievements::end_conversation(db, today)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let sessions = db.get_recent_sessions(10).await.unwrap();
+    assert!(sessions.iter().all(|s| s.sceneid == Some(sceneid)));
+    assert_eq!(
+        db.count_completed_sessions().await.unwrap(),
+        vec![(SessionType::Scenario, 2)]
+    );
+
+    // A level the schema doesn't know is stored as unset
+    let mut hotel = scene(8, "Hotel");
+    hotel.difficulty_level = Some("expert".to_string());
+    assert_ne!(db.save_scene(&hotel).await.unwrap(), sceneid);
+}
+
+#[tokio::test]
+async fn test_conversations_and_annotations() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+
+    db.create_session(&session("chat")).await.unwrap();
+    let user = db
+        .insert_conversation(&conversation("chat", "turn-1", Speaker::User, UseLang::Mix))
+        .await
+        .unwrap();
+    let teacher = db
+        .insert_conversation(&conversation(
+            "chat",
+            "turn-1",
+            Speaker::Teacher,
+            UseLang::En,
+        ))
+        .await
+        .unwrap();
+
+    let turn = db.get_turn_conversations("turn-1").await.unwrap();
+    let ids: Vec<Option<i64>> = turn.iter().map(|c| c.id).collect();
+    assert_eq!(ids, vec![Some(user), Some(teacher)]);
+    assert_eq!(turn[0].use_lang, UseLang::Mix);
+    assert_eq!(turn[0].turn_id.as_deref(), Some("turn-1"));
+    assert_eq!(turn[0].words_per_minute, Some(120.0));
+
+    let history = db.get_conversation_history("chat", 1).await.unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].id, Some(teacher));
+
+    let annotation = ConversationAnnotation {
+        id: None,
+        conversation_id: user,
+        annotation_type: AnnotationType::GrammarError,
+        start_position: Some(7),
+        end_position: Some(9),
+        original_text: Some("want go".to_string()),
+        suggested_text: Some("want to go".to_string()),
+        description_en: Some("Missing 'to'".to_string()),
+        description_zh: None,
+        severity: Severity::High,
+        created_at: now(),
+    };
+    let annotation_id = db.insert_annotation(&annotation).await.unwrap();
+
+    let annotations = db.get_annotations(user).await.unwrap();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].id, Some(annotation_id));
+    assert_eq!(annotations[0].annotation_type, AnnotationType::GrammarError);
+    assert_eq!(annotations[0].severity, Severity::High);
+    assert_eq!(annotations[0].suggested_text.as_deref(), Some("want to go"));
+    assert!(db.get_annotations(teacher).await.unwrap().is_empty());
+}
+
+/// 005 rebuilds `conversations`; rows written before it, and the annotations
+/// that reference them, must survive the upgrade
+#[tokio::test]
+async fn test_migrate_populated_database() {
+    let id = uuid::Uuid::new_v4();
+    let path = std::env::temp_dir().join(format!("colang-db-{id}.db"));
+    let url = format!("sqlite://{}?mode=rwc", path.display());
+
+    // Bring a new database up to 004 only
+    let pre_005 = std::env::temp_dir().join(format!("colang-migrations-{id}"));
+    std::fs::create_dir(&pre_005).unwrap();
+    let migrations = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("migrations");
+    for entry in std::fs::read_dir(migrations).unwrap() {
+        let entry = entry.unwrap();
+        if entry.file_name().to_string_lossy().as_ref() < "005" {
+            std::fs::copy(entry.path(), pre_005.join(entry.file_name())).unwrap();
+        }
+    }
+    let pool = sqlx::SqlitePool::connect(&url).await.unwrap();
+    sqlx::migrate::Migrator::new(pre_005.as_path())
+        .await
+        .unwrap()
+        .run(&pool)
+        .await
+        .unwrap();
+    pool.close().await;
+    std::fs::remove_dir_all(&pre_005).unwrap();
+
+    let t = TestDb {
+        db: Database::new(&url).await.unwrap(),
+        path,
+    };
+    let db = &t.db;
+    let conv = db
+        .insert_conversation(&conversation("chat", "turn-1", Speaker::User, UseLang::En))
+        .await
+        .unwrap();
+    db.insert_annotation(&ConversationAnnotation {
+        id: None,
+        conversation_id: conv,
+        annotation_type: AnnotationType::GrammarError,
+        start_position: None,
+        end_position: None,
+        original_text: Some("want go".to_string()),
+        suggested_text: Some("want to go".to_string()),
+        description_en: None,
+        description_zh: None,
+        severity: Severity::Medium,
+        created_at: now(),
+    })
+    .await
+    .unwrap();
+
+    db.migrate().await.unwrap();
+
+    let turn = db.get_turn_conversations("turn-1").await.unwrap();
+    assert_eq!(turn.len(), 1);
+    assert_eq!(turn[0].id, Some(conv));
+    assert_eq!(turn[0].use_lang, UseLang::En);
+    let annotations = db.get_annotations(conv).await.unwrap();
+    assert_eq!(annotations.len(), 1);
+    assert_eq!(annotations[0].suggested_text.as_deref(), Some("want to go"));
+
+    // The rebuilt table takes `mix`
+    db.insert_conversation(&conversation("chat", "turn-2", Speaker::User, UseLang::Mix))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_notebook_import_and_export() {
+    let t = TestDb::new().await;
+    let db = &t.db;
+    let dir = std::env::temp_dir().join(format!("colang-notebook-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut known = issue_word("schedule", IssueType::Pronunciation);
+    known.audio_timestamp = Some(1200);
+    let known_id = db.insert_issue_word(&known).await.unwrap();
+    db.insert_issue_word(&issue_word("either", IssueType::Usage))
+        .await
+        .unwrap();
+
+    let csv = dir.join("notebook.csv");
+    std::fs::write(
+        &csv,
+        "deck,word,issue_type,description_zh,description_en,context\n\
+         mistakes,schedule,pronunciation,日程,,The schedule changed.\n\
+         mistakes,either,usage,,either needs work,\n\
+         mistakes,affect,usage,影响,,It affects everyone.\n\
+         mistakes,affect,usage,,,Does it affect you?\n\
+         vocabulary,apple,,苹果,,\n\
+         mistakes,teh,typo,,,\n",
+    )
+    .unwrap();
+
+    let report = notebook::import_text(db, &csv, true).await.unwrap();
+    let actions: Vec<(&str, &ImportAction)> = report
+        .rows
+        .iter()
+        .map(|row| (row.word.as_str(), &row.action))
+        .collect();
+    assert_eq!(
+        actions,
+        vec![
+            (
+                "schedule",
+                &ImportAction::Update(vec!["description_zh", "context"])
+            ),
+            ("either", &ImportAction::Unchanged),
+            ("affect", &ImportAction::Add),
+            ("affect", &ImportAction::Update(vec!["context"])),
+        ]
+    );
+    let skipped: Vec<u64> = report.skipped.iter().map(|s| s.line).collect();
+    assert_eq!(skipped, vec![6, 7]);
+    assert_eq!(
+        (report.added(), report.updated(), report.unchanged()),
+        (1, 2, 1)
+    );
+    // A dry run writes nothing
+    assert_eq!(db.get_all_issue_words().await.unwrap().len(), 2);
+
+    let report = notebook::import_text(db, &csv, false).await.unwrap();
+    assert!(!report.dry_run);
+    let words = db.get_all_issue_words().await.unwrap();
+    assert_eq!(words.len(), 3);
+    let schedule = db
+        .get_issue_word("schedule", &IssueType::Pronunciation)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(schedule.id, Some(known_id));
+    assert_eq!(schedule.description_zh.as_deref(), Some("日程"));
+    assert_eq!(schedule.description_en, known.description_en);
+    assert_eq!(schedule.audio_timestamp, Some(1200));
+    let affect = db
+        .get_issue_word("affect", &IssueType::Usage)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(affect.context.as_deref(), Some("Does it affect you?"));
+    assert_eq!(affect.description_zh.as_deref(), Some("影响"));
+
+    // Importing again only replays the repeated `affect` rows
+    let report = notebook::import_text(db, &csv, false).await.unwrap();
+    assert_eq!(
+        (report.added(), report.updated(), report.unchanged()),
+        (0, 2, 2)
+    );
+
+    // Export, then read the package back
+    let book = Notebook::load(db).await.unwrap();
+    assert_eq!(book.cards().len(), 3);
+
+    let tsv = dir.join("notebook.tsv");
+    assert_eq!(notebook::export_text(&book, &tsv).unwrap().cards, 3);
+    let report = notebook::import_text(db, &tsv, true).await.unwrap();
+    assert_eq!(report.unchanged(), 3);
+
+    let clip = dora_messages::tts::cache_key("schedule", "voice", 0, "tts");
+    std::fs::write(dir.join(format!("{clip}.mp3")), b"mp3").unwrap();
+    let audio = TtsAudio::new(&dir, "voice", "tts");
+    let apkg = dir.join("notebook.apkg");
+    let summary = notebook::export_apkg(&book, Some(&audio), &apkg)
+        .await
+        .unwrap();
+    assert_eq!((summary.cards, summary.audio_clips), (3, 1));
+
+    let mut package = zip::ZipArchive::new(std::fs::File::open(&apkg).unwrap()).unwrap();
+    let mut media = String::new();
+    std::io::Read::read_to_string(&mut package.by_name("media").unwrap(), &mut media).unwrap();
+    assert_eq!(media, format!(r#"{{"0":"{clip}.mp3"}}"#));
+    let collection = dir.join("collection.anki2");
+    std::io::copy(
+        &mut package.by_name("collection.anki2").unwrap(),
+        &mut std::fs::File::create(&collection).unwrap(),
+    )
+    .unwrap();
+
+    let anki = sqlx::SqlitePool::connect(&format!("sqlite://{}", collection.display()))
+        .await
+        .unwrap();
+    let notes: Vec<(String, String)> = sqlx::query_as("SELECT flds, tags FROM notes ORDER BY id")
+        .fetch_all(&anki)
+        .await
+        .unwrap();
+    assert_eq!(notes.len(), 3);
+    let fields: Vec<&str> = notes[0].0.split('\u{1f}').collect();
+    assert_eq!(
+        fields,
+        vec![
+            "schedule",
+            "日程",
+            "schedule needs work",
+            "The schedule changed.",
+            &format!("[sound:{clip}.mp3]"),
+        ]
+    );
+    assert_eq!(notes[0].1, " colang mistakes pronunciation ");
+    let cards: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM cards")
+        .fetch_one(&anki)
+        .await
+        .unwrap();
+    assert_eq!(cards, 3);
+    anki.close().await;
+
+    std::fs::remove_dir_all(dir).ok();
+}
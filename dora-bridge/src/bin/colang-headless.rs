@@ -0,0 +1,534 @@
+//! Run a learning session from the terminal, without the Makepad UI
+//!
+//! Usage: `colang-headless --dataflow <file> [--script <file>] [--audio-out <dir>] [--log]`
+//!
+//! Starts the dataflow and connects as its MoFA dynamic nodes
+//! (`mofa-text-input`, `mofa-chat-display`, `mofa-system-log`, ...). Each
+//! input line is one user turn:
+//! - plain text is sent through `mofa-text-input`
+//! - `/wav <file>` sends a WAV recording through `mofa-mic-input`
+//! - `/interrupt`, `/replay` send session control commands
+//! - `/quit` ends the session
+//!
+//! With `--script`, turns are read from a file and each one waits for the
+//! teacher reply; a reply that doesn't arrive within `--timeout` seconds
+//! fails the run (exit status 1). Teacher speech is written as WAV files to
+//! `--audio-out` when given.
+
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, unbounded};
+use dora_bridge::data::{AudioData, ControlCommand, MessageRole};
+use dora_bridge::messages::{AsrOutput, ComprehensiveResponse, INTERRUPT, REPLAY, pcm};
+use dora_bridge::{BridgeEvent, DataflowController, DoraData, DynamicNodeDispatcher};
+
+const USAGE: &str = "Usage: colang-headless --dataflow <file> [options]
+  --dataflow <file>   dataflow YAML to start
+  --script <file>     read turns from a file instead of stdin
+  --audio-out <dir>   write teacher speech as WAV files
+  --timeout <secs>    reply timeout in script mode (default 60)
+  --log               print system log entries
+  --verbose           print bridge tracing output
+
+Turns: plain text, /wav <file>, /interrupt, /replay, /quit";
+
+/// Participant of the audio player's `audio_teacher` input
+const TEACHER_PARTICIPANT: &str = "teacher";
+
+struct Args {
+    dataflow: PathBuf,
+    script: Option<PathBuf>,
+    audio_out: Option<PathBuf>,
+    timeout: Duration,
+    log: bool,
+    verbose: bool,
+}
+
+impl Args {
+    fn parse() -> Result<Self, String> {
+        let mut dataflow = None;
+        let mut script = None;
+        let mut audio_out = None;
+        let mut timeout = Duration::from_secs(60);
+        let mut log = false;
+        let mut verbose = false;
+
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("{} requires a value", name))
+            };
+            match arg.as_str() {
+                "--dataflow" => dataflow = Some(PathBuf::from(value("--dataflow")?)),
+                "--script" => script = Some(PathBuf::from(value("--script")?)),
+                "--audio-out" => audio_out = Some(PathBuf::from(value("--audio-out")?)),
+                "--timeout" => {
+                    let secs = value("--timeout")?;
+                    let secs: u64 = secs
+                        .parse()
+                        .map_err(|_| format!("invalid timeout: {}", secs))?;
+                    timeout = Duration::from_secs(secs);
+                }
+                "--log" => log = true,
+                "--verbose" | "-v" => verbose = true,
+                _ => return Err(format!("unknown argument: {}", arg)),
+            }
+        }
+
+        Ok(Self {
+            dataflow: dataflow.ok_or("--dataflow is required")?,
+            script,
+            audio_out,
+            timeout,
+            log,
+            verbose,
+        })
+    }
+}
+
+/// One line of input
+#[derive(Debug, PartialEq)]
+enum Turn {
+    Text(String),
+    Wav(PathBuf),
+    Control(&'static str),
+    Quit,
+}
+
+impl Turn {
+    /// Parse an input line; blank lines and `#` comments yield `None`
+    fn parse(line: &str) -> Result<Option<Self>, String> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let Some(command) = line.strip_prefix('/') else {
+            return Ok(Some(Turn::Text(line.to_string())));
+        };
+
+        let (name, arg) = command
+            .split_once(char::is_whitespace)
+            .map(|(name, arg)| (name, arg.trim()))
+            .unwrap_or((command, ""));
+        match name {
+            "wav" if !arg.is_empty() => Ok(Some(Turn::Wav(PathBuf::from(arg)))),
+            "wav" => Err("/wav requires a file".to_string()),
+            "interrupt" => Ok(Some(Turn::Control(INTERRUPT))),
+            "replay" => Ok(Some(Turn::Control(REPLAY))),
+            "quit" | "exit" => Ok(Some(Turn::Quit)),
+            _ => Err(format!("unknown command: /{}", name)),
+        }
+    }
+
+    /// Whether the teacher answers this turn
+    fn expects_reply(&self) -> bool {
+        matches!(self, Turn::Text(_) | Turn::Wav(_))
+    }
+}
+
+/// Prints session events and collects teacher audio
+struct Session {
+    audio_out: Option<PathBuf>,
+    show_log: bool,
+    /// Whether the dataflow has a `mofa-chat-display` node to report replies
+    has_chat_display: bool,
+    /// Teacher replies seen so far
+    replies: usize,
+    /// Question id of the latest teacher speech, for interrupt and replay
+    last_question_id: Option<String>,
+    /// Teacher audio by question id, in arrival order
+    audio: Vec<(String, AudioData)>,
+}
+
+impl Session {
+    fn handle_event(&mut self, node_id: &str, event: BridgeEvent) {
+        let (input_id, data) = match event {
+            BridgeEvent::DataReceived { input_id, data, .. } => (input_id, data),
+            BridgeEvent::Error(e) => {
+                eprintln!("[{}] error: {}", node_id, e);
+                return;
+            }
+            _ => return,
+        };
+
+        match (node_id, data) {
+            ("mofa-chat-display", DoraData::Json(json)) => self.handle_chat_json(&input_id, json),
+            // Without a chat display, finished replies come through text input
+            ("mofa-text-input", DoraData::Chat(msg))
+                if !self.has_chat_display
+                    && msg.role == MessageRole::Assistant
+                    && !msg.is_streaming =>
+            {
+                println!("Teacher: {}", msg.content);
+                self.replies += 1;
+            }
+            // The player also receives the learner's mic audio (`audio_myself`)
+            ("mofa-audio-player", DoraData::Audio(audio))
+                if audio.participant_id.as_deref() == Some(TEACHER_PARTICIPANT) =>
+            {
+                if audio.question_id.is_some() {
+                    self.last_question_id = audio.question_id.clone();
+                }
+                if self.audio_out.is_some() {
+                    self.push_audio(audio);
+                }
+            }
+            (_, DoraData::Log(entry)) if self.show_log => {
+                println!("[{}] {}: {}", entry.level, entry.node_id, entry.message);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_chat_json(&mut self, input_id: &str, json: serde_json::Value) {
+        match input_id {
+            "ai_json" => match serde_json::from_value::<ComprehensiveResponse>(json) {
+                Ok(response) => {
+                    println!("Teacher: {}", response.reply_en);
+                    for issue in &response.issues {
+                        println!(
+                            "  [{}] {} -> {}: {}",
+                            issue.issue_type, issue.original, issue.suggested, issue.description_en
+                        );
+                    }
+                    self.replies += 1;
+                }
+                Err(e) => eprintln!("Unreadable teacher response: {}", e),
+            },
+            "user_text" => {
+                let Ok(asr) = serde_json::from_value::<AsrOutput>(json) else {
+                    return;
+                };
+                if asr.is_final && !asr.text.trim().is_empty() {
+                    println!("You (ASR): {}", asr.text);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn push_audio(&mut self, audio: AudioData) {
+        let question_id = audio
+            .question_id
+            .clone()
+            .unwrap_or_else(|| "unknown".to_string());
+        match self.audio.last_mut() {
+            Some((last_id, last)) if *last_id == question_id => {
+                last.samples.extend_from_slice(&audio.samples)
+            }
+            _ => self.audio.push((question_id, audio)),
+        }
+    }
+
+    /// Write collected teacher audio, one file per question
+    fn write_audio(&self) {
+        let Some(dir) = &self.audio_out else {
+            return;
+        };
+        if let Err(e) = std::fs::create_dir_all(dir) {
+            eprintln!("Failed to create {}: {}", dir.display(), e);
+            return;
+        }
+
+        for (index, (question_id, audio)) in self.audio.iter().enumerate() {
+            let path = dir.join(format!("{:03}-{}.wav", index + 1, question_id));
+            let wav = pcm::encode_wav(&audio.samples, audio.sample_rate, audio.channels);
+            match std::fs::write(&path, wav) {
+                Ok(()) => println!("Wrote {}", path.display()),
+                Err(e) => eprintln!("Failed to write {}: {}", path.display(), e),
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(if args.verbose {
+            tracing::Level::INFO
+        } else {
+            tracing::Level::WARN
+        })
+        .init();
+
+    let controller = match DataflowController::new(&args.dataflow) {
+        Ok(controller) => controller,
+        Err(e) => {
+            eprintln!("Failed to load {}: {}", args.dataflow.display(), e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut dispatcher = DynamicNodeDispatcher::new(controller);
+    eprintln!("Starting {}...", args.dataflow.display());
+    match dispatcher.start() {
+        Ok(dataflow_id) => eprintln!("Dataflow {} started", dataflow_id),
+        Err(e) => {
+            eprintln!("Failed to start dataflow: {}", e);
+            let _ = dispatcher.force_stop();
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    {
+        let interrupted = Arc::clone(&interrupted);
+        if let Err(e) = ctrlc::set_handler(move || interrupted.store(true, Ordering::SeqCst)) {
+            eprintln!("Failed to install Ctrl-C handler: {}", e);
+        }
+    }
+
+    let lines = match spawn_reader(args.script.as_deref()) {
+        Ok(lines) => lines,
+        Err(e) => {
+            eprintln!("{}", e);
+            let _ = dispatcher.stop();
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut session = Session {
+        audio_out: args.audio_out.clone(),
+        show_log: args.log,
+        has_chat_display: dispatcher.get_bridge("mofa-chat-display").is_some(),
+        replies: 0,
+        last_question_id: None,
+        audio: Vec::new(),
+    };
+    let ok = run(&args, &dispatcher, &mut session, &lines, &interrupted);
+
+    // Let trailing speech arrive before shutting down
+    if session.audio_out.is_some() {
+        pump_events(&dispatcher, &mut session, Duration::from_secs(2));
+    }
+    session.write_audio();
+
+    eprintln!("Stopping dataflow...");
+    if let Err(e) = dispatcher.stop() {
+        eprintln!("Failed to stop dataflow: {}", e);
+    }
+
+    if ok {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Read input lines on a background thread, so events keep printing
+/// while waiting for the user
+fn spawn_reader(script: Option<&Path>) -> Result<Receiver<String>, String> {
+    let reader: Box<dyn BufRead + Send> = match script {
+        Some(path) => Box::new(std::io::BufReader::new(
+            std::fs::File::open(path)
+                .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?,
+        )),
+        None => Box::new(std::io::BufReader::new(std::io::stdin())),
+    };
+
+    let (tx, rx) = unbounded();
+    std::thread::spawn(move || {
+        for line in reader.lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(rx)
+}
+
+/// Drive the session until input ends; false if a scripted turn failed
+fn run(
+    args: &Args,
+    dispatcher: &DynamicNodeDispatcher,
+    session: &mut Session,
+    lines: &Receiver<String>,
+    interrupted: &AtomicBool,
+) -> bool {
+    loop {
+        if interrupted.load(Ordering::SeqCst) {
+            return args.script.is_none();
+        }
+        if !dispatcher.is_running() {
+            eprintln!("Dataflow is no longer running");
+            return false;
+        }
+
+        let line = match lines.recv_timeout(Duration::from_millis(50)) {
+            Ok(line) => line,
+            Err(RecvTimeoutError::Timeout) => {
+                pump_events(dispatcher, session, Duration::ZERO);
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => return true,
+        };
+
+        let turn = match Turn::parse(&line) {
+            Ok(Some(turn)) => turn,
+            Ok(None) => continue,
+            Err(e) => {
+                eprintln!("{}", e);
+                if args.script.is_some() {
+                    return false;
+                }
+                continue;
+            }
+        };
+        if turn == Turn::Quit {
+            return true;
+        }
+        if args.script.is_some() {
+            println!("> {}", line.trim());
+        }
+
+        let replies = session.replies;
+        if let Err(e) = send_turn(dispatcher, &turn, session.last_question_id.as_deref()) {
+            eprintln!("{}", e);
+            if args.script.is_some() {
+                return false;
+            }
+            continue;
+        }
+
+        // Scripted turns wait for the teacher before the next line
+        if args.script.is_some() && turn.expects_reply() {
+            let deadline = Instant::now() + args.timeout;
+            while session.replies == replies {
+                if Instant::now() >= deadline || interrupted.load(Ordering::SeqCst) {
+                    eprintln!("No teacher reply within {}s", args.timeout.as_secs());
+                    return false;
+                }
+                pump_events(dispatcher, session, Duration::from_millis(50));
+            }
+        }
+    }
+}
+
+/// Send one turn to its MoFA node; control commands target `question_id`
+fn send_turn(
+    dispatcher: &DynamicNodeDispatcher,
+    turn: &Turn,
+    question_id: Option<&str>,
+) -> Result<(), String> {
+    let (node_id, output_id, data) = match turn {
+        Turn::Text(text) => ("mofa-text-input", "text", DoraData::Text(text.clone())),
+        Turn::Wav(path) => {
+            let bytes = std::fs::read(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let (samples, sample_rate, channels) = pcm::decode_wav(&bytes)
+                .map_err(|e| format!("Invalid WAV {}: {}", path.display(), e))?;
+            let audio = AudioData {
+                samples,
+                sample_rate,
+                channels,
+                participant_id: None,
+                question_id: None,
+                span_ms: None,
+                last_chunk: false,
+            };
+            ("mofa-mic-input", "audio", DoraData::Audio(audio))
+        }
+        Turn::Control(command) => {
+            let mut control = ControlCommand::new(*command);
+            if let Some(question_id) = question_id {
+                control = control.with_param("question_id", question_id);
+            }
+            ("mofa-text-input", "control", DoraData::Control(control))
+        }
+        Turn::Quit => return Ok(()),
+    };
+
+    let bridge = dispatcher
+        .get_bridge(node_id)
+        .ok_or_else(|| format!("Dataflow has no {} node", node_id))?;
+    bridge
+        .send(output_id, data)
+        .map_err(|e| format!("Failed to send to {}: {}", node_id, e))
+}
+
+/// Handle bridge events for up to `duration` (a single poll if zero)
+fn pump_events(dispatcher: &DynamicNodeDispatcher, session: &mut Session, duration: Duration) {
+    let deadline = Instant::now() + duration;
+    loop {
+        for (node_id, event) in dispatcher.poll_events() {
+            session.handle_event(&node_id, event);
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_turns() {
+        assert_eq!(Turn::parse("  # comment"), Ok(None));
+        assert_eq!(
+            Turn::parse(" I goes to school "),
+            Ok(Some(Turn::Text("I goes to school".to_string())))
+        );
+        assert_eq!(
+            Turn::parse("/wav  clips/hello.wav"),
+            Ok(Some(Turn::Wav(PathBuf::from("clips/hello.wav"))))
+        );
+        assert_eq!(
+            Turn::parse("/interrupt"),
+            Ok(Some(Turn::Control(INTERRUPT)))
+        );
+        assert_eq!(Turn::parse("/quit"), Ok(Some(Turn::Quit)));
+        assert!(Turn::parse("/wav").is_err());
+        assert!(Turn::parse("/dance").is_err());
+    }
+
+    #[test]
+    fn test_keeps_only_teacher_audio() {
+        let mut session = Session {
+            audio_out: Some(PathBuf::from("audio")),
+            show_log: false,
+            has_chat_display: true,
+            replies: 0,
+            last_question_id: None,
+            audio: Vec::new(),
+        };
+        let audio = |participant: &str, question_id: &str| BridgeEvent::DataReceived {
+            input_id: format!("audio_{}", participant),
+            data: DoraData::Audio(AudioData {
+                samples: vec![0.1; 160],
+                sample_rate: 16000,
+                channels: 1,
+                participant_id: Some(participant.to_string()),
+                question_id: Some(question_id.to_string()),
+                span_ms: None,
+                last_chunk: false,
+            }),
+            metadata: Default::default(),
+        };
+
+        session.handle_event("mofa-audio-player", audio("teacher", "q1"));
+        session.handle_event("mofa-audio-player", audio("myself", "mic"));
+        session.handle_event("mofa-audio-player", audio("teacher", "q1"));
+
+        assert_eq!(session.last_question_id.as_deref(), Some("q1"));
+        assert_eq!(session.audio.len(), 1);
+        assert_eq!(session.audio[0].0, "q1");
+        assert_eq!(session.audio[0].1.samples.len(), 320);
+    }
+}
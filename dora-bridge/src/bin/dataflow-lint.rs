@@ -0,0 +1,97 @@
+//! Lint dora dataflow YAML files
+//!
+//! Usage: `dataflow-lint [--strict] [--quiet] <file-or-dir>...`
+//!
+//! Directories are searched recursively for `.yml`/`.yaml` files. Exits with
+//! status 1 if any file has errors (or warnings, with `--strict`).
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use dora_bridge::{DataflowLinter, Severity};
+
+fn main() -> ExitCode {
+    let mut strict = false;
+    let mut quiet = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--strict" => strict = true,
+            "--quiet" | "-q" => quiet = true,
+            "--help" | "-h" => {
+                println!("Usage: dataflow-lint [--strict] [--quiet] <file-or-dir>...");
+                println!("  --strict  fail on warnings as well as errors");
+                println!("  --quiet   only print errors and warnings");
+                return ExitCode::SUCCESS;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("Usage: dataflow-lint [--strict] [--quiet] <file-or-dir>...");
+        return ExitCode::from(2);
+    }
+
+    let mut files = Vec::new();
+    for path in &paths {
+        collect_yaml_files(path, &mut files);
+    }
+
+    let fail_at = if strict {
+        Severity::Warning
+    } else {
+        Severity::Error
+    };
+    let mut failed = false;
+
+    for file in &files {
+        match DataflowLinter::lint_file(file) {
+            Ok(diagnostics) => {
+                for diagnostic in &diagnostics {
+                    if quiet && diagnostic.severity == Severity::Info {
+                        continue;
+                    }
+                    println!("{}: {}", file.display(), diagnostic);
+                }
+                failed |= diagnostics.iter().any(|d| d.severity >= fail_at);
+            }
+            Err(e) => {
+                println!("{}: error: {}", file.display(), e);
+                failed = true;
+            }
+        }
+    }
+
+    println!(
+        "Checked {} dataflow(s){}",
+        files.len(),
+        if failed { ", found problems" } else { "" }
+    );
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn collect_yaml_files(path: &Path, files: &mut Vec<PathBuf>) {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    let mut entries: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    entries.sort();
+    for entry in entries {
+        if entry.is_dir() {
+            collect_yaml_files(&entry, files);
+        } else if matches!(
+            entry.extension().and_then(|e| e.to_str()),
+            Some("yml" | "yaml")
+        ) {
+            files.push(entry);
+        }
+    }
+}
@@ -42,6 +42,8 @@ pub enum BridgeEvent {
         data: DoraData,
         metadata: EventMetadata,
     },
+    /// An input's source output closed (the sending node exited)
+    InputClosed { input_id: String },
     /// Error occurred
     Error(String),
     /// State changed
@@ -3,7 +3,7 @@
 //! Manages the lifecycle of dora dataflows:
 //! - Start dataflow with env configuration
 //! - Stop dataflow and cleanup resources
-//! - Monitor dataflow status
+//! - Monitor dataflow status (`dora list`, JSON when available)
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -16,6 +16,7 @@ use tracing::{debug, error, info, warn};
 
 use crate::error::{BridgeError, BridgeResult};
 use crate::parser::{DataflowParser, ParsedDataflow};
+use crate::profile::DataflowProfile;
 
 /// Dataflow state
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -88,6 +89,15 @@ impl DataflowController {
         })
     }
 
+    /// Write `profile` as YAML to `dataflow_path` and create a controller for it
+    pub fn from_profile(
+        profile: &DataflowProfile,
+        dataflow_path: impl AsRef<Path>,
+    ) -> BridgeResult<Self> {
+        let path = profile.write(dataflow_path)?;
+        Self::new(path)
+    }
+
     /// Get the parsed dataflow
     pub fn parsed(&self) -> Option<&ParsedDataflow> {
         self.parsed.as_ref()
@@ -110,17 +120,16 @@ impl DataflowController {
 
     /// Check if all required env vars are set
     pub fn check_env_requirements(&self) -> Vec<String> {
-        let mut missing = Vec::new();
-        if let Some(parsed) = &self.parsed {
-            for req in &parsed.env_requirements {
-                if req.required {
-                    if !self.env_vars.contains_key(&req.key) && std::env::var(&req.key).is_err() {
-                        missing.push(req.key.clone());
-                    }
-                }
-            }
-        }
-        missing
+        self.parsed
+            .as_ref()
+            .map(|parsed| {
+                parsed
+                    .missing_env_requirements(&self.env_vars)
+                    .into_iter()
+                    .map(|req| req.key.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
     }
 
     /// Ensure dora daemon is running
@@ -341,24 +350,34 @@ impl DataflowController {
                 ref dataflow_id,
                 ref started_at,
             } => {
-                // Query dora for node status
+                // Query dora for dataflow status
                 let output = Command::new("dora")
-                    .arg("list")
+                    .args(["list", "--format", "json"])
                     .output()
                     .map_err(|e| BridgeError::Unknown(format!("Failed to query status: {}", e)))?;
 
                 let stdout = String::from_utf8_lossy(&output.stdout);
-                let is_running = stdout.contains(dataflow_id);
+                let listed = if output.status.success() {
+                    Self::parse_list_json(&stdout, dataflow_id)
+                } else {
+                    // Older dora without --format: fall back to the table
+                    let output = Command::new("dora").arg("list").output().map_err(|e| {
+                        BridgeError::Unknown(format!("Failed to query status: {}", e))
+                    })?;
+                    Self::parse_list_table(&String::from_utf8_lossy(&output.stdout), dataflow_id)
+                };
                 let uptime = started_at.elapsed();
 
                 Ok(DataflowStatus {
-                    state: if is_running {
-                        DataflowState::Running {
+                    state: match listed {
+                        Some(ListedStatus::Running) => DataflowState::Running {
                             dataflow_id: dataflow_id.clone(),
                             started_at: *started_at,
-                        }
-                    } else {
-                        DataflowState::Stopped
+                        },
+                        Some(ListedStatus::Failed) => DataflowState::Error {
+                            message: format!("Dataflow {} failed", dataflow_id),
+                        },
+                        Some(ListedStatus::Finished) | None => DataflowState::Stopped,
                     },
                     uptime: Some(uptime),
                     node_count: self.parsed.as_ref().map(|p| p.nodes.len()).unwrap_or(0),
@@ -382,6 +401,35 @@ impl DataflowController {
         }
     }
 
+    /// Find `dataflow_id` in `dora list --format json` output (one object per line)
+    fn parse_list_json(output: &str, dataflow_id: &str) -> Option<ListedStatus> {
+        output
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .find(|entry| {
+                ["uuid", "id"]
+                    .iter()
+                    .any(|key| entry.get(*key).and_then(|v| v.as_str()) == Some(dataflow_id))
+            })
+            .map(|entry| {
+                entry
+                    .get("status")
+                    .and_then(|s| s.as_str())
+                    .map(ListedStatus::parse)
+                    .unwrap_or(ListedStatus::Running)
+            })
+    }
+
+    /// Find `dataflow_id` in the `dora list` table
+    fn parse_list_table(output: &str, dataflow_id: &str) -> Option<ListedStatus> {
+        let line = output.lines().find(|line| line.contains(dataflow_id))?;
+        let status = line
+            .split_whitespace()
+            .map(ListedStatus::parse)
+            .find(|status| *status != ListedStatus::Running);
+        Some(status.unwrap_or(ListedStatus::Running))
+    }
+
     /// Parse dataflow ID from dora start output
     fn parse_dataflow_id(output: &str) -> Option<String> {
         // Look for UUID pattern in output
@@ -413,6 +461,25 @@ impl Drop for DataflowController {
     }
 }
 
+/// Dataflow status as reported by `dora list`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListedStatus {
+    Running,
+    Finished,
+    Failed,
+}
+
+impl ListedStatus {
+    /// Unknown values count as running, like a listed dataflow without status
+    fn parse(status: &str) -> Self {
+        match status.to_ascii_lowercase().as_str() {
+            "finished" => ListedStatus::Finished,
+            "failed" => ListedStatus::Failed,
+            _ => ListedStatus::Running,
+        }
+    }
+}
+
 /// Dataflow status information
 #[derive(Debug, Clone)]
 pub struct DataflowStatus {
@@ -421,3 +488,35 @@ pub struct DataflowStatus {
     pub node_count: usize,
     pub mofa_node_count: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "0199a3b2-7c4d-7e21-9f0a-1b2c3d4e5f60";
+
+    #[test]
+    fn test_parse_list_status() {
+        let json = format!(
+            "{{\"uuid\":\"0199a3b2-0000-7e21-9f0a-1b2c3d4e5f60\",\"status\":\"Running\"}}\n\
+             {{\"uuid\":\"{}\",\"name\":null,\"status\":\"Failed\"}}\n",
+            ID
+        );
+        assert_eq!(
+            DataflowController::parse_list_json(&json, ID),
+            Some(ListedStatus::Failed)
+        );
+        assert_eq!(DataflowController::parse_list_json("", ID), None);
+
+        let table = format!("UUID  Name  Status\n{}  -  Running\n", ID);
+        assert_eq!(
+            DataflowController::parse_list_table(&table, ID),
+            Some(ListedStatus::Running)
+        );
+        let table = format!("UUID  Name  Status\n{}  -  Finished\n", ID);
+        assert_eq!(
+            DataflowController::parse_list_table(&table, ID),
+            Some(ListedStatus::Finished)
+        );
+    }
+}
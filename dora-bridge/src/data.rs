@@ -3,6 +3,8 @@
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use dora_messages::Stage;
+use dora_messages::turn::{SESSION_ID, TURN_ID};
 use serde::{Deserialize, Serialize};
 
 /// Unified data type for all MoFA-Dora communication
@@ -35,6 +37,8 @@ impl DoraData {
             channels,
             participant_id: None,
             question_id: None,
+            span_ms: None,
+            last_chunk: false,
         })
     }
 
@@ -76,6 +80,10 @@ pub struct AudioData {
     pub participant_id: Option<String>,
     /// Optional question ID for smart reset (discard stale audio)
     pub question_id: Option<String>,
+    /// Utterance start/end in ms since capture start (set by mic VAD)
+    pub span_ms: Option<(u64, u64)>,
+    /// Last chunk of a streamed utterance (mic `audio_stream` output)
+    pub last_chunk: bool,
 }
 
 impl AudioData {
@@ -186,6 +194,8 @@ pub struct ChatMessage {
     pub is_streaming: bool,
     /// Session/conversation ID
     pub session_id: Option<String>,
+    /// Turn the message belongs to; streaming updates of one turn share it
+    pub turn_id: Option<String>,
 }
 
 impl ChatMessage {
@@ -198,6 +208,7 @@ impl ChatMessage {
             timestamp: current_timestamp(),
             is_streaming: false,
             session_id: None,
+            turn_id: None,
         }
     }
 
@@ -210,6 +221,7 @@ impl ChatMessage {
             timestamp: current_timestamp(),
             is_streaming: false,
             session_id: None,
+            turn_id: None,
         }
     }
 }
@@ -291,15 +303,28 @@ impl EventMetadata {
         self.get("session_status")
     }
 
-    /// Get question ID
+    /// Get question ID (the turn id, see `dora_messages::turn`)
     pub fn question_id(&self) -> Option<&str> {
-        self.get("question_id")
+        self.get(TURN_ID)
+    }
+
+    /// Get session ID
+    pub fn session_id(&self) -> Option<&str> {
+        self.get(SESSION_ID)
     }
 
     /// Get participant ID
     pub fn participant_id(&self) -> Option<&str> {
         self.get("participant_id")
     }
+
+    /// Pipeline stage timestamps stamped by upstream nodes
+    pub fn stage_stamps(&self) -> Vec<(Stage, i64)> {
+        self.values
+            .iter()
+            .filter_map(|(key, value)| Some((Stage::from_key(key)?, value.parse().ok()?)))
+            .collect()
+    }
 }
 
 /// Get current unix timestamp in milliseconds
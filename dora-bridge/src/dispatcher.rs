@@ -17,7 +17,8 @@ use crate::controller::DataflowController;
 use crate::error::{BridgeError, BridgeResult};
 use crate::parser::MofaNodeSpec;
 use crate::widgets::{
-    AudioPlayerBridge, MicInputBridge, PromptInputBridge, SystemLogBridge, TextInputBridge,
+    AudioPlayerBridge, ChatDisplayBridge, MicInputBridge, PromptInputBridge, SystemLogBridge,
+    TextInputBridge,
 };
 
 /// Binding between a widget and its dora node
@@ -82,6 +83,7 @@ impl DynamicNodeDispatcher {
                 MofaNodeType::TextInput => Box::new(TextInputBridge::new(&node_spec.id)),
                 MofaNodeType::PromptInput => Box::new(PromptInputBridge::new(&node_spec.id)),
                 MofaNodeType::MicInput => Box::new(MicInputBridge::new(&node_spec.id)),
+                MofaNodeType::ChatDisplay => Box::new(ChatDisplayBridge::new(&node_spec.id)),
                 MofaNodeType::ChatViewer => {
                     // TODO: Implement ChatViewerBridge
                     continue;
@@ -0,0 +1,386 @@
+//! Per-node health tracking and dataflow restart policy
+//!
+//! Dora doesn't report node exits to dynamic nodes directly, but when a node
+//! exits its outputs close, and `mofa-system-log` (subscribed to every
+//! node's `log`/`status` output) receives `InputClosed`. The monitor maps
+//! those inputs back to their source node using the parsed dataflow and
+//! keeps the node's last reported error as the failure reason.
+//!
+//! Recovery restarts the whole dataflow, not just the failed node: every node
+//! of the session is relaunched, paced by a [`RestartPolicy`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use crate::bridge::BridgeEvent;
+use crate::data::{DoraData, LogEntry, LogLevel};
+use crate::parser::ParsedDataflow;
+
+/// Health state of one dataflow node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeState {
+    /// Node is running (or not known to have exited)
+    Running,
+    /// Node exited; no restart left
+    Failed { reason: String },
+    /// Node exited; the whole dataflow restarts after `delay`
+    DataflowRestarting { attempt: u32, delay: Duration },
+}
+
+/// Health of one dataflow node
+#[derive(Debug, Clone)]
+pub struct NodeHealth {
+    pub node_id: String,
+    pub state: NodeState,
+    /// Last error the node reported through its log or status output
+    pub last_error: Option<String>,
+    /// When the state last changed
+    pub since: Instant,
+}
+
+/// How often and how fast failed dataflows are restarted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Restarts allowed per session (0 disables restarting)
+    pub max_restarts: u32,
+    /// Delay before the first restart
+    pub initial_backoff: Duration,
+    /// Upper bound for the doubling delay
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 3,
+            initial_backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Never restart
+    pub fn disabled() -> Self {
+        Self {
+            max_restarts: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Delay before restart `attempt` (1-based), doubling each time
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Tracks node health from bridge events and schedules restarts
+pub struct HealthMonitor {
+    policy: RestartPolicy,
+    nodes: BTreeMap<String, NodeHealth>,
+    /// (receiving node, input id) -> source node
+    sources: HashMap<(String, String), String>,
+    restarts: u32,
+    restart_at: Option<Instant>,
+}
+
+impl HealthMonitor {
+    /// Monitor the non-dynamic nodes of `dataflow`
+    pub fn new(dataflow: &ParsedDataflow, policy: RestartPolicy) -> Self {
+        let now = Instant::now();
+        let nodes = dataflow
+            .nodes
+            .iter()
+            .filter(|node| !node.is_dynamic)
+            .map(|node| {
+                let health = NodeHealth {
+                    node_id: node.id.clone(),
+                    state: NodeState::Running,
+                    last_error: None,
+                    since: now,
+                };
+                (node.id.clone(), health)
+            })
+            .collect();
+
+        let sources = dataflow
+            .nodes
+            .iter()
+            .flat_map(|node| {
+                node.inputs.iter().filter_map(|input| {
+                    let (source, _) = input.source.split_once('/')?;
+                    Some(((node.id.clone(), input.id.clone()), source.to_string()))
+                })
+            })
+            .collect();
+
+        Self {
+            policy,
+            nodes,
+            sources,
+            restarts: 0,
+            restart_at: None,
+        }
+    }
+
+    pub fn policy(&self) -> &RestartPolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: RestartPolicy) {
+        self.policy = policy;
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &NodeHealth> {
+        self.nodes.values()
+    }
+
+    pub fn node(&self, node_id: &str) -> Option<&NodeHealth> {
+        self.nodes.get(node_id)
+    }
+
+    /// Restarts used so far
+    pub fn restarts(&self) -> u32 {
+        self.restarts
+    }
+
+    /// Track an event from `bridge_id`; returns the nodes whose state changed
+    pub fn observe(&mut self, bridge_id: &str, event: &BridgeEvent) -> Vec<NodeHealth> {
+        match event {
+            BridgeEvent::DataReceived {
+                input_id,
+                data: DoraData::Log(entry),
+                ..
+            } => {
+                let node_id = self
+                    .source(bridge_id, input_id)
+                    .unwrap_or(&entry.node_id)
+                    .to_string();
+                if let (Some(error), Some(node)) =
+                    (error_message(entry), self.nodes.get_mut(&node_id))
+                {
+                    node.last_error = Some(error);
+                }
+                Vec::new()
+            }
+            BridgeEvent::InputClosed { input_id } => match self.source(bridge_id, input_id) {
+                Some(node_id) => {
+                    let node_id = node_id.to_string();
+                    self.node_exited(&node_id)
+                }
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether a restart is scheduled
+    pub fn restart_pending(&self) -> bool {
+        self.restart_at.is_some()
+    }
+
+    /// Whether a scheduled restart is due; consumes the restart attempt
+    pub fn restart_due(&mut self, now: Instant) -> bool {
+        match self.restart_at {
+            Some(at) if now >= at => {
+                self.restart_at = None;
+                self.restarts += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The dataflow was restarted: all nodes are running again
+    pub fn restarted(&mut self) -> Vec<NodeHealth> {
+        let now = Instant::now();
+        let mut changed = Vec::new();
+        for node in self.nodes.values_mut() {
+            if node.state != NodeState::Running {
+                node.state = NodeState::Running;
+                node.last_error = None;
+                node.since = now;
+                changed.push(node.clone());
+            }
+        }
+        changed
+    }
+
+    fn source(&self, bridge_id: &str, input_id: &str) -> Option<&String> {
+        self.sources
+            .get(&(bridge_id.to_string(), input_id.to_string()))
+    }
+
+    fn node_exited(&mut self, node_id: &str) -> Vec<NodeHealth> {
+        let Some(node) = self.nodes.get_mut(node_id) else {
+            return Vec::new();
+        };
+        // Both log and status close on exit; report once
+        if node.state != NodeState::Running {
+            return Vec::new();
+        }
+
+        let now = Instant::now();
+        let reason = node
+            .last_error
+            .clone()
+            .unwrap_or_else(|| "exited".to_string());
+        node.state = NodeState::Failed { reason };
+        node.since = now;
+        let mut changed = vec![node.clone()];
+
+        // One restart covers every node that failed before it is due
+        if self.restart_at.is_none() && self.restarts < self.policy.max_restarts {
+            let attempt = self.restarts + 1;
+            let delay = self.policy.backoff(attempt);
+            self.restart_at = Some(now + delay);
+            node.state = NodeState::DataflowRestarting { attempt, delay };
+            changed.push(node.clone());
+        } else if let Some(at) = self.restart_at {
+            node.state = NodeState::DataflowRestarting {
+                attempt: self.restarts + 1,
+                delay: at.saturating_duration_since(now),
+            };
+            changed.push(node.clone());
+        }
+        changed
+    }
+}
+
+/// Error text of a log entry: error-level messages, or `{"status": "error"}`
+/// status payloads
+fn error_message(entry: &LogEntry) -> Option<String> {
+    let status_error = serde_json::from_str::<serde_json::Value>(&entry.message)
+        .ok()
+        .filter(|json| json.get("status").and_then(|s| s.as_str()) == Some("error"));
+    if let Some(json) = status_error {
+        let error = json
+            .get("error")
+            .and_then(|e| e.as_str())
+            .unwrap_or("error");
+        return Some(error.to_string());
+    }
+    (entry.level >= LogLevel::Error).then(|| entry.message.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::parser::DataflowParser;
+
+    const DATAFLOW: &str = r#"
+nodes:
+  - id: doubao-asr
+    path: ../../../target/debug/dora-doubao-asr
+    outputs:
+      - text
+      - log
+      - status
+  - id: english-teacher
+    path: ../../../target/debug/dora-english-teacher
+    inputs:
+      text: doubao-asr/text
+    outputs:
+      - json_data
+      - status
+  - id: mofa-system-log
+    path: dynamic
+    inputs:
+      asr_log: doubao-asr/log
+      asr_status: doubao-asr/status
+      teacher_status: english-teacher/status
+"#;
+
+    fn closed(input_id: &str) -> BridgeEvent {
+        BridgeEvent::InputClosed {
+            input_id: input_id.to_string(),
+        }
+    }
+
+    fn log(input_id: &str, level: LogLevel, message: &str) -> BridgeEvent {
+        BridgeEvent::DataReceived {
+            input_id: input_id.to_string(),
+            data: DoraData::Log(LogEntry::new(level, message, "asr")),
+            metadata: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_backoff() {
+        let policy = RestartPolicy {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+        };
+        let delays: Vec<u64> = (1..=5).map(|n| policy.backoff(n).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+    }
+
+    #[test]
+    fn test_node_failure_and_restart() {
+        let parsed = DataflowParser::parse_string(DATAFLOW, PathBuf::from("test.yml")).unwrap();
+        let policy = RestartPolicy {
+            max_restarts: 1,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        };
+        let mut monitor = HealthMonitor::new(&parsed, policy);
+        assert_eq!(monitor.nodes().count(), 2);
+
+        let status = r#"{"node":"doubao-asr","status":"error","error":"auth failed"}"#;
+        assert!(
+            monitor
+                .observe(
+                    "mofa-system-log",
+                    &log("asr_status", LogLevel::Info, status)
+                )
+                .is_empty()
+        );
+
+        // First exit: failed, then restarting
+        let changed = monitor.observe("mofa-system-log", &closed("asr_log"));
+        let states: Vec<&NodeState> = changed.iter().map(|n| &n.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                &NodeState::Failed {
+                    reason: "auth failed".to_string()
+                },
+                &NodeState::DataflowRestarting {
+                    attempt: 1,
+                    delay: Duration::ZERO
+                },
+            ]
+        );
+        assert!(
+            monitor
+                .observe("mofa-system-log", &closed("asr_status"))
+                .is_empty()
+        );
+        assert!(monitor.restart_pending());
+        assert!(monitor.restart_due(Instant::now()));
+        assert!(!monitor.restart_due(Instant::now()));
+        assert_eq!(monitor.restarted().len(), 1);
+
+        // Restarts used up: the next exit is final
+        monitor.observe(
+            "mofa-system-log",
+            &log("teacher_status", LogLevel::Error, "model timeout"),
+        );
+        let changed = monitor.observe("mofa-system-log", &closed("teacher_status"));
+        assert_eq!(changed.len(), 1);
+        assert_eq!(changed[0].node_id, "english-teacher");
+        assert_eq!(
+            changed[0].state,
+            NodeState::Failed {
+                reason: "model timeout".to_string()
+            }
+        );
+        assert!(!monitor.restart_due(Instant::now()));
+    }
+}
@@ -27,7 +27,10 @@ pub mod controller;
 pub mod data;
 pub mod dispatcher;
 pub mod error;
+pub mod health;
+pub mod lint;
 pub mod parser;
+pub mod profile;
 
 // Widget-specific bridges
 pub mod widgets;
@@ -37,8 +40,12 @@ pub use bridge::{BridgeEvent, BridgeState, DoraBridge};
 pub use controller::{DataflowController, DataflowState};
 pub use data::{AudioData, ChatMessage, ControlCommand, DoraData, LogEntry};
 pub use dispatcher::{DynamicNodeDispatcher, WidgetBinding};
+pub use dora_messages as messages;
 pub use error::{BridgeError, BridgeResult};
+pub use health::{HealthMonitor, NodeHealth, NodeState, RestartPolicy};
+pub use lint::{DataflowLinter, Diagnostic, LintKind, Severity};
 pub use parser::{DataflowParser, EnvRequirement, LogSource, ParsedDataflow, ParsedNode};
+pub use profile::{AsrBackend, DataflowProfile, TtsBackend, teacher_models};
 
 /// Prefix for MoFA built-in dynamic nodes in dataflow YAML
 pub const MOFA_NODE_PREFIX: &str = "mofa-";
@@ -58,6 +65,8 @@ pub enum MofaNodeType {
     MicInput,
     /// Chat viewer widget - displays conversation
     ChatViewer,
+    /// Chat display widget - receives user turns and teacher analysis
+    ChatDisplay,
     /// Participant panel widget - receives audio and calculates levels for visualization
     ParticipantPanel,
 }
@@ -72,6 +81,7 @@ impl MofaNodeType {
             MofaNodeType::PromptInput => "mofa-prompt-input",
             MofaNodeType::MicInput => "mofa-mic-input",
             MofaNodeType::ChatViewer => "mofa-chat-viewer",
+            MofaNodeType::ChatDisplay => "mofa-chat-display",
             MofaNodeType::ParticipantPanel => "mofa-participant-panel",
         }
     }
@@ -85,6 +95,7 @@ impl MofaNodeType {
             "mofa-prompt-input" => Some(MofaNodeType::PromptInput),
             "mofa-mic-input" => Some(MofaNodeType::MicInput),
             "mofa-chat-viewer" => Some(MofaNodeType::ChatViewer),
+            "mofa-chat-display" => Some(MofaNodeType::ChatDisplay),
             "mofa-participant-panel" => Some(MofaNodeType::ParticipantPanel),
             _ => None,
         }
@@ -0,0 +1,532 @@
+//! Dataflow YAML lint
+//!
+//! Static checks on a parsed dataflow, before handing it to `dora start`:
+//! - Duplicate node IDs
+//! - Inputs whose source node or output does not exist
+//! - `mofa-*` nodes with no matching [`MofaNodeType`]
+//! - Outputs nothing subscribes to
+//! - `path:` binaries that don't exist (and have no `build:` command)
+//! - Cycles between non-dynamic nodes
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use crate::MofaNodeType;
+use crate::error::BridgeResult;
+use crate::parser::{DataflowParser, NodeKind, ParsedDataflow};
+
+/// Diagnostic severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Worth knowing, usually intentional (e.g. unused status outputs)
+    Info,
+    /// Likely a mistake, the dataflow may still start
+    Warning,
+    /// The dataflow is broken
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Info => write!(f, "info"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// What a diagnostic is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintKind {
+    DuplicateNodeId,
+    /// Input source node does not exist
+    DanglingInput,
+    /// Input source node exists but does not declare the output
+    UnknownOutput,
+    UnknownMofaNode,
+    UnusedOutput,
+    MissingBinary,
+    Cycle,
+}
+
+impl LintKind {
+    pub fn severity(&self) -> Severity {
+        match self {
+            LintKind::DuplicateNodeId | LintKind::DanglingInput | LintKind::UnknownOutput => {
+                Severity::Error
+            }
+            LintKind::UnknownMofaNode | LintKind::MissingBinary | LintKind::Cycle => {
+                Severity::Warning
+            }
+            LintKind::UnusedOutput => Severity::Info,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintKind::DuplicateNodeId => "duplicate-node-id",
+            LintKind::DanglingInput => "dangling-input",
+            LintKind::UnknownOutput => "unknown-output",
+            LintKind::UnknownMofaNode => "unknown-mofa-node",
+            LintKind::UnusedOutput => "unused-output",
+            LintKind::MissingBinary => "missing-binary",
+            LintKind::Cycle => "cycle",
+        }
+    }
+}
+
+/// A single lint finding
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: LintKind,
+    pub severity: Severity,
+    /// Node the finding is attached to
+    pub node_id: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(kind: LintKind, node_id: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            severity: kind.severity(),
+            node_id: node_id.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} [{}] {}: {}",
+            self.severity,
+            self.kind.name(),
+            self.node_id,
+            self.message
+        )
+    }
+}
+
+/// Dataflow linter
+pub struct DataflowLinter;
+
+impl DataflowLinter {
+    /// Parse and lint a dataflow file; binaries are resolved relative to it
+    pub fn lint_file(path: impl AsRef<Path>) -> BridgeResult<Vec<Diagnostic>> {
+        let path = path.as_ref();
+        let parsed = DataflowParser::parse(path)?;
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        Ok(Self::lint(&parsed, Some(base_dir)))
+    }
+
+    /// Lint a parsed dataflow
+    ///
+    /// Binary checks are skipped when `base_dir` is `None`. Diagnostics are
+    /// sorted by severity, errors first.
+    pub fn lint(parsed: &ParsedDataflow, base_dir: Option<&Path>) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+
+        Self::check_duplicate_ids(parsed, &mut diagnostics);
+        Self::check_inputs(parsed, &mut diagnostics);
+        Self::check_mofa_nodes(parsed, &mut diagnostics);
+        Self::check_unused_outputs(parsed, &mut diagnostics);
+        if let Some(base_dir) = base_dir {
+            Self::check_binaries(parsed, base_dir, &mut diagnostics);
+        }
+        Self::check_cycles(parsed, &mut diagnostics);
+
+        diagnostics.sort_by_key(|d| std::cmp::Reverse(d.severity));
+        diagnostics
+    }
+
+    fn check_duplicate_ids(parsed: &ParsedDataflow, diagnostics: &mut Vec<Diagnostic>) {
+        let mut seen = HashSet::new();
+        for node in &parsed.nodes {
+            if !seen.insert(node.id.as_str()) {
+                diagnostics.push(Diagnostic::new(
+                    LintKind::DuplicateNodeId,
+                    &node.id,
+                    "node ID is defined more than once",
+                ));
+            }
+        }
+    }
+
+    fn check_inputs(parsed: &ParsedDataflow, diagnostics: &mut Vec<Diagnostic>) {
+        for node in &parsed.nodes {
+            for input in &node.inputs {
+                // Built-in sources such as dora/timer/millis/100
+                if input.source.starts_with("dora/") {
+                    continue;
+                }
+
+                let Some((source_node, output)) = input.source.split_once('/') else {
+                    diagnostics.push(Diagnostic::new(
+                        LintKind::DanglingInput,
+                        &node.id,
+                        format!(
+                            "input `{}` source `{}` is not `node/output`",
+                            input.id, input.source
+                        ),
+                    ));
+                    continue;
+                };
+
+                match parsed.get_node(source_node) {
+                    None => diagnostics.push(Diagnostic::new(
+                        LintKind::DanglingInput,
+                        &node.id,
+                        format!(
+                            "input `{}` reads from unknown node `{}`",
+                            input.id, source_node
+                        ),
+                    )),
+                    Some(source) if !source.outputs.iter().any(|o| o == output) => diagnostics
+                        .push(Diagnostic::new(
+                            LintKind::UnknownOutput,
+                            &node.id,
+                            format!(
+                                "input `{}` reads `{}`, but `{}` does not declare output `{}`",
+                                input.id, input.source, source_node, output
+                            ),
+                        )),
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    fn check_mofa_nodes(parsed: &ParsedDataflow, diagnostics: &mut Vec<Diagnostic>) {
+        for node in &parsed.nodes {
+            if MofaNodeType::is_mofa_node(&node.id)
+                && MofaNodeType::from_node_id(&node.id).is_none()
+            {
+                diagnostics.push(Diagnostic::new(
+                    LintKind::UnknownMofaNode,
+                    &node.id,
+                    "no MoFA widget bridge handles this node, it will never connect",
+                ));
+            }
+        }
+    }
+
+    fn check_unused_outputs(parsed: &ParsedDataflow, diagnostics: &mut Vec<Diagnostic>) {
+        let used: HashSet<&str> = parsed
+            .nodes
+            .iter()
+            .flat_map(|node| node.inputs.iter().map(|input| input.source.as_str()))
+            .collect();
+
+        for node in &parsed.nodes {
+            for output in &node.outputs {
+                if !used.contains(format!("{}/{}", node.id, output).as_str()) {
+                    diagnostics.push(Diagnostic::new(
+                        LintKind::UnusedOutput,
+                        &node.id,
+                        format!("output `{}` is not used by any node", output),
+                    ));
+                }
+            }
+        }
+    }
+
+    fn check_binaries(parsed: &ParsedDataflow, base_dir: &Path, diagnostics: &mut Vec<Diagnostic>) {
+        for node in &parsed.nodes {
+            let NodeKind::Native { path, .. } = &node.kind else {
+                continue;
+            };
+            // Bare names (e.g. `dora-primespeech`) are looked up on PATH
+            if !path.contains('/') {
+                continue;
+            }
+            if base_dir.join(path).exists() {
+                continue;
+            }
+
+            let message = match &node.build {
+                Some(_) => format!("`{}` does not exist yet, run `dora build`", path),
+                None => format!(
+                    "`{}` does not exist and the node has no build command",
+                    path
+                ),
+            };
+            diagnostics.push(Diagnostic::new(LintKind::MissingBinary, &node.id, message));
+        }
+    }
+
+    /// Cycles through dynamic (UI) nodes are expected, e.g. text input ->
+    /// teacher -> chat display; only report cycles between the other nodes.
+    fn check_cycles(parsed: &ParsedDataflow, diagnostics: &mut Vec<Diagnostic>) {
+        let static_nodes: Vec<&str> = parsed
+            .nodes
+            .iter()
+            .filter(|node| !node.is_dynamic)
+            .map(|node| node.id.as_str())
+            .collect();
+        let index: HashMap<&str, usize> = static_nodes
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i))
+            .collect();
+
+        // Edges from source node to consuming node
+        let mut edges = vec![Vec::new(); static_nodes.len()];
+        for node in &parsed.nodes {
+            let Some(&to) = index.get(node.id.as_str()) else {
+                continue;
+            };
+            for input in &node.inputs {
+                let source = input.source.split('/').next().unwrap_or_default();
+                let Some(&from) = index.get(source) else {
+                    continue;
+                };
+                if !edges[from].contains(&to) {
+                    edges[from].push(to);
+                }
+            }
+        }
+
+        for component in strongly_connected_components(&edges) {
+            let self_loop = component.len() == 1 && edges[component[0]].contains(&component[0]);
+            if component.len() > 1 || self_loop {
+                let mut ids: Vec<&str> = component.iter().map(|&i| static_nodes[i]).collect();
+                ids.sort_unstable();
+                diagnostics.push(Diagnostic::new(
+                    LintKind::Cycle,
+                    ids[0],
+                    format!("nodes form a cycle: {}", ids.join(" -> ")),
+                ));
+            }
+        }
+    }
+}
+
+/// Tarjan's algorithm over an adjacency list
+fn strongly_connected_components(edges: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct State<'a> {
+        edges: &'a [Vec<usize>],
+        next_index: usize,
+        index: Vec<Option<usize>>,
+        low_link: Vec<usize>,
+        on_stack: Vec<bool>,
+        stack: Vec<usize>,
+        components: Vec<Vec<usize>>,
+    }
+
+    fn visit(state: &mut State, v: usize) {
+        state.index[v] = Some(state.next_index);
+        state.low_link[v] = state.next_index;
+        state.next_index += 1;
+        state.stack.push(v);
+        state.on_stack[v] = true;
+
+        for &w in &state.edges[v] {
+            match state.index[w] {
+                None => {
+                    visit(state, w);
+                    state.low_link[v] = state.low_link[v].min(state.low_link[w]);
+                }
+                Some(w_index) if state.on_stack[w] => {
+                    state.low_link[v] = state.low_link[v].min(w_index);
+                }
+                Some(_) => {}
+            }
+        }
+
+        if Some(state.low_link[v]) == state.index[v] {
+            let mut component = Vec::new();
+            while let Some(w) = state.stack.pop() {
+                state.on_stack[w] = false;
+                component.push(w);
+                if w == v {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let n = edges.len();
+    let mut state = State {
+        edges,
+        next_index: 0,
+        index: vec![None; n],
+        low_link: vec![0; n],
+        on_stack: vec![false; n],
+        stack: Vec::new(),
+        components: Vec::new(),
+    };
+    for v in 0..n {
+        if state.index[v].is_none() {
+            visit(&mut state, v);
+        }
+    }
+    state.components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn lint(yaml: &str) -> Vec<Diagnostic> {
+        let parsed = DataflowParser::parse_string(yaml, PathBuf::from("test.yml")).unwrap();
+        DataflowLinter::lint(&parsed, None)
+    }
+
+    fn kinds(diagnostics: &[Diagnostic], severity: Severity) -> Vec<(LintKind, &str)> {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .map(|d| (d.kind, d.node_id.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_diagnostics() {
+        let yaml = r#"
+nodes:
+  - id: asr
+    path: ./asr
+    inputs:
+      audio: mofa-mic-input/audio
+      control: controller/control
+    outputs:
+      - text
+
+  - id: teacher
+    path: ./teacher
+    inputs:
+      text: asr/text
+      feedback: tts/feedback
+      missing: asr/partial
+    outputs:
+      - reply
+
+  - id: tts
+    path: ./tts
+    inputs:
+      text: teacher/reply
+    outputs:
+      - feedback
+      - audio
+
+  - id: tts
+    path: dynamic
+
+  - id: mofa-mic-input
+    path: dynamic
+    outputs:
+      - audio
+
+  - id: mofa-score-board
+    path: dynamic
+    inputs:
+      text: teacher/reply
+"#;
+
+        let diagnostics = lint(yaml);
+
+        assert_eq!(
+            kinds(&diagnostics, Severity::Error),
+            vec![
+                (LintKind::DuplicateNodeId, "tts"),
+                (LintKind::DanglingInput, "asr"),
+                (LintKind::UnknownOutput, "teacher"),
+            ]
+        );
+        assert_eq!(
+            kinds(&diagnostics, Severity::Warning),
+            vec![
+                (LintKind::UnknownMofaNode, "mofa-score-board"),
+                (LintKind::Cycle, "teacher"),
+            ]
+        );
+        assert_eq!(
+            kinds(&diagnostics, Severity::Info),
+            vec![(LintKind::UnusedOutput, "tts")]
+        );
+        assert!(diagnostics[4].message.contains("teacher -> tts"));
+    }
+
+    #[test]
+    fn test_missing_binary() {
+        let dir = std::env::temp_dir().join(format!("dora-bridge-lint-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        std::fs::write(dir.join("bin/present"), b"").unwrap();
+        let path = dir.join("dataflow.yml");
+        std::fs::write(
+            &path,
+            r#"
+nodes:
+  - id: present
+    path: bin/present
+  - id: built
+    build: cargo build
+    path: bin/built
+  - id: unbuilt
+    path: bin/unbuilt
+  - id: on-path
+    path: dora-primespeech
+"#,
+        )
+        .unwrap();
+
+        let diagnostics = DataflowLinter::lint_file(&path).unwrap();
+        let missing: Vec<&str> = diagnostics
+            .iter()
+            .filter(|d| d.kind == LintKind::MissingBinary)
+            .map(|d| d.node_id.as_str())
+            .collect();
+        assert_eq!(missing, vec!["built", "unbuilt"]);
+        assert!(diagnostics[1].message.contains("no build command"));
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    /// Every dataflow shipped with the app must be free of lint errors
+    #[test]
+    fn test_core_dataflows_have_no_errors() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../core/dataflows");
+        let mut files = Vec::new();
+        collect_yaml_files(&root, &mut files);
+        assert!(
+            !files.is_empty(),
+            "no dataflows found in {}",
+            root.display()
+        );
+
+        for file in files {
+            let errors: Vec<String> = DataflowLinter::lint_file(&file)
+                .unwrap()
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .map(|d| d.to_string())
+                .collect();
+            assert!(
+                errors.is_empty(),
+                "{}:\n{}",
+                file.display(),
+                errors.join("\n")
+            );
+        }
+    }
+
+    fn collect_yaml_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect_yaml_files(&path, files);
+            } else if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("yml" | "yaml")
+            ) {
+                files.push(path);
+            }
+        }
+    }
+}
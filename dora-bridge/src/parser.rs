@@ -56,6 +56,8 @@ pub struct ParsedNode {
     pub outputs: Vec<String>,
     /// Environment variables
     pub env: HashMap<String, String>,
+    /// Build command (`build:`), if any
+    pub build: Option<String>,
     /// Whether this is a dynamic node
     pub is_dynamic: bool,
 }
@@ -72,6 +74,8 @@ pub enum NodeKind {
         source: String,
         args: Option<String>,
     },
+    /// Native node built from a path (e.g. `../../../target/debug/dora-doubao-asr`)
+    Native { path: String, args: Option<String> },
     /// Dynamic node (connected at runtime)
     Dynamic,
 }
@@ -217,8 +221,19 @@ impl DataflowParser {
                 .and_then(|a| a.as_str())
                 .map(|s| s.to_string());
             NodeKind::Custom { source, args }
-        } else if value.get("path").and_then(|p| p.as_str()) == Some("dynamic") {
-            NodeKind::Dynamic
+        } else if let Some(path) = value.get("path").and_then(|p| p.as_str()) {
+            if path == "dynamic" {
+                NodeKind::Dynamic
+            } else {
+                let args = value
+                    .get("args")
+                    .and_then(|a| a.as_str())
+                    .map(|s| s.to_string());
+                NodeKind::Native {
+                    path: path.to_string(),
+                    args,
+                }
+            }
         } else {
             return None;
         };
@@ -230,7 +245,11 @@ impl DataflowParser {
         let mut inputs = Vec::new();
         if let Some(inputs_map) = value.get("inputs").and_then(|i| i.as_mapping()) {
             for (key, val) in inputs_map {
-                if let (Some(id), Some(source)) = (key.as_str(), val.as_str()) {
+                // `input: node/output` or `input: { source: node/output, queue_size: N }`
+                let source = val
+                    .as_str()
+                    .or_else(|| val.get("source").and_then(|s| s.as_str()));
+                if let (Some(id), Some(source)) = (key.as_str(), source) {
                     inputs.push(InputDef {
                         id: id.to_string(),
                         source: source.to_string(),
@@ -265,12 +284,18 @@ impl DataflowParser {
             }
         }
 
+        let build = value
+            .get("build")
+            .and_then(|b| b.as_str())
+            .map(|s| s.to_string());
+
         Some(ParsedNode {
             id,
             kind,
             inputs,
             outputs,
             env,
+            build,
             is_dynamic,
         })
     }
@@ -385,6 +410,27 @@ impl ParsedDataflow {
             .filter(|r| r.required && std::env::var(&r.key).is_err())
             .collect()
     }
+
+    /// Get required env vars that are neither in `provided` nor in the process env
+    ///
+    /// Empty values count as missing, so the UI can list credentials that still
+    /// have to be configured before starting the dataflow.
+    pub fn missing_env_requirements(
+        &self,
+        provided: &HashMap<String, String>,
+    ) -> Vec<&EnvRequirement> {
+        self.env_requirements
+            .iter()
+            .filter(|r| r.required)
+            .filter(|r| {
+                let value = provided
+                    .get(&r.key)
+                    .cloned()
+                    .or_else(|| std::env::var(&r.key).ok());
+                value.is_none_or(|v| v.trim().is_empty())
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -424,4 +470,45 @@ nodes:
         assert_eq!(parsed.log_sources.len(), 1);
         assert_eq!(parsed.log_sources[0].node_id, "tts");
     }
+
+    #[test]
+    fn test_missing_env_requirements() {
+        let yaml = r#"
+nodes:
+  - id: asr
+    custom:
+      source: asr
+    env:
+      COLANG_TEST_APP_ID: ${COLANG_TEST_APP_ID}
+      COLANG_TEST_TOKEN: ${COLANG_TEST_TOKEN}
+      COLANG_TEST_RESOURCE: ${COLANG_TEST_RESOURCE:-volc.bigasr.sauc.duration}
+
+  - id: tts
+    custom:
+      source: tts
+    env:
+      COLANG_TEST_TOKEN: ${COLANG_TEST_TOKEN}
+"#;
+
+        let parsed = DataflowParser::parse_string(yaml, PathBuf::from("test.yml")).unwrap();
+
+        let token = parsed
+            .env_requirements
+            .iter()
+            .find(|r| r.key == "COLANG_TEST_TOKEN")
+            .unwrap();
+        assert!(token.secret);
+        assert_eq!(token.used_by, vec!["asr", "tts"]);
+
+        let mut provided = HashMap::new();
+        provided.insert("COLANG_TEST_APP_ID".to_string(), "123".to_string());
+        provided.insert("COLANG_TEST_TOKEN".to_string(), " ".to_string());
+
+        let missing: Vec<&str> = parsed
+            .missing_env_requirements(&provided)
+            .iter()
+            .map(|r| r.key.as_str())
+            .collect();
+        assert_eq!(missing, vec!["COLANG_TEST_TOKEN"]);
+    }
 }
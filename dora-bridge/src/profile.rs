@@ -0,0 +1,529 @@
+//! Dataflow profiles
+//!
+//! Builds the learning dataflow from a typed profile instead of a hand-edited
+//! YAML file:
+//! - ASR backend (doubao-asr or bigmodel-asr)
+//! - TTS backend (doubao-tts or bigmodel-tts)
+//! - Teacher model (`MAAS_DEFAULT_MODEL` for english-teacher)
+//! - Optional session-controller and learning-db-reader nodes
+//!
+//! The generated YAML is meant to be written next to the static dataflow so
+//! node paths and `teacher_config.toml` resolve the same way.
+
+use std::path::{Path, PathBuf};
+
+use figment::Figment;
+use figment::providers::{Format, Toml};
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use crate::error::{BridgeError, BridgeResult};
+
+/// Header written at the top of generated dataflows
+const GENERATED_HEADER: &str =
+    "# Generated by dora-bridge from a DataflowProfile. Do not edit, changes are overwritten.\n";
+
+/// Speech recognition backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AsrBackend {
+    /// Volcano Engine streaming ASR (`dora-doubao-asr`)
+    #[default]
+    Doubao,
+    /// ZhipuAI BigModel transcription (`dora-bigmodel-asr`)
+    BigModel,
+}
+
+impl AsrBackend {
+    /// Map a settings provider ID to a backend
+    pub fn from_provider_id(provider_id: &str) -> Option<Self> {
+        match provider_id {
+            "volcano" | "doubao" => Some(AsrBackend::Doubao),
+            "bigmodel" | "zhipu" => Some(AsrBackend::BigModel),
+            _ => None,
+        }
+    }
+
+    /// Node ID in the generated dataflow
+    pub fn node_id(&self) -> &'static str {
+        match self {
+            AsrBackend::Doubao => "doubao-asr",
+            AsrBackend::BigModel => "bigmodel-asr",
+        }
+    }
+}
+
+/// Speech synthesis backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TtsBackend {
+    /// Volcano Engine bidirectional streaming TTS (`dora-doubao-tts`)
+    #[default]
+    Doubao,
+    /// ZhipuAI BigModel speech (`dora-bigmodel-tts`)
+    BigModel,
+}
+
+impl TtsBackend {
+    /// Map a settings provider ID to a backend
+    pub fn from_provider_id(provider_id: &str) -> Option<Self> {
+        match provider_id {
+            "volcano" | "doubao" => Some(TtsBackend::Doubao),
+            "bigmodel" | "zhipu" => Some(TtsBackend::BigModel),
+            _ => None,
+        }
+    }
+
+    /// Node ID in the generated dataflow
+    pub fn node_id(&self) -> &'static str {
+        match self {
+            TtsBackend::Doubao => "doubao-tts",
+            TtsBackend::BigModel => "bigmodel-tts",
+        }
+    }
+}
+
+/// Model entry in `teacher_config.toml`; only the ID is needed here
+#[derive(Debug, Deserialize)]
+struct TeacherModel {
+    id: String,
+}
+
+/// Model IDs that have a route in `teacher_config.toml`, in file order
+pub fn teacher_models(config_path: impl AsRef<Path>) -> BridgeResult<Vec<String>> {
+    let models: Vec<TeacherModel> = Figment::new()
+        .merge(Toml::file(config_path.as_ref()))
+        .extract_inner("models")
+        .map_err(|e| BridgeError::ParseError(e.to_string()))?;
+    Ok(models.into_iter().map(|model| model.id).collect())
+}
+
+/// Typed description of the learning dataflow
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataflowProfile {
+    pub asr: AsrBackend,
+    pub tts: TtsBackend,
+    /// Model ID from `teacher_config.toml`; `None` keeps its `default_model`
+    pub teacher_model: Option<String>,
+    /// Broadcast start/stop/interrupt/replay from the UI to the nodes
+    pub session_controller: bool,
+    /// Select review words from the learning database on session control
+    pub db_reader: bool,
+    /// Workspace root relative to the dataflow file (node binaries and sources)
+    pub workspace_root: String,
+}
+
+impl Default for DataflowProfile {
+    fn default() -> Self {
+        Self {
+            asr: AsrBackend::default(),
+            tts: TtsBackend::default(),
+            teacher_model: None,
+            session_controller: true,
+            db_reader: false,
+            workspace_root: "../../..".to_string(),
+        }
+    }
+}
+
+impl DataflowProfile {
+    /// Render the dataflow YAML
+    pub fn to_yaml(&self) -> BridgeResult<String> {
+        let nodes: Vec<Value> = self.nodes().into_iter().map(NodeSpec::into_value).collect();
+
+        let mut root = Mapping::new();
+        root.insert("nodes".into(), Value::Sequence(nodes));
+
+        Ok(format!(
+            "{}{}",
+            GENERATED_HEADER,
+            serde_yaml::to_string(&root)?
+        ))
+    }
+
+    /// Write the dataflow YAML to `path`, returning the path for
+    /// [`crate::DataflowController::new`]
+    pub fn write(&self, path: impl AsRef<Path>) -> BridgeResult<PathBuf> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_yaml()?)?;
+        Ok(path.to_path_buf())
+    }
+
+    fn nodes(&self) -> Vec<NodeSpec> {
+        let asr = self.asr.node_id();
+        let tts = self.tts.node_id();
+        let asr_text = format!("{}/text", asr);
+
+        let mut nodes = Vec::new();
+
+        // ============ User input ============
+
+        let mut text_input = NodeSpec::dynamic("mofa-text-input");
+        if self.asr == AsrBackend::Doubao {
+            text_input = text_input
+                .input("asr_partial", "doubao-asr/partial")
+                .input("asr_final", "doubao-asr/final");
+        } else {
+            text_input = text_input.input("asr_final", &asr_text);
+        }
+        nodes.push(
+            text_input
+                .input("teacher_text", "english-teacher/reply_segment")
+                .outputs(&["text", "control"]),
+        );
+        nodes.push(NodeSpec::dynamic("mofa-mic-input").outputs(&["audio", "audio_stream"]));
+
+        // ============ ASR ============
+
+        nodes.push(match self.asr {
+            AsrBackend::Doubao => self
+                .native("doubao-asr", "dora-doubao-asr")
+                .input("audio", "mofa-mic-input/audio")
+                .input("audio_stream", "mofa-mic-input/audio_stream")
+                .outputs(&["text", "partial", "final", "status", "log"])
+                .env("DOUBAO_APP_ID", "${DOUBAO_APP_ID}")
+                .env("DOUBAO_ACCESS_TOKEN", "${DOUBAO_ACCESS_TOKEN}")
+                .env("DOUBAO_CLUSTER", "${DOUBAO_CLUSTER:-volcano_asr}")
+                .env("ASR_MODE", "${ASR_MODE:-streaming}")
+                .env(
+                    "DOUBAO_ASR_RESOURCE_ID",
+                    "${DOUBAO_ASR_RESOURCE_ID:-volc.bigasr.sauc.duration}",
+                )
+                .env("LANGUAGE", "en")
+                .env("LOG_LEVEL", "INFO")
+                .env("RUST_LOG", "info"),
+            AsrBackend::BigModel => self
+                .native("bigmodel-asr", "dora-bigmodel-asr")
+                .input("audio", "mofa-mic-input/audio")
+                .outputs(&["text", "status"])
+                .env("BIGMODEL_API_KEY", "${BIGMODEL_API_KEY}")
+                .env("RUST_LOG", "info"),
+        });
+
+        // ============ Session control ============
+
+        if self.session_controller {
+            nodes.push(
+                self.native("session-controller", "dora-session-controller")
+                    .input("user_input", "mofa-text-input/control")
+                    .input("audio_complete", "mofa-audio-player/audio_complete")
+                    .outputs(&["control", "status", "log"])
+                    .env("SESSION_MODE", "learning")
+                    .env("RUST_LOG", "info"),
+            );
+        }
+
+        // ============ Storage ============
+
+        nodes.push(
+            self.native("learning-db-writer", "dora-learning-db-writer")
+                .input("user_text", &asr_text)
+                .input("ai_json", "english-teacher/json_data")
+                .outputs(&["result", "status", "log"])
+                .env(
+                    "DATABASE_URL",
+                    "${DATABASE_URL:-sqlite://learning_companion.db}",
+                )
+                .env("LOG_LEVEL", "INFO")
+                .env("RUST_LOG", "info"),
+        );
+
+        if self.db_reader {
+            let trigger = if self.session_controller {
+                "session-controller/control"
+            } else {
+                "mofa-text-input/control"
+            };
+            nodes.push(
+                self.native("learning-db-reader", "dora-learning-db-reader")
+                    .input("trigger", trigger)
+                    .outputs(&["selected_words"])
+                    .env(
+                        "DATABASE_URL",
+                        "${DATABASE_URL:-sqlite://learning_companion.db}",
+                    )
+                    .env("RUST_LOG", "info"),
+            );
+        }
+
+        // ============ Teacher ============
+
+        let mut teacher = self
+            .native("english-teacher", "dora-english-teacher")
+            .input("asr_text", &asr_text)
+            .input("text_input", "mofa-text-input/text");
+        if self.session_controller {
+            teacher = teacher.input("control", "session-controller/control");
+        }
+        teacher = teacher
+            .outputs(&["reply_segment", "json_data", "status", "log"])
+            .env("MAAS_CONFIG_PATH", "teacher_config.toml");
+        if let Some(model) = &self.teacher_model {
+            teacher = teacher.env("MAAS_DEFAULT_MODEL", model);
+        }
+        nodes.push(
+            teacher
+                .env("DOUBAO_API_KEY", "${DOUBAO_API_KEY:-}")
+                .env("DEEPSEEK_API_KEY", "${DEEPSEEK_API_KEY:-}")
+                .env("DASHSCOPE_API_KEY", "${DASHSCOPE_API_KEY:-}")
+                .env("OPENAI_API_KEY", "${OPENAI_API_KEY:-}")
+                .env("BIGMODEL_API_KEY", "${BIGMODEL_API_KEY:-}")
+                .env("MAX_SEGMENT_WORDS", "20")
+                .env("LOG_LEVEL", "INFO")
+                .env("RUST_LOG", "info"),
+        );
+
+        // ============ TTS ============
+
+        nodes.push(match self.tts {
+            TtsBackend::Doubao => {
+                let mut node = self
+                    .native("doubao-tts", "dora-doubao-tts")
+                    .input("text", "english-teacher/reply_segment");
+                if self.session_controller {
+                    node = node.input("control", "session-controller/control");
+                }
+                node.outputs(&["audio_bytes", "audio_metadata", "status", "log"])
+                    .env("DOUBAO_APP_ID", "${DOUBAO_APP_ID}")
+                    .env("DOUBAO_API_KEY", "${DOUBAO_API_KEY:-}")
+                    .env("DOUBAO_ACCESS_TOKEN", "${DOUBAO_ACCESS_TOKEN}")
+                    .env("DOUBAO_RESOURCE_ID", "${DOUBAO_RESOURCE_ID:-seed-tts-2.0}")
+                    .env("VOICE_TYPE", "${VOICE_TYPE:-zh_female_vv_uranus_bigtts}")
+                    .env("SPEED_RATIO", "1.0")
+                    .env("TTS_CACHE_DIR", "${TTS_CACHE_DIR:-}")
+                    .env("LOG_LEVEL", "INFO")
+                    .env("RUST_LOG", "info")
+            }
+            // bigmodel-tts synthesizes whole replies, not sentence segments
+            TtsBackend::BigModel => self
+                .native("bigmodel-tts", "dora-bigmodel-tts")
+                .input("text", "english-teacher/json_data")
+                .outputs(&["audio_bytes", "audio_metadata", "status"])
+                .env("BIGMODEL_API_KEY", "${BIGMODEL_API_KEY}")
+                .env("BIGMODEL_VOICE", "${BIGMODEL_VOICE:-alloy}")
+                .env("RUST_LOG", "info"),
+        });
+
+        // ============ MoFA UI ============
+
+        nodes.push(
+            NodeSpec::dynamic("mofa-audio-player")
+                .queued_input("audio_myself", "mofa-mic-input/audio", 1000)
+                .queued_input("audio_teacher", &format!("{}/audio_bytes", tts), 1000)
+                .outputs(&[
+                    "buffer_status",
+                    "status",
+                    "session_start",
+                    "audio_complete",
+                    "log",
+                ]),
+        );
+
+        nodes.push(
+            NodeSpec::dynamic("mofa-chat-display")
+                .queued_input("user_text", &asr_text, 1000)
+                .queued_input("text_input", "mofa-text-input/text", 1000)
+                .queued_input("ai_json", "english-teacher/json_data", 100)
+                .outputs(&["status"]),
+        );
+
+        // System log subscribes to the log/status outputs of native nodes and
+        // of UI nodes that log
+        let mut system_log = NodeSpec::dynamic("mofa-system-log");
+        for node in &nodes {
+            let prefix = node.id.replace('-', "_");
+            let has_log = node.outputs.iter().any(|o| o == "log");
+            if has_log {
+                system_log = system_log.queued_input(
+                    &format!("{}_log", prefix),
+                    &format!("{}/log", node.id),
+                    1000,
+                );
+            }
+            let native = node.build.is_some();
+            if node.outputs.iter().any(|o| o == "status") && (native || has_log) {
+                system_log = system_log.input(
+                    &format!("{}_status", prefix),
+                    &format!("{}/status", node.id),
+                );
+            }
+        }
+        nodes.push(system_log);
+
+        nodes
+    }
+
+    /// Native node built from `rust-nodes/<crate_name>`
+    fn native(&self, id: &str, crate_name: &str) -> NodeSpec {
+        let root = self.workspace_root.trim_end_matches('/');
+        NodeSpec {
+            id: id.to_string(),
+            build: Some(format!(
+                "cargo build --manifest-path {}/rust-nodes/{}/Cargo.toml",
+                root, crate_name
+            )),
+            path: format!("{}/target/debug/{}", root, crate_name),
+            inputs: Mapping::new(),
+            outputs: Vec::new(),
+            env: Mapping::new(),
+        }
+    }
+}
+
+/// One node of the generated dataflow (mappings keep insertion order)
+struct NodeSpec {
+    id: String,
+    build: Option<String>,
+    path: String,
+    inputs: Mapping,
+    outputs: Vec<String>,
+    env: Mapping,
+}
+
+impl NodeSpec {
+    fn dynamic(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            build: None,
+            path: "dynamic".to_string(),
+            inputs: Mapping::new(),
+            outputs: Vec::new(),
+            env: Mapping::new(),
+        }
+    }
+
+    fn input(mut self, id: &str, source: &str) -> Self {
+        self.inputs.insert(id.into(), source.into());
+        self
+    }
+
+    fn queued_input(mut self, id: &str, source: &str, queue_size: u32) -> Self {
+        let mut input = Mapping::new();
+        input.insert("source".into(), source.into());
+        input.insert("queue_size".into(), queue_size.into());
+        self.inputs.insert(id.into(), Value::Mapping(input));
+        self
+    }
+
+    fn outputs(mut self, outputs: &[&str]) -> Self {
+        self.outputs
+            .extend(outputs.iter().map(|output| output.to_string()));
+        self
+    }
+
+    fn env(mut self, key: &str, value: &str) -> Self {
+        self.env.insert(key.into(), value.into());
+        self
+    }
+
+    fn into_value(self) -> Value {
+        let mut node = Mapping::new();
+        node.insert("id".into(), self.id.into());
+        if let Some(build) = self.build {
+            node.insert("build".into(), build.into());
+        }
+        node.insert("path".into(), self.path.into());
+        if !self.inputs.is_empty() {
+            node.insert("inputs".into(), Value::Mapping(self.inputs));
+        }
+        if !self.outputs.is_empty() {
+            let outputs = self.outputs.into_iter().map(Value::from).collect();
+            node.insert("outputs".into(), Value::Sequence(outputs));
+        }
+        if !self.env.is_empty() {
+            node.insert("env".into(), Value::Mapping(self.env));
+        }
+        Value::Mapping(node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::DataflowParser;
+
+    #[test]
+    fn test_default_profile_matches_learning_dataflow() {
+        let yaml = DataflowProfile::default().to_yaml().unwrap();
+        let parsed = DataflowParser::parse_string(&yaml, PathBuf::from("profile.yml")).unwrap();
+
+        let asr = parsed.get_node("doubao-asr").unwrap();
+        assert_eq!(asr.inputs[0].source, "mofa-mic-input/audio");
+        assert_eq!(asr.inputs[1].source, "mofa-mic-input/audio_stream");
+        assert!(parsed.get_node("session-controller").is_some());
+        assert!(parsed.get_node("learning-db-reader").is_none());
+
+        let player = parsed.get_mofa_node("mofa-audio-player").unwrap();
+        assert!(
+            player
+                .inputs
+                .iter()
+                .any(|input| input.source == "doubao-tts/audio_bytes")
+        );
+
+        let token = parsed
+            .env_requirements
+            .iter()
+            .find(|r| r.key == "DOUBAO_ACCESS_TOKEN")
+            .unwrap();
+        assert!(token.required);
+        assert_eq!(token.used_by, vec!["doubao-asr", "doubao-tts"]);
+
+        let database = parsed
+            .env_requirements
+            .iter()
+            .find(|r| r.key == "DATABASE_URL")
+            .unwrap();
+        assert!(!database.required);
+    }
+
+    #[test]
+    fn test_bigmodel_profile() {
+        let profile = DataflowProfile {
+            asr: AsrBackend::BigModel,
+            tts: TtsBackend::BigModel,
+            teacher_model: Some("deepseek-chat".to_string()),
+            session_controller: false,
+            db_reader: true,
+            ..Default::default()
+        };
+        let yaml = profile.to_yaml().unwrap();
+        let parsed = DataflowParser::parse_string(&yaml, PathBuf::from("profile.yml")).unwrap();
+
+        assert!(parsed.nodes.iter().all(|n| !n.id.starts_with("doubao")));
+        assert!(parsed.get_node("session-controller").is_none());
+
+        let teacher = parsed.get_node("english-teacher").unwrap();
+        assert_eq!(teacher.env["MAAS_DEFAULT_MODEL"], "deepseek-chat");
+        assert!(teacher.inputs.iter().all(|input| input.id != "control"));
+
+        let tts = parsed.get_node("bigmodel-tts").unwrap();
+        assert_eq!(tts.inputs[0].source, "english-teacher/json_data");
+
+        let reader = parsed.get_node("learning-db-reader").unwrap();
+        assert_eq!(reader.inputs[0].source, "mofa-text-input/control");
+
+        let missing: Vec<&str> = parsed
+            .env_requirements
+            .iter()
+            .filter(|r| r.required)
+            .map(|r| r.key.as_str())
+            .collect();
+        assert_eq!(missing, vec!["BIGMODEL_API_KEY"]);
+    }
+
+    #[test]
+    fn test_teacher_models() {
+        let config = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../core/dataflows/dialog/teacher_config.toml");
+        let models = teacher_models(&config).unwrap();
+        assert_eq!(
+            models.first().map(String::as_str),
+            Some("doubao-seed-1-8-251228")
+        );
+        assert!(models.iter().any(|id| id == "deepseek-chat"));
+
+        assert!(teacher_models("missing/teacher_config.toml").is_err());
+    }
+}
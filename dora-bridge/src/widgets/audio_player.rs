@@ -532,6 +532,8 @@ impl AudioPlayerBridge {
             channels: 1,
             participant_id,
             question_id,
+            span_ms: None,
+            last_chunk: false,
         })
     }
 
@@ -0,0 +1,266 @@
+//! Chat display bridge
+//!
+//! Connects to dora as `mofa-chat-display` dynamic node.
+//! Receives the finished turns of a learning session:
+//! - User text (`AsrOutput` from ASR, `{"text": ...}` from text input)
+//! - Teacher analysis (`ComprehensiveResponse` with reply and issues)
+//!
+//! Payloads are forwarded as JSON so the consumer can decode the typed
+//! message it cares about.
+
+use std::sync::Arc;
+use std::thread;
+
+use arrow::array::Array;
+use crossbeam_channel::{Receiver, Sender, bounded};
+use dora_node_api::dora_core::config::NodeId;
+use dora_node_api::{DoraNode, Event, Parameter};
+use parking_lot::RwLock;
+use tracing::{error, info, warn};
+
+use crate::bridge::{BridgeEvent, BridgeState, DoraBridge};
+use crate::data::{DoraData, EventMetadata};
+use crate::error::{BridgeError, BridgeResult};
+
+/// Chat display bridge - receives user turns and teacher analysis
+pub struct ChatDisplayBridge {
+    /// Node ID (e.g., "mofa-chat-display")
+    node_id: String,
+    /// Current state
+    state: Arc<RwLock<BridgeState>>,
+    /// Event sender to widget
+    event_sender: Sender<BridgeEvent>,
+    /// Event receiver for widget
+    event_receiver: Receiver<BridgeEvent>,
+    /// Stop signal
+    stop_sender: Option<Sender<()>>,
+    /// Worker thread handle
+    worker_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl ChatDisplayBridge {
+    /// Create a new chat display bridge
+    pub fn new(node_id: &str) -> Self {
+        let (event_tx, event_rx) = bounded(1000);
+
+        Self {
+            node_id: node_id.to_string(),
+            state: Arc::new(RwLock::new(BridgeState::Disconnected)),
+            event_sender: event_tx,
+            event_receiver: event_rx,
+            stop_sender: None,
+            worker_handle: None,
+        }
+    }
+
+    /// Run the dora event loop in background thread
+    fn run_event_loop(
+        node_id: String,
+        state: Arc<RwLock<BridgeState>>,
+        event_sender: Sender<BridgeEvent>,
+        stop_receiver: Receiver<()>,
+    ) {
+        info!("Starting chat display bridge event loop for {}", node_id);
+
+        // Initialize dora node
+        let (_node, mut events) = match DoraNode::init_from_node_id(NodeId::from(node_id.clone())) {
+            Ok(n) => n,
+            Err(e) => {
+                error!("Failed to init dora node {}: {}", node_id, e);
+                *state.write() = BridgeState::Error;
+                let _ = event_sender.send(BridgeEvent::Error(format!("Init failed: {}", e)));
+                return;
+            }
+        };
+
+        *state.write() = BridgeState::Connected;
+        let _ = event_sender.send(BridgeEvent::Connected);
+
+        loop {
+            if stop_receiver.try_recv().is_ok() {
+                info!("Chat display bridge received stop signal");
+                break;
+            }
+
+            if let Some(event) = events.recv_timeout(std::time::Duration::from_millis(100)) {
+                Self::handle_dora_event(event, &event_sender);
+            }
+        }
+
+        *state.write() = BridgeState::Disconnected;
+        let _ = event_sender.send(BridgeEvent::Disconnected);
+        info!("Chat display bridge event loop ended");
+    }
+
+    /// Handle a dora event
+    fn handle_dora_event(event: Event, event_sender: &Sender<BridgeEvent>) {
+        match event {
+            Event::Input { id, data, metadata } => {
+                let mut event_meta = EventMetadata::default();
+                for (key, value) in metadata.parameters.iter() {
+                    let string_value = match value {
+                        Parameter::String(s) => s.clone(),
+                        Parameter::Integer(i) => i.to_string(),
+                        Parameter::Float(f) => f.to_string(),
+                        Parameter::Bool(b) => b.to_string(),
+                        Parameter::ListInt(l) => format!("{:?}", l),
+                        Parameter::ListFloat(l) => format!("{:?}", l),
+                        Parameter::ListString(l) => format!("{:?}", l),
+                    };
+                    event_meta.values.insert(key.clone(), string_value);
+                }
+
+                let Some(raw) = Self::extract_string(&data) else {
+                    return;
+                };
+                let data = match serde_json::from_str::<serde_json::Value>(&raw) {
+                    Ok(json) => DoraData::Json(json),
+                    Err(_) => DoraData::Text(raw),
+                };
+
+                if let Err(e) = event_sender.try_send(BridgeEvent::DataReceived {
+                    input_id: id.to_string(),
+                    data,
+                    metadata: event_meta,
+                }) {
+                    warn!("Event channel full, dropping event: {}", e);
+                }
+            }
+            Event::Stop(_) => {
+                info!("Received stop event from dora");
+            }
+            _ => {}
+        }
+    }
+
+    /// Extract string from arrow data
+    fn extract_string(data: &dora_node_api::ArrowData) -> Option<String> {
+        match data.0.data_type() {
+            arrow::datatypes::DataType::Utf8 => {
+                let array = data
+                    .0
+                    .as_any()
+                    .downcast_ref::<arrow::array::StringArray>()?;
+                if array.len() > 0 {
+                    return Some(array.value(0).to_string());
+                }
+            }
+            arrow::datatypes::DataType::LargeUtf8 => {
+                let array = data
+                    .0
+                    .as_any()
+                    .downcast_ref::<arrow::array::LargeStringArray>()?;
+                if array.len() > 0 {
+                    return Some(array.value(0).to_string());
+                }
+            }
+            arrow::datatypes::DataType::UInt8 => {
+                let array = data.0.as_any().downcast_ref::<arrow::array::UInt8Array>()?;
+                let bytes: Vec<u8> = array.values().to_vec();
+                return String::from_utf8(bytes).ok();
+            }
+            _ => {
+                warn!("Unsupported chat data type: {:?}", data.0.data_type());
+            }
+        }
+        None
+    }
+}
+
+impl DoraBridge for ChatDisplayBridge {
+    fn node_id(&self) -> &str {
+        &self.node_id
+    }
+
+    fn state(&self) -> BridgeState {
+        *self.state.read()
+    }
+
+    fn connect(&mut self) -> BridgeResult<()> {
+        if self.is_connected() {
+            return Err(BridgeError::AlreadyConnected);
+        }
+
+        *self.state.write() = BridgeState::Connecting;
+
+        let (stop_tx, stop_rx) = bounded(1);
+        self.stop_sender = Some(stop_tx);
+
+        let node_id = self.node_id.clone();
+        let state = Arc::clone(&self.state);
+        let event_sender = self.event_sender.clone();
+
+        let handle = thread::spawn(move || {
+            Self::run_event_loop(node_id, state, event_sender, stop_rx);
+        });
+
+        self.worker_handle = Some(handle);
+
+        // Wait for connection result (Connected or Error) with timeout
+        let timeout = std::time::Duration::from_secs(5);
+        let start = std::time::Instant::now();
+
+        loop {
+            match *self.state.read() {
+                BridgeState::Connected => return Ok(()),
+                BridgeState::Error => {
+                    if let Ok(BridgeEvent::Error(msg)) = self.event_receiver.try_recv() {
+                        return Err(BridgeError::ConnectionFailed(msg));
+                    }
+                    return Err(BridgeError::ConnectionFailed(
+                        "Connection failed".to_string(),
+                    ));
+                }
+                _ => {}
+            }
+
+            if start.elapsed() >= timeout {
+                return Err(BridgeError::ConnectionFailed(
+                    "Connection timeout".to_string(),
+                ));
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(50));
+        }
+    }
+
+    fn disconnect(&mut self) -> BridgeResult<()> {
+        if let Some(stop_tx) = self.stop_sender.take() {
+            let _ = stop_tx.send(());
+        }
+
+        if let Some(handle) = self.worker_handle.take() {
+            let _ = handle.join();
+        }
+
+        *self.state.write() = BridgeState::Disconnected;
+        Ok(())
+    }
+
+    fn send(&self, _output_id: &str, _data: DoraData) -> BridgeResult<()> {
+        // Chat display bridge doesn't send outputs
+        Ok(())
+    }
+
+    fn subscribe(&self) -> Receiver<BridgeEvent> {
+        self.event_receiver.clone()
+    }
+
+    fn expected_inputs(&self) -> Vec<String> {
+        vec![
+            "user_text".to_string(),
+            "text_input".to_string(),
+            "ai_json".to_string(),
+        ]
+    }
+
+    fn expected_outputs(&self) -> Vec<String> {
+        vec![]
+    }
+}
+
+impl Drop for ChatDisplayBridge {
+    fn drop(&mut self) {
+        let _ = self.disconnect();
+    }
+}
@@ -2,11 +2,17 @@
 //!
 //! Connects to dora as `mofa-mic-input` dynamic node.
 //! Receives audio from UI's microphone and sends to ASR nodes.
+//!
+//! Each `audio` message is one VAD utterance and starts a turn: the bridge
+//! stamps its turn id and the session (dataflow) id into the metadata.
+//! While the user speaks, `audio_stream` carries the same utterance in chunks
+//! under its turn id, the last one flagged with `last_chunk`.
 
 use std::sync::Arc;
 use std::thread;
 
 use crossbeam_channel::{Receiver, Sender, bounded};
+use dora_messages::turn::{LAST_CHUNK, SESSION_ID, TURN_ID};
 use dora_node_api::dora_core::config::{DataId, NodeId};
 use dora_node_api::{DoraNode, Event, Parameter};
 use parking_lot::RwLock;
@@ -26,10 +32,10 @@ pub struct MicInputBridge {
     event_sender: Sender<BridgeEvent>,
     /// Event receiver for widget
     event_receiver: Receiver<BridgeEvent>,
-    /// Audio data sender from widget
-    audio_sender: Sender<AudioData>,
+    /// Audio data sender from widget, with the output it goes to
+    audio_sender: Sender<(&'static str, AudioData)>,
     /// Audio data receiver for dora
-    audio_receiver: Receiver<AudioData>,
+    audio_receiver: Receiver<(&'static str, AudioData)>,
     /// Stop signal
     stop_sender: Option<Sender<()>>,
     /// Worker thread handle
@@ -57,7 +63,14 @@ impl MicInputBridge {
     /// Send audio data to dora (widget calls this)
     pub fn send_audio(&self, audio_data: AudioData) -> BridgeResult<()> {
         self.audio_sender
-            .try_send(audio_data)
+            .try_send(("audio", audio_data))
+            .map_err(|_| BridgeError::ChannelSendError)
+    }
+
+    /// Stream a chunk of the utterance in progress to dora
+    pub fn send_audio_chunk(&self, audio_data: AudioData) -> BridgeResult<()> {
+        self.audio_sender
+            .try_send(("audio_stream", audio_data))
             .map_err(|_| BridgeError::ChannelSendError)
     }
 
@@ -66,7 +79,7 @@ impl MicInputBridge {
         node_id: String,
         state: Arc<RwLock<BridgeState>>,
         event_sender: Sender<BridgeEvent>,
-        audio_receiver: Receiver<AudioData>,
+        audio_receiver: Receiver<(&'static str, AudioData)>,
         stop_receiver: Receiver<()>,
     ) {
         info!("Starting mic input bridge event loop for {}", node_id);
@@ -85,6 +98,7 @@ impl MicInputBridge {
 
         *state.write() = BridgeState::Connected;
         let _ = event_sender.send(BridgeEvent::Connected);
+        let session_id = node.dataflow_id().to_string();
 
         // Event loop
         loop {
@@ -95,9 +109,10 @@ impl MicInputBridge {
             }
 
             // Send audio data from widget to dora
-            while let Ok(audio_data) = audio_receiver.try_recv() {
+            while let Ok((output_id, audio_data)) = audio_receiver.try_recv() {
                 debug!(
-                    "Sending audio: {} samples at {}Hz",
+                    "Sending {}: {} samples at {}Hz",
+                    output_id,
                     audio_data.samples.len(),
                     audio_data.sample_rate
                 );
@@ -108,6 +123,25 @@ impl MicInputBridge {
                     Parameter::Integer(audio_data.sample_rate as i64),
                 );
                 params.insert("channels".to_string(), Parameter::Integer(1));
+                if let Some((start_ms, end_ms)) = audio_data.span_ms {
+                    params.insert("start_ms".to_string(), Parameter::Integer(start_ms as i64));
+                    params.insert("end_ms".to_string(), Parameter::Integer(end_ms as i64));
+                }
+                let turn_id = audio_data
+                    .question_id
+                    .clone()
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+                params.insert(TURN_ID.to_string(), Parameter::String(turn_id));
+                params.insert(
+                    SESSION_ID.to_string(),
+                    Parameter::String(session_id.clone()),
+                );
+                if output_id == "audio_stream" {
+                    params.insert(
+                        LAST_CHUNK.to_string(),
+                        Parameter::Bool(audio_data.last_chunk),
+                    );
+                }
 
                 // Convert f32 samples to Arrow ListArray
                 let audio_array = dora_node_api::arrow::array::ListArray::from_iter_primitive::<
@@ -119,9 +153,9 @@ impl MicInputBridge {
                 )));
 
                 if let Err(e) =
-                    node.send_output(DataId::from("audio".to_string()), params, audio_array)
+                    node.send_output(DataId::from(output_id.to_string()), params, audio_array)
                 {
-                    error!("Failed to send audio: {}", e);
+                    error!("Failed to send {}: {}", output_id, e);
                 }
             }
 
@@ -257,6 +291,9 @@ impl DoraBridge for MicInputBridge {
             ("audio", DoraData::Audio(audio)) => {
                 self.send_audio(audio)?;
             }
+            ("audio_stream", DoraData::Audio(audio)) => {
+                self.send_audio_chunk(audio)?;
+            }
             _ => {
                 warn!("Unknown output: {}", output_id);
             }
@@ -275,7 +312,11 @@ impl DoraBridge for MicInputBridge {
     }
 
     fn expected_outputs(&self) -> Vec<String> {
-        vec!["audio".to_string(), "status".to_string()]
+        vec![
+            "audio".to_string(),
+            "audio_stream".to_string(),
+            "status".to_string(),
+        ]
     }
 }
 
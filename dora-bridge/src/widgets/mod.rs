@@ -4,17 +4,20 @@
 //! - `mofa-audio-player`: Receives audio, forwards to UI for playback
 //! - `mofa-system-log`: Receives logs from multiple nodes
 //! - `mofa-prompt-input`: Sends user prompts to LLM
+//! - `mofa-chat-display`: Receives user turns and teacher analysis
 //!
 //! Note: LED visualization is calculated in screen.rs from output waveform
 //! (more accurate since it reflects what's actually being played)
 
 mod audio_player;
+mod chat_display;
 mod mic_input;
 mod prompt_input;
 mod system_log;
 mod text_input;
 
 pub use audio_player::AudioPlayerBridge;
+pub use chat_display::ChatDisplayBridge;
 pub use mic_input::MicInputBridge;
 pub use prompt_input::PromptInputBridge;
 pub use system_log::SystemLogBridge;
@@ -118,7 +118,7 @@ impl PromptInputBridge {
         *state.write() = BridgeState::Connected;
         let _ = event_sender.send(BridgeEvent::Connected);
 
-        // Streaming text accumulation by (sender, session_id)
+        // Streaming text accumulation by (sender, turn_id)
         let mut streaming_text: HashMap<(String, String), String> = HashMap::new();
 
         // Event loop
@@ -194,16 +194,13 @@ impl PromptInputBridge {
                 if input_id.contains("text") || input_id.contains("response") {
                     if let Some(text) = Self::extract_string(&data) {
                         let sender = Self::extract_sender(input_id);
-                        let session_id = event_meta
-                            .get("question_id")
-                            .map(|s| s.to_string())
-                            .unwrap_or_else(|| "unknown".to_string());
+                        let turn_id = event_meta.question_id().map(|s| s.to_string());
                         let session_status = event_meta
                             .get("session_status")
                             .map(|s| s.to_string())
                             .unwrap_or_else(|| "unknown".to_string());
 
-                        let key = (sender.clone(), session_id.clone());
+                        let key = (sender.clone(), turn_id.clone().unwrap_or_default());
 
                         // Accumulate streaming text
                         let accumulated = streaming_text
@@ -221,7 +218,8 @@ impl PromptInputBridge {
                             role: MessageRole::Assistant,
                             timestamp: crate::data::current_timestamp(),
                             is_streaming: !is_complete,
-                            session_id: Some(session_id.clone()),
+                            session_id: event_meta.session_id().map(|s| s.to_string()),
+                            turn_id,
                         };
 
                         // Use try_send to avoid blocking if channel is full
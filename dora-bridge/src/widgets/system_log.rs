@@ -189,6 +189,13 @@ impl SystemLogBridge {
                     }
                 }
             }
+            Event::InputClosed { id } => {
+                // Log and status outputs close when their node exits
+                debug!("Input closed: {}", id);
+                let _ = event_sender.send(BridgeEvent::InputClosed {
+                    input_id: id.to_string(),
+                });
+            }
             Event::Stop(_) => {
                 info!("Received stop event from dora");
             }
@@ -1,8 +1,10 @@
 //! Prompt input bridge
 //!
 //! Connects to dora as `mofa-text-input` dynamic node.
-//! Sends user texts to LLM nodes and receives:
+//! Sends user texts to LLM nodes (each text starts a turn, stamped with its
+//! turn and session ids) and receives:
 //! - Text responses (streaming)
+//! - ASR transcripts on `asr*` inputs (partial, then final)
 //! - Status updates
 
 use std::collections::HashMap;
@@ -11,8 +13,10 @@ use std::thread;
 
 use arrow::array::Array;
 use crossbeam_channel::{Receiver, Sender, bounded};
+use dora_messages::turn::{SESSION_ID, TURN_ID};
+use dora_messages::{AsrOutput, ComprehensiveResponse, ReplySegment};
 use dora_node_api::dora_core::config::{DataId, NodeId};
-use dora_node_api::{DoraNode, Event, IntoArrow, Parameter};
+use dora_node_api::{DoraNode, Event, IntoArrow, MetadataParameters, Parameter};
 use parking_lot::RwLock;
 use tracing::{error, info, warn};
 
@@ -97,6 +101,7 @@ impl TextInputBridge {
         state: Arc<RwLock<BridgeState>>,
         event_sender: Sender<BridgeEvent>,
         text_receiver: Receiver<String>,
+        control_receiver: Receiver<ControlCommand>,
         chat_sender: Sender<ChatMessage>,
         stop_receiver: Receiver<()>,
     ) {
@@ -116,8 +121,9 @@ impl TextInputBridge {
 
         *state.write() = BridgeState::Connected;
         let _ = event_sender.send(BridgeEvent::Connected);
+        let session_id = node.dataflow_id().to_string();
 
-        // Streaming text accumulation by (sender, session_id)
+        // Streaming text accumulation by (sender, turn_id)
         let mut streaming_text: HashMap<(String, String), String> = HashMap::new();
 
         // Event loop
@@ -130,11 +136,18 @@ impl TextInputBridge {
 
             // Check for texts to send
             while let Ok(text) = text_receiver.try_recv() {
-                if let Err(e) = Self::send_text_to_dora(&mut node, &text) {
+                if let Err(e) = Self::send_text_to_dora(&mut node, &session_id, &text) {
                     warn!("Failed to send text: {}", e);
                 }
             }
 
+            // Check for control commands to send (e.g. barge-in interrupts)
+            while let Ok(cmd) = control_receiver.try_recv() {
+                if let Err(e) = Self::send_control_to_dora(&mut node, &cmd) {
+                    warn!("Failed to send control: {}", e);
+                }
+            }
+
             // Receive dora events with timeout
             match events.recv_timeout(std::time::Duration::from_millis(100)) {
                 Some(event) => {
@@ -182,20 +195,34 @@ impl TextInputBridge {
                     event_meta.values.insert(key.clone(), string_value);
                 }
 
+                // Handle ASR transcripts (partial results replace, not append)
+                if input_id.starts_with("asr") {
+                    if let Some(asr) = Self::extract_string(&data)
+                        .and_then(|raw| dora_messages::decode::<AsrOutput>(raw.as_bytes()).ok())
+                    {
+                        Self::forward_transcript(
+                            asr,
+                            input_id,
+                            event_meta,
+                            chat_sender,
+                            event_sender,
+                        );
+                    }
+                    return;
+                }
+
                 // Handle text inputs (responses from LLM)
                 if input_id.contains("text") || input_id.contains("response") {
-                    if let Some(text) = Self::extract_string(&data) {
+                    if let Some(raw) = Self::extract_string(&data) {
+                        let (text, role) = Self::decode_payload(raw);
                         let sender = Self::extract_sender(input_id);
-                        let session_id = event_meta
-                            .get("question_id")
-                            .map(|s| s.to_string())
-                            .unwrap_or_else(|| "unknown".to_string());
+                        let turn_id = event_meta.question_id().map(|s| s.to_string());
                         let session_status = event_meta
                             .get("session_status")
                             .map(|s| s.to_string())
                             .unwrap_or_else(|| "unknown".to_string());
 
-                        let key = (sender.clone(), session_id.clone());
+                        let key = (sender.clone(), turn_id.clone().unwrap_or_default());
 
                         // Accumulate streaming text
                         let accumulated = streaming_text
@@ -210,10 +237,11 @@ impl TextInputBridge {
                         let msg = ChatMessage {
                             content,
                             sender,
-                            role: MessageRole::Assistant,
+                            role,
                             timestamp: crate::data::current_timestamp(),
                             is_streaming: !is_complete,
-                            session_id: Some(session_id.clone()),
+                            session_id: event_meta.session_id().map(|s| s.to_string()),
+                            turn_id,
                         };
 
                         // Use try_send to avoid blocking if channel is full
@@ -255,6 +283,73 @@ impl TextInputBridge {
         }
     }
 
+    /// Unwrap typed node payloads into display text
+    ///
+    /// Teacher responses show the English reply, ASR results show the
+    /// recognized user text; anything else is passed through as raw text.
+    /// Reply segments are sentences of one reply and get a joining space.
+    fn decode_payload(raw: String) -> (String, MessageRole) {
+        if let Ok(segment) = dora_messages::decode::<ReplySegment>(raw.as_bytes()) {
+            let text = if segment.index > 0 && !segment.text.is_empty() {
+                format!(" {}", segment.text)
+            } else {
+                segment.text
+            };
+            return (text, MessageRole::Assistant);
+        }
+        if let Ok(response) = dora_messages::decode::<ComprehensiveResponse>(raw.as_bytes()) {
+            return (response.reply_en, MessageRole::Assistant);
+        }
+        if let Ok(asr) = dora_messages::decode::<AsrOutput>(raw.as_bytes()) {
+            return (asr.text, MessageRole::User);
+        }
+        (raw, MessageRole::Assistant)
+    }
+
+    /// Forward an ASR transcript as a user chat message
+    ///
+    /// Each partial result carries the full hypothesis so far, so the message
+    /// content is replaced rather than accumulated; the turn id (or the
+    /// utterance id for inputs without one) keys the streaming consolidation
+    /// in the chat screen.
+    fn forward_transcript(
+        asr: AsrOutput,
+        input_id: &str,
+        event_meta: EventMetadata,
+        chat_sender: &Sender<ChatMessage>,
+        event_sender: &Sender<BridgeEvent>,
+    ) {
+        if asr.text.trim().is_empty() {
+            return;
+        }
+
+        let msg = ChatMessage {
+            content: asr.text,
+            sender: "Myself".to_string(),
+            role: MessageRole::User,
+            timestamp: crate::data::current_timestamp(),
+            is_streaming: !asr.is_final,
+            session_id: asr
+                .session_id
+                .or_else(|| event_meta.session_id().map(|s| s.to_string())),
+            turn_id: event_meta
+                .question_id()
+                .map(|s| s.to_string())
+                .or(asr.utterance_id),
+        };
+
+        if let Err(e) = chat_sender.try_send(msg.clone()) {
+            warn!("Chat channel full, dropping transcript: {}", e);
+        }
+        if let Err(e) = event_sender.try_send(BridgeEvent::DataReceived {
+            input_id: input_id.to_string(),
+            data: DoraData::Chat(msg),
+            metadata: event_meta,
+        }) {
+            warn!("Event channel full, dropping event: {}", e);
+        }
+    }
+
     /// Extract string from arrow data
     fn extract_string(data: &dora_node_api::ArrowData) -> Option<String> {
         match data.0.data_type() {
@@ -288,15 +383,42 @@ impl TextInputBridge {
         None
     }
 
-    /// Send text to dora via text output
-    fn send_text_to_dora(node: &mut DoraNode, text: &str) -> BridgeResult<()> {
+    /// Send text to dora via text output, as a new turn
+    fn send_text_to_dora(node: &mut DoraNode, session_id: &str, text: &str) -> BridgeResult<()> {
         let payload = serde_json::json!({
             "text": text
         });
 
-        info!("Sending text to dora: {}", text);
+        let turn_id = uuid::Uuid::new_v4().to_string();
+        let mut params = MetadataParameters::default();
+        params.insert(TURN_ID.to_string(), Parameter::String(turn_id.clone()));
+        params.insert(
+            SESSION_ID.to_string(),
+            Parameter::String(session_id.to_string()),
+        );
+
+        info!("Sending text to dora (turn {}): {}", turn_id, text);
         let data = payload.to_string().into_arrow();
         let output_id: DataId = "text".to_string().into();
+        node.send_output(output_id, params, data)
+            .map_err(|e| BridgeError::SendFailed(e.to_string()))
+    }
+
+    /// Send control command to dora
+    ///
+    /// Encoded as the shared `ControlCommand` so `session-controller` can
+    /// decode it; params travel in its `data` field.
+    fn send_control_to_dora(node: &mut DoraNode, cmd: &ControlCommand) -> BridgeResult<()> {
+        let mut control = dora_messages::ControlCommand::new(cmd.command.clone());
+        if !cmd.params.is_empty() {
+            control = control.with_data(serde_json::json!(cmd.params));
+        }
+        let payload =
+            dora_messages::encode(&control).map_err(|e| BridgeError::SendFailed(e.to_string()))?;
+
+        info!("Sending control to dora: {}", cmd.command);
+        let data = payload.into_arrow();
+        let output_id: DataId = "control".to_string().into();
         node.send_output(output_id, Default::default(), data)
             .map_err(|e| BridgeError::SendFailed(e.to_string()))
     }
@@ -325,6 +447,7 @@ impl DoraBridge for TextInputBridge {
         let state = Arc::clone(&self.state);
         let event_sender = self.event_sender.clone();
         let text_receiver = self.text_receiver.clone();
+        let control_receiver = self.control_receiver.clone();
         let chat_sender = self.chat_sender.clone();
 
         let handle = thread::spawn(move || {
@@ -333,6 +456,7 @@ impl DoraBridge for TextInputBridge {
                 state,
                 event_sender,
                 text_receiver,
+                control_receiver,
                 chat_sender,
                 stop_rx,
             );
@@ -417,6 +541,8 @@ impl DoraBridge for TextInputBridge {
             "student1_text".to_string(),
             "student2_text".to_string(),
             "tutor_text".to_string(),
+            "asr_partial".to_string(),
+            "asr_final".to_string(),
         ]
     }
 
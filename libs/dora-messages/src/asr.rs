@@ -0,0 +1,88 @@
+//! Speech recognition payloads
+//!
+//! `mofa-mic-input` → ASR node (`AudioInput`), ASR node → teacher / DB writer
+//! (`AsrOutput`).
+
+use serde::{Deserialize, Serialize};
+
+use crate::{SCHEMA_VERSION, schema_version};
+
+/// Encoded audio handed to an ASR node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioInput {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    /// Encoded audio bytes (see `format`)
+    pub audio_data: Vec<u8>,
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+    /// Container/codec: "wav", "mp3", "pcm"
+    pub format: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+impl AudioInput {
+    pub fn new(audio_data: Vec<u8>, sample_rate: u32, format: impl Into<String>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            audio_data,
+            sample_rate,
+            format: format.into(),
+            session_id: None,
+        }
+    }
+}
+
+/// Recognized text produced by an ASR node
+///
+/// Streaming ASR emits a series of partial results for the same
+/// `utterance_id`, each holding the full hypothesis so far, followed by one
+/// final result. One-shot ASR only emits final results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AsrOutput {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub text: String,
+    #[serde(default)]
+    pub confidence: f32,
+    #[serde(default)]
+    pub words: Vec<WordTiming>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// `false` for partial (still changing) transcripts
+    #[serde(default = "default_true")]
+    pub is_final: bool,
+    /// Groups partial and final results of one utterance
+    #[serde(default)]
+    pub utterance_id: Option<String>,
+}
+
+impl AsrOutput {
+    pub fn new(text: impl Into<String>, confidence: f32) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            text: text.into(),
+            confidence,
+            words: Vec::new(),
+            session_id: None,
+            is_final: true,
+            utterance_id: None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Word-level timing information
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordTiming {
+    pub word: String,
+    /// Start time in seconds
+    pub start_time: f64,
+    /// End time in seconds
+    pub end_time: f64,
+    pub confidence: f32,
+}
@@ -0,0 +1,94 @@
+//! Arrow decoding for ASR audio inputs
+//!
+//! ASR nodes receive audio in two shapes:
+//! - `mofa-mic-input`: `ListArray<Float32>` of raw samples, with `sample_rate`
+//!   and `channels` in the metadata parameters
+//! - other nodes: a JSON [`AudioInput`] as `StringArray` or `UInt8Array`
+//!
+//! [`audio_input_from_arrow`] accepts both and always yields an encoded
+//! `AudioInput`, so the ASR request code only deals with one format.
+//! [`pcm_from_arrow`] gives streaming ASR the raw samples instead.
+
+use arrow::array::{Array, Float32Array, ListArray, StringArray, UInt8Array};
+use arrow::datatypes::DataType;
+
+use crate::pcm::DEFAULT_SAMPLE_RATE;
+use crate::{AudioInput, DecodeError, decode};
+
+/// Decode an ASR audio input from an arrow array
+///
+/// `sample_rate` and `channels` come from the input metadata and are only
+/// used for raw float samples; JSON payloads carry their own.
+pub fn audio_input_from_arrow(
+    array: &dyn Array,
+    sample_rate: Option<u32>,
+    channels: Option<u16>,
+) -> Result<AudioInput, DecodeError> {
+    if let Some(samples) = pcm_from_arrow(array)? {
+        return Ok(AudioInput::from_pcm(
+            &samples,
+            sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE),
+            channels.unwrap_or(1),
+        ));
+    }
+
+    match array.data_type() {
+        DataType::Utf8 => {
+            let strings = array
+                .as_any()
+                .downcast_ref::<StringArray>()
+                .ok_or_else(|| unsupported(array))?;
+            if strings.is_empty() {
+                return Err(DecodeError::UnsupportedAudio(
+                    "empty string array".to_string(),
+                ));
+            }
+            decode(strings.value(0).as_bytes())
+        }
+        DataType::UInt8 => {
+            let bytes = array
+                .as_any()
+                .downcast_ref::<UInt8Array>()
+                .ok_or_else(|| unsupported(array))?;
+            decode(bytes.values())
+        }
+        _ => Err(unsupported(array)),
+    }
+}
+
+/// Extract raw f32 samples as sent by `mofa-mic-input`
+///
+/// Returns `None` for non-float arrays (e.g. JSON payloads).
+pub fn pcm_from_arrow(array: &dyn Array) -> Result<Option<Vec<f32>>, DecodeError> {
+    match array.data_type() {
+        DataType::List(_) => {
+            let list = array
+                .as_any()
+                .downcast_ref::<ListArray>()
+                .ok_or_else(|| unsupported(array))?;
+            let values = list.values();
+            let floats = values
+                .as_any()
+                .downcast_ref::<Float32Array>()
+                .ok_or_else(|| {
+                    DecodeError::UnsupportedAudio(format!(
+                        "list of {:?}, expected Float32",
+                        values.data_type()
+                    ))
+                })?;
+            Ok(Some(floats.values().to_vec()))
+        }
+        DataType::Float32 => {
+            let floats = array
+                .as_any()
+                .downcast_ref::<Float32Array>()
+                .ok_or_else(|| unsupported(array))?;
+            Ok(Some(floats.values().to_vec()))
+        }
+        _ => Ok(None),
+    }
+}
+
+fn unsupported(array: &dyn Array) -> DecodeError {
+    DecodeError::UnsupportedAudio(format!("{:?}", array.data_type()))
+}
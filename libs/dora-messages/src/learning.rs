@@ -0,0 +1,65 @@
+//! Learning database payloads
+//!
+//! Used by `learning-db-reader` (word selection) and `learning-db-writer`
+//! (storage results).
+
+use serde::{Deserialize, Serialize};
+
+use crate::{SCHEMA_VERSION, schema_version};
+
+/// Request for `learning-db-reader` to select review words
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TriggerCommand {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub command: String,
+    #[serde(default)]
+    pub min_words: Option<usize>,
+    #[serde(default)]
+    pub max_words: Option<usize>,
+}
+
+/// A word picked from `issue_words` for review
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectedWord {
+    pub id: i64,
+    pub word: String,
+    pub issue_type: String,
+    pub description_en: Option<String>,
+    pub description_zh: Option<String>,
+    pub difficulty_level: i64,
+    pub context: Option<String>,
+}
+
+/// Words selected by `learning-db-reader`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordSelectionOutput {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub words: Vec<String>,
+    pub word_details: Vec<SelectedWord>,
+    pub session_id: String,
+    pub total_selected: usize,
+}
+
+/// Result reported by `learning-db-writer` after storing a turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageResult {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub success: bool,
+    pub issues_stored: usize,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl StorageResult {
+    pub fn ok() -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            success: true,
+            issues_stored: 0,
+            error: None,
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! # Dora Messages
+//!
+//! Typed payloads exchanged between colang dora nodes.
+//!
+//! Every rust node (ASR, teacher, TTS, DB reader/writer, session nodes) and
+//! `dora-bridge` serialize their inter-node JSON through these types, so a
+//! schema change breaks the build instead of failing at runtime mid-session.
+//!
+//! ## Versioning
+//!
+//! Each payload carries a `version` field. Senders always write
+//! [`SCHEMA_VERSION`]; receivers accept payloads without the field (treated as
+//! the current version) and reject payloads from a newer schema through
+//! [`decode`].
+//!
+//! Turn/session ids and stage timestamps for latency metrics travel in dora
+//! metadata rather than in the payloads; [`turn`] and [`timing`] define their
+//! keys.
+//!
+//! With the `arrow` feature, [`audio_arrow`] decodes ASR audio straight from
+//! the arrow arrays sent by `mofa-mic-input`. With the `metadata` feature,
+//! `turn::string_param` reads the ids from the dora metadata of an input.
+//!
+//! ```text
+//! mofa-mic-input ──AudioInput──▶ doubao-asr ──AsrOutput──▶ english-teacher
+//!                                                     │
+//!                           ComprehensiveResponse ◀───┘
+//!                                  │
+//!               ┌──────────────────┼───────────────────┐
+//!               ▼                  ▼                   ▼
+//!          doubao-tts      learning-db-writer    mofa-text-input
+//! ```
+
+pub mod asr;
+#[cfg(feature = "arrow")]
+pub mod audio_arrow;
+pub mod learning;
+pub mod pcm;
+pub mod session;
+pub mod teacher;
+pub mod timing;
+pub mod tts;
+pub mod turn;
+
+pub use asr::{AsrOutput, AudioInput, WordTiming};
+pub use learning::{SelectedWord, StorageResult, TriggerCommand, WordSelectionOutput};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+pub use session::{ContextualInput, ControlCommand, INTERRUPT, REPLAY, SessionStatus, TopicInfo};
+pub use teacher::{ComprehensiveResponse, ReplySegment, TextIssue};
+pub use timing::Stage;
+use thiserror::Error;
+pub use tts::{AudioMetadata, TextInput};
+
+/// Current version of the message schema
+///
+/// Bump this whenever a payload changes in a way older receivers cannot read.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Serde default for the `version` field of every payload
+pub(crate) fn schema_version() -> u32 {
+    SCHEMA_VERSION
+}
+
+/// A payload that travels between dora nodes
+pub trait Message: Serialize + DeserializeOwned {
+    /// Schema version the payload was written with
+    fn version(&self) -> u32;
+}
+
+/// Errors that can occur when decoding a payload
+#[derive(Error, Debug)]
+pub enum DecodeError {
+    #[error("Invalid payload: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unsupported schema version {found} (this build understands up to {supported})")]
+    UnsupportedVersion { found: u32, supported: u32 },
+
+    #[error("Unsupported audio data: {0}")]
+    UnsupportedAudio(String),
+}
+
+/// Decode a payload from raw bytes, rejecting newer schema versions
+pub fn decode<T: Message>(bytes: &[u8]) -> Result<T, DecodeError> {
+    let message: T = serde_json::from_slice(bytes)?;
+    if message.version() > SCHEMA_VERSION {
+        return Err(DecodeError::UnsupportedVersion {
+            found: message.version(),
+            supported: SCHEMA_VERSION,
+        });
+    }
+    Ok(message)
+}
+
+/// Encode a payload as a JSON string
+pub fn encode<T: Message>(message: &T) -> Result<String, serde_json::Error> {
+    serde_json::to_string(message)
+}
+
+macro_rules! impl_message {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Message for $ty {
+                fn version(&self) -> u32 {
+                    self.version
+                }
+            }
+        )*
+    };
+}
+
+impl_message!(
+    AudioInput,
+    AsrOutput,
+    ComprehensiveResponse,
+    ReplySegment,
+    TextInput,
+    AudioMetadata,
+    ControlCommand,
+    SessionStatus,
+    TopicInfo,
+    ContextualInput,
+    TriggerCommand,
+    WordSelectionOutput,
+    StorageResult,
+);
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+
+    use super::*;
+
+    fn round_trip<T: Message + PartialEq + Debug>(message: T) {
+        let encoded = encode(&message).unwrap();
+        let decoded: T = decode(encoded.as_bytes()).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.version(), SCHEMA_VERSION);
+    }
+
+    fn sample_issue() -> TextIssue {
+        TextIssue {
+            issue_type: "grammar".to_string(),
+            original: "I goes".to_string(),
+            suggested: "I go".to_string(),
+            description_en: "Subject-verb agreement".to_string(),
+            description_zh: "主谓一致".to_string(),
+            severity: "medium".to_string(),
+            start_position: Some(0),
+            end_position: Some(6),
+        }
+    }
+
+    #[test]
+    fn test_asr_round_trip() {
+        round_trip(AudioInput::new(vec![1, 2, 3], 16000, "wav"));
+        round_trip(AsrOutput {
+            version: SCHEMA_VERSION,
+            text: "hello world".to_string(),
+            confidence: 0.9,
+            words: vec![WordTiming {
+                word: "hello".to_string(),
+                start_time: 0.0,
+                end_time: 0.4,
+                confidence: 0.95,
+            }],
+            session_id: Some("s1".to_string()),
+            is_final: false,
+            utterance_id: Some("u1".to_string()),
+        });
+    }
+
+    #[test]
+    fn test_teacher_round_trip() {
+        round_trip(ComprehensiveResponse {
+            version: SCHEMA_VERSION,
+            session_id: "s1".to_string(),
+            use_lang: "mix".to_string(),
+            original_en: "I goes to school".to_string(),
+            original_zh: "我去上学".to_string(),
+            reply_en: "Nice!".to_string(),
+            reply_zh: "很好！".to_string(),
+            issues: vec![sample_issue()],
+            timestamp: 1_700_000_000,
+        });
+        round_trip(ReplySegment {
+            version: SCHEMA_VERSION,
+            session_id: "s1".to_string(),
+            question_id: "q1".to_string(),
+            index: 0,
+            text: "Nice!".to_string(),
+            is_final: false,
+        });
+    }
+
+    #[test]
+    fn test_tts_round_trip() {
+        round_trip(TextInput::new("Hello there"));
+        round_trip(AudioMetadata {
+            version: SCHEMA_VERSION,
+            duration_ms: 1200,
+            format: "mp3".to_string(),
+            sample_rate: 24000,
+            bytes: 4096,
+        });
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        round_trip(ControlCommand::new("start").with_session(Some("s1".to_string())));
+        round_trip(SessionStatus::new("session_started", Some("s1"), "started"));
+        round_trip(TopicInfo {
+            version: SCHEMA_VERSION,
+            session_id: "s1".to_string(),
+            topic: "Travel".to_string(),
+            target_words: vec!["airport".to_string()],
+        });
+        round_trip(ContextualInput {
+            version: SCHEMA_VERSION,
+            user_text: "hi".to_string(),
+            session_id: "s1".to_string(),
+            topic: None,
+            target_words: None,
+            is_first_in_session: true,
+        });
+    }
+
+    #[test]
+    fn test_interrupt_targets_question() {
+        let cmd = ControlCommand::interrupt(Some("q1"));
+        round_trip(cmd.clone());
+        assert!(cmd.is_interrupt());
+        assert_eq!(cmd.question_id(), Some("q1"));
+        assert!(cmd.interrupts(Some("q1")));
+        assert!(!cmd.interrupts(Some("q2")));
+        assert!(!cmd.interrupts(None));
+
+        let any = ControlCommand::interrupt(None);
+        assert!(any.interrupts(Some("q2")));
+        assert!(!ControlCommand::new("stop").interrupts(None));
+    }
+
+    #[test]
+    fn test_replay_is_not_interrupt() {
+        let cmd = ControlCommand::replay("q1");
+        round_trip(cmd.clone());
+        assert!(cmd.is_replay());
+        assert_eq!(cmd.question_id(), Some("q1"));
+        assert!(!cmd.interrupts(None));
+    }
+
+    #[test]
+    fn test_learning_round_trip() {
+        round_trip(TriggerCommand {
+            version: SCHEMA_VERSION,
+            command: "select".to_string(),
+            min_words: Some(5),
+            max_words: None,
+        });
+        round_trip(WordSelectionOutput {
+            version: SCHEMA_VERSION,
+            words: vec!["schedule".to_string()],
+            word_details: vec![SelectedWord {
+                id: 1,
+                word: "schedule".to_string(),
+                issue_type: "pronunciation".to_string(),
+                description_en: None,
+                description_zh: Some("日程".to_string()),
+                difficulty_level: 3,
+                context: None,
+            }],
+            session_id: "s1".to_string(),
+            total_selected: 1,
+        });
+        round_trip(StorageResult::ok());
+    }
+
+    #[test]
+    fn test_missing_version_defaults_to_current() {
+        let json = r#"{"text":"hello","confidence":1.0,"session_id":null}"#;
+        let output: AsrOutput = decode(json.as_bytes()).unwrap();
+        assert_eq!(output.version, SCHEMA_VERSION);
+        assert!(output.words.is_empty());
+        assert!(output.is_final);
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let json = format!(
+            r#"{{"version":{},"text":"hi","session_id":null}}"#,
+            SCHEMA_VERSION + 1
+        );
+        let err = decode::<TextInput>(json.as_bytes()).unwrap_err();
+        assert!(matches!(err, DecodeError::UnsupportedVersion { .. }));
+    }
+
+    #[test]
+    fn test_issue_requires_bilingual_descriptions() {
+        // The old single-`description` shape must no longer parse silently
+        let json = r#"{"type":"grammar","original":"a","suggested":"b","description":"x","severity":"low"}"#;
+        assert!(serde_json::from_str::<TextIssue>(json).is_err());
+    }
+}
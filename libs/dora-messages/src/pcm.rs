@@ -0,0 +1,138 @@
+//! PCM helpers for audio captured by `mofa-mic-input`
+//!
+//! The mic bridge streams raw f32 samples; speech APIs want an encoded file.
+//! ASR nodes wrap the samples into a 16-bit PCM WAV before calling out.
+
+use crate::AudioInput;
+
+/// Sample rate assumed when the sender did not attach one
+pub const DEFAULT_SAMPLE_RATE: u32 = 16000;
+
+/// Encode f32 samples (-1.0..=1.0, interleaved) as a 16-bit PCM WAV file
+pub fn encode_wav(samples: &[f32], sample_rate: u32, channels: u16) -> Vec<u8> {
+    let channels = channels.max(1);
+    let bits_per_sample: u16 = 16;
+    let block_align = channels * bits_per_sample / 8;
+    let byte_rate = sample_rate * block_align as u32;
+    let data_len = (samples.len() * 2) as u32;
+
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+
+    // fmt chunk
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&bits_per_sample.to_le_bytes());
+
+    // data chunk
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        wav.extend_from_slice(&value.to_le_bytes());
+    }
+
+    wav
+}
+
+/// Decode a WAV file into f32 samples, returning `(samples, sample_rate, channels)`
+///
+/// Supports 16-bit integer and 32-bit float PCM; other chunks are skipped.
+pub fn decode_wav(bytes: &[u8]) -> Result<(Vec<f32>, u32, u16), String> {
+    if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+        return Err("not a RIFF/WAVE file".to_string());
+    }
+
+    let mut format = None;
+    let mut pos = 12;
+    while pos + 8 <= bytes.len() {
+        let id = &bytes[pos..pos + 4];
+        let len = u32::from_le_bytes(bytes[pos + 4..pos + 8].try_into().unwrap()) as usize;
+        let body = &bytes[pos + 8..(pos + 8 + len).min(bytes.len())];
+
+        if id == b"fmt " {
+            if body.len() < 16 {
+                return Err("fmt chunk too short".to_string());
+            }
+            let tag = u16::from_le_bytes([body[0], body[1]]);
+            let channels = u16::from_le_bytes([body[2], body[3]]);
+            let sample_rate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+            let bits = u16::from_le_bytes([body[14], body[15]]);
+            format = Some((tag, channels, sample_rate, bits));
+        } else if id == b"data" {
+            let Some((tag, channels, sample_rate, bits)) = format else {
+                return Err("data chunk before fmt chunk".to_string());
+            };
+            let samples = match (tag, bits) {
+                (1, 16) => body
+                    .chunks_exact(2)
+                    .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+                    .collect(),
+                (3, 32) => body
+                    .chunks_exact(4)
+                    .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                    .collect(),
+                _ => {
+                    return Err(format!(
+                        "unsupported WAV format {} with {} bits per sample",
+                        tag, bits
+                    ));
+                }
+            };
+            return Ok((samples, sample_rate, channels.max(1)));
+        }
+
+        // Chunks are padded to an even length
+        pos += 8 + len + (len & 1);
+    }
+
+    Err("missing data chunk".to_string())
+}
+
+impl AudioInput {
+    /// Build a WAV `AudioInput` from raw f32 samples
+    pub fn from_pcm(samples: &[f32], sample_rate: u32, channels: u16) -> Self {
+        Self::new(
+            encode_wav(samples, sample_rate, channels),
+            sample_rate,
+            "wav",
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wav_header() {
+        let wav = encode_wav(&[0.0, 1.0, -1.0], 16000, 1);
+        assert_eq!(wav.len(), 44 + 6);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 16000);
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 6);
+        assert_eq!(i16::from_le_bytes([wav[46], wav[47]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([wav[48], wav[49]]), -i16::MAX);
+    }
+
+    #[test]
+    fn test_wav_round_trip() {
+        let wav = encode_wav(&[0.0, 0.5, -1.0, 1.0], 24000, 2);
+        let (samples, sample_rate, channels) = decode_wav(&wav).unwrap();
+        assert_eq!((sample_rate, channels), (24000, 2));
+        assert_eq!(samples.len(), 4);
+        assert!((samples[1] - 0.5).abs() < 1e-3);
+        assert_eq!(samples[2], -1.0);
+
+        assert!(decode_wav(b"RIFF\0\0\0\0WAVE").is_err());
+        assert!(decode_wav(&wav[..20]).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+//! Session control and context payloads
+
+use serde::{Deserialize, Serialize};
+
+use crate::{SCHEMA_VERSION, schema_version};
+
+/// Control command broadcast by `session-controller`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ControlCommand {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    /// "start" | "stop" | "reset" | "pause" | "resume" | "ready" | "interrupt" | "replay"
+    pub command: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+}
+
+impl ControlCommand {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            command: command.into(),
+            session_id: None,
+            data: None,
+        }
+    }
+
+    pub fn with_session(mut self, session_id: Option<String>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    pub fn with_data(mut self, data: serde_json::Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Barge-in: cancel the teacher turn `question_id` (or whatever is in
+    /// flight when `None`)
+    pub fn interrupt(question_id: Option<&str>) -> Self {
+        let cmd = Self::new(INTERRUPT);
+        match question_id {
+            Some(qid) => cmd.with_data(serde_json::json!({ "question_id": qid })),
+            None => cmd,
+        }
+    }
+
+    pub fn is_interrupt(&self) -> bool {
+        self.command == INTERRUPT
+    }
+
+    /// Replay the cached TTS audio of the teacher turn `question_id`
+    pub fn replay(question_id: &str) -> Self {
+        Self::new(REPLAY).with_data(serde_json::json!({ "question_id": question_id }))
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.command == REPLAY
+    }
+
+    /// `question_id` carried in `data`, if any
+    pub fn question_id(&self) -> Option<&str> {
+        self.data.as_ref()?.get("question_id")?.as_str()
+    }
+
+    /// Whether an interrupt applies to the turn `question_id`
+    pub fn interrupts(&self, question_id: Option<&str>) -> bool {
+        self.is_interrupt()
+            && match (self.question_id(), question_id) {
+                (None, _) => true,
+                (Some(target), Some(qid)) => target == qid,
+                (Some(_), None) => false,
+            }
+    }
+}
+
+/// Control command name for barge-in
+pub const INTERRUPT: &str = "interrupt";
+
+/// Control command name for replaying a cached teacher reply
+pub const REPLAY: &str = "replay";
+
+/// Status report from `session-controller`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionStatus {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub status: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    pub message: String,
+}
+
+impl SessionStatus {
+    pub fn new(status: &str, session_id: Option<&str>, message: &str) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            status: status.to_string(),
+            session_id: session_id.map(String::from),
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Conversation topic chosen for a session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopicInfo {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub session_id: String,
+    pub topic: String,
+    #[serde(default)]
+    pub target_words: Vec<String>,
+}
+
+/// User text enriched with session context by `session-context`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContextualInput {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub user_text: String,
+    pub session_id: String,
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub target_words: Option<Vec<String>>,
+    pub is_first_in_session: bool,
+}
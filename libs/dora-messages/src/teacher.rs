@@ -0,0 +1,70 @@
+//! English teacher payloads
+//!
+//! `english-teacher/reply_segment` → TTS and UI bridges, one sentence at a
+//! time while the reply is generated.
+//! `english-teacher/json_data` → DB writer and UI bridges, once the issue
+//! analysis for the turn is done.
+
+use serde::{Deserialize, Serialize};
+
+use crate::schema_version;
+
+/// Reply and analysis produced by the teacher for one user turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComprehensiveResponse {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub session_id: String,
+    /// Language of the user text: "en" | "zh" | "mix"
+    pub use_lang: String,
+    /// User text in English (translated if needed)
+    pub original_en: String,
+    /// User text in Chinese (translated if needed)
+    pub original_zh: String,
+    /// Teacher reply in English
+    pub reply_en: String,
+    /// Teacher reply in Chinese
+    pub reply_zh: String,
+    /// Grammar / word choice issues in the user text
+    #[serde(default)]
+    pub issues: Vec<TextIssue>,
+    /// Unix timestamp in seconds
+    pub timestamp: i64,
+}
+
+/// One sentence of a teacher reply, streamed as soon as it is generated
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplySegment {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub session_id: String,
+    /// Teacher turn the segment belongs to
+    pub question_id: String,
+    /// 0-based position in the reply
+    pub index: u32,
+    pub text: String,
+    /// Last segment of the reply (its text may be empty)
+    #[serde(default)]
+    pub is_final: bool,
+}
+
+/// A grammar, word choice or phrasing issue in user text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextIssue {
+    /// "grammar" | "word_choice" | "suggestion"
+    #[serde(rename = "type")]
+    pub issue_type: String,
+    pub original: String,
+    pub suggested: String,
+    pub description_en: String,
+    pub description_zh: String,
+    /// "low" | "medium" | "high"
+    pub severity: String,
+
+    /// 0-based character offset where the issue starts
+    #[serde(default)]
+    pub start_position: Option<i32>,
+    /// 0-based character offset where the issue ends (exclusive)
+    #[serde(default)]
+    pub end_position: Option<i32>,
+}
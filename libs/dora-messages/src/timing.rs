@@ -0,0 +1,92 @@
+//! Pipeline stage timestamps
+//!
+//! Nodes stamp the time they reach a stage into the dora metadata parameters
+//! of their outputs (`t_<stage>` as an integer of unix milliseconds). Nodes
+//! forward the parameters they receive, so the audio reaching
+//! `mofa-audio-player` carries every stamp of its turn, next to its
+//! `question_id`.
+//!
+//! ```text
+//! doubao-asr       t_audio_received, t_asr_done
+//! english-teacher  t_llm_first_token, t_llm_complete (final segment)
+//! doubao-tts       t_tts_first_chunk, t_tts_complete (not on cache hits)
+//! MoFA app         t_playback_start
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A point in a voice turn, in pipeline order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Stage {
+    /// ASR received the utterance
+    AudioReceived,
+    /// ASR produced the final transcript
+    AsrDone,
+    /// The LLM streamed its first token
+    LlmFirstToken,
+    /// The LLM response is complete
+    LlmComplete,
+    /// TTS produced the first audio chunk of a segment
+    TtsFirstChunk,
+    /// TTS finished synthesizing a segment
+    TtsComplete,
+    /// The app started playing the reply
+    PlaybackStart,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 7] = [
+        Stage::AudioReceived,
+        Stage::AsrDone,
+        Stage::LlmFirstToken,
+        Stage::LlmComplete,
+        Stage::TtsFirstChunk,
+        Stage::TtsComplete,
+        Stage::PlaybackStart,
+    ];
+
+    /// Metadata parameter key
+    pub fn key(self) -> &'static str {
+        match self {
+            Stage::AudioReceived => "t_audio_received",
+            Stage::AsrDone => "t_asr_done",
+            Stage::LlmFirstToken => "t_llm_first_token",
+            Stage::LlmComplete => "t_llm_complete",
+            Stage::TtsFirstChunk => "t_tts_first_chunk",
+            Stage::TtsComplete => "t_tts_complete",
+            Stage::PlaybackStart => "t_playback_start",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stage| stage.key() == key)
+    }
+
+    /// A turn has one "first" stamp but several segments that each stamp
+    /// completion; the earliest stamp counts for first-of stages, the latest
+    /// for completion stages.
+    pub fn keeps_latest(self) -> bool {
+        matches!(self, Stage::LlmComplete | Stage::TtsComplete)
+    }
+}
+
+/// Current time as a stage stamp (unix milliseconds)
+pub fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_keys_round_trip() {
+        for stage in Stage::ALL {
+            assert_eq!(Stage::from_key(stage.key()), Some(stage));
+        }
+        assert_eq!(Stage::from_key("question_id"), None);
+    }
+}
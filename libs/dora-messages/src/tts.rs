@@ -0,0 +1,72 @@
+//! Speech synthesis payloads
+//!
+//! Also the key of the TTS audio cache, shared by the TTS node that fills it
+//! and the app that reads cached clips back.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{SCHEMA_VERSION, schema_version};
+
+/// Plain text sent to a TTS node
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextInput {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub text: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+impl TextInput {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            text: text.into(),
+            session_id: None,
+        }
+    }
+}
+
+/// Description of synthesized audio (`audio_metadata` output)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AudioMetadata {
+    #[serde(default = "schema_version")]
+    pub version: u32,
+    pub duration_ms: u64,
+    pub format: String,
+    pub sample_rate: u32,
+    pub bytes: usize,
+}
+
+/// TTS cache key: sha256 (hex) of the text and the synthesis parameters
+///
+/// Cached audio lives at `<TTS_CACHE_DIR>/<key>.mp3`.
+pub fn cache_key(text: &str, voice: &str, speech_rate: i32, resource_id: &str) -> String {
+    let mut hasher = Sha256::new();
+    // Fields are separated by \0 so that concatenations can't collide
+    for field in [resource_id, voice, &speech_rate.to_string(), text.trim()] {
+        hasher.update(field.as_bytes());
+        hasher.update([0u8]);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_key_depends_on_all_fields() {
+        let key = cache_key("Hello.", "voice", 0, "seed-tts-2.0");
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, cache_key(" Hello. ", "voice", 0, "seed-tts-2.0"));
+        assert_ne!(key, cache_key("Hello.", "other", 0, "seed-tts-2.0"));
+        assert_ne!(key, cache_key("Hello.", "voice", 10, "seed-tts-2.0"));
+        assert_ne!(key, cache_key("Hello.", "voice", 0, "seed-tts-1.0"));
+    }
+}
@@ -0,0 +1,40 @@
+//! Turn and session ids
+//!
+//! A turn is one user utterance (or typed message) and the teacher reply to
+//! it. Its id is minted where the input enters the dataflow (`mofa-mic-input`
+//! per VAD utterance, `mofa-text-input` per message) and travels in dora
+//! metadata: every node copies the parameters it receives onto its outputs,
+//! so transcripts, reply segments, TTS audio and DB rows of a turn all carry
+//! the same id.
+//!
+//! ```text
+//! mofa-mic-input ─▶ doubao-asr ─▶ english-teacher ─▶ doubao-tts ─▶ mofa-audio-player
+//!   (mints)                            │
+//!                                      └──▶ learning-db-writer
+//! ```
+//!
+//! While the learner speaks, `mofa-mic-input` also streams the utterance in
+//! chunks on `audio_stream` under the same turn id; the last chunk carries
+//! [`LAST_CHUNK`] so streaming ASR can close the request.
+//!
+//! The turn id uses the `question_id` key that the audio player, the TTS
+//! cache and interrupt/replay commands already match on. The session id is
+//! the id of the dataflow instance, shared by every node of one run.
+
+/// Metadata key of the turn id
+pub const TURN_ID: &str = "question_id";
+
+/// Metadata key of the session id
+pub const SESSION_ID: &str = "session_id";
+
+/// Metadata key marking the last `audio_stream` chunk of a turn
+pub const LAST_CHUNK: &str = "last_chunk";
+
+/// Read a string metadata parameter, e.g. [`TURN_ID`] or [`SESSION_ID`]
+#[cfg(feature = "metadata")]
+pub fn string_param(parameters: &dora_node_api::MetadataParameters, key: &str) -> Option<String> {
+    match parameters.get(key) {
+        Some(dora_node_api::Parameter::String(value)) => Some(value.clone()),
+        _ => None,
+    }
+}
@@ -0,0 +1,79 @@
+//! Feed `mofa-mic-input` shaped arrow data through the ASR input decoding
+
+use std::sync::Arc;
+
+use arrow::array::{Array, ListArray, StringArray, UInt8Array};
+use arrow::datatypes::Float32Type;
+use dora_messages::audio_arrow::audio_input_from_arrow;
+use dora_messages::{AudioInput, DecodeError};
+
+/// Build the array exactly like `MicInputBridge::run_event_loop`
+fn bridge_audio(samples: &[f32]) -> ListArray {
+    ListArray::from_iter_primitive::<Float32Type, _, _>(std::iter::once(Some(
+        samples.iter().map(|&s| Some(s)),
+    )))
+}
+
+#[test]
+fn test_bridge_float_pcm_becomes_wav() {
+    let samples: Vec<f32> = (0..1600).map(|i| (i as f32 / 1600.0) - 0.5).collect();
+    let array = bridge_audio(&samples);
+
+    let input = audio_input_from_arrow(&array, Some(16000), Some(1)).unwrap();
+
+    assert_eq!(input.format, "wav");
+    assert_eq!(input.sample_rate, 16000);
+    assert_eq!(&input.audio_data[0..4], b"RIFF");
+    assert_eq!(input.audio_data.len(), 44 + samples.len() * 2);
+}
+
+#[test]
+fn test_missing_metadata_uses_defaults() {
+    let array = bridge_audio(&[0.1, 0.2]);
+
+    let input = audio_input_from_arrow(&array, None, None).unwrap();
+
+    assert_eq!(input.sample_rate, 16000);
+    // channels field of the fmt chunk
+    assert_eq!(
+        u16::from_le_bytes([input.audio_data[22], input.audio_data[23]]),
+        1
+    );
+}
+
+#[test]
+fn test_sample_rate_from_metadata_is_kept() {
+    let array = bridge_audio(&[0.0; 480]);
+
+    let input = audio_input_from_arrow(&array, Some(48000), Some(2)).unwrap();
+
+    assert_eq!(input.sample_rate, 48000);
+    let wav = &input.audio_data;
+    assert_eq!(u16::from_le_bytes([wav[22], wav[23]]), 2);
+    assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 48000);
+}
+
+#[test]
+fn test_json_audio_input_still_accepted() {
+    let json = dora_messages::encode(&AudioInput::new(vec![1, 2, 3], 8000, "mp3")).unwrap();
+
+    let from_string =
+        audio_input_from_arrow(&StringArray::from(vec![json.as_str()]), None, None).unwrap();
+    assert_eq!(from_string.format, "mp3");
+    assert_eq!(from_string.audio_data, vec![1, 2, 3]);
+
+    let from_bytes =
+        audio_input_from_arrow(&UInt8Array::from(json.into_bytes()), None, None).unwrap();
+    assert_eq!(from_bytes.sample_rate, 8000);
+}
+
+#[test]
+fn test_non_float_list_is_rejected() {
+    let array = ListArray::from_iter_primitive::<arrow::datatypes::Int32Type, _, _>(
+        std::iter::once(Some(vec![Some(1), Some(2)])),
+    );
+    let array: Arc<dyn Array> = Arc::new(array);
+
+    let err = audio_input_from_arrow(array.as_ref(), Some(16000), Some(1)).unwrap_err();
+    assert!(matches!(err, DecodeError::UnsupportedAudio(_)));
+}
@@ -1,39 +1,19 @@
 // Dora Node: BigModel ASR (Automatic Speech Recognition)
 // Converts user audio to text using ZhipuAI GLM-ASR API
+// 回合 ID / 会话 ID 由 mofa-mic-input 写入 metadata, 随输出继续传递, 并填入 AsrOutput
 
-use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
-use dora_node_api::{DoraNode, Event};
+use dora_messages::audio_arrow::audio_input_from_arrow;
+use dora_messages::turn::{SESSION_ID, TURN_ID, string_param};
+use dora_messages::{AsrOutput, AudioInput, SCHEMA_VERSION, Stage, timing};
+use dora_node_api::arrow::array::StringArray;
+use dora_node_api::{DoraNode, Event, Parameter};
 use eyre::{Context, Result};
+use reqwest::Client;
 use reqwest::header;
 use reqwest::multipart::{Form, Part};
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_json::json;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct AudioInput {
-    audio_data: Vec<u8>,
-    sample_rate: u32,
-    format: String,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct AsrOutput {
-    text: String,
-    confidence: f32,
-    words: Vec<WordTiming>,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct WordTiming {
-    word: String,
-    start_time: f64,
-    end_time: f64,
-    confidence: f32,
-}
-
 #[derive(Debug, Deserialize)]
 struct BigModelResponse {
     #[allow(dead_code)]
@@ -89,56 +69,66 @@ async fn main() -> Result<()> {
                 "audio" => {
                     log::debug!("Received audio input");
 
-                    let raw_data: Vec<u8> = extract_bytes(&data)
-                        .ok_or_else(|| eyre::eyre!("Failed to get bytes from arrow data"))?;
-                    log::debug!("Received audio input ({} bytes)", raw_data.len());
-
-                    match serde_json::from_slice::<AudioInput>(&raw_data) {
-                        Ok(input) => {
-                            match perform_asr(&client, &api_key, &input).await {
-                                Ok(asr_result) => {
-                                    log::info!("ASR result: {}", asr_result.text);
-
-                                    let output_json = serde_json::to_string(&asr_result)?;
-                                    let output_array =
-                                        StringArray::from(vec![output_json.as_str()]);
-                                    node.send_output(
-                                        "text".to_string().into(),
-                                        metadata.parameters.clone(),
-                                        output_array,
-                                    )?;
-
-                                    let status = json!({
-                                        "node": "bigmodel-asr",
-                                        "status": "ok",
-                                        "text_length": asr_result.text.len(),
-                                    });
-                                    let status_array =
-                                        StringArray::from(vec![status.to_string().as_str()]);
-                                    node.send_output(
-                                        "status".to_string().into(),
-                                        metadata.parameters.clone(),
-                                        status_array,
-                                    )?;
-                                }
-                                Err(e) => {
-                                    log::error!("ASR failed: {}", e);
-
-                                    let status = json!({
-                                        "node": "bigmodel-asr",
-                                        "status": "error",
-                                        "error": e.to_string(),
-                                    });
-                                    let status_array =
-                                        StringArray::from(vec![status.to_string().as_str()]);
-                                    node.send_output(
-                                        "status".to_string().into(),
-                                        metadata.parameters.clone(),
-                                        status_array,
-                                    )?;
-                                }
+                    // mofa-mic-input 发送 Float32 PCM, 采样率/声道在 metadata 中
+                    let sample_rate = int_param(&metadata, "sample_rate").map(|v| v as u32);
+                    let channels = int_param(&metadata, "channels").map(|v| v as u16);
+                    let mut params = metadata.parameters.clone();
+                    params.insert(
+                        Stage::AudioReceived.key().to_string(),
+                        Parameter::Integer(timing::now_ms()),
+                    );
+
+                    match audio_input_from_arrow(data.0.as_ref(), sample_rate, channels) {
+                        Ok(input) => match perform_asr(&client, &api_key, &input).await {
+                            Ok(mut asr_result) => {
+                                asr_result.session_id =
+                                    string_param(&metadata.parameters, SESSION_ID);
+                                asr_result.utterance_id =
+                                    string_param(&metadata.parameters, TURN_ID);
+                                log::info!("ASR result: {}", asr_result.text);
+                                params.insert(
+                                    Stage::AsrDone.key().to_string(),
+                                    Parameter::Integer(timing::now_ms()),
+                                );
+
+                                let output_json = dora_messages::encode(&asr_result)?;
+                                let output_array = StringArray::from(vec![output_json.as_str()]);
+                                node.send_output(
+                                    "text".to_string().into(),
+                                    params.clone(),
+                                    output_array,
+                                )?;
+
+                                let status = json!({
+                                    "node": "bigmodel-asr",
+                                    "status": "ok",
+                                    "text_length": asr_result.text.len(),
+                                });
+                                let status_array =
+                                    StringArray::from(vec![status.to_string().as_str()]);
+                                node.send_output(
+                                    "status".to_string().into(),
+                                    metadata.parameters.clone(),
+                                    status_array,
+                                )?;
                             }
-                        }
+                            Err(e) => {
+                                log::error!("ASR failed: {}", e);
+
+                                let status = json!({
+                                    "node": "bigmodel-asr",
+                                    "status": "error",
+                                    "error": e.to_string(),
+                                });
+                                let status_array =
+                                    StringArray::from(vec![status.to_string().as_str()]);
+                                node.send_output(
+                                    "status".to_string().into(),
+                                    metadata.parameters.clone(),
+                                    status_array,
+                                )?;
+                            }
+                        },
                         Err(e) => {
                             log::error!("Failed to parse audio input: {}", e);
                         }
@@ -188,9 +178,7 @@ async fn perform_asr(client: &Client, api_key: &str, input: &AudioInput) -> Resu
         .file_name(file_name.to_string())
         .mime_str(mime_type)?;
 
-    let form = Form::new()
-        .text("model", "glm-asr")
-        .part("file", file_part);
+    let form = Form::new().text("model", "glm-asr").part("file", file_part);
 
     let response = client
         .post(API_URL)
@@ -220,57 +208,21 @@ async fn perform_asr(client: &Client, api_key: &str, input: &AudioInput) -> Resu
         .unwrap_or_default();
 
     Ok(AsrOutput {
+        version: SCHEMA_VERSION,
         text,
         confidence: 1.0, // BigModel doesn't provide confidence scores
         words: vec![],   // BigModel doesn't provide word-level timing
         session_id: input.session_id.clone(),
+        is_final: true,
+        utterance_id: None,
     })
 }
 
-/// Extract bytes from ArrowData (handles both UInt8Array and StringArray)
-fn extract_bytes(data: &dora_node_api::ArrowData) -> Option<Vec<u8>> {
-    use dora_node_api::arrow::array::ListArray;
-    use dora_node_api::arrow::datatypes::DataType;
-
-    let array = &data.0;
-    match array.data_type() {
-        DataType::UInt8 => {
-            let arr = array.as_any().downcast_ref::<UInt8Array>()?;
-            Some(arr.values().to_vec())
-        }
-        DataType::Utf8 => {
-            let arr = array.as_any().downcast_ref::<StringArray>()?;
-            if arr.len() > 0 {
-                Some(arr.value(0).as_bytes().to_vec())
-            } else {
-                None
-            }
-        }
-        DataType::List(_) => {
-            let list_array = array.as_any().downcast_ref::<ListArray>()?;
-            if list_array.len() > 0 {
-                let values = list_array.values();
-                if let Some(float_array) = values
-                    .as_any()
-                    .downcast_ref::<dora_node_api::arrow::array::Float32Array>()
-                {
-                    let bytes: Vec<u8> = float_array
-                        .values()
-                        .iter()
-                        .flat_map(|f| f.to_le_bytes())
-                        .collect();
-                    Some(bytes)
-                } else {
-                    log::warn!("List contains non-Float32 data");
-                    None
-                }
-            } else {
-                None
-            }
-        }
-        _ => {
-            log::warn!("Unsupported data type: {:?}", array.data_type());
-            None
-        }
+/// Read an integer metadata parameter (e.g. `sample_rate`, `channels`)
+fn int_param(metadata: &dora_node_api::Metadata, key: &str) -> Option<i64> {
+    match metadata.parameters.get(key) {
+        Some(Parameter::Integer(value)) => Some(*value),
+        Some(Parameter::String(value)) => value.parse().ok(),
+        _ => None,
     }
 }
@@ -1,56 +1,15 @@
 // Dora Node: BigModel TTS (Text-to-Speech)
 // Converts AI text responses to speech using ZhipuAI GLM-TTS API
 
+use dora_messages::{
+    AudioMetadata, ComprehensiveResponse, SCHEMA_VERSION, Stage, TextInput, timing,
+};
 use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
 use dora_node_api::{ArrowData, DoraNode, Event};
 use eyre::{Context, Result};
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-/// Comprehensive response format (from english-teacher/json_data)
-#[derive(Debug, Serialize, Deserialize)]
-struct ComprehensiveResponse {
-    session_id: String,
-    use_lang: String,
-    original_en: String,
-    original_zh: String,
-    reply_en: String,
-    reply_zh: String,
-    issues: Vec<TextIssue>,
-    timestamp: i64,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct TextIssue {
-    #[serde(rename = "type")]
-    issue_type: String,
-    original: String,
-    suggested: String,
-    description_en: String,
-    description_zh: String,
-    severity: String,
-    #[serde(default)]
-    start_position: Option<i32>,
-    #[serde(default)]
-    end_position: Option<i32>,
-}
-
-/// Simple text input format
-#[derive(Debug, Serialize, Deserialize)]
-struct TextInput {
-    text: String,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct AudioMetadata {
-    duration_ms: u64,
-    format: String,
-    sample_rate: u32,
-    bytes: usize,
-}
-
 const API_URL: &str = "https://open.bigmodel.cn/api/paas/v4/audio/speech";
 
 #[tokio::main]
@@ -94,11 +53,10 @@ async fn main() -> Result<()> {
                         log::debug!("Received text input");
 
                         let text_to_convert = if let Ok(comprehensive_response) =
-                            serde_json::from_slice::<ComprehensiveResponse>(&raw_data)
+                            dora_messages::decode::<ComprehensiveResponse>(&raw_data)
                         {
                             comprehensive_response.reply_en
-                        } else if let Ok(text_input) =
-                            serde_json::from_slice::<TextInput>(&raw_data)
+                        } else if let Ok(text_input) = dora_messages::decode::<TextInput>(&raw_data)
                         {
                             text_input.text
                         } else {
@@ -125,6 +83,8 @@ async fn main() -> Result<()> {
                         {
                             Ok((audio_bytes, audio_metadata)) => {
                                 log::info!("TTS generated {} bytes", audio_bytes.len());
+                                // Whole-segment synthesis: first chunk and completion coincide
+                                let synthesized_at = timing::now_ms();
 
                                 // Convert WAV to f32 samples
                                 let (audio_samples, actual_sample_rate) =
@@ -151,6 +111,12 @@ async fn main() -> Result<()> {
                                     "sample_rate".to_string(),
                                     dora_node_api::Parameter::Integer(actual_sample_rate as i64),
                                 );
+                                for stage in [Stage::TtsFirstChunk, Stage::TtsComplete] {
+                                    output_params.insert(
+                                        stage.key().to_string(),
+                                        dora_node_api::Parameter::Integer(synthesized_at),
+                                    );
+                                }
 
                                 node.send_output(
                                     "audio_bytes".to_string().into(),
@@ -158,7 +124,7 @@ async fn main() -> Result<()> {
                                     audio_array,
                                 )?;
 
-                                let metadata_json = serde_json::to_string(&audio_metadata)?;
+                                let metadata_json = dora_messages::encode(&audio_metadata)?;
                                 let audio_metadata =
                                     StringArray::from(vec![metadata_json.as_str()]);
 
@@ -266,6 +232,7 @@ async fn perform_tts(
     let (sample_rate, duration_ms) = parse_wav_info(&audio_bytes).unwrap_or((24000, 0));
 
     let metadata = AudioMetadata {
+        version: SCHEMA_VERSION,
         duration_ms,
         format: response_format.to_string(),
         sample_rate,
@@ -327,7 +294,8 @@ fn decode_wav_to_samples(data: &[u8]) -> Result<(Vec<f32>, u32)> {
 
     while pos + 8 <= data.len() {
         let chunk_id = &data[pos..pos + 4];
-        let chunk_size = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]);
+        let chunk_size =
+            u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]);
 
         if chunk_id == b"data" {
             data_start = pos + 8;
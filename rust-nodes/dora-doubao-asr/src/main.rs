@@ -1,37 +1,32 @@
 // Dora Node: Doubao ASR (Automatic Speech Recognition)
 // Converts user audio to text using Doubao Volcanic Engine API
 // 不再直接操作数据库，由 history-db-writer 负责保存对话历史
+// 回合 ID / 会话 ID 由 mofa-mic-input 写入 metadata, 随输出继续传递, 并填入 AsrOutput
+
+mod streaming;
+
+use std::collections::{HashMap, HashSet};
 
 use base64::Engine;
-use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
-use dora_node_api::{DoraNode, Event};
+use dora_messages::audio_arrow::{audio_input_from_arrow, pcm_from_arrow};
+use dora_messages::pcm::DEFAULT_SAMPLE_RATE;
+use dora_messages::turn::{LAST_CHUNK, SESSION_ID, TURN_ID, string_param};
+use dora_messages::{AsrOutput, AudioInput, SCHEMA_VERSION, Stage, WordTiming, timing};
+use dora_node_api::arrow::array::StringArray;
+use dora_node_api::{DoraNode, Event, MetadataParameters, Parameter};
 use eyre::{Context, Result};
 use reqwest::{Client, header};
-use serde::{Deserialize, Serialize};
 use serde_json::json;
+use streaming::{StreamSession, StreamUpdate, StreamingConfig};
+use tokio::sync::mpsc;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct AudioInput {
-    audio_data: Vec<u8>,
-    sample_rate: u32,
-    format: String,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct AsrOutput {
-    text: String,
-    confidence: f32,
-    words: Vec<WordTiming>,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct WordTiming {
-    word: String,
-    start_time: f64,
-    end_time: f64,
-    confidence: f32,
+/// 流式模式下进行中的识别回合
+struct ActiveStream {
+    session: StreamSession,
+    /// 回合的 metadata (回合 ID, 会话 ID, 阶段时间戳), 随结果输出
+    params: MetadataParameters,
+    /// 已推入最后一块音频
+    ended: bool,
 }
 
 #[tokio::main]
@@ -49,6 +44,19 @@ async fn main() -> Result<()> {
 
     let language = std::env::var("LANGUAGE").unwrap_or_else(|_| "en".to_string());
 
+    // ASR_MODE=streaming 使用大模型流式识别: 用户说话时即接收 audio_stream 分块并输出 partial.
+    // 其他值使用 HTTP 一次性识别整段 audio. 数据流默认 streaming
+    let streaming_config = match std::env::var("ASR_MODE").as_deref() {
+        Ok("streaming") => Some(StreamingConfig {
+            app_id: app_id.clone(),
+            access_token: access_token.clone(),
+            resource_id: std::env::var("DOUBAO_ASR_RESOURCE_ID")
+                .unwrap_or_else(|_| "volc.bigasr.sauc.duration".to_string()),
+            language: language.clone(),
+        }),
+        _ => None,
+    };
+
     let client = Client::builder()
         .timeout(std::time::Duration::from_secs(30))
         .build()?;
@@ -56,83 +64,168 @@ async fn main() -> Result<()> {
     let (mut node, mut events) = DoraNode::init_from_env()?;
 
     log::info!(
-        "Doubao ASR node started (language: {}, cluster: {})",
+        "Doubao ASR node started (language: {}, cluster: {}, streaming: {})",
         language,
-        cluster
+        cluster,
+        streaming_config.is_some()
     );
 
-    while let Some(event) = events.recv() {
+    let (update_tx, mut update_rx) = mpsc::unbounded_channel();
+    // 进行中的流式识别, 按回合 ID
+    let mut streams: HashMap<String, ActiveStream> = HashMap::new();
+    // 已经通过 audio_stream 识别的回合, 随后到达的整段 audio 不再重复识别
+    let mut streamed_turns: HashSet<String> = HashSet::new();
+
+    loop {
+        // 等待 dora 事件, 期间转发流式识别的中间结果和最终结果
+        let event = tokio::select! {
+            event = events.recv_async() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            Some(update) = update_rx.recv() => {
+                handle_stream_update(&mut node, &mut streams, update)?;
+                continue;
+            }
+        };
+
         match event {
             Event::Input { id, data, metadata } => {
                 match id.as_str() {
                     "audio" => {
                         println!("Received audio input");
-                        // Extract raw bytes from arrow data
-                        let raw_data: Vec<u8> = extract_bytes(&data)
-                            .ok_or_else(|| eyre::eyre!("Failed to get bytes from arrow data"))?;
-                        log::debug!("Received audio input ({} bytes)", raw_data.len());
-
-                        match serde_json::from_slice::<AudioInput>(&raw_data) {
-                            Ok(input) => {
-                                match perform_asr(
-                                    &client,
-                                    &app_id,
-                                    &access_token,
-                                    &cluster,
-                                    &language,
-                                    &input,
-                                )
-                                .await
-                                {
-                                    Ok(asr_result) => {
-                                        log::info!("ASR result: {}", asr_result.text);
-
-                                        // Send output as StringArray (JSON)
-                                        // history-db-writer 会负责保存到数据库
-                                        let output_json = serde_json::to_string(&asr_result)?;
-                                        let output_array =
-                                            StringArray::from(vec![output_json.as_str()]);
-                                        node.send_output(
-                                            "text".to_string().into(),
-                                            metadata.parameters.clone(),
-                                            output_array,
-                                        )?;
-
-                                        // Send status
-                                        let status = json!({
-                                            "node": "doubao-asr",
-                                            "status": "ok",
-                                            "text_length": asr_result.text.len(),
-                                        });
-                                        let status_array =
-                                            StringArray::from(vec![status.to_string().as_str()]);
-                                        node.send_output(
-                                            "status".to_string().into(),
-                                            metadata.parameters.clone(),
-                                            status_array,
-                                        )?;
+                        // mofa-mic-input 发送 Float32 PCM, 采样率/声道在 metadata 中
+                        let sample_rate = int_param(&metadata, "sample_rate").map(|v| v as u32);
+                        let channels = int_param(&metadata, "channels").map(|v| v as u16);
+                        let session_id = string_param(&metadata.parameters, SESSION_ID);
+                        let turn_id = string_param(&metadata.parameters, TURN_ID);
+                        let mut params = metadata.parameters.clone();
+                        params.insert(
+                            Stage::AudioReceived.key().to_string(),
+                            Parameter::Integer(timing::now_ms()),
+                        );
+
+                        if streaming_config.is_some()
+                            && turn_id.as_ref().is_some_and(|id| streamed_turns.remove(id))
+                        {
+                            log::debug!("Turn already recognized from audio_stream");
+                            continue;
+                        }
+
+                        // 流式模式只处理原始 PCM, JSON 音频仍走 HTTP 接口
+                        let pcm = match &streaming_config {
+                            Some(_) => pcm_from_arrow(data.0.as_ref()).ok().flatten(),
+                            None => None,
+                        };
+
+                        match (&streaming_config, pcm) {
+                            (Some(config), Some(samples)) => {
+                                // 没有经过 audio_stream 的整段音频 (如 colang-headless), 一次推入
+                                let utterance_id =
+                                    turn_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+                                let session = StreamSession::start(
+                                    config.clone(),
+                                    session_id,
+                                    utterance_id.clone(),
+                                    update_tx.clone(),
+                                );
+                                session.push(
+                                    &samples,
+                                    sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE),
+                                    true,
+                                );
+                                streams.insert(
+                                    utterance_id,
+                                    ActiveStream {
+                                        session,
+                                        params,
+                                        ended: true,
+                                    },
+                                );
+                            }
+                            _ => {
+                                let result = match audio_input_from_arrow(
+                                    data.0.as_ref(),
+                                    sample_rate,
+                                    channels,
+                                ) {
+                                    Ok(mut input) => {
+                                        input.session_id = session_id;
+                                        perform_asr(
+                                            &client,
+                                            &app_id,
+                                            &access_token,
+                                            &cluster,
+                                            &language,
+                                            &input,
+                                        )
+                                        .await
                                     }
                                     Err(e) => {
-                                        log::error!("ASR failed: {}", e);
-
-                                        let status = json!({
-                                            "node": "doubao-asr",
-                                            "status": "error",
-                                            "error": e.to_string(),
-                                        });
-                                        let status_array =
-                                            StringArray::from(vec![status.to_string().as_str()]);
-                                        node.send_output(
-                                            "status".to_string().into(),
-                                            metadata.parameters.clone(),
-                                            status_array,
-                                        )?;
+                                        Err(eyre::eyre!("Failed to parse audio input: {}", e))
                                     }
-                                }
+                                };
+                                let result = result.map(|mut asr_result| {
+                                    asr_result.utterance_id = asr_result.utterance_id.or(turn_id);
+                                    asr_result
+                                });
+                                send_result(&mut node, &mut params, result)?;
                             }
-                            Err(e) => {
-                                log::error!("Failed to parse audio input: {}", e);
+                        }
+                    }
+                    "audio_stream" => {
+                        // HTTP 模式等待整段 audio
+                        let Some(config) = &streaming_config else {
+                            continue;
+                        };
+                        let (Some(turn_id), Some(samples)) = (
+                            string_param(&metadata.parameters, TURN_ID),
+                            pcm_from_arrow(data.0.as_ref()).ok().flatten(),
+                        ) else {
+                            log::warn!("Ignoring audio_stream chunk without turn id or PCM");
+                            continue;
+                        };
+                        let sample_rate = int_param(&metadata, "sample_rate")
+                            .map(|v| v as u32)
+                            .unwrap_or(DEFAULT_SAMPLE_RATE);
+                        let last = matches!(
+                            metadata.parameters.get(LAST_CHUNK),
+                            Some(Parameter::Bool(true))
+                        );
+
+                        // 回合的第一块音频: 建立识别连接
+                        if streamed_turns.insert(turn_id.clone()) {
+                            // 采集端重置时上一回合收不到最后一块, 按已推入的音频结束
+                            for stream in streams.values_mut().filter(|s| !s.ended) {
+                                stream.session.finish();
+                                stream.ended = true;
                             }
+
+                            let mut params = metadata.parameters.clone();
+                            params.insert(
+                                Stage::AudioReceived.key().to_string(),
+                                Parameter::Integer(timing::now_ms()),
+                            );
+                            let session = StreamSession::start(
+                                config.clone(),
+                                string_param(&metadata.parameters, SESSION_ID),
+                                turn_id.clone(),
+                                update_tx.clone(),
+                            );
+                            streams.insert(
+                                turn_id.clone(),
+                                ActiveStream {
+                                    session,
+                                    params,
+                                    ended: false,
+                                },
+                            );
+                        }
+
+                        // 识别已失败的回合不再有会话
+                        if let Some(stream) = streams.get_mut(&turn_id).filter(|s| !s.ended) {
+                            stream.session.push(&samples, sample_rate, last);
+                            stream.ended = last;
                         }
                     }
                     _ => {
@@ -154,6 +247,75 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// 输出流式识别的中间结果, 回合结束时输出最终结果
+fn handle_stream_update(
+    node: &mut DoraNode,
+    streams: &mut HashMap<String, ActiveStream>,
+    update: StreamUpdate,
+) -> Result<()> {
+    match update {
+        StreamUpdate::Partial(partial) => {
+            let stream = partial.utterance_id.as_ref().and_then(|id| streams.get(id));
+            if let Some(stream) = stream {
+                log::debug!("ASR partial: {}", partial.text);
+                send_asr_output(node, "partial", &stream.params, &partial)?;
+            }
+        }
+        StreamUpdate::Final {
+            utterance_id,
+            result,
+        } => {
+            if let Some(mut stream) = streams.remove(&utterance_id) {
+                send_result(node, &mut stream.params, result)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 输出一个回合的识别结果和状态
+fn send_result(
+    node: &mut DoraNode,
+    params: &mut MetadataParameters,
+    result: Result<AsrOutput>,
+) -> Result<()> {
+    let status = match result {
+        Ok(asr_result) => {
+            log::info!("ASR result: {}", asr_result.text);
+            params.insert(
+                Stage::AsrDone.key().to_string(),
+                Parameter::Integer(timing::now_ms()),
+            );
+
+            // Send output as StringArray (JSON)
+            // history-db-writer 会负责保存到数据库
+            // `text` 保留给现有数据流, `final` 与 `partial` 成对使用
+            send_asr_output(node, "final", params, &asr_result)?;
+            send_asr_output(node, "text", params, &asr_result)?;
+
+            json!({
+                "node": "doubao-asr",
+                "status": "ok",
+                "text_length": asr_result.text.len(),
+            })
+        }
+        Err(e) => {
+            log::error!("ASR failed: {}", e);
+
+            json!({
+                "node": "doubao-asr",
+                "status": "error",
+                "error": e.to_string(),
+            })
+        }
+    };
+
+    // Send status
+    let status_array = StringArray::from(vec![status.to_string().as_str()]);
+    node.send_output("status".to_string().into(), params.clone(), status_array)?;
+    Ok(())
+}
+
 async fn perform_asr(
     client: &Client,
     app_id: &str,
@@ -218,57 +380,38 @@ async fn perform_asr(
         .unwrap_or_default();
 
     Ok(AsrOutput {
+        version: SCHEMA_VERSION,
         text,
         confidence,
         words,
         session_id: input.session_id.clone(),
+        is_final: true,
+        utterance_id: None,
     })
 }
 
-/// Extract bytes from ArrowData (handles both UInt8Array and StringArray)
-fn extract_bytes(data: &dora_node_api::ArrowData) -> Option<Vec<u8>> {
-    use dora_node_api::arrow::array::ListArray;
-    use dora_node_api::arrow::datatypes::DataType;
+/// Send an ASR result as JSON on the given output
+fn send_asr_output(
+    node: &mut DoraNode,
+    output_id: &str,
+    parameters: &MetadataParameters,
+    output: &AsrOutput,
+) -> Result<()> {
+    let output_json = dora_messages::encode(output)?;
+    let output_array = StringArray::from(vec![output_json.as_str()]);
+    node.send_output(
+        output_id.to_string().into(),
+        parameters.clone(),
+        output_array,
+    )?;
+    Ok(())
+}
 
-    let array = &data.0;
-    match array.data_type() {
-        DataType::UInt8 => {
-            let arr = array.as_any().downcast_ref::<UInt8Array>()?;
-            Some(arr.values().to_vec())
-        }
-        DataType::Utf8 => {
-            let arr = array.as_any().downcast_ref::<StringArray>()?;
-            if arr.len() > 0 {
-                Some(arr.value(0).as_bytes().to_vec())
-            } else {
-                None
-            }
-        }
-        DataType::List(_) => {
-            let list_array = array.as_any().downcast_ref::<ListArray>()?;
-            if list_array.len() > 0 {
-                let values = list_array.values();
-                if let Some(float_array) = values
-                    .as_any()
-                    .downcast_ref::<dora_node_api::arrow::array::Float32Array>()
-                {
-                    let bytes: Vec<u8> = float_array
-                        .values()
-                        .iter()
-                        .flat_map(|f| f.to_le_bytes())
-                        .collect();
-                    Some(bytes)
-                } else {
-                    log::warn!("List contains non-Float32 data");
-                    None
-                }
-            } else {
-                None
-            }
-        }
-        _ => {
-            log::warn!("Unsupported data type: {:?}", array.data_type());
-            None
-        }
+/// Read an integer metadata parameter (e.g. `sample_rate`, `channels`)
+fn int_param(metadata: &dora_node_api::Metadata, key: &str) -> Option<i64> {
+    match metadata.parameters.get(key) {
+        Some(Parameter::Integer(value)) => Some(*value),
+        Some(Parameter::String(value)) => value.parse().ok(),
+        _ => None,
     }
 }
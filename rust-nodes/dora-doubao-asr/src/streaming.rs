@@ -0,0 +1,436 @@
+// 流式语音识别 - 火山引擎大模型流式 ASR (sauc/bigmodel WebSocket 协议)
+//
+// 帧格式: 4 字节头 + [4 字节序号] + 4 字节负载长度 + 负载
+//   byte0: 协议版本(高4位) | 头长度/4(低4位)   = 0x11
+//   byte1: 消息类型(高4位) | 标志位(低4位)
+//   byte2: 序列化方式(高4位) | 压缩方式(低4位)
+//   byte3: 保留
+//
+// 客户端先发送 full client request (JSON 参数), 再随用户说话分包发送 PCM
+// 音频, 最后一包带 LAST 标志. 服务端对每包返回当前完整识别结果, 最后一包的
+// 响应即最终结果.
+
+use dora_messages::{AsrOutput, SCHEMA_VERSION, WordTiming};
+use eyre::{Result, bail};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+pub const STREAMING_URL: &str = "wss://openspeech.bytedance.com/api/v3/sauc/bigmodel";
+
+/// 服务端要求 16kHz 单声道 16bit PCM
+pub const TARGET_SAMPLE_RATE: u32 = 16000;
+/// 每个音频包的时长 (ms), 官方建议 100-200ms
+const PACKET_MS: usize = 200;
+
+const PROTOCOL_HEADER: u8 = 0x11; // version 1, header size 1 (4 bytes)
+
+// 消息类型
+pub const MSG_FULL_CLIENT_REQUEST: u8 = 0b0001;
+pub const MSG_AUDIO_ONLY: u8 = 0b0010;
+pub const MSG_FULL_SERVER_RESPONSE: u8 = 0b1001;
+pub const MSG_ERROR: u8 = 0b1111;
+
+// 标志位
+pub const FLAG_NONE: u8 = 0b0000;
+pub const FLAG_SEQUENCE: u8 = 0b0001;
+pub const FLAG_LAST: u8 = 0b0010;
+
+// 序列化 / 压缩
+const SERIALIZATION_NONE: u8 = 0x00;
+const SERIALIZATION_JSON: u8 = 0x10;
+const NO_COMPRESSION: u8 = 0x00;
+
+/// 流式 ASR 连接参数
+#[derive(Debug, Clone)]
+pub struct StreamingConfig {
+    pub app_id: String,
+    pub access_token: String,
+    pub resource_id: String,
+    pub language: String,
+}
+
+/// 服务端消息
+#[derive(Debug, PartialEq)]
+pub enum ServerMessage {
+    /// 识别结果, `last` 表示最终结果
+    Response {
+        last: bool,
+        payload: Value,
+    },
+    Error {
+        code: u32,
+        message: String,
+    },
+}
+
+/// 构造客户端帧 (不带序号)
+pub fn build_frame(msg_type: u8, flags: u8, json_payload: bool, payload: &[u8]) -> Vec<u8> {
+    let serialization = if json_payload {
+        SERIALIZATION_JSON
+    } else {
+        SERIALIZATION_NONE
+    };
+
+    let mut frame = Vec::with_capacity(8 + payload.len());
+    frame.push(PROTOCOL_HEADER);
+    frame.push((msg_type << 4) | flags);
+    frame.push(serialization | NO_COMPRESSION);
+    frame.push(0x00);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+/// 解析服务端帧
+pub fn parse_frame(data: &[u8]) -> Result<ServerMessage> {
+    if data.len() < 4 {
+        bail!("Frame too short: {} bytes", data.len());
+    }
+
+    let header_len = ((data[0] & 0x0F) as usize) * 4;
+    let msg_type = data[1] >> 4;
+    let flags = data[1] & 0x0F;
+    let compression = data[2] & 0x0F;
+    let mut offset = header_len;
+
+    let read_u32 = |offset: usize| -> Result<u32> {
+        let bytes = data
+            .get(offset..offset + 4)
+            .ok_or_else(|| eyre::eyre!("Truncated frame at offset {}", offset))?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    };
+
+    match msg_type {
+        MSG_FULL_SERVER_RESPONSE => {
+            if flags & FLAG_SEQUENCE != 0 {
+                offset += 4;
+            }
+            let size = read_u32(offset)? as usize;
+            offset += 4;
+            let payload = data
+                .get(offset..offset + size)
+                .ok_or_else(|| eyre::eyre!("Truncated payload ({} bytes expected)", size))?;
+            if compression != NO_COMPRESSION {
+                bail!("Compressed responses are not supported");
+            }
+            let payload = if payload.is_empty() {
+                Value::Null
+            } else {
+                serde_json::from_slice(payload)?
+            };
+            Ok(ServerMessage::Response {
+                last: flags & FLAG_LAST != 0,
+                payload,
+            })
+        }
+        MSG_ERROR => {
+            let code = read_u32(offset)?;
+            let size = read_u32(offset + 4)? as usize;
+            let message = data
+                .get(offset + 8..offset + 8 + size)
+                .map(|m| String::from_utf8_lossy(m).to_string())
+                .unwrap_or_default();
+            Ok(ServerMessage::Error { code, message })
+        }
+        other => bail!("Unexpected message type: {:#x}", other),
+    }
+}
+
+/// f32 采样转换为 16kHz 16bit 小端 PCM (线性插值重采样)
+pub fn to_pcm16(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let resampled: Vec<f32> = if sample_rate == TARGET_SAMPLE_RATE || sample_rate == 0 {
+        samples.to_vec()
+    } else {
+        let ratio = sample_rate as f64 / TARGET_SAMPLE_RATE as f64;
+        let out_len = (samples.len() as f64 / ratio) as usize;
+        (0..out_len)
+            .map(|i| {
+                let pos = i as f64 * ratio;
+                let idx = pos as usize;
+                let frac = (pos - idx as f64) as f32;
+                let a = samples[idx.min(samples.len() - 1)];
+                let b = samples[(idx + 1).min(samples.len() - 1)];
+                a + (b - a) * frac
+            })
+            .collect()
+    };
+
+    resampled
+        .iter()
+        .flat_map(|s| ((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes())
+        .collect()
+}
+
+/// 从识别结果 JSON 中提取文本和词级时间戳 (服务端时间单位为 ms)
+pub fn parse_result(payload: &Value) -> (String, Vec<WordTiming>) {
+    let result = &payload["result"];
+    let text = result["text"].as_str().unwrap_or("").to_string();
+
+    let words = result["utterances"]
+        .as_array()
+        .map(|utterances| {
+            utterances
+                .iter()
+                .filter_map(|u| u["words"].as_array())
+                .flatten()
+                .filter_map(|w| {
+                    Some(WordTiming {
+                        word: w["text"].as_str()?.to_string(),
+                        start_time: w["start_time"].as_f64()? / 1000.0,
+                        end_time: w["end_time"].as_f64()? / 1000.0,
+                        confidence: w["confidence"].as_f64().unwrap_or(1.0) as f32,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    (text, words)
+}
+
+/// 流式识别会话的输出
+#[derive(Debug)]
+pub enum StreamUpdate {
+    /// 中间结果 (is_final: false)
+    Partial(AsrOutput),
+    /// 会话结束, 带最终结果或错误
+    Final {
+        utterance_id: String,
+        result: Result<AsrOutput>,
+    },
+}
+
+/// 一个回合的流式识别会话
+///
+/// 连接在后台任务中运行: 音频随说话分块推入, `last` 结束本回合;
+/// 中间结果和最终结果通过 `updates` 返回. 会话被丢弃时按已推入的音频结束.
+pub struct StreamSession {
+    audio_tx: mpsc::UnboundedSender<(Vec<u8>, bool)>,
+}
+
+impl StreamSession {
+    pub fn start(
+        config: StreamingConfig,
+        session_id: Option<String>,
+        utterance_id: String,
+        updates: mpsc::UnboundedSender<StreamUpdate>,
+    ) -> Self {
+        let (audio_tx, audio_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let result = recognize(&config, session_id, &utterance_id, audio_rx, &updates).await;
+            let _ = updates.send(StreamUpdate::Final {
+                utterance_id,
+                result,
+            });
+        });
+        Self { audio_tx }
+    }
+
+    /// 推入一块音频, `last` 表示本回合音频结束
+    pub fn push(&self, samples: &[f32], sample_rate: u32, last: bool) {
+        let _ = self.audio_tx.send((to_pcm16(samples, sample_rate), last));
+    }
+
+    /// 按已推入的音频结束本回合
+    pub fn finish(&self) {
+        let _ = self.audio_tx.send((Vec::new(), true));
+    }
+}
+
+/// 流式识别一个回合, 音频来自 `audio_rx`
+async fn recognize(
+    config: &StreamingConfig,
+    session_id: Option<String>,
+    utterance_id: &str,
+    mut audio_rx: mpsc::UnboundedReceiver<(Vec<u8>, bool)>,
+    updates: &mpsc::UnboundedSender<StreamUpdate>,
+) -> Result<AsrOutput> {
+    let request = tokio_tungstenite::tungstenite::http::Request::builder()
+        .uri(STREAMING_URL)
+        .header("Host", "openspeech.bytedance.com")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header(
+            "Sec-WebSocket-Key",
+            tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+        )
+        .header("X-Api-App-Key", &config.app_id)
+        .header("X-Api-Access-Key", &config.access_token)
+        .header("X-Api-Resource-Id", &config.resource_id)
+        .header("X-Api-Connect-Id", utterance_id)
+        .body(())?;
+
+    let (ws_stream, response) = connect_async(request).await?;
+    if let Some(logid) = response.headers().get("X-Tt-Logid") {
+        log::info!("X-Tt-Logid: {:?}", logid);
+    }
+    let (mut write, mut read) = ws_stream.split();
+
+    // 1. 发送识别参数
+    let params = json!({
+        "user": { "uid": "user_001" },
+        "audio": {
+            "format": "pcm",
+            "codec": "raw",
+            "rate": TARGET_SAMPLE_RATE,
+            "bits": 16,
+            "channel": 1,
+            "language": config.language,
+        },
+        "request": {
+            "model_name": "bigmodel",
+            "enable_punc": true,
+            "show_utterances": true,
+            "result_type": "full",
+        }
+    });
+    let frame = build_frame(
+        MSG_FULL_CLIENT_REQUEST,
+        FLAG_NONE,
+        true,
+        params.to_string().as_bytes(),
+    );
+    write.send(Message::Binary(frame)).await?;
+
+    // 2. 边说边分包发送音频, 同时接收中间结果
+    let packet_bytes = TARGET_SAMPLE_RATE as usize * 2 * PACKET_MS / 1000;
+
+    let send_audio = async {
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut count = 0;
+        loop {
+            // 会话被丢弃时按已收到的音频结束
+            let (pcm, last) = audio_rx.recv().await.unwrap_or((Vec::new(), true));
+            buffer.extend_from_slice(&pcm);
+
+            // 留下最后一包, 与 LAST 标志一起发送
+            while buffer.len() > packet_bytes {
+                let packet: Vec<u8> = buffer.drain(..packet_bytes).collect();
+                let frame = build_frame(MSG_AUDIO_ONLY, FLAG_NONE, false, &packet);
+                write.send(Message::Binary(frame)).await?;
+                count += 1;
+            }
+            if last {
+                let frame = build_frame(MSG_AUDIO_ONLY, FLAG_LAST, false, &buffer);
+                write.send(Message::Binary(frame)).await?;
+                log::debug!("Sent {} audio packets", count + 1);
+                return Ok::<_, eyre::Report>(());
+            }
+        }
+    };
+
+    let receive_results = async {
+        loop {
+            let data = match read.next().await {
+                Some(Ok(Message::Binary(data))) => data,
+                Some(Ok(Message::Close(frame))) => bail!("Connection closed: {:?}", frame),
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(e.into()),
+                None => bail!("Connection closed before final result"),
+            };
+
+            match parse_frame(&data)? {
+                ServerMessage::Response { last, payload } => {
+                    let (text, words) = parse_result(&payload);
+                    let output = AsrOutput {
+                        version: SCHEMA_VERSION,
+                        text,
+                        confidence: 1.0,
+                        words,
+                        session_id: session_id.clone(),
+                        is_final: last,
+                        utterance_id: Some(utterance_id.to_string()),
+                    };
+                    if last {
+                        return Ok(output);
+                    }
+                    if !output.text.is_empty() {
+                        let _ = updates.send(StreamUpdate::Partial(output));
+                    }
+                }
+                ServerMessage::Error { code, message } => {
+                    bail!("Streaming ASR error {}: {}", code, message)
+                }
+            }
+        }
+    };
+
+    // 任一方出错即结束, 不再等待另一方
+    let ((), result) = tokio::try_join!(send_audio, receive_results)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server_frame(flags: u8, sequence: Option<i32>, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![
+            PROTOCOL_HEADER,
+            (MSG_FULL_SERVER_RESPONSE << 4) | flags,
+            SERIALIZATION_JSON,
+            0,
+        ];
+        if let Some(seq) = sequence {
+            frame.extend_from_slice(&seq.to_be_bytes());
+        }
+        frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[test]
+    fn test_build_audio_frame() {
+        let frame = build_frame(MSG_AUDIO_ONLY, FLAG_LAST, false, &[1, 2, 3]);
+        assert_eq!(frame, vec![0x11, 0x22, 0x00, 0x00, 0, 0, 0, 3, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_partial_and_final_responses() {
+        let payload = br#"{"result":{"text":"hello","utterances":[{"words":[{"text":"hello","start_time":100,"end_time":500}]}]}}"#;
+
+        let partial = parse_frame(&server_frame(FLAG_SEQUENCE, Some(2), payload)).unwrap();
+        let ServerMessage::Response {
+            last,
+            payload: result,
+        } = partial
+        else {
+            panic!("expected response");
+        };
+        assert!(!last);
+        let (text, words) = parse_result(&result);
+        assert_eq!(text, "hello");
+        assert_eq!(words[0].start_time, 0.1);
+
+        let last =
+            parse_frame(&server_frame(FLAG_SEQUENCE | FLAG_LAST, Some(-3), payload)).unwrap();
+        assert!(matches!(last, ServerMessage::Response { last: true, .. }));
+    }
+
+    #[test]
+    fn test_parse_error_frame() {
+        let mut frame = vec![PROTOCOL_HEADER, MSG_ERROR << 4, SERIALIZATION_JSON, 0];
+        frame.extend_from_slice(&45000001u32.to_be_bytes());
+        frame.extend_from_slice(&3u32.to_be_bytes());
+        frame.extend_from_slice(b"bad");
+        assert_eq!(
+            parse_frame(&frame).unwrap(),
+            ServerMessage::Error {
+                code: 45000001,
+                message: "bad".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_pcm16_resamples_to_16k() {
+        let samples = vec![0.5f32; 48000];
+        let pcm = to_pcm16(&samples, 48000);
+        assert_eq!(pcm.len(), 16000 * 2);
+        assert_eq!(i16::from_le_bytes([pcm[0], pcm[1]]), i16::MAX / 2);
+    }
+}
@@ -0,0 +1,223 @@
+// TTS 音频磁盘缓存
+//
+// 以内容寻址: dora_messages::tts::cache_key -> <dir>/<key>.mp3,
+// 相同文本和音色的句子只合成一次, 命中时不访问网络.
+// 另外记录每个老师回合 (question_id) 依次用到的缓存条目, 保存在
+// <dir>/replies.json, 用于 UI 重播之前的回复.
+// 索引只保留最近 MAX_REPLIES 个回合; 不再被索引引用的音频文件在回合移出
+// 索引时删除, 打开缓存时也清理上次运行留下的未引用文件.
+
+use std::collections::{HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// 保留可重播的回合数
+const MAX_REPLIES: usize = 200;
+
+const INDEX_FILE: &str = "replies.json";
+
+/// 一个回合的音频, 按句子顺序
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ReplyEntry {
+    question_id: String,
+    keys: Vec<String>,
+}
+
+pub struct TtsCache {
+    dir: PathBuf,
+    replies: VecDeque<ReplyEntry>,
+}
+
+impl TtsCache {
+    /// 打开缓存目录, 读取重播索引
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)
+            .wrap_err_with(|| format!("Failed to create TTS cache dir {}", dir.display()))?;
+
+        let replies = fs::read_to_string(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let cache = Self { dir, replies };
+        cache.remove_unreferenced();
+        Ok(cache)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn get(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.audio_path(key)).ok()
+    }
+
+    /// 写入音频 (先写临时文件再重命名, 避免读到半个文件)
+    pub fn put(&self, key: &str, audio: &[u8]) -> Result<()> {
+        let path = self.audio_path(key);
+        let tmp = path.with_extension("mp3.tmp");
+        fs::write(&tmp, audio)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    /// 记录回合 `question_id` 的下一句音频
+    pub fn record_reply(&mut self, question_id: &str, key: &str) -> Result<()> {
+        match self
+            .replies
+            .iter_mut()
+            .find(|entry| entry.question_id == question_id)
+        {
+            Some(entry) => entry.keys.push(key.to_string()),
+            None => {
+                self.replies.push_back(ReplyEntry {
+                    question_id: question_id.to_string(),
+                    keys: vec![key.to_string()],
+                });
+                let mut evicted = Vec::new();
+                while self.replies.len() > MAX_REPLIES {
+                    evicted.extend(self.replies.pop_front().map(|entry| entry.keys));
+                }
+                self.save_index()?;
+                self.remove_audio(evicted.into_iter().flatten());
+                return Ok(());
+            }
+        }
+        self.save_index()
+    }
+
+    /// 回合 `question_id` 的全部音频, 缺失的条目跳过
+    pub fn reply_audio(&self, question_id: &str) -> Option<Vec<Vec<u8>>> {
+        let entry = self
+            .replies
+            .iter()
+            .find(|entry| entry.question_id == question_id)?;
+        let audio: Vec<Vec<u8>> = entry.keys.iter().filter_map(|key| self.get(key)).collect();
+        (!audio.is_empty()).then_some(audio)
+    }
+
+    /// 最近一个回合的 question_id
+    pub fn last_reply(&self) -> Option<&str> {
+        self.replies.back().map(|entry| entry.question_id.as_str())
+    }
+
+    /// 所有仍被重播索引引用的条目
+    fn referenced(&self) -> HashSet<&str> {
+        self.replies
+            .iter()
+            .flat_map(|entry| entry.keys.iter().map(String::as_str))
+            .collect()
+    }
+
+    /// 删除不再被索引引用的 `keys` 的音频
+    fn remove_audio(&self, keys: impl IntoIterator<Item = String>) {
+        let referenced = self.referenced();
+        for key in keys {
+            if !referenced.contains(key.as_str()) {
+                fs::remove_file(self.audio_path(&key)).ok();
+            }
+        }
+    }
+
+    /// 删除目录中未被索引引用的音频和残留的临时文件
+    fn remove_unreferenced(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let referenced = self.referenced();
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            let stale = match name.strip_suffix(".mp3") {
+                Some(key) => !referenced.contains(key),
+                None => name.ends_with(".mp3.tmp"),
+            };
+            if stale {
+                if let Err(e) = fs::remove_file(entry.path()) {
+                    log::warn!("Failed to remove cached audio {}: {}", name, e);
+                }
+            }
+        }
+    }
+
+    fn audio_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.mp3", key))
+    }
+
+    fn save_index(&self) -> Result<()> {
+        let content = serde_json::to_string(&self.replies)?;
+        fs::write(self.dir.join(INDEX_FILE), content)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("dora-doubao-tts-{}-{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_replies_survive_reopen() {
+        let dir = temp_dir("replies");
+        let mut cache = TtsCache::open(&dir).unwrap();
+        cache.put("a", b"first").unwrap();
+        cache.put("b", b"second").unwrap();
+        cache.record_reply("q1", "a").unwrap();
+        cache.record_reply("q1", "b").unwrap();
+
+        let cache = TtsCache::open(&dir).unwrap();
+        assert_eq!(cache.last_reply(), Some("q1"));
+        assert_eq!(
+            cache.reply_audio("q1").unwrap(),
+            vec![b"first".to_vec(), b"second".to_vec()]
+        );
+        assert!(cache.reply_audio("q2").is_none());
+
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_evicts_unreferenced_audio() {
+        let dir = temp_dir("evict");
+        let mut cache = TtsCache::open(&dir).unwrap();
+        cache.put("shared", b"shared").unwrap();
+        cache.put("old", b"old").unwrap();
+        cache.record_reply("q0", "old").unwrap();
+        cache.record_reply("q0", "shared").unwrap();
+        for i in 1..MAX_REPLIES {
+            let key = format!("k{}", i);
+            cache.put(&key, b"audio").unwrap();
+            cache.record_reply(&format!("q{}", i), &key).unwrap();
+        }
+        cache.record_reply("q1", "shared").unwrap();
+
+        // q0 dropped out of the index; only its unshared audio goes
+        cache.put("next", b"next").unwrap();
+        cache.record_reply("next", "next").unwrap();
+        assert!(cache.reply_audio("q0").is_none());
+        assert!(cache.get("old").is_none());
+        assert_eq!(cache.get("shared").unwrap(), b"shared");
+        assert!(cache.get("k1").is_some());
+
+        // Audio no reply refers to is swept on open
+        cache.put("orphan", b"orphan").unwrap();
+        fs::write(dir.join("partial.mp3.tmp"), b"partial").unwrap();
+        let cache = TtsCache::open(&dir).unwrap();
+        assert!(cache.get("orphan").is_none());
+        assert!(!dir.join("partial.mp3.tmp").exists());
+        assert!(cache.get("next").is_some());
+        assert!(dir.join(INDEX_FILE).exists());
+
+        fs::remove_dir_all(dir).ok();
+    }
+}
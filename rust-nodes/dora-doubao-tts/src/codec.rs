@@ -0,0 +1,365 @@
+// 火山引擎双向流式 TTS 二进制帧编解码 (tts/bidirection WebSocket 协议)
+//
+// 帧格式: 4 字节头 + [4 字节事件号] + [4 字节 ID 长度 + ID] + 4 字节负载长度 + 负载
+//   byte0: 协议版本(高4位) | 头长度/4(低4位)   = 0x11
+//   byte1: 消息类型(高4位) | 标志位(低4位), 0b0100 表示带事件号
+//   byte2: 序列化方式(高4位) | 压缩方式(低4位)
+//   byte3: 保留
+//
+// 连接级事件 (StartConnection/FinishConnection) 不带 ID, 服务端的连接事件
+// (ConnectionStarted/Failed/Finished) 带 connect_id, 其余事件带 session_id.
+// 错误帧: 4 字节头 + 4 字节错误码 + 4 字节负载长度 + 负载.
+// 负载可能经 gzip 压缩, 解码时自动解压.
+
+use std::io::{Read, Write};
+
+use eyre::{Result, bail};
+use flate2::Compression as GzLevel;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde_json::Value;
+
+const PROTOCOL_HEADER: u8 = 0x11; // version 1, header size 1 (4 bytes)
+
+// 消息类型
+pub const MSG_FULL_CLIENT_REQUEST: u8 = 0b0001;
+pub const MSG_FULL_SERVER_RESPONSE: u8 = 0b1001;
+pub const MSG_AUDIO_ONLY_RESPONSE: u8 = 0b1011;
+pub const MSG_ERROR: u8 = 0b1111;
+
+// 标志位
+const FLAG_WITH_EVENT: u8 = 0b0100;
+
+// 序列化 / 压缩
+const SERIALIZATION_NONE: u8 = 0x0;
+const SERIALIZATION_JSON: u8 = 0x1;
+const COMPRESSION_NONE: u8 = 0x0;
+const COMPRESSION_GZIP: u8 = 0x1;
+
+// 客户端事件
+pub const EVENT_START_CONNECTION: i32 = 1;
+pub const EVENT_FINISH_CONNECTION: i32 = 2;
+pub const EVENT_START_SESSION: i32 = 100;
+pub const EVENT_CANCEL_SESSION: i32 = 101;
+pub const EVENT_FINISH_SESSION: i32 = 102;
+pub const EVENT_TASK_REQUEST: i32 = 200;
+
+// 服务端事件
+pub const EVENT_CONNECTION_STARTED: i32 = 50;
+pub const EVENT_CONNECTION_FAILED: i32 = 51;
+pub const EVENT_CONNECTION_FINISHED: i32 = 52;
+pub const EVENT_SESSION_STARTED: i32 = 150;
+pub const EVENT_SESSION_CANCELED: i32 = 151;
+pub const EVENT_SESSION_FINISHED: i32 = 152;
+pub const EVENT_SESSION_FAILED: i32 = 153;
+pub const EVENT_TTS_SENTENCE_START: i32 = 350;
+pub const EVENT_TTS_SENTENCE_END: i32 = 351;
+pub const EVENT_TTS_RESPONSE: i32 = 352;
+
+/// 负载压缩方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+}
+
+/// 服务端消息
+#[derive(Debug, PartialEq)]
+pub enum ServerMessage {
+    /// 带 JSON 负载的事件, `id` 为 connect_id 或 session_id
+    Event {
+        event: i32,
+        id: Option<String>,
+        payload: Value,
+    },
+    /// 音频数据 (TTSResponse)
+    Audio {
+        session_id: String,
+        data: Vec<u8>,
+    },
+    Error {
+        code: u32,
+        message: String,
+    },
+}
+
+/// 事件是否不带 ID (客户端连接级事件)
+fn event_without_id(event: i32) -> bool {
+    matches!(event, EVENT_START_CONNECTION | EVENT_FINISH_CONNECTION)
+}
+
+/// 构造带事件号的客户端帧
+pub fn encode_event(
+    event: i32,
+    session_id: Option<&str>,
+    payload: &Value,
+    compression: Compression,
+) -> Result<Vec<u8>> {
+    let payload = compress(payload.to_string().as_bytes(), compression)?;
+    let compression_bits = match compression {
+        Compression::None => COMPRESSION_NONE,
+        Compression::Gzip => COMPRESSION_GZIP,
+    };
+
+    let mut frame = Vec::with_capacity(16 + payload.len());
+    frame.push(PROTOCOL_HEADER);
+    frame.push((MSG_FULL_CLIENT_REQUEST << 4) | FLAG_WITH_EVENT);
+    frame.push((SERIALIZATION_JSON << 4) | compression_bits);
+    frame.push(0x00);
+    frame.extend_from_slice(&event.to_be_bytes());
+
+    if !event_without_id(event) {
+        let sid = session_id.unwrap_or_default().as_bytes();
+        frame.extend_from_slice(&(sid.len() as u32).to_be_bytes());
+        frame.extend_from_slice(sid);
+    }
+
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(&payload);
+    Ok(frame)
+}
+
+/// 解析服务端帧
+pub fn decode(data: &[u8]) -> Result<ServerMessage> {
+    if data.len() < 4 {
+        bail!("Frame too short: {} bytes", data.len());
+    }
+
+    let header_len = ((data[0] & 0x0F) as usize) * 4;
+    let msg_type = data[1] >> 4;
+    let flags = data[1] & 0x0F;
+    let serialization = data[2] >> 4;
+    let compression = data[2] & 0x0F;
+    let mut reader = FrameReader {
+        data,
+        offset: header_len,
+    };
+
+    if msg_type == MSG_ERROR {
+        let code = reader.u32()?;
+        let payload = decompress(reader.sized()?, compression)?;
+        // 错误负载通常是 JSON {"error": "..."}, 否则按文本处理
+        let message = serde_json::from_slice::<Value>(&payload)
+            .ok()
+            .and_then(|v| v["error"].as_str().map(|s| s.to_string()))
+            .unwrap_or_else(|| String::from_utf8_lossy(&payload).to_string());
+        return Ok(ServerMessage::Error { code, message });
+    }
+
+    if flags & FLAG_WITH_EVENT == 0 {
+        bail!("Frame without event number (flags {:#06b})", flags);
+    }
+    let event = reader.u32()? as i32;
+    let id = String::from_utf8_lossy(reader.sized()?).to_string();
+    let payload = decompress(reader.sized()?, compression)?;
+
+    match msg_type {
+        MSG_AUDIO_ONLY_RESPONSE if event == EVENT_TTS_RESPONSE => Ok(ServerMessage::Audio {
+            session_id: id,
+            data: payload,
+        }),
+        MSG_FULL_SERVER_RESPONSE => {
+            let payload = if payload.is_empty() || serialization == SERIALIZATION_NONE {
+                Value::Null
+            } else {
+                serde_json::from_slice(&payload)?
+            };
+            Ok(ServerMessage::Event {
+                event,
+                id: (!id.is_empty()).then_some(id),
+                payload,
+            })
+        }
+        other => bail!("Unexpected message type {:#x} (event {})", other, event),
+    }
+}
+
+struct FrameReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> FrameReader<'a> {
+    fn u32(&mut self) -> Result<u32> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + 4)
+            .ok_or_else(|| eyre::eyre!("Truncated frame at offset {}", self.offset))?;
+        self.offset += 4;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// 4 字节长度 + 内容
+    fn sized(&mut self) -> Result<&'a [u8]> {
+        let size = self.u32()? as usize;
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + size)
+            .ok_or_else(|| eyre::eyre!("Truncated field ({} bytes expected)", size))?;
+        self.offset += size;
+        Ok(bytes)
+    }
+}
+
+fn compress(payload: &[u8], compression: Compression) -> Result<Vec<u8>> {
+    match compression {
+        Compression::None => Ok(payload.to_vec()),
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), GzLevel::default());
+            encoder.write_all(payload)?;
+            Ok(encoder.finish()?)
+        }
+    }
+}
+
+fn decompress(payload: &[u8], compression: u8) -> Result<Vec<u8>> {
+    match compression {
+        COMPRESSION_NONE => Ok(payload.to_vec()),
+        COMPRESSION_GZIP => {
+            let mut out = Vec::new();
+            GzDecoder::new(payload).read_to_end(&mut out)?;
+            Ok(out)
+        }
+        other => bail!("Unsupported compression: {:#x}", other),
+    }
+}
+
+/// 构造带事件号的服务端帧 (测试用)
+#[cfg(test)]
+pub(crate) fn server_frame(
+    msg_type: u8,
+    compression: u8,
+    event: i32,
+    id: &str,
+    payload: &[u8],
+) -> Vec<u8> {
+    let mut frame = vec![
+        PROTOCOL_HEADER,
+        (msg_type << 4) | FLAG_WITH_EVENT,
+        (SERIALIZATION_JSON << 4) | compression,
+        0,
+    ];
+    frame.extend_from_slice(&event.to_be_bytes());
+    frame.extend_from_slice(&(id.len() as u32).to_be_bytes());
+    frame.extend_from_slice(id.as_bytes());
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_encode_connection_event_has_no_id() {
+        let frame = encode_event(
+            EVENT_START_CONNECTION,
+            Some("ignored"),
+            &json!({}),
+            Compression::None,
+        )
+        .unwrap();
+        assert_eq!(
+            frame,
+            vec![0x11, 0x14, 0x10, 0x00, 0, 0, 0, 1, 0, 0, 0, 2, b'{', b'}']
+        );
+    }
+
+    #[test]
+    fn test_encode_session_event_with_gzip() {
+        let payload = json!({"text": "hello"});
+        let frame =
+            encode_event(EVENT_TASK_REQUEST, Some("s1"), &payload, Compression::Gzip).unwrap();
+        assert_eq!(frame[2], 0x11);
+        assert_eq!(&frame[8..14], &[0, 0, 0, 2, b's', b'1']);
+
+        let body = &frame[18..];
+        assert_eq!(
+            decompress(body, COMPRESSION_GZIP).unwrap(),
+            payload.to_string().as_bytes()
+        );
+    }
+
+    #[test]
+    fn test_decode_event_and_audio() {
+        let started = server_frame(
+            MSG_FULL_SERVER_RESPONSE,
+            COMPRESSION_NONE,
+            EVENT_CONNECTION_STARTED,
+            "conn-1",
+            b"{}",
+        );
+        assert_eq!(
+            decode(&started).unwrap(),
+            ServerMessage::Event {
+                event: EVENT_CONNECTION_STARTED,
+                id: Some("conn-1".to_string()),
+                payload: json!({}),
+            }
+        );
+
+        let audio = server_frame(
+            MSG_AUDIO_ONLY_RESPONSE,
+            COMPRESSION_NONE,
+            EVENT_TTS_RESPONSE,
+            "s1",
+            &[1, 2, 3],
+        );
+        assert_eq!(
+            decode(&audio).unwrap(),
+            ServerMessage::Audio {
+                session_id: "s1".to_string(),
+                data: vec![1, 2, 3],
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_gzip_payload() {
+        let payload = compress(br#"{"status_code":20000000}"#, Compression::Gzip).unwrap();
+        let frame = server_frame(
+            MSG_FULL_SERVER_RESPONSE,
+            COMPRESSION_GZIP,
+            EVENT_SESSION_FINISHED,
+            "s1",
+            &payload,
+        );
+        let ServerMessage::Event { event, payload, .. } = decode(&frame).unwrap() else {
+            panic!("expected event");
+        };
+        assert_eq!(event, EVENT_SESSION_FINISHED);
+        assert_eq!(payload["status_code"], 20000000);
+    }
+
+    #[test]
+    fn test_decode_error_frame() {
+        let mut frame = vec![PROTOCOL_HEADER, MSG_ERROR << 4, SERIALIZATION_JSON << 4, 0];
+        let body = br#"{"error":"quota exceeded"}"#;
+        frame.extend_from_slice(&45000000u32.to_be_bytes());
+        frame.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        frame.extend_from_slice(body);
+        assert_eq!(
+            decode(&frame).unwrap(),
+            ServerMessage::Error {
+                code: 45000000,
+                message: "quota exceeded".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_decode_truncated_frame() {
+        let mut frame = server_frame(
+            MSG_FULL_SERVER_RESPONSE,
+            COMPRESSION_NONE,
+            EVENT_SESSION_STARTED,
+            "s1",
+            b"{}",
+        );
+        frame.truncate(frame.len() - 1);
+        assert!(decode(&frame).is_err());
+        assert!(decode(&[0x11]).is_err());
+    }
+}
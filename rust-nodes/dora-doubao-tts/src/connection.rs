@@ -0,0 +1,675 @@
+// 长连接 TTS 客户端
+//
+// 一个 WebSocket 连接 (StartConnection) 在多个回合间复用, 每次合成只开一个
+// session: StartSession -> TaskRequest -> FinishSession -> 收音频直到 SessionFinished.
+// 连接断开时按指数退避自动重连; 合成被取消 (插话) 时, 下次使用前先
+// CancelSession 并丢弃残留帧, 失败则重建连接.
+
+use std::time::Duration;
+
+use dora_messages::timing;
+use eyre::{Context, Result, bail};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio_tungstenite::tungstenite::error::ProtocolError;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+
+use crate::codec::{self, Compression, ServerMessage};
+
+const TTS_URL: &str = "wss://openspeech.bytedance.com/api/v3/tts/bidirection";
+
+/// 重连退避: 初始间隔, 上限, 最大尝试次数
+const BACKOFF_INITIAL: Duration = Duration::from_millis(250);
+const BACKOFF_MAX: Duration = Duration::from_secs(8);
+const MAX_CONNECT_ATTEMPTS: u32 = 5;
+
+/// 等待单个服务端事件的超时
+const EVENT_TIMEOUT: Duration = Duration::from_secs(10);
+
+type WsStream = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// TTS 连接参数
+#[derive(Debug, Clone)]
+pub struct TtsConfig {
+    pub app_id: String,
+    pub api_key: String,
+    pub access_token: String,
+    pub resource_id: String,
+    pub speaker: String,
+    pub speech_rate: i32,
+    /// 请求负载使用 gzip 压缩
+    pub gzip_requests: bool,
+}
+
+/// 一段文本的合成结果
+pub struct Synthesis {
+    /// mp3 数据
+    pub audio: Vec<u8>,
+    /// 收到首个音频帧的时间 (unix 毫秒), 缓存命中时为空
+    pub first_chunk_ms: Option<i64>,
+    /// 合成结束的时间 (unix 毫秒), 缓存命中时为空
+    pub complete_ms: Option<i64>,
+}
+
+impl Synthesis {
+    /// 缓存中的音频, 没有合成时间
+    pub fn cached(audio: Vec<u8>) -> Self {
+        Self {
+            audio,
+            first_chunk_ms: None,
+            complete_ms: None,
+        }
+    }
+}
+
+/// 复用的双向 TTS 连接
+pub struct TtsConnection {
+    config: TtsConfig,
+    url: String,
+    ws: Option<WsStream>,
+    /// 被中途取消、尚未结束的 session
+    open_session: Option<String>,
+    user_id: String,
+}
+
+impl TtsConnection {
+    pub fn new(config: TtsConfig) -> Self {
+        Self {
+            config,
+            url: TTS_URL.to_string(),
+            ws: None,
+            open_session: None,
+            user_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// 确保连接可用, 断开时按退避重连
+    pub async fn connect(&mut self) -> Result<()> {
+        if self.ws.is_some() {
+            return Ok(());
+        }
+
+        let mut delay = BACKOFF_INITIAL;
+        let mut attempt = 1;
+        loop {
+            match self.open().await {
+                Ok(ws) => {
+                    self.ws = Some(ws);
+                    self.open_session = None;
+                    return Ok(());
+                }
+                Err(e) if attempt < MAX_CONNECT_ATTEMPTS => {
+                    log::warn!(
+                        "TTS connect attempt {}/{} failed: {}, retrying in {:?}",
+                        attempt,
+                        MAX_CONNECT_ATTEMPTS,
+                        e,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(BACKOFF_MAX);
+                    attempt += 1;
+                }
+                Err(e) => {
+                    return Err(e).wrap_err_with(|| {
+                        format!("TTS connect failed after {} attempts", attempt)
+                    });
+                }
+            }
+        }
+    }
+
+    /// 合成一段文本
+    ///
+    /// 连接在合成开始前断开时会重连并重试一次
+    pub async fn synthesize(&mut self, text: &str) -> Result<Synthesis> {
+        self.connect().await?;
+        self.cancel_open_session().await;
+
+        match self.run_session(text).await {
+            Ok(synthesis) => Ok(synthesis),
+            Err(SessionError::Disconnected(e)) => {
+                log::warn!("TTS connection lost ({}), reconnecting", e);
+                self.drop_connection();
+                self.connect().await?;
+                self.run_session(text)
+                    .await
+                    .map_err(SessionError::into_report)
+            }
+            Err(e) => Err(e.into_report()),
+        }
+    }
+
+    /// 结束连接 (FinishConnection)
+    pub async fn close(&mut self) {
+        if let Some(mut ws) = self.ws.take() {
+            if let Ok(frame) = self.frame(codec::EVENT_FINISH_CONNECTION, None, json!({})) {
+                ws.send(Message::Binary(frame)).await.ok();
+            }
+            ws.close(None).await.ok();
+        }
+    }
+
+    async fn open(&self) -> Result<WsStream> {
+        let connect_id = uuid::Uuid::new_v4().to_string();
+
+        // 认证参数在 HTTP 头中 (Authorization 格式: "Bearer;{token}")
+        let request = tokio_tungstenite::tungstenite::http::Request::builder()
+            .uri(&self.url)
+            .header("Host", "openspeech.bytedance.com")
+            .header("Connection", "Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header(
+                "Sec-WebSocket-Key",
+                tokio_tungstenite::tungstenite::handshake::client::generate_key(),
+            )
+            .header("Authorization", format!("Bearer;{}", self.config.api_key))
+            .header("X-Api-App-Key", &self.config.app_id)
+            .header("X-Api-Access-Key", &self.config.access_token)
+            .header("X-Api-Resource-Id", &self.config.resource_id)
+            .header("X-Api-Connect-Id", &connect_id)
+            .body(())?;
+
+        let (mut ws, response) = connect_async(request)
+            .await
+            .wrap_err("TTS WebSocket handshake failed")?;
+
+        // 打印响应头中的 X-Tt-Logid 便于调试
+        if let Some(logid) = response.headers().get("X-Tt-Logid") {
+            log::info!("TTS connected, X-Tt-Logid: {:?}", logid);
+        }
+
+        let frame = self.frame(codec::EVENT_START_CONNECTION, None, json!({}))?;
+        ws.send(Message::Binary(frame)).await?;
+        expect_event(&mut ws, codec::EVENT_CONNECTION_STARTED).await?;
+        log::debug!("Received ConnectionStarted");
+
+        Ok(ws)
+    }
+
+    async fn run_session(&mut self, text: &str) -> Result<Synthesis, SessionError> {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let req_params = self.req_params(None);
+        let start = self
+            .frame(
+                codec::EVENT_START_SESSION,
+                Some(&session_id),
+                json!({
+                    "user": { "uid": self.user_id },
+                    "event": codec::EVENT_START_SESSION,
+                    "namespace": "BidirectionalTTS",
+                    "req_params": req_params,
+                }),
+            )
+            .map_err(SessionError::Protocol)?;
+
+        let ws = self.ws.as_mut().ok_or_else(|| {
+            SessionError::Disconnected(eyre::eyre!("TTS connection not established"))
+        })?;
+        ws.send(Message::Binary(start))
+            .await
+            .map_err(|e| SessionError::Disconnected(e.into()))?;
+        // 从这里开始 session 需要被结束, 被取消时由下一次调用清理
+        self.open_session = Some(session_id.clone());
+
+        let ws = self.ws.as_mut().expect("connected");
+        match expect_event(ws, codec::EVENT_SESSION_STARTED).await {
+            Ok(_) => {}
+            Err(e) if is_disconnect(&e) => return Err(SessionError::Disconnected(e)),
+            Err(e) => return Err(SessionError::Protocol(e)),
+        }
+        log::debug!("Received SessionStarted");
+
+        let task = self
+            .frame(
+                codec::EVENT_TASK_REQUEST,
+                Some(&session_id),
+                json!({
+                    "user": { "uid": self.user_id },
+                    "event": codec::EVENT_TASK_REQUEST,
+                    "namespace": "BidirectionalTTS",
+                    "req_params": self.req_params(Some(text)),
+                }),
+            )
+            .map_err(SessionError::Protocol)?;
+        let finish = self
+            .frame(codec::EVENT_FINISH_SESSION, Some(&session_id), json!({}))
+            .map_err(SessionError::Protocol)?;
+
+        let ws = self.ws.as_mut().expect("connected");
+        for frame in [task, finish] {
+            ws.send(Message::Binary(frame))
+                .await
+                .map_err(|e| SessionError::Disconnected(e.into()))?;
+        }
+        log::debug!("Sent TaskRequest and FinishSession");
+
+        // 接收音频直到 SessionFinished, 此后连接可直接用于下一回合
+        let mut audio = Vec::new();
+        let mut first_chunk_ms = None;
+        loop {
+            let message = match next_message(ws).await {
+                Ok(message) => message,
+                Err(e) => {
+                    // 已收到部分音频时不重试, 避免重复播放
+                    self.drop_connection();
+                    return Err(SessionError::Protocol(e));
+                }
+            };
+
+            match message {
+                ServerMessage::Audio { data, .. } => {
+                    first_chunk_ms.get_or_insert_with(timing::now_ms);
+                    audio.extend_from_slice(&data);
+                }
+                ServerMessage::Event {
+                    event: codec::EVENT_SESSION_FINISHED,
+                    payload,
+                    ..
+                } => {
+                    self.open_session = None;
+                    check_status(&payload).map_err(SessionError::Protocol)?;
+                    log::debug!("Session finished, {} audio bytes", audio.len());
+                    return Ok(Synthesis {
+                        audio,
+                        first_chunk_ms,
+                        complete_ms: Some(timing::now_ms()),
+                    });
+                }
+                ServerMessage::Event {
+                    event: codec::EVENT_SESSION_FAILED,
+                    payload,
+                    ..
+                } => {
+                    self.open_session = None;
+                    return Err(SessionError::Protocol(eyre::eyre!(
+                        "TTS session failed: {}",
+                        payload
+                    )));
+                }
+                ServerMessage::Event {
+                    event: codec::EVENT_TTS_SENTENCE_START | codec::EVENT_TTS_SENTENCE_END,
+                    payload,
+                    ..
+                } => {
+                    log::debug!("Sentence event: {}", payload);
+                }
+                ServerMessage::Event { event, .. } => {
+                    log::debug!("Ignoring TTS event {}", event);
+                }
+                ServerMessage::Error { code, message } => {
+                    self.drop_connection();
+                    return Err(SessionError::Protocol(eyre::eyre!(
+                        "TTS error {}: {}",
+                        code,
+                        message
+                    )));
+                }
+            }
+        }
+    }
+
+    /// 结束上一次被取消的 session, 失败时丢弃连接
+    async fn cancel_open_session(&mut self) {
+        let Some(session_id) = self.open_session.take() else {
+            return;
+        };
+        log::debug!("Cancelling interrupted session {}", session_id);
+
+        let Ok(frame) = self.frame(codec::EVENT_CANCEL_SESSION, Some(&session_id), json!({}))
+        else {
+            self.drop_connection();
+            return;
+        };
+        let Some(ws) = self.ws.as_mut() else {
+            return;
+        };
+
+        let cancelled = async {
+            ws.send(Message::Binary(frame)).await?;
+            // 丢弃残留音频, 直到 session 结束
+            loop {
+                if let ServerMessage::Event {
+                    event:
+                        codec::EVENT_SESSION_CANCELED
+                        | codec::EVENT_SESSION_FINISHED
+                        | codec::EVENT_SESSION_FAILED,
+                    ..
+                } = next_message(ws).await?
+                {
+                    return Ok::<_, eyre::Report>(());
+                }
+            }
+        };
+
+        if let Err(e) = cancelled.await {
+            log::warn!(
+                "Failed to cancel session {}: {}, reconnecting",
+                session_id,
+                e
+            );
+            self.drop_connection();
+        }
+    }
+
+    fn drop_connection(&mut self) {
+        self.ws = None;
+        self.open_session = None;
+    }
+
+    fn frame(&self, event: i32, session_id: Option<&str>, payload: Value) -> Result<Vec<u8>> {
+        let compression = if self.config.gzip_requests {
+            Compression::Gzip
+        } else {
+            Compression::None
+        };
+        codec::encode_event(event, session_id, &payload, compression)
+    }
+
+    fn req_params(&self, text: Option<&str>) -> Value {
+        let mut params = json!({
+            "speaker": self.config.speaker,
+            "audio_params": {
+                "format": "mp3",
+                "sample_rate": 24000,
+                "speech_rate": self.config.speech_rate,
+                "enable_timestamp": true
+            },
+            "additions": json!({
+                "disable_markdown_filter": false
+            }).to_string()
+        });
+        if let Some(text) = text {
+            params["text"] = json!(text);
+        }
+        params
+    }
+}
+
+/// 合成失败原因: 连接断开 (可重连重试) 或协议/服务端错误
+enum SessionError {
+    Disconnected(eyre::Report),
+    Protocol(eyre::Report),
+}
+
+impl SessionError {
+    fn into_report(self) -> eyre::Report {
+        match self {
+            SessionError::Disconnected(e) | SessionError::Protocol(e) => e,
+        }
+    }
+}
+
+/// 错误是否表示连接已断开 (可重连重试)
+fn is_disconnect(error: &eyre::Report) -> bool {
+    use tokio_tungstenite::tungstenite::Error;
+
+    matches!(
+        error.downcast_ref::<Error>(),
+        Some(
+            Error::ConnectionClosed
+                | Error::AlreadyClosed
+                | Error::Io(_)
+                | Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+        )
+    )
+}
+
+/// 读取下一个服务端消息 (跳过 ping/pong 等控制帧)
+async fn next_message(ws: &mut WsStream) -> Result<ServerMessage> {
+    loop {
+        let message = tokio::time::timeout(EVENT_TIMEOUT, ws.next())
+            .await
+            .map_err(|_| eyre::eyre!("Timed out waiting for TTS response"))?;
+
+        match message {
+            Some(Ok(Message::Binary(data))) => return codec::decode(&data),
+            Some(Ok(Message::Text(txt))) => {
+                log::warn!("Received unexpected text message: {}", txt);
+            }
+            Some(Ok(Message::Close(frame))) => {
+                log::debug!("TTS WebSocket closed by server: {:?}", frame);
+                return Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed.into());
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(e.into()),
+            None => return Err(tokio_tungstenite::tungstenite::Error::ConnectionClosed.into()),
+        }
+    }
+}
+
+/// 等待指定事件, 连接/会话失败事件和错误帧直接返回错误
+async fn expect_event(ws: &mut WsStream, expected: i32) -> Result<Value> {
+    loop {
+        match next_message(ws).await? {
+            ServerMessage::Event { event, payload, .. } if event == expected => {
+                return Ok(payload);
+            }
+            ServerMessage::Event {
+                event:
+                    event @ (codec::EVENT_CONNECTION_FAILED
+                    | codec::EVENT_CONNECTION_FINISHED
+                    | codec::EVENT_SESSION_FAILED),
+                payload,
+                ..
+            } => bail!(
+                "TTS event {} while waiting for {}: {}",
+                event,
+                expected,
+                payload
+            ),
+            ServerMessage::Error { code, message } => bail!("TTS error {}: {}", code, message),
+            ServerMessage::Event { event, .. } => {
+                log::debug!("Skipping event {} while waiting for {}", event, expected);
+            }
+            ServerMessage::Audio { .. } => {}
+        }
+    }
+}
+
+/// SessionFinished 的负载带 status_code, 20000000 表示成功
+fn check_status(payload: &Value) -> Result<()> {
+    match payload["status_code"].as_i64() {
+        None | Some(20000000) => Ok(()),
+        Some(code) => bail!(
+            "TTS session finished with status {}: {}",
+            code,
+            payload["message"].as_str().unwrap_or_default()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::codec::{MSG_AUDIO_ONLY_RESPONSE, MSG_FULL_SERVER_RESPONSE, server_frame};
+
+    /// 模拟服务端对一个客户端事件的响应
+    enum Reply {
+        Frames(Vec<Vec<u8>>),
+        Close,
+    }
+
+    /// 收到的 (连接序号, 事件, session ID)
+    type Received = Arc<Mutex<Vec<(usize, i32, String)>>>;
+
+    fn event_frame(event: i32, id: &str) -> Vec<u8> {
+        server_frame(MSG_FULL_SERVER_RESPONSE, 0, event, id, b"{}")
+    }
+
+    fn audio_frame(id: &str, data: &[u8]) -> Vec<u8> {
+        server_frame(
+            MSG_AUDIO_ONLY_RESPONSE,
+            0,
+            codec::EVENT_TTS_RESPONSE,
+            id,
+            data,
+        )
+    }
+
+    /// 正常服务端: 每个 session 返回 "mp3", 取消时先送出残留音频
+    fn respond(event: i32, id: &str) -> Reply {
+        let frames = match event {
+            codec::EVENT_START_CONNECTION => {
+                vec![event_frame(codec::EVENT_CONNECTION_STARTED, "connect")]
+            }
+            codec::EVENT_START_SESSION => vec![event_frame(codec::EVENT_SESSION_STARTED, id)],
+            codec::EVENT_FINISH_SESSION => vec![
+                audio_frame(id, b"mp3"),
+                event_frame(codec::EVENT_SESSION_FINISHED, id),
+            ],
+            codec::EVENT_CANCEL_SESSION => vec![
+                audio_frame(id, b"stale"),
+                event_frame(codec::EVENT_SESSION_CANCELED, id),
+            ],
+            _ => Vec::new(),
+        };
+        Reply::Frames(frames)
+    }
+
+    /// 客户端帧的事件号和 session ID
+    fn client_event(frame: &[u8]) -> (i32, String) {
+        let event = i32::from_be_bytes(frame[4..8].try_into().unwrap());
+        if matches!(
+            event,
+            codec::EVENT_START_CONNECTION | codec::EVENT_FINISH_CONNECTION
+        ) {
+            return (event, String::new());
+        }
+        let len = u32::from_be_bytes(frame[8..12].try_into().unwrap()) as usize;
+        (
+            event,
+            String::from_utf8_lossy(&frame[12..12 + len]).to_string(),
+        )
+    }
+
+    /// 本地 WebSocket 服务端, 依次处理每个连接
+    async fn serve(
+        respond: impl Fn(usize, i32, &str) -> Reply + Send + 'static,
+    ) -> (TtsConnection, Received) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let received = Received::default();
+        let log = received.clone();
+
+        tokio::spawn(async move {
+            let mut conn = 0;
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                while let Some(Ok(Message::Binary(frame))) = ws.next().await {
+                    let (event, id) = client_event(&frame);
+                    log.lock().unwrap().push((conn, event, id.clone()));
+                    match respond(conn, event, &id) {
+                        Reply::Frames(frames) => {
+                            for frame in frames {
+                                ws.send(Message::Binary(frame)).await.ok();
+                            }
+                        }
+                        Reply::Close => {
+                            ws.close(None).await.ok();
+                            break;
+                        }
+                    }
+                }
+                conn += 1;
+            }
+        });
+
+        let mut connection = TtsConnection::new(TtsConfig {
+            app_id: "app".to_string(),
+            api_key: "key".to_string(),
+            access_token: "token".to_string(),
+            resource_id: "seed-tts-2.0".to_string(),
+            speaker: "speaker".to_string(),
+            speech_rate: 0,
+            gzip_requests: false,
+        });
+        connection.url = format!("ws://{}", addr);
+        (connection, received)
+    }
+
+    fn events(received: &Received, event: i32) -> Vec<(usize, String)> {
+        received
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, e, _)| *e == event)
+            .map(|(conn, _, id)| (*conn, id.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_is_disconnect() {
+        use tokio_tungstenite::tungstenite::Error;
+
+        assert!(is_disconnect(&Error::ConnectionClosed.into()));
+        assert!(is_disconnect(&Error::AlreadyClosed.into()));
+        assert!(is_disconnect(
+            &Error::Io(std::io::ErrorKind::ConnectionReset.into()).into()
+        ));
+        assert!(!is_disconnect(&Error::Utf8.into()));
+        assert!(!is_disconnect(&eyre::eyre!("WebSocket closed")));
+    }
+
+    #[tokio::test]
+    async fn test_reconnects_when_server_closes() {
+        // 第二个 session 开始时服务端关闭连接
+        let sessions = AtomicUsize::new(0);
+        let (mut connection, received) = serve(move |_, event, id| {
+            if event == codec::EVENT_START_SESSION && sessions.fetch_add(1, Ordering::SeqCst) == 1 {
+                return Reply::Close;
+            }
+            respond(event, id)
+        })
+        .await;
+
+        assert_eq!(connection.synthesize("one").await.unwrap().audio, b"mp3");
+        assert_eq!(connection.synthesize("two").await.unwrap().audio, b"mp3");
+
+        let connects = events(&received, codec::EVENT_START_CONNECTION);
+        assert_eq!(connects.len(), 2);
+        let starts = events(&received, codec::EVENT_START_SESSION);
+        assert_eq!(
+            starts.iter().map(|(conn, _)| *conn).collect::<Vec<_>>(),
+            [0, 0, 1]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancels_interrupted_session() {
+        // 第一个 session 不结束, 模拟合成中途被插话取消
+        let finishes = AtomicUsize::new(0);
+        let (mut connection, received) = serve(move |_, event, id| {
+            if event == codec::EVENT_FINISH_SESSION && finishes.fetch_add(1, Ordering::SeqCst) == 0
+            {
+                return Reply::Frames(vec![audio_frame(id, b"partial")]);
+            }
+            respond(event, id)
+        })
+        .await;
+
+        let interrupted =
+            tokio::time::timeout(Duration::from_millis(200), connection.synthesize("one")).await;
+        assert!(interrupted.is_err());
+
+        // 残留音频被丢弃, 连接被复用
+        let synthesis = connection.synthesize("two").await.unwrap();
+        assert_eq!(synthesis.audio, b"mp3");
+        assert!(synthesis.first_chunk_ms.unwrap() <= synthesis.complete_ms.unwrap());
+
+        let starts = events(&received, codec::EVENT_START_SESSION);
+        assert_eq!(starts.len(), 2);
+        assert_eq!(
+            events(&received, codec::EVENT_CANCEL_SESSION),
+            [(0, starts[0].1.clone())]
+        );
+        assert_eq!(events(&received, codec::EVENT_START_CONNECTION).len(), 1);
+    }
+}
@@ -1,83 +1,35 @@
 // Dora Node: Doubao TTS (Text-to-Speech)
 // Converts AI text responses to speech using Doubao Volcanic Engine Bidirectional WebSocket API
 // 不再直接操作数据库，由 history-db-writer 负责保存对话历史
-
-use std::fs;
-
+// 输入为 english-teacher 的 reply_segment, 每句话单独合成
+// 收到 control/interrupt (用户插话) 时取消对应 question_id 的合成
+// WebSocket 连接在回合间复用 (见 connection.rs), 每句话一个 session
+// 合成结果缓存在磁盘 (见 cache.rs), 收到 control/replay 时从缓存重播老师回复
+
+mod cache;
+mod codec;
+mod connection;
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use dora_messages::tts::cache_key;
+use dora_messages::turn::{TURN_ID, string_param};
+use dora_messages::{
+    AudioMetadata, ComprehensiveResponse, ControlCommand, ReplySegment, SCHEMA_VERSION, Stage,
+    TextInput,
+};
 use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
-use dora_node_api::{ArrowData, DoraNode, Event};
+use dora_node_api::{ArrowData, DoraNode, Event, MetadataParameters, Parameter};
 use eyre::{Context, Result};
-use futures_util::{SinkExt, StreamExt};
 use minimp3::{Decoder, Frame};
-use serde::{Deserialize, Serialize};
 use serde_json::json;
-use tokio_tungstenite::connect_async;
-use tokio_tungstenite::tungstenite::protocol::Message;
-
-/// 综合响应格式 (来自 english-teacher/json_data)
-#[derive(Debug, Serialize, Deserialize)]
-struct ComprehensiveResponse {
-    session_id: String,
-    use_lang: String,       // 用户文本语言: "en" | "zh" | "mix"
-    original_en: String,    // 用户原始文本（英文）
-    original_zh: String,    // 用户原始文本（中文）
-    reply_en: String,       // AI对该消息的英文回复
-    reply_zh: String,       // AI对该消息的中文回复
-    issues: Vec<TextIssue>, // 语法/用词问题
-    timestamp: i64,
-}
 
-/// 文本问题
-#[derive(Debug, Serialize, Deserialize)]
-struct TextIssue {
-    #[serde(rename = "type")]
-    issue_type: String, // grammar | word_choice | suggestion
-    original: String,
-    suggested: String,
-    description_en: String,
-    description_zh: String,
-    severity: String, // low | medium | high
-
-    #[serde(default)]
-    start_position: Option<i32>,
-    #[serde(default)]
-    end_position: Option<i32>,
-}
+use crate::cache::TtsCache;
+use crate::connection::{Synthesis, TtsConfig, TtsConnection};
 
-/// 简单文本输入格式
-#[derive(Debug, Serialize, Deserialize)]
-struct TextInput {
-    text: String,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct AudioMetadata {
-    duration_ms: u64,
-    format: String,
-    sample_rate: u32,
-    bytes: usize,
-}
-
-// WebSocket 协议相关常量
-const PROTOCOL_VERSION: u8 = 0x11; // v1, 4-byte header
-const MSG_TYPE_FULL_CLIENT: u8 = 0x14; // Full-client request with event
-const MSG_TYPE_FULL_SERVER: u8 = 0x94; // Full-server response with event
-const MSG_TYPE_AUDIO_ONLY: u8 = 0xB4; // Audio-only response with event
-const SERIALIZATION_JSON: u8 = 0x10; // JSON serialization
-const NO_COMPRESSION: u8 = 0x00;
-const RESERVED: u8 = 0x00;
-
-// Event 定义
-const EVENT_START_CONNECTION: i32 = 1;
-const EVENT_CONNECTION_STARTED: i32 = 50;
-const EVENT_START_SESSION: i32 = 100;
-const EVENT_SESSION_STARTED: i32 = 150;
-const EVENT_TASK_REQUEST: i32 = 200;
-const EVENT_TTS_RESPONSE: i32 = 352;
-const EVENT_SESSION_FINISHED: i32 = 152;
-const EVENT_FINISH_SESSION: i32 = 102;
-const EVENT_FINISH_CONNECTION: i32 = 2;
+// 记录最近被中断的 question_id 数量
+const MAX_INTERRUPTED: usize = 32;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -92,10 +44,8 @@ async fn main() -> Result<()> {
     let access_token = std::env::var("DOUBAO_ACCESS_TOKEN")
         .wrap_err("DOUBAO_ACCESS_TOKEN environment variable not set")?;
 
-    println!("========app_id: {}, api_key: {}", app_id, api_key);
     let resource_id =
         std::env::var("DOUBAO_RESOURCE_ID").unwrap_or_else(|_| "seed-tts-2.0".to_string()); // 默认使用豆包2.0
-    println!("========resource_id: {resource_id}");
 
     let voice_type =
         std::env::var("VOICE_TYPE").unwrap_or_else(|_| "zh_female_cancan_mars_bigtts".to_string());
@@ -106,6 +56,11 @@ async fn main() -> Result<()> {
         .map(|f| ((f - 1.0) * 100.0) as i32) // 转换为 [-50, 100] 范围
         .unwrap_or(0);
 
+    // 请求负载 gzip 压缩 (默认关闭)
+    let gzip_requests = std::env::var("TTS_GZIP_REQUESTS")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
     let (mut node, mut events) = DoraNode::init_from_env()?;
 
     log::info!(
@@ -115,21 +70,94 @@ async fn main() -> Result<()> {
         resource_id
     );
 
-    while let Some(event) = events.recv() {
+    // 音频缓存目录, 默认在数据目录下
+    let cache_dir = std::env::var("TTS_CACHE_DIR")
+        .ok()
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(default_cache_dir);
+    let mut cache = match TtsCache::open(&cache_dir) {
+        Ok(cache) => {
+            log::info!("TTS cache: {}", cache.dir().display());
+            Some(cache)
+        }
+        Err(e) => {
+            log::warn!("TTS cache disabled: {:#}", e);
+            None
+        }
+    };
+
+    let mut tts = TtsConnection::new(TtsConfig {
+        app_id,
+        api_key,
+        access_token,
+        resource_id: resource_id.clone(),
+        speaker: voice_type.clone(),
+        speech_rate: speed_ratio,
+        gzip_requests,
+    });
+
+    // 预先建立连接, 失败时在第一次合成时重试
+    if let Err(e) = tts.connect().await {
+        log::warn!("TTS pre-connect failed: {:#}", e);
+    }
+
+    // 已被中断的回合, 排队中的文本直接丢弃
+    let mut interrupted: VecDeque<String> = VecDeque::new();
+    // 合成进行中收到的事件, 合成结束后再处理
+    let mut deferred: VecDeque<Event> = VecDeque::new();
+
+    loop {
+        let event = match deferred.pop_front() {
+            Some(event) => event,
+            None => match events.recv_async().await {
+                Some(event) => event,
+                None => break,
+            },
+        };
+
         match event {
             Event::Input { id, data, metadata } => {
                 let raw_data = extract_bytes(&data);
                 match id.as_str() {
+                    "control" => {
+                        if let Some(cmd) = decode_control(&raw_data) {
+                            remember_interrupt(&mut interrupted, &cmd);
+                            if cmd.is_replay() {
+                                replay_reply(
+                                    &mut node,
+                                    &metadata.parameters,
+                                    cache.as_ref(),
+                                    cmd.question_id(),
+                                )?;
+                            }
+                        }
+                    }
                     "text" => {
                         log::debug!("Received text input");
 
-                        // 尝试解析为 ComprehensiveResponse
-                        let text_to_convert = if let Ok(comprehensive_response) =
-                            serde_json::from_slice::<ComprehensiveResponse>(&raw_data)
+                        let question_id = string_param(&metadata.parameters, TURN_ID);
+                        if question_id
+                            .as_ref()
+                            .is_some_and(|qid| interrupted.contains(qid))
+                        {
+                            log::info!(
+                                "Skipping TTS for interrupted question_id={:?}",
+                                question_id
+                            );
+                            continue;
+                        }
+
+                        // 尝试解析为 ReplySegment (按句流式) 或 ComprehensiveResponse
+                        let text_to_convert = if let Ok(segment) =
+                            dora_messages::decode::<ReplySegment>(&raw_data)
+                        {
+                            segment.text
+                        } else if let Ok(comprehensive_response) =
+                            dora_messages::decode::<ComprehensiveResponse>(&raw_data)
                         {
                             comprehensive_response.reply_en
-                        } else if let Ok(text_input) =
-                            serde_json::from_slice::<TextInput>(&raw_data)
+                        } else if let Ok(text_input) = dora_messages::decode::<TextInput>(&raw_data)
                         {
                             text_input.text
                         } else {
@@ -142,133 +170,97 @@ async fn main() -> Result<()> {
                         }
 
                         log::info!("Converting to speech: {}", text_to_convert);
+                        let key =
+                            cache_key(&text_to_convert, &voice_type, speed_ratio, &resource_id);
 
-                        match perform_tts_websocket(
-                            &app_id,
-                            &api_key,
-                            &resource_id,
-                            &access_token,
-                            &voice_type,
-                            speed_ratio,
-                            &text_to_convert,
-                        )
-                        .await
-                        {
-                            Ok((audio_bytes, audio_metadata)) => {
-                                log::info!("TTS generated {} bytes", audio_bytes.len());
-
-                                // Convert MP3 bytes to Float32 mono samples for audio player
-                                // Decode MP3 to PCM samples using minimp3
-                                // minimp3 Frame.data contains interleaved i16 samples: [L, R, L, R,
-                                // ...] for stereo
-
-                                let mut decoder = Decoder::new(&audio_bytes[..]);
-                                let mut audio_samples: Vec<f32> = Vec::new();
-                                let mut actual_sample_rate = 24000; // Default from API config
-                                let mut total_channels = 1;
-
-                                loop {
-                                    match decoder.next_frame() {
-                                        Ok(Frame {
-                                            data,
-                                            sample_rate,
-                                            channels,
-                                            ..
-                                        }) => {
-                                            actual_sample_rate = sample_rate as u32;
-                                            total_channels = channels;
-
-                                            log::debug!(
-                                                "Frame: {} samples, {} channels, {} Hz",
-                                                data.len(),
-                                                channels,
-                                                sample_rate
-                                            );
-
-                                            // Convert i16 PCM to f32 normalized samples
-                                            // data is Vec<i16> with interleaved channels:
-                                            // [L,R,L,R,...] for stereo
-                                            if channels == 2 {
-                                                // Stereo: data is interleaved [L0, R0, L1, R1, ...]
-                                                // Convert to mono by averaging left and right
-                                                for chunk in data.chunks_exact(2) {
-                                                    let left = chunk[0] as f32 / 32768.0;
-                                                    let right = chunk[1] as f32 / 32768.0;
-                                                    audio_samples.push((left + right) / 2.0);
-                                                }
-                                            } else if channels == 1 {
-                                                // Mono: direct conversion
-                                                for &sample in &data {
-                                                    audio_samples.push(sample as f32 / 32768.0);
-                                                }
-                                            } else {
-                                                // Multi-channel: just take first channel
-                                                for chunk in data.chunks(channels) {
-                                                    if let Some(&sample) = chunk.first() {
-                                                        audio_samples.push(sample as f32 / 32768.0);
+                        let tts_result = match cache.as_ref().and_then(|cache| cache.get(&key)) {
+                            Some(audio) => {
+                                log::info!("TTS cache hit ({} bytes)", audio.len());
+                                Ok(Synthesis::cached(audio))
+                            }
+                            None => {
+                                // 合成期间监听 control, 用户插话时取消请求
+                                let tts_result = {
+                                    let request = tts.synthesize(&text_to_convert);
+                                    tokio::pin!(request);
+
+                                    loop {
+                                        tokio::select! {
+                                            result = &mut request => break Some(result),
+                                            event = events.recv_async() => match event {
+                                                Some(event) => {
+                                                    if let Some(cmd) = control_command(&event) {
+                                                        if cmd.is_interrupt() {
+                                                            remember_interrupt(&mut interrupted, &cmd);
+                                                            if cmd.interrupts(question_id.as_deref()) {
+                                                                break None;
+                                                            }
+                                                            continue;
+                                                        }
                                                     }
+                                                    deferred.push_back(event);
                                                 }
-                                            }
-                                        }
-                                        Err(minimp3::Error::Eof) => break,
-                                        Err(e) => {
-                                            log::error!("MP3 decode error: {}", e);
-                                            break;
+                                                None => break Some((&mut request).await),
+                                            },
                                         }
                                     }
+                                };
+
+                                let Some(tts_result) = tts_result else {
+                                    log::info!("TTS cancelled for question_id={:?}", question_id);
+
+                                    let status = json!({
+                                        "node": "doubao-tts",
+                                        "status": "interrupted",
+                                        TURN_ID: question_id,
+                                    });
+                                    let status_array =
+                                        StringArray::from(vec![status.to_string().as_str()]);
+                                    node.send_output(
+                                        "status".to_string().into(),
+                                        metadata.parameters.clone(),
+                                        status_array,
+                                    )?;
+                                    continue;
+                                };
+
+                                if let (Ok(synthesis), Some(cache)) = (&tts_result, cache.as_ref())
+                                {
+                                    if let Err(e) = cache.put(&key, &synthesis.audio) {
+                                        log::warn!("Failed to cache TTS audio: {}", e);
+                                    }
                                 }
+                                tts_result
+                            }
+                        };
 
-                                log::info!(
-                                    "Decoded {} MP3 bytes to {} mono samples at {}Hz (source: {} channels)",
-                                    audio_bytes.len(),
-                                    audio_samples.len(),
-                                    actual_sample_rate,
-                                    total_channels
-                                );
-
-                                let audio_array =
-                                    dora_node_api::arrow::array::ListArray::from_iter_primitive::<
-                                        dora_node_api::arrow::datatypes::Float32Type,
-                                        _,
-                                        _,
-                                    >(std::iter::once(Some(
-                                        audio_samples.iter().map(|&s| Some(s)),
-                                    )));
-
-                                // Add sample rate to metadata parameters for audio player
-                                let mut output_params = metadata.parameters.clone();
-                                output_params.insert(
-                                    "sample_rate".to_string(),
-                                    dora_node_api::Parameter::Integer(actual_sample_rate as i64),
-                                );
-
-                                node.send_output(
-                                    "audio_bytes".to_string().into(),
-                                    output_params.clone(),
-                                    audio_array,
-                                )?;
-
-                                let metadata_json = serde_json::to_string(&audio_metadata)?;
-                                let audio_metadata =
-                                    StringArray::from(vec![metadata_json.as_str()]);
+                        match tts_result {
+                            Ok(synthesis) => {
+                                log::info!("TTS generated {} bytes", synthesis.audio.len());
 
-                                node.send_output(
-                                    "audio_metadata".to_string().into(),
-                                    output_params.clone(),
-                                    audio_metadata,
-                                )?;
+                                if let (Some(cache), Some(qid)) =
+                                    (cache.as_mut(), question_id.as_deref())
+                                {
+                                    if let Err(e) = cache.record_reply(qid, &key) {
+                                        log::warn!("Failed to record reply audio: {}", e);
+                                    }
+                                }
 
-                                let status = json!({
-                                    "node": "doubao-tts",
-                                    "status": "ok",
-                                });
-                                let status_array =
-                                    StringArray::from(vec![status.to_string().as_str()]);
-                                node.send_output(
-                                    "status".to_string().into(),
-                                    output_params,
-                                    status_array,
-                                )?;
+                                // 应用按回合取最早的首包和最晚的完成时间;
+                                // 缓存命中没有合成时间, 不计入 TTS 延迟
+                                let mut params = metadata.parameters.clone();
+                                for (stage, at_ms) in [
+                                    (Stage::TtsFirstChunk, synthesis.first_chunk_ms),
+                                    (Stage::TtsComplete, synthesis.complete_ms),
+                                ] {
+                                    if let Some(at_ms) = at_ms {
+                                        params.insert(
+                                            stage.key().to_string(),
+                                            Parameter::Integer(at_ms),
+                                        );
+                                    }
+                                }
+                                send_audio(&mut node, &params, &synthesis.audio)?;
                             }
                             Err(e) => {
                                 log::error!("TTS failed: {}", e);
@@ -304,290 +296,223 @@ async fn main() -> Result<()> {
         }
     }
 
+    tts.close().await;
     Ok(())
 }
 
-fn extract_bytes(data: &ArrowData) -> Vec<u8> {
-    if let Some(array) = data.0.as_any().downcast_ref::<StringArray>() {
-        if array.len() > 0 {
-            return array.value(0).as_bytes().to_vec();
-        }
-    }
-    if let Some(array) = data.0.as_any().downcast_ref::<UInt8Array>() {
-        return array.values().to_vec();
-    }
-    Vec::new()
+/// 默认缓存目录: <文档>/colang/tts_cache (与 UI 默认数据目录一致)
+fn default_cache_dir() -> PathBuf {
+    dirs::document_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("colang")
+        .join("tts_cache")
 }
 
-async fn perform_tts_websocket(
-    app_id: &str,
-    api_key: &str,
-    resource_id: &str,
-    access_token: &str,
-    speaker: &str,
-    speech_rate: i32,
-    text: &str,
-) -> Result<(Vec<u8>, AudioMetadata)> {
-    let url = "wss://openspeech.bytedance.com/api/v3/tts/bidirection";
-
-    // 根据文档,认证参数应该在 HTTP 头中,而不是 URL 参数中
-    // 生成唯一的连接 ID (用于追踪)
-    let connect_id = uuid::Uuid::new_v4().to_string();
-
-    // 创建 Authorization header (格式: "Bearer;{token}")
-    let authorization = format!("Bearer;{}", api_key);
-
-    // 使用 tokio_tungstenite 的 http 模块创建请求
-    let request = tokio_tungstenite::tungstenite::http::Request::builder()
-        .uri(url)
-        .header("Host", "openspeech.bytedance.com")
-        .header("Connection", "Upgrade")
-        .header("Upgrade", "websocket")
-        .header("Sec-WebSocket-Version", "13")
-        .header(
-            "Sec-WebSocket-Key",
-            tokio_tungstenite::tungstenite::handshake::client::generate_key(),
-        )
-        .header("Authorization", &authorization)
-        .header("X-Api-App-Key", app_id)
-        .header("X-Api-Access-Key", access_token)
-        .header("X-Api-Resource-Id", resource_id)
-        .header("X-Api-Connect-Id", &connect_id)
-        .body(())?;
-
-    // 建立 WebSocket 连接
-    let (ws_stream, response) = connect_async(request).await.unwrap();
-
-    // 打印响应头中的 X-Tt-Logid 便于调试
-    if let Some(logid) = response.headers().get("X-Tt-Logid") {
-        log::info!("X-Tt-Logid: {:?}", logid);
-    }
+/// 解码 mp3 并输出给音频播放器 (audio_bytes + audio_metadata + status)
+fn send_audio(node: &mut DoraNode, params: &MetadataParameters, audio_bytes: &[u8]) -> Result<()> {
+    // Convert MP3 bytes to Float32 mono samples for audio player
+    // Decode MP3 to PCM samples using minimp3
+    // minimp3 Frame.data contains interleaved i16 samples: [L, R, L, R, ...] for stereo
 
-    let (mut write, mut read) = ws_stream.split();
-
-    // 生成唯一 ID
-    let session_id = uuid::Uuid::new_v4().to_string();
-
-    // 1. 发送 StartConnection
-    let start_conn_frame = build_event_frame(EVENT_START_CONNECTION, None, json!({}));
-    write.send(Message::Binary(start_conn_frame)).await?;
-    log::debug!("Sent StartConnection");
-
-    // 等待 ConnectionStarted
-    wait_for_event(&mut read, EVENT_CONNECTION_STARTED).await?;
-    log::debug!("Received ConnectionStarted");
-
-    // 2. 发送 StartSession
-    let user_id = uuid::Uuid::new_v4().to_string();
-    let start_session_payload = json!({
-        "user": {
-            "uid": user_id
-        },
-        "event": EVENT_START_SESSION,
-        "namespace": "BidirectionalTTS",
-        "req_params": {
-            "speaker": speaker,
-            "audio_params": {
-                "format": "mp3",
-                "sample_rate": 48000,
-                "speech_rate": speech_rate,
-                "enable_timestamp": true
-            },
-            "additions": json!({
-                "disable_markdown_filter": false
-            }).to_string()
-        }
-    });
-    let start_session_frame = build_event_frame(
-        EVENT_START_SESSION,
-        Some(&session_id),
-        start_session_payload,
-    );
-    write.send(Message::Binary(start_session_frame)).await?;
-    log::debug!("Sent StartSession");
-
-    // 等待 SessionStarted
-    wait_for_event(&mut read, EVENT_SESSION_STARTED).await?;
-    log::debug!("Received SessionStarted");
-
-    // 3. 发送 TaskRequest (文本)
-    let task_payload = json!({
-        "user": {
-            "uid": user_id
-        },
-        "event": EVENT_TASK_REQUEST,
-        "namespace": "BidirectionalTTS",
-        "req_params": {
-            "speaker": speaker,
-            "audio_params": {
-                "format": "mp3",
-                "sample_rate": 24000,
-                "speech_rate": speech_rate,
-                "enable_timestamp": true
-            },
-            "text": text,
-            "additions": json!({
-                "disable_markdown_filter": false
-            }).to_string()
-        }
-    });
-    let task_frame = build_event_frame(EVENT_TASK_REQUEST, Some(&session_id), task_payload);
-    println!("==================6");
-    write.send(Message::Binary(task_frame)).await?;
-    log::debug!("Sent TaskRequest with text");
-
-    println!("==================7");
-    // 4. 接收音频数据
-    let mut audio_data = Vec::new();
-    loop {
-        match read.next().await {
-            Some(Ok(Message::Binary(data))) => {
-                println!("==================8");
-                if data.len() < 4 {
-                    println!("==================9");
-                    continue;
-                }
+    let mut decoder = Decoder::new(audio_bytes);
+    let mut audio_samples: Vec<f32> = Vec::new();
+    let mut actual_sample_rate = 24000; // Default from API config
+    let mut total_channels = 1;
 
-                println!("==================10");
-                let event = parse_event(&data)?;
-                println!("==================10 -- 0?");
-                log::debug!("Received event: {}", event);
-                println!("Received eventd: {}", event);
-
-                match event {
-                    EVENT_TTS_RESPONSE => {
-                        println!("==================11");
-                        // 提取音频数据
-                        if let Some(audio) = extract_audio_from_frame(&data) {
-                            println!("==================12 audio {}", audio.len());
-                            audio_data.extend_from_slice(&audio);
-                            println!("==================12 audio 1");
-                        }
+    loop {
+        match decoder.next_frame() {
+            Ok(Frame {
+                data,
+                sample_rate,
+                channels,
+                ..
+            }) => {
+                actual_sample_rate = sample_rate as u32;
+                total_channels = channels;
+
+                log::debug!(
+                    "Frame: {} samples, {} channels, {} Hz",
+                    data.len(),
+                    channels,
+                    sample_rate
+                );
+
+                // Convert i16 PCM to f32 normalized samples
+                // data is Vec<i16> with interleaved channels: [L,R,L,R,...] for stereo
+                if channels == 2 {
+                    // Stereo: data is interleaved [L0, R0, L1, R1, ...]
+                    // Convert to mono by averaging left and right
+                    for chunk in data.chunks_exact(2) {
+                        let left = chunk[0] as f32 / 32768.0;
+                        let right = chunk[1] as f32 / 32768.0;
+                        audio_samples.push((left + right) / 2.0);
                     }
-                    EVENT_SESSION_FINISHED => {
-                        println!("==================13");
-                        log::debug!("Session finished");
-                        break;
+                } else if channels == 1 {
+                    // Mono: direct conversion
+                    for &sample in &data {
+                        audio_samples.push(sample as f32 / 32768.0);
                     }
-                    _ => {
-                        println!("xxxxxxxxxxxx ? 4");
-                        println!("Received evenxxx: {} {:?}", event, data);
+                } else {
+                    // Multi-channel: just take first channel
+                    for chunk in data.chunks(channels) {
+                        if let Some(&sample) = chunk.first() {
+                            audio_samples.push(sample as f32 / 32768.0);
+                        }
                     }
                 }
-                println!("xxxxxxxxxxxx ? 5");
-            }
-            Some(Ok(Message::Text(txt))) => {
-                log::warn!("Received unexpected text message: {}", txt);
             }
-            // Some(Ok(Message::Ping(_))) => {
-            //     log::warn!("Received  ping message");
-            //     break;
-            // }
-            Some(Err(e)) => {
-                log::error!("WebSocket error: {}", e);
-                break;
-            }
-            None => {
-                log::debug!("WebSocket stream ended");
-                break;
-            }
-            _ => {
+            Err(minimp3::Error::Eof) => break,
+            Err(e) => {
+                log::error!("MP3 decode error: {}", e);
                 break;
             }
         }
     }
 
-    // 5. 发送 FinishSession
-    let finish_session_frame =
-        build_event_frame(EVENT_FINISH_SESSION, Some(&session_id), json!({}));
-    write.send(Message::Binary(finish_session_frame)).await.ok();
-
-    println!("==================x 9");
-    // 6. 发送 FinishConnection
-    let finish_conn_frame = build_event_frame(EVENT_FINISH_CONNECTION, None, json!({}));
-    write.send(Message::Binary(finish_conn_frame)).await.ok();
-
-    println!("==================x 10");
-    // Save audio data to test_output.mp3 in project root
-    let output_path = std::path::Path::new("test_output.mp3");
-    if fs::metadata(output_path).is_ok() {
-        fs::remove_file(output_path).wrap_err("Failed to remove existing test_output.mp3")?;
-    }
-    std::fs::write(output_path, &audio_data)
-        .wrap_err("Failed to write audio data to test_output.mp3")?;
     log::info!(
-        "Audio saved to test_output.mp3 ({} bytes)",
-        audio_data.len()
+        "Decoded {} MP3 bytes to {} mono samples at {}Hz (source: {} channels)",
+        audio_bytes.len(),
+        audio_samples.len(),
+        actual_sample_rate,
+        total_channels
     );
 
-    // Calculate actual duration using minimp3
-    let (duration_ms, sample_rate) = calculate_mp3_duration(&audio_data)?;
+    let audio_array = dora_node_api::arrow::array::ListArray::from_iter_primitive::<
+        dora_node_api::arrow::datatypes::Float32Type,
+        _,
+        _,
+    >(std::iter::once(Some(
+        audio_samples.iter().map(|&s| Some(s)),
+    )));
+
+    // Add sample rate to metadata parameters for audio player
+    let mut output_params = params.clone();
+    output_params.insert(
+        "sample_rate".to_string(),
+        dora_node_api::Parameter::Integer(actual_sample_rate as i64),
+    );
 
-    let metadata = AudioMetadata {
+    node.send_output(
+        "audio_bytes".to_string().into(),
+        output_params.clone(),
+        audio_array,
+    )?;
+
+    // Calculate actual duration using minimp3
+    let (duration_ms, sample_rate) = calculate_mp3_duration(audio_bytes)?;
+    let audio_metadata = AudioMetadata {
+        version: SCHEMA_VERSION,
         duration_ms,
         format: "mp3".to_string(),
         sample_rate,
-        bytes: audio_data.len(),
+        bytes: audio_bytes.len(),
     };
-
-    Ok((audio_data, metadata))
+    let metadata_json = dora_messages::encode(&audio_metadata)?;
+    let audio_metadata = StringArray::from(vec![metadata_json.as_str()]);
+
+    node.send_output(
+        "audio_metadata".to_string().into(),
+        output_params.clone(),
+        audio_metadata,
+    )?;
+
+    let status = json!({
+        "node": "doubao-tts",
+        "status": "ok",
+    });
+    let status_array = StringArray::from(vec![status.to_string().as_str()]);
+    node.send_output("status".to_string().into(), output_params, status_array)?;
+    Ok(())
 }
 
-fn build_event_frame(event: i32, session_id: Option<&str>, payload: serde_json::Value) -> Vec<u8> {
-    let mut frame = Vec::new();
-
-    // Header (4 bytes)
-    frame.push(PROTOCOL_VERSION); // byte 0
-    frame.push(MSG_TYPE_FULL_CLIENT); // byte 1
-    frame.push(SERIALIZATION_JSON | NO_COMPRESSION); // byte 2
-    frame.push(RESERVED); // byte 3
+/// 从缓存重播一个老师回合 (未指定 question_id 时为最近一次)
+///
+/// 重播使用新的 question_id, 插话时可以单独清除, 不影响原回合
+fn replay_reply(
+    node: &mut DoraNode,
+    params: &MetadataParameters,
+    cache: Option<&TtsCache>,
+    question_id: Option<&str>,
+) -> Result<()> {
+    let Some(cache) = cache else {
+        log::warn!("Replay requested but TTS cache is disabled");
+        return Ok(());
+    };
+    let Some(question_id) = question_id.or_else(|| cache.last_reply()) else {
+        log::warn!("Replay requested but no reply has been cached yet");
+        return Ok(());
+    };
+    let Some(segments) = cache.reply_audio(question_id) else {
+        log::warn!("No cached audio for question_id={}", question_id);
+
+        let status = json!({
+            "node": "doubao-tts",
+            "status": "replay_missing",
+            TURN_ID: question_id,
+        });
+        let status_array = StringArray::from(vec![status.to_string().as_str()]);
+        node.send_output("status".to_string().into(), params.clone(), status_array)?;
+        return Ok(());
+    };
 
-    // Event number (4 bytes, big-endian)
-    frame.extend_from_slice(&event.to_be_bytes());
+    log::info!(
+        "Replaying {} cached segments for question_id={}",
+        segments.len(),
+        question_id
+    );
 
-    // Session ID (if provided)
-    if let Some(sid) = session_id {
-        let sid_bytes = sid.as_bytes();
-        frame.extend_from_slice(&(sid_bytes.len() as u32).to_be_bytes());
-        frame.extend_from_slice(sid_bytes);
+    let mut replay_params = params.clone();
+    replay_params.insert(
+        TURN_ID.to_string(),
+        Parameter::String(format!("replay-{}", uuid::Uuid::new_v4())),
+    );
+    replay_params.insert(
+        "replay_of".to_string(),
+        Parameter::String(question_id.to_string()),
+    );
+    for audio in segments {
+        send_audio(node, &replay_params, &audio)?;
     }
+    Ok(())
+}
 
-    // Payload
-    let payload = payload.to_string();
-    let payload = payload.as_bytes();
-    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
-    frame.extend_from_slice(payload);
+fn control_command(event: &Event) -> Option<ControlCommand> {
+    match event {
+        Event::Input { id, data, .. } if id.as_str() == "control" => {
+            decode_control(&extract_bytes(data))
+        }
+        _ => None,
+    }
+}
 
-    frame
+fn decode_control(raw_data: &[u8]) -> Option<ControlCommand> {
+    dora_messages::decode::<ControlCommand>(raw_data).ok()
 }
 
-async fn wait_for_event(
-    read: &mut futures_util::stream::SplitStream<
-        tokio_tungstenite::WebSocketStream<
-            tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>,
-        >,
-    >,
-    expected_event: i32,
-) -> Result<()> {
-    while let Some(Ok(Message::Binary(data))) = read.next().await {
-        if data.len() >= 8 {
-            let event = i32::from_be_bytes([data[4], data[5], data[6], data[7]]);
-            if event == expected_event {
-                return Ok(());
-            }
+/// 记录插话中断的 question_id, 之后到达的同一回合文本不再合成
+fn remember_interrupt(interrupted: &mut VecDeque<String>, cmd: &ControlCommand) {
+    if !cmd.is_interrupt() {
+        return;
+    }
+    log::info!("Barge-in, interrupting question_id={:?}", cmd.question_id());
+    if let Some(qid) = cmd.question_id() {
+        interrupted.push_back(qid.to_string());
+        if interrupted.len() > MAX_INTERRUPTED {
+            interrupted.pop_front();
         }
     }
-    eyre::bail!("Expected event {} not received", expected_event)
 }
 
-fn parse_event(data: &[u8]) -> Result<i32> {
-    println!("==================parse_event {}", data.len());
-    if data.len() < 8 {
-        println!("==================parse_event too short");
-        eyre::bail!("Frame too short");
+fn extract_bytes(data: &ArrowData) -> Vec<u8> {
+    if let Some(array) = data.0.as_any().downcast_ref::<StringArray>() {
+        if array.len() > 0 {
+            return array.value(0).as_bytes().to_vec();
+        }
     }
-    println!("==================parse_event2");
-    Ok(i32::from_be_bytes([data[4], data[5], data[6], data[7]]))
+    if let Some(array) = data.0.as_any().downcast_ref::<UInt8Array>() {
+        return array.values().to_vec();
+    }
+    Vec::new()
 }
 
 fn calculate_mp3_duration(audio_data: &[u8]) -> Result<(u64, u32)> {
@@ -624,36 +549,3 @@ fn calculate_mp3_duration(audio_data: &[u8]) -> Result<(u64, u32)> {
 
     Ok((duration_ms, sample_rate))
 }
-
-fn extract_audio_from_frame(data: &[u8]) -> Option<Vec<u8>> {
-    // 检查消息类型
-    if data.len() < 4 {
-        return None;
-    }
-
-    let msg_type = data[1];
-
-    // 如果是 audio-only 响应 (0xB4)
-    if msg_type == MSG_TYPE_AUDIO_ONLY {
-        println!("==MSG_TYPE_AUDIO_ONLY");
-        // Header (4 bytes) + Event (4 bytes) + Session ID length (4 bytes)
-        if data.len() < 12 {
-            return None;
-        }
-
-        let session_id_len = u32::from_be_bytes([data[8], data[9], data[10], data[11]]) as usize;
-        let audio_offset = 12 + session_id_len + 4; // +4 for payload size
-
-        if data.len() > audio_offset {
-            return Some(data[audio_offset..].to_vec());
-        }
-    }
-    // 如果是 full-server 响应,可能包含混合数据
-    else if msg_type == MSG_TYPE_FULL_SERVER {
-        println!("==MSG_TYPE_FULL_SERVER");
-        // 需要解析 JSON 然后提取音频
-        // 这里简化处理,返回 None
-    }
-
-    None
-}
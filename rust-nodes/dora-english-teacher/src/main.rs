@@ -1,36 +1,39 @@
 // Dora Node: English Teacher
-// AI 英语老师 - 使用豆包 API 生成对话回复和语法分析
-// 使用 structured outputs 一次性输出: 用户文本 + AI回复 + 语法分析
+// AI 英语老师 - 生成对话回复和语法分析
+// LLM 通过 dora-maas-client 的 Config::route_model 路由 (MAAS_CONFIG_PATH 指向的 TOML),
+// 可以使用豆包, DeepSeek, 通义千问, OpenAI 等 provider
+// 1. 流式生成回复, 用 StreamSegmenter 按句切分, 每句立即输出 reply_segment (TTS 可以马上开始)
+// 2. 回复结束后用 structured outputs 生成翻译 + 语法分析, 作为稍晚的 json_data 输出
+//    (provider 不支持 json_schema 时, 在提示中给出 schema 并从回复中解析 JSON)
+// 输出: reply_segment (JSON: ReplySegment)
 // 输出: json_data (JSON: {session_id, user_text, reply_text, issues[], pronunciation_issues[]})
+// 每个回合带 question_id 元数据 (回合 ID, 由 mofa-mic-input / mofa-text-input 生成并经 ASR 传入);
+// 收到 control/interrupt 时取消进行中的请求
 
 use std::collections::VecDeque;
-use std::sync::Mutex;
-
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use dora_maas_client::client::ChatClient;
+use dora_maas_client::config::Config;
+use dora_maas_client::segmenter::StreamSegmenter;
+use dora_messages::turn::{SESSION_ID, TURN_ID, string_param};
+use dora_messages::{
+    AsrOutput, ComprehensiveResponse, ControlCommand, ReplySegment, SCHEMA_VERSION, Stage,
+    TextIssue, WordTiming, timing,
+};
 use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
-use dora_node_api::{DoraNode, Event};
-use eyre::{Context, Result};
-use reqwest::{Client, header};
+use dora_node_api::{ArrowData, DoraNode, Event, EventStream, MetadataParameters, Parameter};
+use eyre::Result;
+use outfox_openai::spec::{
+    ChatCompletionRequestAssistantMessage, ChatCompletionRequestAssistantMessageContent,
+    ChatCompletionRequestMessage, ChatCompletionRequestSystemMessage,
+    ChatCompletionRequestUserMessage, ChatCompletionRequestUserMessageContent,
+    CreateChatCompletionRequest, PartibleTextContent,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
-
-/// ASR 输出格式
-#[derive(Debug, Serialize, Deserialize)]
-struct AsrOutput {
-    text: String,
-    confidence: f32,
-    #[serde(default)]
-    words: Vec<WordTiming>,
-    session_id: Option<String>,
-}
-
-/// 词汇时序信息
-#[derive(Debug, Serialize, Deserialize)]
-struct WordTiming {
-    word: String,
-    start_time: f64,
-    end_time: f64,
-    confidence: f32,
-}
+use tokio::sync::mpsc;
 
 /// 对话消息
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,70 +72,103 @@ impl ConversationHistory {
         self.trim_history();
     }
 
+    /// 回合没有完整回复 (插话或出错): 已说出的部分记为助手回复,
+    /// 一句都没说则撤回用户消息, 保持 user/assistant 成对
+    fn end_partial_turn(&mut self, spoken: &str) {
+        if !spoken.is_empty() {
+            self.add_assistant_message(spoken);
+        } else if self.messages.back().is_some_and(|m| m.role == "user") {
+            self.messages.pop_back();
+        }
+    }
+
     fn trim_history(&mut self) {
         while self.messages.len() > self.max_history * 2 {
             self.messages.pop_front();
         }
     }
 
-    fn get_messages(&self) -> Vec<&ChatMessage> {
-        self.messages.iter().collect()
+    /// 当前历史的副本, 请求期间不必持有锁
+    fn get_messages(&self) -> Vec<ChatMessage> {
+        self.messages.iter().cloned().collect()
     }
 }
 
-/// 综合响应输出 (structured output from Doubao)
-/// 一次性输出：用户文本 + AI回复 + 语法分析
-#[derive(Debug, Serialize, Deserialize)]
-struct ComprehensiveResponse {
-    session_id: String,
-    use_lang: String,       // 用户文本语言: "en" | "zh" | "mix"
-    original_en: String,    // 用户原始文本（英文）
-    original_zh: String,    // 用户原始文本（中文）
-    reply_en: String,       // AI对该消息的英文回复
-    reply_zh: String,       // AI对该消息的中文回复
-    issues: Vec<TextIssue>, // 语法/用词问题
-    timestamp: i64,
+/// 路由后的 LLM: provider 客户端 + 实际模型名
+struct Llm {
+    client: Arc<dyn ChatClient>,
+    model: String,
+    /// provider 支持流式输出
+    streaming: bool,
+    /// provider 支持 JSON schema structured outputs
+    structured_output: bool,
 }
 
-/// 文本问题
-#[derive(Debug, Serialize, Deserialize)]
-struct TextIssue {
-    #[serde(rename = "type")]
-    issue_type: String, // grammar | word_choice | suggestion
-    original: String,
-    suggested: String,
-    description_en: String,
-    description_zh: String,
-    severity: String, // low | medium | high
-
-    #[serde(default)]
-    start_position: Option<i32>,
-    #[serde(default)]
-    end_position: Option<i32>,
+impl Llm {
+    /// 按 default_model 路由到 provider
+    fn from_config(config: &Config) -> Result<Self> {
+        let (provider_id, model) = config
+            .route_model(&config.default_model)
+            .ok_or_else(|| eyre::eyre!("No route found for model: {}", config.default_model))?;
+        let provider = config
+            .provider(&provider_id)
+            .ok_or_else(|| eyre::eyre!("Unknown provider: {}", provider_id))?;
+        let client = config
+            .create_clients()
+            .remove(&provider_id)
+            .ok_or_else(|| eyre::eyre!("No client found for provider: {}", provider_id))?;
+
+        Ok(Self {
+            client,
+            model,
+            streaming: provider.supports_streaming(),
+            structured_output: config.supports_structured_output(&config.default_model),
+        })
+    }
+
+    fn request(&self, messages: Vec<ChatCompletionRequestMessage>) -> CreateChatCompletionRequest {
+        let mut request = CreateChatCompletionRequest::new(self.model.clone(), messages);
+        request.temperature = Some(0.7);
+        request
+    }
+
+    /// 非流式请求, 返回回复文本
+    async fn complete_text(&self, request: CreateChatCompletionRequest) -> Result<String> {
+        let response = self.client.complete(request).await?;
+        response
+            .choices
+            .into_iter()
+            .next()
+            .and_then(|choice| choice.message.content)
+            .ok_or_else(|| eyre::eyre!("No content in response"))
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
-    let api_key =
-        std::env::var("DOUBAO_API_KEY").wrap_err("DOUBAO_API_KEY environment variable not set")?;
-    log::info!("DOUBAO_API_KEY {api_key}");
-
-    let model =
-        std::env::var("DOUBAO_MODEL").unwrap_or_else(|_| "doubao-seed-1-8-251228".to_string());
+    // MAAS_CONFIG_PATH 指定的 provider/模型配置
+    let config = Config::load()?;
+    let llm = Llm::from_config(&config)?;
 
-    let system_prompt =
-        std::env::var("SYSTEM_PROMPT").unwrap_or_else(|_| DEFAULT_SYSTEM_PROMPT.to_string());
+    // 优先级: SYSTEM_PROMPT 环境变量 > 配置文件 > 内置提示
+    let system_prompt = std::env::var("SYSTEM_PROMPT")
+        .ok()
+        .or_else(|| Some(config.system_prompt.clone()))
+        .filter(|prompt| !prompt.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SYSTEM_PROMPT.to_string());
 
     let max_history: usize = std::env::var("MAX_HISTORY")
-        .unwrap_or_else(|_| "10".to_string())
-        .parse()
-        .unwrap_or(10);
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(config.max_history_exchanges);
 
-    let client = Client::builder()
-        .timeout(std::time::Duration::from_secs(60))
-        .build()?;
+    // 没有标点时, 超过该词数也切出一句
+    let max_segment_words: usize = std::env::var("MAX_SEGMENT_WORDS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(20);
 
     let (mut node, mut events) = DoraNode::init_from_env()?;
 
@@ -140,9 +176,25 @@ async fn main() -> Result<()> {
     let history = Mutex::new(ConversationHistory::new(max_history));
     let current_session: Mutex<Option<String>> = Mutex::new(None);
 
-    log::info!("English Teacher node started (model: {})", model);
+    log::info!(
+        "English Teacher node started (model: {} -> {}, structured output: {})",
+        config.default_model,
+        llm.model,
+        llm.structured_output
+    );
+
+    // 请求进行中收到的事件, 请求结束后再处理
+    let mut deferred: VecDeque<Event> = VecDeque::new();
+
+    loop {
+        let event = match deferred.pop_front() {
+            Some(event) => event,
+            None => match events.recv_async().await {
+                Some(event) => event,
+                None => break,
+            },
+        };
 
-    while let Some(event) = events.recv() {
         match event {
             Event::Input { id, data, metadata } => {
                 let raw_data = extract_bytes(&data);
@@ -151,7 +203,7 @@ async fn main() -> Result<()> {
                         // 处理 ASR 输出 (JSON 格式)
                         log::info!("Received ASR text");
                         if let Some(bytes) = &raw_data {
-                            match serde_json::from_slice::<AsrOutput>(bytes) {
+                            match dora_messages::decode::<AsrOutput>(bytes) {
                                 Ok(asr_result) => {
                                     if asr_result.text.trim().is_empty() {
                                         continue;
@@ -184,13 +236,20 @@ async fn main() -> Result<()> {
                             continue;
                         }
                     }
+                    "control" => {
+                        // 没有进行中的回合, 中断无需处理
+                        log::debug!("Received control while idle");
+                        continue;
+                    }
                     _ => {
                         log::warn!("Received unknown input: {}", id);
                         continue;
                     }
                 };
 
-                // 更新或获取 session ID
+                // 更新或获取 session ID (ASR 结果或采集端 metadata 中的会话 ID)
+                let session_id =
+                    session_id.or_else(|| string_param(&metadata.parameters, SESSION_ID));
                 let session = {
                     let mut current = current_session.lock().unwrap();
                     if let Some(sid) = session_id {
@@ -212,41 +271,134 @@ async fn main() -> Result<()> {
                     hist.add_user_message(&user_text);
                 }
 
-                // 使用 structured outputs 一次性生成回复和分析
-                let response = generate_comprehensive_response(
-                    &client,
-                    &api_key,
-                    &model,
-                    &system_prompt,
-                    &user_text,
-                    &history.lock().unwrap(),
-                    &session,
-                    words.as_ref(),
-                )
-                .await;
+                // 本回合 ID, 随 reply_segment 传给 TTS 和播放器 (用于插话时清除音频)
+                // 采集端已生成时沿用, 旧数据流的输入没有时在此生成
+                let question_id = string_param(&metadata.parameters, TURN_ID)
+                    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+                let mut output_params = metadata.parameters.clone();
+                output_params.insert(TURN_ID.to_string(), Parameter::String(question_id.clone()));
+                output_params.insert(SESSION_ID.to_string(), Parameter::String(session.clone()));
+
+                // 1. 流式生成回复, 每句话立即发给 TTS
+                // 等待期间监听 control, 用户插话时取消请求
+                let mut segmenter = StreamSegmenter::new(max_segment_words);
+                let mut segment_index = 0u32;
+                // 已发给 TTS 的句子, 回合中断时记入历史
+                let mut spoken = Vec::new();
+                let reply = {
+                    let messages = history.lock().unwrap().get_messages();
+                    let request = stream_reply(&llm, &system_prompt, &messages, |delta| {
+                        // 首个 token 的时间, 随每句 reply_segment 传下去 (延迟统计)
+                        if !output_params.contains_key(Stage::LlmFirstToken.key()) {
+                            output_params.insert(
+                                Stage::LlmFirstToken.key().to_string(),
+                                Parameter::Integer(timing::now_ms()),
+                            );
+                        }
+                        if let Some(sentence) = segmenter.add_chunk(delta) {
+                            send_segment(
+                                &mut node,
+                                &output_params,
+                                &session,
+                                &question_id,
+                                &mut segment_index,
+                                &sentence,
+                                false,
+                            )?;
+                            spoken.push(sentence);
+                        }
+                        Ok(())
+                    });
+                    run_interruptible(request, &mut events, &mut deferred, &question_id).await
+                };
+
+                let Some(reply) = reply else {
+                    history.lock().unwrap().end_partial_turn(&spoken.join(" "));
+                    send_interrupted(&mut node, &output_params, &session, &question_id)?;
+                    continue;
+                };
+
+                let reply_en = match reply {
+                    Ok(reply_en) => {
+                        output_params.insert(
+                            Stage::LlmComplete.key().to_string(),
+                            Parameter::Integer(timing::now_ms()),
+                        );
+                        // 剩余文本作为最后一句
+                        let rest = segmenter.flush().unwrap_or_default();
+                        send_segment(
+                            &mut node,
+                            &output_params,
+                            &session,
+                            &question_id,
+                            &mut segment_index,
+                            &rest,
+                            true,
+                        )?;
+                        reply_en
+                    }
+                    Err(e) => {
+                        log::error!("Failed to stream reply: {}", e);
+                        history.lock().unwrap().end_partial_turn(&spoken.join(" "));
+                        // 已发出部分句子时用空的最后一句结束本回合, 未说完的半句丢弃
+                        if segment_index > 0 {
+                            segmenter.flush();
+                            send_segment(
+                                &mut node,
+                                &output_params,
+                                &session,
+                                &question_id,
+                                &mut segment_index,
+                                "",
+                                true,
+                            )?;
+                        }
+                        send_error(&mut node, &metadata.parameters, &e)?;
+                        continue;
+                    }
+                };
+
+                log::info!("AI reply (en): {}", reply_en);
+
+                // 添加 AI 回复到历史
+                {
+                    let mut hist = history.lock().unwrap();
+                    hist.add_assistant_message(&reply_en);
+                }
+
+                // 2. 翻译 + 语法分析, 作为稍晚的 json_data 发送
+                let response = {
+                    let messages = history.lock().unwrap().get_messages();
+                    let request = analyze_turn(
+                        &llm,
+                        &system_prompt,
+                        &reply_en,
+                        &messages,
+                        &session,
+                        words.as_ref(),
+                    );
+                    run_interruptible(request, &mut events, &mut deferred, &question_id).await
+                };
+
+                let Some(response) = response else {
+                    send_interrupted(&mut node, &output_params, &session, &question_id)?;
+                    continue;
+                };
+
                 match response {
                     Ok(response) => {
-                        log::info!("AI reply (en): {}", response.reply_en);
                         log::info!("AI reply (zh): {}", response.reply_zh);
                         log::info!("Found issues: {:#?}", response.issues,);
 
-                        // 添加 AI 回复到历史
-                        {
-                            let mut hist = history.lock().unwrap();
-                            hist.add_assistant_message(&response.reply_en);
-                        }
-
-                        println!("====================techer 2");
                         // 发送综合 JSON 输出 (json_data)
-                        let output_str = serde_json::to_string(&response)?;
+                        let output_str = dora_messages::encode(&response)?;
                         let output_array = StringArray::from(vec![output_str.as_str()]);
                         node.send_output(
                             "json_data".to_string().into(),
-                            metadata.parameters.clone(),
+                            output_params.clone(),
                             output_array,
                         )?;
 
-                        println!("====================techer 3");
                         // 发送状态
                         let status = json!({
                             "node": "english-teacher",
@@ -255,28 +407,11 @@ async fn main() -> Result<()> {
                         });
 
                         let status_array = StringArray::from(vec![status.to_string().as_str()]);
-                        println!("====================techer 4");
-                        node.send_output(
-                            "status".to_string().into(),
-                            metadata.parameters.clone(),
-                            status_array,
-                        )?;
+                        node.send_output("status".to_string().into(), output_params, status_array)?;
                     }
                     Err(e) => {
                         log::error!("Failed to generate comprehensive response: {}", e);
-
-                        let status = json!({
-                            "node": "english-teacher",
-                            "status": "error",
-                            "error": e.to_string(),
-                        });
-
-                        let status_array = StringArray::from(vec![status.to_string().as_str()]);
-                        node.send_output(
-                            "status".to_string().into(),
-                            metadata.parameters.clone(),
-                            status_array,
-                        )?;
+                        send_error(&mut node, &metadata.parameters, &e)?;
                     }
                 }
             }
@@ -309,37 +444,100 @@ Guidelines:
 
 Remember: Your goal is to help the user practice speaking naturally, not to lecture them."#;
 
-/// 使用 structured outputs 一次性生成 AI 回复和语法分析
-async fn generate_comprehensive_response(
-    client: &Client,
-    api_key: &str,
-    model: &str,
+/// 系统提示 + 对话历史
+fn build_messages(
+    system_prompt: String,
+    history: &[ChatMessage],
+) -> Vec<ChatCompletionRequestMessage> {
+    let mut messages = vec![ChatCompletionRequestMessage::System(
+        ChatCompletionRequestSystemMessage {
+            content: PartibleTextContent::Text(system_prompt),
+            name: None,
+        },
+    )];
+
+    // Add conversation history
+    for msg in history {
+        let message = if msg.role == "assistant" {
+            ChatCompletionRequestMessage::Assistant(ChatCompletionRequestAssistantMessage {
+                content: Some(ChatCompletionRequestAssistantMessageContent::Text(
+                    msg.content.clone(),
+                )),
+                name: None,
+                tool_calls: None,
+                audio: None,
+                refusal: None,
+            })
+        } else {
+            ChatCompletionRequestMessage::User(ChatCompletionRequestUserMessage {
+                content: ChatCompletionRequestUserMessageContent::Text(msg.content.clone()),
+                name: None,
+            })
+        };
+        messages.push(message);
+    }
+
+    messages
+}
+
+/// 流式生成 AI 回复, 每个文本增量调用 on_delta, 返回完整回复
+async fn stream_reply(
+    llm: &Llm,
+    system_prompt: &str,
+    history: &[ChatMessage],
+    mut on_delta: impl FnMut(&str) -> Result<()>,
+) -> Result<String> {
+    let request = llm.request(build_messages(system_prompt.to_string(), history));
+
+    if !llm.streaming {
+        // 不支持流式的 provider: 整个回复作为一个增量
+        let reply = llm.complete_text(request).await?;
+        on_delta(&reply)?;
+        return Ok(reply);
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+    let completion = llm.client.complete_streaming(request, tx);
+    tokio::pin!(completion);
+
+    loop {
+        tokio::select! {
+            result = &mut completion => {
+                // 请求结束前已发出的增量
+                while let Ok(delta) = rx.try_recv() {
+                    on_delta(&delta)?;
+                }
+                let (reply, _) = result?;
+                return Ok(reply);
+            }
+            Some(delta) = rx.recv() => {
+                if !delta.is_empty() {
+                    on_delta(&delta)?;
+                }
+            }
+        }
+    }
+}
+
+/// 使用 structured outputs 生成回复翻译和用户上一句的语法分析
+async fn analyze_turn(
+    llm: &Llm,
     system_prompt: &str,
-    user_text: &str,
-    history: &ConversationHistory,
+    reply_en: &str,
+    history: &[ChatMessage],
     session_id: &str,
     words: Option<&Vec<WordTiming>>,
 ) -> Result<ComprehensiveResponse> {
     // Use Chat Completions API with response_format for structured outputs
     // Per https://www.volcengine.com/docs/82379/1568221
 
-    let mut messages = vec![json!({
-        "role": "system",
-        "content": format!(
-            "{}\n\nIMPORTANT: You must respond with a JSON object containing:\n\
-            1. A natural conversational reply to the user\n\
-            2. Grammar/vocabulary analysis of the user's last message",
-            system_prompt
-        )
-    })];
-
-    // Add conversation history
-    for msg in history.get_messages() {
-        messages.push(json!({
-            "role": msg.role,
-            "content": msg.content,
-        }));
-    }
+    let mut instructions = format!(
+        "{}\n\nIMPORTANT: Your last reply has already been sent to the user. \
+        You must now respond with a JSON object containing:\n\
+        1. The Chinese translation of your last reply\n\
+        2. Grammar/vocabulary analysis of the user's last message",
+        system_prompt
+    );
 
     // JSON Schema for structured output
     let response_schema = json!({
@@ -357,13 +555,9 @@ async fn generate_comprehensive_response(
                 "type": "string",
                 "description": "The original user text in Chinese. If the user wrote in English or mixed language, translate it to Chinese here."
             },
-            "reply_en": {
-                "type": "string",
-                "description": "Your natural conversational response to the user in English. Keep it concise and encouraging."
-            },
             "reply_zh": {
                 "type": "string",
-                "description": "Translation of your reply_en into Chinese."
+                "description": "Translation of your last reply into Chinese."
             },
             "issues": {
                 "type": "array",
@@ -411,54 +605,38 @@ async fn generate_comprehensive_response(
                 }
             }
         },
-        "required": ["use_lang", "original_en", "original_zh", "reply_en", "reply_zh", "issues"],
+        "required": ["use_lang", "original_en", "original_zh", "reply_zh", "issues"],
         "additionalProperties": false
     });
 
-    let payload = json!({
-        "model": model,
-        "messages": messages,
-        "response_format": {
+    let response_format = if llm.structured_output {
+        json!({
             "type": "json_schema",
             "json_schema": {
                 "name": "english_teacher_response",
                 "strict": true,
                 "schema": response_schema
             }
-        },
-        "temperature": 0.7,
-        "max_tokens": 2000
-    });
-
-    println!("Request payload");
-    let response = client
-        .post("https://ark.cn-beijing.volces.com/api/v3/chat/completions")
-        .header(header::AUTHORIZATION, format!("Bearer {}", api_key))
-        .header(header::CONTENT_TYPE, "application/json")
-        .json(&payload)
-        .send()
-        .await?;
-
-    println!("Request payload 1");
-    if !response.status().is_success() {
-        let error_text = response.text().await?;
-        eyre::bail!("API error: {}", error_text);
-    }
-
-    println!("Request payload 2");
-    let result: serde_json::Value = response.json().await?;
-    println!("Request payload 13");
-
-    // Extract content from Chat Completions response
-    let content = result["choices"][0]["message"]["content"]
-        .as_str()
-        .ok_or_else(|| eyre::eyre!("No content in response"))?;
-
-    println!("Request payload 4");
+        })
+    } else {
+        // 不支持 json_schema: 在提示中给出 schema, 只要求 JSON 对象
+        instructions.push_str(&format!(
+            "\n\nRespond with ONLY a JSON object (no markdown) that matches this JSON Schema:\n{}",
+            response_schema
+        ));
+        json!({ "type": "json_object" })
+    };
+
+    let mut request = llm.request(build_messages(instructions, history));
+    request.response_format = Some(serde_json::from_value(response_format)?);
+
+    let content = llm.complete_text(request).await?;
     log::debug!("Structured response: {}", content);
 
     // Parse structured JSON response
-    let structured: serde_json::Value = serde_json::from_str(content)?;
+    let json_text = extract_json_object(&content)
+        .ok_or_else(|| eyre::eyre!("No JSON object in response: {}", content))?;
+    let structured: serde_json::Value = serde_json::from_str(json_text)?;
 
     let use_lang = structured["use_lang"]
         .as_str()
@@ -475,11 +653,6 @@ async fn generate_comprehensive_response(
         .ok_or_else(|| eyre::eyre!("Missing original_zh in structured response"))?
         .to_string();
 
-    let reply_en = structured["reply_en"]
-        .as_str()
-        .ok_or_else(|| eyre::eyre!("Missing reply_en in structured response"))?
-        .to_string();
-
     let reply_zh = structured["reply_zh"]
         .as_str()
         .ok_or_else(|| eyre::eyre!("Missing reply_zh in structured response"))?
@@ -489,19 +662,142 @@ async fn generate_comprehensive_response(
         serde_json::from_value(structured["issues"].clone()).unwrap_or_default();
 
     Ok(ComprehensiveResponse {
+        version: SCHEMA_VERSION,
         session_id: session_id.to_string(),
         use_lang,
         original_en,
         original_zh,
-        reply_en,
+        reply_en: reply_en.to_string(),
         reply_zh,
         issues,
         timestamp: chrono::Utc::now().timestamp(),
     })
 }
 
+/// 回复中的 JSON 对象 (去掉代码块标记等多余文本)
+fn extract_json_object(content: &str) -> Option<&str> {
+    let start = content.find('{')?;
+    let end = content.rfind('}')?;
+    (start < end).then(|| &content[start..=end])
+}
+
+/// 等待请求完成, 期间收到的其他事件放入 deferred
+///
+/// 收到本回合的 interrupt 时返回 None; 请求 future 被丢弃即取消
+async fn run_interruptible<T>(
+    request: impl Future<Output = T>,
+    events: &mut EventStream,
+    deferred: &mut VecDeque<Event>,
+    question_id: &str,
+) -> Option<T> {
+    tokio::pin!(request);
+
+    loop {
+        tokio::select! {
+            result = &mut request => return Some(result),
+            event = events.recv_async() => match event {
+                Some(Event::Input { id, data, .. }) if id.as_str() == "control" => {
+                    if interrupts_turn(&data, question_id) {
+                        return None;
+                    }
+                }
+                Some(event) => deferred.push_back(event),
+                None => return Some((&mut request).await),
+            },
+        }
+    }
+}
+
+/// 发送回复的一句话 (reply_segment)
+fn send_segment(
+    node: &mut DoraNode,
+    params: &MetadataParameters,
+    session_id: &str,
+    question_id: &str,
+    index: &mut u32,
+    text: &str,
+    is_final: bool,
+) -> Result<()> {
+    let text = text.trim();
+    if text.is_empty() && !is_final {
+        return Ok(());
+    }
+
+    let segment = ReplySegment {
+        version: SCHEMA_VERSION,
+        session_id: session_id.to_string(),
+        question_id: question_id.to_string(),
+        index: *index,
+        text: text.to_string(),
+        is_final,
+    };
+    *index += 1;
+    log::debug!("Reply segment {}: {}", segment.index, segment.text);
+
+    // UI 桥按 question_id 累积, session_status=ended 时结束
+    let mut params = params.clone();
+    params.insert(
+        "session_status".to_string(),
+        Parameter::String(if is_final { "ended" } else { "streaming" }.to_string()),
+    );
+
+    let output_str = dora_messages::encode(&segment)?;
+    node.send_output(
+        "reply_segment".to_string().into(),
+        params,
+        StringArray::from(vec![output_str.as_str()]),
+    )?;
+    Ok(())
+}
+
+fn send_interrupted(
+    node: &mut DoraNode,
+    params: &MetadataParameters,
+    session_id: &str,
+    question_id: &str,
+) -> Result<()> {
+    log::info!("Turn {} interrupted, request cancelled", question_id);
+
+    let status = json!({
+        "node": "english-teacher",
+        "status": "interrupted",
+        "session_id": session_id,
+        "question_id": question_id,
+    });
+    let status_array = StringArray::from(vec![status.to_string().as_str()]);
+    node.send_output("status".to_string().into(), params.clone(), status_array)?;
+    Ok(())
+}
+
+fn send_error(
+    node: &mut DoraNode,
+    params: &MetadataParameters,
+    error: &eyre::Report,
+) -> Result<()> {
+    let status = json!({
+        "node": "english-teacher",
+        "status": "error",
+        "error": error.to_string(),
+    });
+
+    let status_array = StringArray::from(vec![status.to_string().as_str()]);
+    node.send_output("status".to_string().into(), params.clone(), status_array)?;
+    Ok(())
+}
+
+/// 是否为取消本回合的插话中断
+fn interrupts_turn(data: &ArrowData, question_id: &str) -> bool {
+    let interrupted = extract_bytes(data)
+        .and_then(|bytes| dora_messages::decode::<ControlCommand>(&bytes).ok())
+        .is_some_and(|cmd| cmd.interrupts(Some(question_id)));
+    if interrupted {
+        log::info!("Barge-in for question_id={}", question_id);
+    }
+    interrupted
+}
+
 /// 从 ArrowData 提取字节
-fn extract_bytes(data: &dora_node_api::ArrowData) -> Option<Vec<u8>> {
+fn extract_bytes(data: &ArrowData) -> Option<Vec<u8>> {
     use dora_node_api::arrow::datatypes::DataType;
 
     let array = &data.0;
@@ -524,3 +820,41 @@ fn extract_bytes(data: &dora_node_api::ArrowData) -> Option<Vec<u8>> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_object() {
+        let fenced = "```json\n{\"use_lang\": \"en\", \"issues\": []}\n```";
+        assert_eq!(
+            extract_json_object(fenced),
+            Some("{\"use_lang\": \"en\", \"issues\": []}")
+        );
+        assert_eq!(extract_json_object("{}"), Some("{}"));
+        assert_eq!(extract_json_object("no json here"), None);
+        assert_eq!(extract_json_object("} {"), None);
+    }
+    #[test]
+    fn test_end_partial_turn() {
+        let roles = |h: &ConversationHistory| {
+            h.get_messages()
+                .into_iter()
+                .map(|m| m.role)
+                .collect::<Vec<_>>()
+        };
+        let mut history = ConversationHistory::new(2);
+
+        // 插话前已说出的句子记为助手回复
+        history.add_user_message("hi");
+        history.end_partial_turn("Hello there.");
+        assert_eq!(roles(&history), ["user", "assistant"]);
+
+        // 一句都没说: 撤回用户消息
+        history.add_user_message("how are you");
+        history.end_partial_turn("");
+        assert_eq!(roles(&history), ["user", "assistant"]);
+        assert_eq!(history.get_messages()[1].content, "Hello there.");
+    }
+}
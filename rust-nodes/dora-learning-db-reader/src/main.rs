@@ -1,53 +1,40 @@
 // Dora Node: Learning DB Reader
 // 专门负责从数据库随机读取问题词汇
 // 基于间隔重复算法选择需要复习的词汇
+// 通过 LearningStore 读写, 设置 LEARN_API_URL 时使用后端 /learn API, 否则使用本地 SQLite
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use colang_core::db::Database;
+use colang_core::learn_api::LearnApiClient;
+use colang_core::models::{IssueWord, LearningSession, SessionType};
+use colang_core::store::LearningStore;
+use dora_messages::{SCHEMA_VERSION, SelectedWord, TriggerCommand, WordSelectionOutput};
 use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
 use dora_node_api::{DoraNode, Event};
 use eyre::{Context, Result};
-use serde::{Deserialize, Serialize};
-use sqlx::Row;
-use sqlx::sqlite::SqlitePool;
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct IssueWord {
-    id: i64,
-    word: String,
-    issue_type: String,
-    description_en: Option<String>,
-    description_zh: Option<String>,
-    difficulty_level: i64,
-    context: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct WordSelectionOutput {
-    words: Vec<String>,
-    word_details: Vec<IssueWord>,
-    session_id: String,
-    total_selected: usize,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct TriggerCommand {
-    command: String,
-    min_words: Option<usize>,
-    max_words: Option<usize>,
-}
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
 
-    let database_url = std::env::var("DATABASE_URL")
-        .unwrap_or_else(|_| "sqlite://learning_companion.db".to_string());
-
-    log::info!("DB Reader connecting to database: {}", database_url);
-    let pool = SqlitePool::connect(&database_url)
-        .await
-        .wrap_err("Failed to connect to database")?;
+    let store: Box<dyn LearningStore> = match std::env::var("LEARN_API_URL") {
+        Ok(base_url) => {
+            log::info!("DB Reader using learn API: {}", base_url);
+            let token = std::env::var("LEARN_API_TOKEN").ok();
+            Box::new(LearnApiClient::new(&base_url, token))
+        }
+        Err(_) => {
+            let database_url = std::env::var("DATABASE_URL")
+                .unwrap_or_else(|_| "sqlite://learning_companion.db".to_string());
+            log::info!("DB Reader connecting to database: {}", database_url);
+            Box::new(
+                Database::new(&database_url)
+                    .await
+                    .wrap_err("Failed to connect to database")?,
+            )
+        }
+    };
 
     // log::info!("Running database migrations...");
     // sqlx::migrate!("../.. /apps/colang/migrations")
@@ -83,7 +70,7 @@ async fn main() -> Result<()> {
                         let raw_data = extract_bytes(&data);
                         let (min_words, max_words) = if !raw_data.is_empty() {
                             // Try to parse trigger command
-                            match serde_json::from_slice::<TriggerCommand>(&raw_data) {
+                            match dora_messages::decode::<TriggerCommand>(&raw_data) {
                                 Ok(cmd) => (
                                     cmd.min_words.unwrap_or(default_min_words),
                                     cmd.max_words.unwrap_or(default_max_words),
@@ -97,7 +84,7 @@ async fn main() -> Result<()> {
                         log::info!("Selecting words (min: {}, max: {})", min_words, max_words);
 
                         // Select words from database
-                        match select_words(&pool, max_words).await {
+                        match select_words(store.as_ref(), max_words).await {
                             Ok(words) => {
                                 // Generate new session ID
                                 let session_id = uuid::Uuid::new_v4().to_string();
@@ -106,13 +93,14 @@ async fn main() -> Result<()> {
                                     words.iter().map(|w| w.word.clone()).collect();
 
                                 let output = WordSelectionOutput {
+                                    version: SCHEMA_VERSION,
                                     words: word_strings.clone(),
                                     word_details: words.clone(),
                                     session_id: session_id.clone(),
                                     total_selected: words.len(),
                                 };
 
-                                let output_json = serde_json::to_string(&output)?;
+                                let output_json = dora_messages::encode(&output)?;
                                 let output_array = StringArray::from(vec![output_json.as_str()]);
                                 node.send_output(
                                     "selected_words".to_string().into(),
@@ -129,7 +117,8 @@ async fn main() -> Result<()> {
 
                                 // Create learning session in database
                                 if let Err(e) =
-                                    create_learning_session(&pool, &session_id, &words).await
+                                    create_learning_session(store.as_ref(), &session_id, &words)
+                                        .await
                                 {
                                     log::error!("Failed to create learning session: {}", e);
                                 }
@@ -139,12 +128,13 @@ async fn main() -> Result<()> {
 
                                 // Send empty result
                                 let output = WordSelectionOutput {
+                                    version: SCHEMA_VERSION,
                                     words: vec![],
                                     word_details: vec![],
                                     session_id: uuid::Uuid::new_v4().to_string(),
                                     total_selected: 0,
                                 };
-                                let output_json = serde_json::to_string(&output)?;
+                                let output_json = dora_messages::encode(&output)?;
                                 let output_array = StringArray::from(vec![output_json.as_str()]);
                                 node.send_output(
                                     "selected_words".to_string().into(),
@@ -185,85 +175,62 @@ fn extract_bytes(data: &dora_node_api::ArrowData) -> Vec<u8> {
     Vec::new()
 }
 
-async fn select_words(pool: &SqlitePool, limit: usize) -> Result<Vec<IssueWord>> {
-    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
-
-    let one_day_ago = now - 86400; // 24 hours in seconds
-
-    // Select words based on spaced repetition:
-    // 1. Priority to words due for review (next_review_at <= now)
-    // 2. Limit to 5 times per day per word
-    // 3. Sort by difficulty and creation date
-    let rows = sqlx::query(
-        r#"
-        SELECT 
-            w.id, w.word, w.issue_type, w.description_en, w.description_zh,
-            w.difficulty_level, w.context,
-            COALESCE(
-                (SELECT COUNT(*) FROM word_practice_log 
-                 WHERE word_id = w.id 
-                 AND practiced_at >= ?),
-                0
-            ) as today_count
-        FROM issue_words w
-        WHERE 
-            (w.next_review_at IS NULL OR w.next_review_at <= ?)
-        HAVING today_count < 5
-        ORDER BY 
-            CASE WHEN w.next_review_at IS NULL THEN 0 ELSE 1 END,
-            w.next_review_at ASC,
-            w.difficulty_level DESC,
-            w.created_at ASC
-        LIMIT ?
-        "#,
-    )
-    .bind(one_day_ago)
-    .bind(now)
-    .bind(limit as i64)
-    .fetch_all(pool)
-    .await?;
-
-    let mut words = Vec::new();
-    for row in rows {
-        words.push(IssueWord {
-            id: row.get("id"),
-            word: row.get("word"),
-            issue_type: row.get("issue_type"),
-            description_en: row.get("description_en"),
-            description_zh: row.get("description_zh"),
-            difficulty_level: row.get("difficulty_level"),
-            context: row.get("context"),
-        });
-    }
+async fn select_words(store: &dyn LearningStore, limit: usize) -> Result<Vec<SelectedWord>> {
+    // 优先选择到期的词 (从未复习过的最先), 每个词每天最多练习 5 次
+    let words: Vec<SelectedWord> = store
+        .words_for_review(limit as i64)
+        .await?
+        .into_iter()
+        .filter_map(selected_word)
+        .collect();
 
     log::info!("Found {} words for review", words.len());
     Ok(words)
 }
 
+/// 只有已存储 (有 id) 的词可以被选中
+fn selected_word(word: IssueWord) -> Option<SelectedWord> {
+    Some(SelectedWord {
+        id: word.id?,
+        issue_type: word.issue_type.to_string(),
+        word: word.word,
+        description_en: word.description_en,
+        description_zh: word.description_zh,
+        difficulty_level: word.difficulty_level,
+        context: word.context,
+    })
+}
+
 async fn create_learning_session(
-    pool: &SqlitePool,
+    store: &dyn LearningStore,
     session_id: &str,
-    words: &[IssueWord],
+    words: &[SelectedWord],
 ) -> Result<()> {
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
 
     let target_words_json =
         serde_json::to_string(&words.iter().map(|w| &w.word).collect::<Vec<_>>())?;
 
-    sqlx::query(
-        r#"
-        INSERT INTO learning_sessions (
-            session_id, topic, target_words, started_at, total_exchanges
-        ) VALUES (?, ?, ?, ?, ?)
-        "#,
-    )
-    .bind(session_id)
-    .bind("English Learning Session")
-    .bind(target_words_json)
-    .bind(now)
-    .bind(0)
-    .execute(pool)
-    .await?;
+    store
+        .start_session(&LearningSession {
+            session_id: session_id.to_string(),
+            session_type: Some(SessionType::FreeTalk),
+            sceneid: None,
+            scene_dialogue_id: None,
+            classic_clip_id: None,
+            target_words: Some(target_words_json),
+            started_at: now,
+            ended_at: None,
+            duration_seconds: None,
+            total_words_spoken: 0,
+            average_wpm: None,
+            error_count: 0,
+            correction_count: 0,
+            notes: None,
+            ai_summary_en: None,
+            ai_summary_zh: None,
+        })
+        .await?;
 
     log::info!("Created learning session: {}", session_id);
     Ok(())
@@ -3,69 +3,17 @@
 // 功能：
 // 1. 接收 user_text 输入（纯文本），存储用户消息到 conversations 表
 // 2. 接收 ai_json 输入（综合JSON），存储用户消息+AI回复+语法分析到数据库
+// 对话记录带回合 ID (metadata question_id), 语法问题关联到同一回合的用户消息
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use dora_messages::turn::{SESSION_ID, TURN_ID, string_param};
+use dora_messages::{AsrOutput, ComprehensiveResponse, StorageResult, TextIssue};
 use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
 use dora_node_api::{DoraNode, Event};
 use eyre::{Context, Result};
-use serde::{Deserialize, Serialize};
 use sqlx::sqlite::SqlitePool;
 
-/// 综合响应（来自 english-teacher）
-#[derive(Debug, Serialize, Deserialize)]
-struct ComprehensiveResponse {
-    session_id: String,
-    use_lang: String,       // 用户文本语言: "en" | "zh" | "mix"
-    original_en: String,    // 用户原始文本（英文）
-    original_zh: String,    // 用户原始文本（中文）
-    reply_en: String,       // AI对该消息的英文回复
-    reply_zh: String,       // AI对该消息的中文回复
-    issues: Vec<TextIssue>, // 语法/用词问题
-    timestamp: i64,
-}
-
-/// ASR 输出格式（从 doubao-asr 接收）
-#[derive(Debug, Serialize, Deserialize)]
-struct AsrOutput {
-    text: String,
-    confidence: f32,
-    #[serde(default)]
-    words: Vec<WordTiming>,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct WordTiming {
-    word: String,
-    start_time: f64,
-    end_time: f64,
-    confidence: f32,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct TextIssue {
-    #[serde(rename = "type")]
-    issue_type: String,
-    original: String,
-    suggested: String,
-    description_en: String,
-    description_zh: String,
-    severity: String,
-
-    #[serde(default)]
-    start_position: Option<i32>,
-    #[serde(default)]
-    end_position: Option<i32>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct StorageResult {
-    success: bool,
-    issues_stored: usize,
-    error: Option<String>,
-}
-
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
@@ -101,31 +49,32 @@ async fn main() -> Result<()> {
                         }
 
                         // 尝试解析为 ASR 输出（JSON）或纯文本
+                        // 会话 ID 来自 ASR 结果或采集端写入的 metadata
                         let (user_text, session_id) =
-                            if let Ok(asr) = serde_json::from_slice::<AsrOutput>(&raw_data) {
-                                (
-                                    asr.text,
-                                    asr.session_id.unwrap_or_else(|| "default".to_string()),
-                                )
+                            if let Ok(asr) = dora_messages::decode::<AsrOutput>(&raw_data) {
+                                (asr.text, asr.session_id)
                             } else {
                                 let text = String::from_utf8_lossy(&raw_data).to_string();
-                                (text, "default".to_string())
+                                (text, None)
                             };
+                        let session_id =
+                            session_id.or_else(|| string_param(&metadata.parameters, SESSION_ID));
 
                         if user_text.trim().is_empty() {
                             log::debug!("Empty text, skipping storage");
                             continue;
                         }
 
-                        log::info!("Storing user message: {}", user_text);
+                        log::info!(
+                            "Storing user message (session {:?}, turn {:?}): {}",
+                            session_id,
+                            string_param(&metadata.parameters, TURN_ID),
+                            user_text
+                        );
 
-                        let mut result = StorageResult {
-                            success: true,
-                            issues_stored: 0,
-                            error: None,
-                        };
+                        let result = StorageResult::ok();
 
-                        // match save_comprehensive(&pool, &session_id, "user", &user_text).await {
+                        // match save_comprehensive(&pool, &session_id, turn_id, "user", &user_text).await {
                         //     Ok(_) => result.conversations_stored += 1,
                         //     Err(e) => {
                         //         log::error!("Failed to save user message: {}", e);
@@ -145,7 +94,7 @@ async fn main() -> Result<()> {
                             continue;
                         }
 
-                        match serde_json::from_slice::<ComprehensiveResponse>(&raw_data) {
+                        match dora_messages::decode::<ComprehensiveResponse>(&raw_data) {
                             Ok(response) => {
                                 log::info!(
                                     "Storing comprehensive response: user='{}', ai='{}', {} issues",
@@ -154,34 +103,24 @@ async fn main() -> Result<()> {
                                     response.issues.len()
                                 );
 
-                                let mut result = StorageResult {
-                                    success: true,
-                                    issues_stored: 0,
-                                    error: None,
-                                };
-
-                                // 2. 存储 AI 回复到 conversations
-                                match save_comprehensive(&pool, &response.session_id, &response)
-                                    .await
-                                {
-                                    Ok(_) => {}
-                                    Err(e) => {
-                                        log::error!("Failed to save AI conversation: {}", e);
-                                        result.success = false;
-                                    }
-                                }
+                                let mut result = StorageResult::ok();
+                                let turn_id = string_param(&metadata.parameters, TURN_ID);
 
-                                // 3. 获取 conversation ID 用于关联 annotations
-                                let conv_id = match get_latest_conversation_id(
+                                // 2. 存储本回合的用户消息和 AI 回复到 conversations
+                                // 返回用户消息的 ID, 用于关联 annotations
+                                let conv_id = match save_comprehensive(
                                     &pool,
                                     &response.session_id,
-                                    "user",
+                                    turn_id.as_deref(),
+                                    &response,
                                 )
                                 .await
                                 {
                                     Ok(id) => id,
                                     Err(e) => {
-                                        log::error!("Failed to get conversation ID: {}", e);
+                                        log::error!("Failed to save AI conversation: {}", e);
+                                        result.success = false;
+                                        result.error = Some(e.to_string());
                                         send_result(&mut node, &metadata, &result)?;
                                         continue;
                                     }
@@ -232,69 +171,47 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// 保存对话记录到 conversations 表
+/// 保存一个回合的对话记录到 conversations 表, 返回用户消息的 ID
 async fn save_comprehensive(
     pool: &SqlitePool,
     session_id: &str,
+    turn_id: Option<&str>,
     comprehensive: &ComprehensiveResponse,
 ) -> Result<i64> {
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
 
     // Update the last user message with comprehensive data
-    sqlx::query(
+    let user = sqlx::query!(
         r#"
-        INSERT INTO conversations (session_id, speaker, use_lang, content_en, content_zh, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO conversations (session_id, turn_id, speaker, use_lang, content_en, content_zh, created_at)
+        VALUES (?, ?, 'user', ?, ?, ?, ?)
         "#,
+        session_id,
+        turn_id,
+        comprehensive.use_lang,
+        comprehensive.original_en,
+        comprehensive.original_zh,
+        now,
     )
-   .bind(session_id)
-    .bind("user")
-    .bind(comprehensive.use_lang.as_str())
-    .bind(&comprehensive.original_en)
-    .bind(&comprehensive.original_zh)
-    .bind(now)
     .execute(pool)
     .await?;
 
     // Determine use_lang based on speaker (user typically uses en, teacher can use both)
-    let result = sqlx::query(
+    sqlx::query!(
         r#"
-        INSERT INTO conversations (session_id, speaker, use_lang, content_en, content_zh, created_at)
-        VALUES (?, ?, ?, ?, ?, ?)
+        INSERT INTO conversations (session_id, turn_id, speaker, use_lang, content_en, content_zh, created_at)
+        VALUES (?, ?, 'teacher', 'en', ?, ?, ?)
         "#,
+        session_id,
+        turn_id,
+        comprehensive.reply_en,
+        comprehensive.reply_zh,
+        now,
     )
-    .bind(session_id)
-    .bind("teacher")
-    .bind("en")
-    .bind(&comprehensive.reply_en)
-    .bind(&comprehensive.reply_zh)
-    .bind(now)
     .execute(pool)
     .await?;
 
-    Ok(result.last_insert_rowid())
-}
-
-/// 获取最新的 conversation ID
-async fn get_latest_conversation_id(
-    pool: &SqlitePool,
-    session_id: &str,
-    speaker: &str,
-) -> Result<i64> {
-    let id: i64 = sqlx::query_scalar(
-        r#"
-        SELECT id FROM conversations 
-        WHERE session_id = ? AND speaker = ?
-        ORDER BY created_at DESC 
-        LIMIT 1
-        "#,
-    )
-    .bind(session_id)
-    .bind(speaker)
-    .fetch_one(pool)
-    .await?;
-
-    Ok(id)
+    Ok(user.last_insert_rowid())
 }
 
 /// 保存文本问题到 conversation_annotations 和 issue_words 表
@@ -314,7 +231,7 @@ async fn save_text_issue(
         _ => "correction",
     };
 
-    sqlx::query(
+    sqlx::query!(
         r#"
         INSERT INTO conversation_annotations (
             conversation_id, annotation_type,
@@ -323,17 +240,17 @@ async fn save_text_issue(
             description_en, description_zh, severity, created_at
         ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
+        conversation_id,
+        annotation_type,
+        issue.start_position,
+        issue.end_position,
+        issue.original,
+        issue.suggested,
+        issue.description_en,
+        issue.description_zh,
+        issue.severity,
+        now,
     )
-    .bind(conversation_id)
-    .bind(annotation_type)
-    .bind(issue.start_position)
-    .bind(issue.end_position)
-    .bind(&issue.original)
-    .bind(&issue.suggested)
-    .bind(&issue.description_en)
-    .bind(&issue.description_zh)
-    .bind(&issue.severity)
-    .bind(now)
     .execute(pool)
     .await?;
 
@@ -355,7 +272,7 @@ async fn save_text_issue(
             continue;
         }
 
-        sqlx::query(
+        sqlx::query!(
             r#"
             INSERT INTO issue_words (
                 word, issue_type, description_en, description_zh, created_at, pick_count,
@@ -367,13 +284,13 @@ async fn save_text_issue(
                 context = excluded.context,
                 difficulty_level = MAX(difficulty_level, 3)
             "#,
+            clean_word,
+            issue_type_db,
+            issue.description_en,
+            issue.description_zh,
+            now,
+            context,
         )
-        .bind(&clean_word)
-        .bind(issue_type_db)
-        .bind(&issue.description_en)
-        .bind(&issue.description_zh)
-        .bind(now)
-        .bind(context)
         .execute(pool)
         .await?;
     }
@@ -403,22 +320,21 @@ async fn save_pronunciation_issue(
         confidence
     );
 
-    sqlx::query(
+    sqlx::query!(
         r#"
         INSERT INTO issue_words (
             word, issue_type, description_en, description_zh, created_at, pick_count,
             review_interval_days, difficulty_level, context
-        ) VALUES (?, 'pronunciation', ?, ?, ?, 0, 1, 2, ?)
+        ) VALUES (?, 'pronunciation', ?, NULL, ?, 0, 1, 2, ?)
         ON CONFLICT(word, issue_type) DO UPDATE SET
             difficulty_level = MIN(difficulty_level + 1, 5),
             description_en = excluded.description_en
         "#,
+        clean_word,
+        description,
+        now,
+        context,
     )
-    .bind(&clean_word)
-    .bind(&description)
-    .bind(None::<String>) // description_zh placeholder
-    .bind(now)
-    .bind(context)
     .execute(pool)
     .await?;
 
@@ -437,13 +353,14 @@ fn extract_bytes(data: &dora_node_api::ArrowData) -> Vec<u8> {
     Vec::new()
 }
 
+/// 发送存储结果, 带上输入的 metadata (回合 ID)
 fn send_result(
     node: &mut DoraNode,
-    _metadata: &dora_node_api::Metadata,
+    metadata: &dora_node_api::Metadata,
     result: &StorageResult,
 ) -> Result<()> {
-    let output_str = serde_json::to_string(result)?;
+    let output_str = dora_messages::encode(result)?;
     let output_array = StringArray::from(vec![output_str.as_str()]);
-    node.send_output("result".into(), Default::default(), output_array)?;
+    node.send_output("result".into(), metadata.parameters.clone(), output_array)?;
     Ok(())
 }
@@ -68,6 +68,30 @@ pub enum ProviderConfig {
     Deepseek(DeepseekConfig),
 }
 
+impl ProviderConfig {
+    pub fn id(&self) -> &str {
+        match self {
+            ProviderConfig::Openai(c) => &c.id,
+            ProviderConfig::Gemini(c) => &c.id,
+            ProviderConfig::Alicloud(c) => &c.id,
+            ProviderConfig::Deepseek(c) => &c.id,
+        }
+    }
+
+    /// Whether [`ChatClient::complete_streaming`] is implemented for this provider
+    pub fn supports_streaming(&self) -> bool {
+        !matches!(self, ProviderConfig::Gemini(_))
+    }
+
+    /// Whether the provider accepts `response_format: {"type": "json_schema", ...}`.
+    ///
+    /// DeepSeek and DashScope only understand `json_object`, so callers have to
+    /// describe the schema in the prompt and parse the reply themselves.
+    pub fn supports_json_schema(&self) -> bool {
+        matches!(self, ProviderConfig::Openai(_) | ProviderConfig::Gemini(_))
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct OpenaiConfig {
     pub id: String,
@@ -119,6 +143,9 @@ pub struct ModelConfig {
 pub struct ModelRoute {
     pub provider: String,
     pub model: Option<String>,
+    /// Override the provider default for JSON-schema structured output
+    #[serde(default)]
+    pub structured_output: Option<bool>,
 }
 
 impl Config {
@@ -181,14 +208,7 @@ impl Config {
                 }
             };
 
-            let provider_id = match provider {
-                ProviderConfig::Openai(c) => &c.id,
-                ProviderConfig::Gemini(c) => &c.id,
-                ProviderConfig::Alicloud(c) => &c.id,
-                ProviderConfig::Deepseek(c) => &c.id,
-            };
-
-            clients.insert(provider_id.clone(), client);
+            clients.insert(provider.id().to_string(), client);
         }
 
         clients
@@ -209,6 +229,25 @@ impl Config {
             (provider, model)
         })
     }
+
+    /// Look up a provider by ID.
+    pub fn provider(&self, provider_id: &str) -> Option<&ProviderConfig> {
+        self.providers.iter().find(|p| p.id() == provider_id)
+    }
+
+    /// Whether requests for `model_id` can use JSON-schema structured output.
+    ///
+    /// Uses the model route's `structured_output` if set, otherwise the
+    /// provider default (see [`ProviderConfig::supports_json_schema`]).
+    pub fn supports_structured_output(&self, model_id: &str) -> bool {
+        let Some(model) = self.models.iter().find(|m| m.id == model_id) else {
+            return false;
+        };
+        model.route.structured_output.unwrap_or_else(|| {
+            self.provider(&model.route.provider)
+                .is_some_and(ProviderConfig::supports_json_schema)
+        })
+    }
 }
 
 /// MCP (Model Context Protocol) configuration
@@ -0,0 +1,11 @@
+//! Library part of the MaaS client, shared with other nodes
+//!
+//! - [`config`]: provider/model configuration and [`config::Config::route_model`]
+//! - [`client`]: the [`client::ChatClient`] trait and provider implementations
+//! - [`segmenter`]: cut streamed LLM text into TTS-sized segments
+
+pub mod client;
+pub mod config;
+pub mod segmenter;
+pub mod streaming;
+pub mod tool;
@@ -36,18 +36,10 @@ use serde_json::json;
 use tokio::sync::Mutex as AsyncMutex;
 use tokio_util::sync::CancellationToken;
 
-mod client;
-mod config;
-mod segmenter;
-mod streaming;
-mod tool;
-
-use config::{Config, format_anchor_context, load_anchor_context};
-use segmenter::StreamSegmenter;
-use tool::ToolSet;
-
-// Import CancellationReason from streaming module
-use crate::streaming::CancellationReason;
+use dora_maas_client::config::{Config, format_anchor_context, load_anchor_context};
+use dora_maas_client::segmenter::StreamSegmenter;
+use dora_maas_client::streaming::CancellationReason;
+use dora_maas_client::tool::ToolSet;
 
 // Helper function to send log messages
 fn send_log(node: &mut DoraNode, level: &str, message: &str) -> Result<()> {
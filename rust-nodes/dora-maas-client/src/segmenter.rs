@@ -6,6 +6,8 @@
 ///
 /// # Example
 /// ```
+/// use dora_maas_client::segmenter::StreamSegmenter;
+///
 /// let mut segmenter = StreamSegmenter::new(10);
 /// assert_eq!(segmenter.add_chunk("Hello"), None);
 /// assert_eq!(
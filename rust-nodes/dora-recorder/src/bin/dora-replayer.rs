@@ -0,0 +1,126 @@
+// Dora Node: Replayer
+// Re-emits a recording made by dora-recorder in place of the recorded node
+//
+// Declare the replayer with the id and outputs of the node it replaces, so
+// downstream inputs stay unchanged:
+//
+//   - id: english-teacher
+//     path: ../../../target/release/dora-replayer
+//     outputs:
+//       - reply_segment
+//       - json_data
+//     env:
+//       REPLAY_PATH: recordings/session.dorarec
+//
+// Environment:
+//   REPLAY_PATH            recording to replay (required)
+//   REPLAY_NODE            recorded node to replay (default: this node's id)
+//   REPLAY_SPEED           time scale, 2.0 = twice as fast, 0 = no delays (default 1.0)
+//   REPLAY_START_DELAY_MS  wait before the first message, e.g. for UI bridges to connect (default 0)
+
+use std::fs::File;
+use std::io::BufReader;
+use std::time::{Duration, Instant};
+
+use dora_node_api::{DoraNode, Event};
+use dora_recorder::RecordingReader;
+use dora_recorder::convert::{params_to_dora, payload_to_arrow};
+use eyre::{Context, Result};
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let path = std::env::var("REPLAY_PATH").wrap_err("REPLAY_PATH is not set")?;
+    let speed: f64 = std::env::var("REPLAY_SPEED")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1.0);
+    let start_delay = std::env::var("REPLAY_START_DELAY_MS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .map(Duration::from_millis)
+        .unwrap_or_default();
+
+    let file = File::open(&path).wrap_err_with(|| format!("Failed to open {}", path))?;
+    let reader = RecordingReader::new(BufReader::new(file))
+        .wrap_err_with(|| format!("Failed to read {}", path))?;
+
+    let (mut node, mut events) = DoraNode::init_from_env()?;
+    let replay_node = std::env::var("REPLAY_NODE").unwrap_or_else(|_| node.id().to_string());
+    log::info!(
+        "Replayer started: {} from {} (speed {})",
+        replay_node,
+        path,
+        speed
+    );
+
+    let mut stopped = wait(&mut events, start_delay);
+    let start = Instant::now();
+    let mut first_ms = None;
+    let mut count = 0usize;
+
+    for record in reader {
+        if stopped {
+            break;
+        }
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                log::warn!("Recording ends early: {}", e);
+                break;
+            }
+        };
+        if record.node_id != replay_node {
+            continue;
+        }
+
+        // Keep the recorded spacing relative to the first replayed message
+        let offset_ms = record
+            .t_ms
+            .saturating_sub(*first_ms.get_or_insert(record.t_ms));
+        if speed > 0.0 {
+            let due = Duration::from_secs_f64(offset_ms as f64 / 1000.0 / speed);
+            stopped = wait(&mut events, due.saturating_sub(start.elapsed()));
+            if stopped {
+                break;
+            }
+        }
+
+        node.send_output(
+            record.output_id.clone().into(),
+            params_to_dora(record.params),
+            payload_to_arrow(record.payload),
+        )?;
+        count += 1;
+        log::debug!("Replayed {} at {}ms", record.output_id, offset_ms);
+    }
+
+    log::info!("Replayed {} message(s) from {}", count, replay_node);
+
+    // Stay up until the dataflow stops, like the node being replaced
+    while !stopped {
+        match events.recv() {
+            Some(Event::Stop(_)) | None => stopped = true,
+            Some(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Wait for `duration`, ignoring inputs; true if the dataflow stopped
+fn wait(events: &mut dora_node_api::EventStream, duration: Duration) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return false;
+        }
+        match events.recv_timeout(remaining) {
+            Some(Event::Stop(_)) => {
+                log::info!("Received stop event");
+                return true;
+            }
+            Some(_) | None => {}
+        }
+    }
+}
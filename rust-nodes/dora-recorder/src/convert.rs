@@ -0,0 +1,103 @@
+//! Conversion between dora messages and recorded payloads
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use dora_node_api::arrow::array::{
+    Array, ArrayRef, Float32Array, LargeStringArray, ListArray, NullArray, StringArray, UInt8Array,
+};
+use dora_node_api::arrow::datatypes::{DataType, Float32Type};
+use dora_node_api::{MetadataParameters, Parameter};
+use eyre::{Result, bail, eyre};
+
+use crate::format::{ParamValue, Payload};
+
+/// Capture an arrow array as a payload
+pub fn payload_from_arrow(array: &dyn Array) -> Result<Payload> {
+    fn downcast<T: 'static>(array: &dyn Array) -> Result<&T> {
+        array
+            .as_any()
+            .downcast_ref::<T>()
+            .ok_or_else(|| eyre!("unexpected array for {:?}", array.data_type()))
+    }
+
+    Ok(match array.data_type() {
+        DataType::Null => Payload::Empty,
+        DataType::Utf8 => Payload::Utf8(
+            downcast::<StringArray>(array)?
+                .iter()
+                .map(|s| s.unwrap_or_default().to_string())
+                .collect(),
+        ),
+        DataType::LargeUtf8 => Payload::Utf8(
+            downcast::<LargeStringArray>(array)?
+                .iter()
+                .map(|s| s.unwrap_or_default().to_string())
+                .collect(),
+        ),
+        DataType::UInt8 => Payload::Bytes(downcast::<UInt8Array>(array)?.values().to_vec()),
+        DataType::Float32 => Payload::Float32(downcast::<Float32Array>(array)?.values().to_vec()),
+        DataType::List(field) if field.data_type() == &DataType::Float32 => {
+            let list = downcast::<ListArray>(array)?;
+            let mut lists = Vec::with_capacity(list.len());
+            for i in 0..list.len() {
+                let values = list.value(i);
+                lists.push(downcast::<Float32Array>(values.as_ref())?.values().to_vec());
+            }
+            Payload::Float32List(lists)
+        }
+        other => bail!("unsupported data type {:?}", other),
+    })
+}
+
+/// Rebuild the arrow array for a recorded payload
+pub fn payload_to_arrow(payload: Payload) -> ArrayRef {
+    match payload {
+        Payload::Empty => Arc::new(NullArray::new(0)),
+        Payload::Utf8(strings) => Arc::new(StringArray::from(strings)),
+        Payload::Bytes(bytes) => Arc::new(UInt8Array::from(bytes)),
+        Payload::Float32(values) => Arc::new(Float32Array::from(values)),
+        Payload::Float32List(lists) => {
+            Arc::new(ListArray::from_iter_primitive::<Float32Type, _, _>(
+                lists
+                    .into_iter()
+                    .map(|values| Some(values.into_iter().map(Some))),
+            ))
+        }
+    }
+}
+
+pub fn params_from_dora(params: &MetadataParameters) -> BTreeMap<String, ParamValue> {
+    params
+        .iter()
+        .map(|(key, value)| {
+            let value = match value {
+                Parameter::String(s) => ParamValue::String(s.clone()),
+                Parameter::Integer(i) => ParamValue::Integer(*i),
+                Parameter::Float(f) => ParamValue::Float(*f),
+                Parameter::Bool(b) => ParamValue::Bool(*b),
+                Parameter::ListInt(l) => ParamValue::ListInt(l.clone()),
+                Parameter::ListFloat(l) => ParamValue::ListFloat(l.clone()),
+                Parameter::ListString(l) => ParamValue::ListString(l.clone()),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+pub fn params_to_dora(params: BTreeMap<String, ParamValue>) -> MetadataParameters {
+    let mut out = MetadataParameters::default();
+    for (key, value) in params {
+        let value = match value {
+            ParamValue::String(s) => Parameter::String(s),
+            ParamValue::Integer(i) => Parameter::Integer(i),
+            ParamValue::Float(f) => Parameter::Float(f),
+            ParamValue::Bool(b) => Parameter::Bool(b),
+            ParamValue::ListInt(l) => Parameter::ListInt(l),
+            ParamValue::ListFloat(l) => Parameter::ListFloat(l),
+            ParamValue::ListString(l) => Parameter::ListString(l),
+        };
+        out.insert(key, value);
+    }
+    out
+}
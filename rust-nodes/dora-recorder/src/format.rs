@@ -0,0 +1,316 @@
+//! Recording file format
+//!
+//! A recording starts with the magic bytes `DORAREC1`, followed by one frame
+//! per message:
+//!
+//! ```text
+//! u32 LE header length | JSON RecordHeader | u32 LE payload length | payload
+//! ```
+//!
+//! The header holds the timing, source and metadata parameters; the payload
+//! is stored in binary so recorded audio stays close to its in-memory size.
+
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
+
+use serde::{Deserialize, Serialize};
+
+/// Magic bytes at the start of every recording
+pub const MAGIC: &[u8; 8] = b"DORAREC1";
+
+/// Metadata parameter value (mirrors dora's `Parameter`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParamValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+    ListInt(Vec<i64>),
+    ListFloat(Vec<f64>),
+    ListString(Vec<String>),
+}
+
+/// Message data, for the arrow types colang nodes exchange
+#[derive(Debug, Clone, PartialEq)]
+pub enum Payload {
+    /// Null or empty array (ticks, signals)
+    Empty,
+    /// `StringArray` / `LargeStringArray` (JSON messages)
+    Utf8(Vec<String>),
+    /// `UInt8Array` (JSON bytes, encoded audio)
+    Bytes(Vec<u8>),
+    /// `Float32Array`
+    Float32(Vec<f32>),
+    /// `ListArray<Float32>` (mic PCM samples)
+    Float32List(Vec<Vec<f32>>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum PayloadKind {
+    Empty,
+    Utf8,
+    Bytes,
+    Float32,
+    Float32List,
+}
+
+/// One recorded message
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// Milliseconds since the recording started
+    pub t_ms: u64,
+    /// Node that sent the message (e.g. "doubao-asr")
+    pub node_id: String,
+    /// Output the message was sent on (e.g. "text")
+    pub output_id: String,
+    /// Metadata parameters (question_id, session_status, sample_rate...)
+    pub params: BTreeMap<String, ParamValue>,
+    pub payload: Payload,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordHeader {
+    t_ms: u64,
+    node: String,
+    output: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    params: BTreeMap<String, ParamValue>,
+    payload: PayloadKind,
+}
+
+/// Writes records to a recording
+pub struct RecordingWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> RecordingWriter<W> {
+    /// Start a recording (writes the magic bytes)
+    pub fn new(mut inner: W) -> io::Result<Self> {
+        inner.write_all(MAGIC)?;
+        Ok(Self { inner })
+    }
+
+    /// Append one record
+    pub fn write(&mut self, record: &Record) -> io::Result<()> {
+        let (kind, payload) = encode_payload(&record.payload);
+        let header = RecordHeader {
+            t_ms: record.t_ms,
+            node: record.node_id.clone(),
+            output: record.output_id.clone(),
+            params: record.params.clone(),
+            payload: kind,
+        };
+        let header = serde_json::to_vec(&header).map_err(io::Error::other)?;
+
+        write_chunk(&mut self.inner, &header)?;
+        write_chunk(&mut self.inner, &payload)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reads records from a recording, in order
+pub struct RecordingReader<R: Read> {
+    inner: R,
+}
+
+impl<R: Read> RecordingReader<R> {
+    /// Open a recording (checks the magic bytes)
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        inner.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a dora recording"));
+        }
+        Ok(Self { inner })
+    }
+
+    /// Read the next record; `None` at the end of the recording
+    pub fn read(&mut self) -> io::Result<Option<Record>> {
+        let Some(header) = read_chunk(&mut self.inner, true)? else {
+            return Ok(None);
+        };
+        let header: RecordHeader = serde_json::from_slice(&header).map_err(invalid)?;
+        let payload = read_chunk(&mut self.inner, false)?.unwrap_or_default();
+
+        Ok(Some(Record {
+            t_ms: header.t_ms,
+            node_id: header.node,
+            output_id: header.output,
+            params: header.params,
+            payload: decode_payload(header.payload, &payload)?,
+        }))
+    }
+}
+
+impl<R: Read> Iterator for RecordingReader<R> {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read().transpose()
+    }
+}
+
+fn write_chunk(w: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(bytes.len()).map_err(|_| invalid("frame too large"))?;
+    w.write_all(&len.to_le_bytes())?;
+    w.write_all(bytes)
+}
+
+/// Read a length-prefixed chunk; a clean end of file is `None` if allowed
+fn read_chunk(r: &mut impl Read, eof_ok: bool) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    let mut filled = 0;
+    while filled < len.len() {
+        match r.read(&mut len[filled..])? {
+            0 if filled == 0 && eof_ok => return Ok(None),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => filled += n,
+        }
+    }
+
+    let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+    r.read_exact(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
+fn encode_payload(payload: &Payload) -> (PayloadKind, Vec<u8>) {
+    let mut out = Vec::new();
+    let kind = match payload {
+        Payload::Empty => PayloadKind::Empty,
+        Payload::Utf8(strings) => {
+            for s in strings {
+                out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+                out.extend_from_slice(s.as_bytes());
+            }
+            PayloadKind::Utf8
+        }
+        Payload::Bytes(bytes) => {
+            out.extend_from_slice(bytes);
+            PayloadKind::Bytes
+        }
+        Payload::Float32(values) => {
+            out.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+            PayloadKind::Float32
+        }
+        Payload::Float32List(lists) => {
+            for values in lists {
+                out.extend_from_slice(&(values.len() as u32).to_le_bytes());
+                out.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+            }
+            PayloadKind::Float32List
+        }
+    };
+    (kind, out)
+}
+
+fn decode_payload(kind: PayloadKind, mut bytes: &[u8]) -> io::Result<Payload> {
+    fn take<'a>(bytes: &mut &'a [u8], n: usize) -> io::Result<&'a [u8]> {
+        if bytes.len() < n {
+            return Err(invalid("truncated payload"));
+        }
+        let (head, tail) = bytes.split_at(n);
+        *bytes = tail;
+        Ok(head)
+    }
+    fn take_len(bytes: &mut &[u8]) -> io::Result<usize> {
+        let len = take(bytes, 4)?;
+        Ok(u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize)
+    }
+    fn floats(bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+
+    Ok(match kind {
+        PayloadKind::Empty => Payload::Empty,
+        PayloadKind::Bytes => Payload::Bytes(bytes.to_vec()),
+        PayloadKind::Float32 => Payload::Float32(floats(bytes)),
+        PayloadKind::Utf8 => {
+            let mut strings = Vec::new();
+            while !bytes.is_empty() {
+                let len = take_len(&mut bytes)?;
+                let s = std::str::from_utf8(take(&mut bytes, len)?).map_err(invalid)?;
+                strings.push(s.to_string());
+            }
+            Payload::Utf8(strings)
+        }
+        PayloadKind::Float32List => {
+            let mut lists = Vec::new();
+            while !bytes.is_empty() {
+                let len = take_len(&mut bytes)?;
+                lists.push(floats(take(&mut bytes, len * 4)?));
+            }
+            Payload::Float32List(lists)
+        }
+    })
+}
+
+fn invalid(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(t_ms: u64, output_id: &str, payload: Payload) -> Record {
+        Record {
+            t_ms,
+            node_id: "doubao-asr".to_string(),
+            output_id: output_id.to_string(),
+            params: BTreeMap::from([
+                (
+                    "question_id".to_string(),
+                    ParamValue::String("q1".to_string()),
+                ),
+                ("sample_rate".to_string(), ParamValue::Integer(16000)),
+            ]),
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let records = vec![
+            record(
+                0,
+                "text",
+                Payload::Utf8(vec![r#"{"text":"hi"}"#.to_string()]),
+            ),
+            record(
+                5,
+                "audio",
+                Payload::Float32List(vec![vec![0.5, -0.25], vec![]]),
+            ),
+            record(9, "audio_bytes", Payload::Bytes(vec![1, 2, 3])),
+            record(12, "level", Payload::Float32(vec![0.75])),
+            record(20, "tick", Payload::Empty),
+        ];
+
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+        for r in &records {
+            writer.write(r).unwrap();
+        }
+        let bytes = writer.inner;
+
+        let read: Vec<Record> = RecordingReader::new(bytes.as_slice())
+            .unwrap()
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, records);
+
+        // A frame cut short by a crash is an error, not a silent end
+        let mut reader = RecordingReader::new(&bytes[..bytes.len() - 2]).unwrap();
+        assert_eq!(reader.by_ref().take(4).count(), 4);
+        assert!(reader.next().unwrap().is_err());
+
+        assert!(RecordingReader::new(&b"NOTAREC!"[..]).is_err());
+    }
+}
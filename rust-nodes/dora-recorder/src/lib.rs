@@ -0,0 +1,24 @@
+//! Record and replay dataflow traffic
+//!
+//! `dora-recorder` subscribes to node outputs and writes every message
+//! (source, metadata parameters, payload, timing) to a recording file.
+//! `dora-replayer` takes the place of a recorded node and re-emits its
+//! messages with the original timing, so downstream nodes can be tested
+//! offline and repeatably.
+
+pub mod convert;
+pub mod format;
+
+pub use format::{ParamValue, Payload, Record, RecordingReader, RecordingWriter};
+
+/// Recorder input id for a source output: `<node>__<output>`
+pub const SOURCE_SEPARATOR: &str = "__";
+
+/// Split a recorder input id into `(node_id, output_id)`
+///
+/// Ids without the separator are recorded under an empty node id.
+pub fn split_input_id(input_id: &str) -> (&str, &str) {
+    input_id
+        .split_once(SOURCE_SEPARATOR)
+        .unwrap_or(("", input_id))
+}
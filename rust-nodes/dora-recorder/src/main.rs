@@ -0,0 +1,93 @@
+// Dora Node: Recorder
+// Records dataflow traffic to a file for offline replay (see dora-replayer)
+//
+// Each input is one recorded output, named `<node>__<output>`:
+//
+//   - id: recorder
+//     path: ../../../target/release/dora-recorder
+//     inputs:
+//       doubao-asr__text: doubao-asr/text
+//       english-teacher__json_data: english-teacher/json_data
+//       english-teacher__reply_segment: english-teacher/reply_segment
+//     env:
+//       RECORD_PATH: recordings/session.dorarec
+//
+// RECORD_PATH defaults to `recordings/session-<unix time>.dorarec`.
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use dora_node_api::{DoraNode, Event};
+use dora_recorder::convert::{params_from_dora, payload_from_arrow};
+use dora_recorder::{Record, RecordingWriter, split_input_id};
+use eyre::{Context, Result};
+
+fn main() -> Result<()> {
+    env_logger::init();
+
+    let path = std::env::var("RECORD_PATH")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default();
+            PathBuf::from(format!("recordings/session-{}.dorarec", now))
+        });
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .wrap_err_with(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let file =
+        File::create(&path).wrap_err_with(|| format!("Failed to create {}", path.display()))?;
+    let mut writer = RecordingWriter::new(BufWriter::new(file))?;
+
+    let (_node, mut events) = DoraNode::init_from_env()?;
+    log::info!("Recorder started, writing to {}", path.display());
+
+    let start = Instant::now();
+    let mut count = 0usize;
+
+    while let Some(event) = events.recv() {
+        match event {
+            Event::Input { id, data, metadata } => {
+                let payload = match payload_from_arrow(data.0.as_ref()) {
+                    Ok(payload) => payload,
+                    Err(e) => {
+                        log::warn!("Skipping {}: {}", id, e);
+                        continue;
+                    }
+                };
+
+                let (node_id, output_id) = split_input_id(id.as_str());
+                let record = Record {
+                    t_ms: start.elapsed().as_millis() as u64,
+                    node_id: node_id.to_string(),
+                    output_id: output_id.to_string(),
+                    params: params_from_dora(&metadata.parameters),
+                    payload,
+                };
+
+                // Flush every message so a crashed session is still replayable
+                writer.write(&record)?;
+                writer.flush()?;
+                count += 1;
+                log::debug!("Recorded {}/{} at {}ms", node_id, output_id, record.t_ms);
+            }
+            Event::Stop(_) => {
+                log::info!("Received stop event");
+                break;
+            }
+            _ => {}
+        }
+    }
+
+    log::info!(
+        "Recorder stopped, {} message(s) in {}",
+        count,
+        path.display()
+    );
+    Ok(())
+}
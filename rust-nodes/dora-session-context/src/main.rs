@@ -4,35 +4,12 @@
 
 use std::sync::{Arc, Mutex};
 
+use dora_messages::{AsrOutput, ContextualInput, SCHEMA_VERSION, TopicInfo};
 use dora_node_api::arrow::array::{Array, StringArray};
 use dora_node_api::{DoraNode, Event};
 use eyre::Result;
-use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct TopicInfo {
-    session_id: String,
-    topic: String,
-    target_words: Vec<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct UserInput {
-    text: String,
-    confidence: f32,
-    session_id: Option<String>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct ContextualInput {
-    user_text: String,
-    session_id: String,
-    topic: Option<String>,
-    target_words: Option<Vec<String>>,
-    is_first_in_session: bool,
-}
-
 struct SessionState {
     current_topic: Option<TopicInfo>,
     user_inputs_in_session: usize,
@@ -60,7 +37,7 @@ async fn main() -> Result<()> {
                         // Receive topic but DON'T output anything yet
                         // Just store it for when user speaks
                         if let Some(bytes) = &raw_data {
-                            match serde_json::from_slice::<TopicInfo>(bytes) {
+                            match dora_messages::decode::<TopicInfo>(bytes) {
                                 Ok(topic_info) => {
                                     log::info!(
                                         "Topic received for session {}: {}",
@@ -94,7 +71,7 @@ async fn main() -> Result<()> {
                     "user_text" | "text_input" | "asr_text" => {
                         // User spoke! Now we can combine context and forward to AI
                         if let Some(bytes) = &raw_data {
-                            match serde_json::from_slice::<UserInput>(bytes) {
+                            match dora_messages::decode::<AsrOutput>(bytes) {
                                 Ok(user_input) => {
                                     if user_input.text.trim().is_empty() {
                                         log::debug!("Ignoring empty user input");
@@ -127,6 +104,7 @@ async fn main() -> Result<()> {
 
                                     // Create contextual input
                                     let contextual = ContextualInput {
+                                        version: SCHEMA_VERSION,
                                         user_text: user_input.text,
                                         session_id: session_id.clone(),
                                         topic: topic.clone(),
@@ -135,7 +113,7 @@ async fn main() -> Result<()> {
                                     };
 
                                     // Output to AI
-                                    let output_str = serde_json::to_string(&contextual)?;
+                                    let output_str = dora_messages::encode(&contextual)?;
                                     let output_array = StringArray::from(vec![output_str.as_str()]);
                                     node.send_output(
                                         "user_text".to_string().into(),
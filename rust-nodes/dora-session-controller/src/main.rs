@@ -1,26 +1,10 @@
 // Dora Node: Session Controller
 // Controls learning session flow: start, stop, reset, next
 
+use dora_messages::{ControlCommand, SessionStatus};
 use dora_node_api::arrow::array::{Array, StringArray, UInt8Array};
 use dora_node_api::{ArrowData, DoraNode, Event};
 use eyre::Result;
-use serde::{Deserialize, Serialize};
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct ControlCommand {
-    command: String,
-    #[serde(default)]
-    session_id: Option<String>,
-    #[serde(default)]
-    data: Option<serde_json::Value>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct StatusOutput {
-    status: String,
-    session_id: Option<String>,
-    message: String,
-}
 
 #[derive(Debug, Clone, PartialEq)]
 enum SessionState {
@@ -51,7 +35,7 @@ fn main() -> Result<()> {
                 match id.as_str() {
                     "user_input" => {
                         // Handle control commands from user input
-                        if let Ok(cmd) = serde_json::from_slice::<ControlCommand>(&raw_data) {
+                        if let Ok(cmd) = dora_messages::decode::<ControlCommand>(&raw_data) {
                             log::info!("Received user control command: {:?}", cmd.command);
 
                             match cmd.command.as_str() {
@@ -65,12 +49,9 @@ fn main() -> Result<()> {
                                         log::info!("Starting new session: {}", session_id);
 
                                         // Broadcast start signal
-                                        let control = ControlCommand {
-                                            command: "start".to_string(),
-                                            session_id: Some(session_id.clone()),
-                                            data: None,
-                                        };
-                                        let control_json = serde_json::to_string(&control)?;
+                                        let control = ControlCommand::new("start")
+                                            .with_session(Some(session_id.clone()));
+                                        let control_json = dora_messages::encode(&control)?;
                                         let control_array =
                                             StringArray::from(vec![control_json.as_str()]);
                                         node.send_output(
@@ -98,12 +79,9 @@ fn main() -> Result<()> {
                                         log::info!("Stopping session");
 
                                         // Broadcast stop signal
-                                        let control = ControlCommand {
-                                            command: "stop".to_string(),
-                                            session_id,
-                                            data: None,
-                                        };
-                                        let control_json = serde_json::to_string(&control)?;
+                                        let control =
+                                            ControlCommand::new("stop").with_session(session_id);
+                                        let control_json = dora_messages::encode(&control)?;
                                         let control_array =
                                             StringArray::from(vec![control_json.as_str()]);
                                         node.send_output(
@@ -125,12 +103,9 @@ fn main() -> Result<()> {
                                     log::info!("Resetting session");
 
                                     // Broadcast reset signal
-                                    let control = ControlCommand {
-                                        command: "reset".to_string(),
-                                        session_id: current_session_id.clone(),
-                                        data: None,
-                                    };
-                                    let control_json = serde_json::to_string(&control)?;
+                                    let control = ControlCommand::new("reset")
+                                        .with_session(current_session_id.clone());
+                                    let control_json = dora_messages::encode(&control)?;
                                     let control_array =
                                         StringArray::from(vec![control_json.as_str()]);
                                     node.send_output(
@@ -151,12 +126,9 @@ fn main() -> Result<()> {
                                     if state == SessionState::Active {
                                         state = SessionState::Paused;
 
-                                        let control = ControlCommand {
-                                            command: "pause".to_string(),
-                                            session_id: current_session_id.clone(),
-                                            data: None,
-                                        };
-                                        let control_json = serde_json::to_string(&control)?;
+                                        let control = ControlCommand::new("pause")
+                                            .with_session(current_session_id.clone());
+                                        let control_json = dora_messages::encode(&control)?;
                                         let control_array =
                                             StringArray::from(vec![control_json.as_str()]);
                                         node.send_output(
@@ -178,12 +150,9 @@ fn main() -> Result<()> {
                                     if state == SessionState::Paused {
                                         state = SessionState::Active;
 
-                                        let control = ControlCommand {
-                                            command: "resume".to_string(),
-                                            session_id: current_session_id.clone(),
-                                            data: None,
-                                        };
-                                        let control_json = serde_json::to_string(&control)?;
+                                        let control = ControlCommand::new("resume")
+                                            .with_session(current_session_id.clone());
+                                        let control_json = dora_messages::encode(&control)?;
                                         let control_array =
                                             StringArray::from(vec![control_json.as_str()]);
                                         node.send_output(
@@ -201,6 +170,57 @@ fn main() -> Result<()> {
                                         )?;
                                     }
                                 }
+                                "interrupt" => {
+                                    // 用户插话: 广播给 TTS 和 english-teacher 取消当前回合
+                                    log::info!(
+                                        "Barge-in, interrupting question_id={:?}",
+                                        cmd.question_id()
+                                    );
+
+                                    let control = ControlCommand::interrupt(cmd.question_id())
+                                        .with_session(current_session_id.clone());
+                                    let control_json = dora_messages::encode(&control)?;
+                                    let control_array =
+                                        StringArray::from(vec![control_json.as_str()]);
+                                    node.send_output(
+                                        "control".to_string().into(),
+                                        metadata.parameters.clone(),
+                                        control_array,
+                                    )?;
+
+                                    if state == SessionState::Processing {
+                                        state = SessionState::WaitingForInput;
+                                    }
+
+                                    send_status(
+                                        &mut node,
+                                        &metadata,
+                                        "turn_interrupted",
+                                        current_session_id.as_deref(),
+                                        "Teacher interrupted by user",
+                                    )?;
+                                }
+                                "replay" => {
+                                    // 重播老师回复: 转发给 TTS, 从缓存输出音频
+                                    log::info!(
+                                        "Replay requested for question_id={:?}",
+                                        cmd.question_id()
+                                    );
+
+                                    let control = match cmd.question_id() {
+                                        Some(qid) => ControlCommand::replay(qid),
+                                        None => ControlCommand::new(dora_messages::REPLAY),
+                                    }
+                                    .with_session(current_session_id.clone());
+                                    let control_json = dora_messages::encode(&control)?;
+                                    let control_array =
+                                        StringArray::from(vec![control_json.as_str()]);
+                                    node.send_output(
+                                        "control".to_string().into(),
+                                        metadata.parameters.clone(),
+                                        control_array,
+                                    )?;
+                                }
                                 _ => {
                                     log::warn!("Unknown command: {}", cmd.command);
                                 }
@@ -219,12 +239,9 @@ fn main() -> Result<()> {
                             state = SessionState::WaitingForInput;
 
                             // Signal ready for next input
-                            let control = ControlCommand {
-                                command: "ready".to_string(),
-                                session_id: current_session_id.clone(),
-                                data: None,
-                            };
-                            let control_json = serde_json::to_string(&control)?;
+                            let control = ControlCommand::new("ready")
+                                .with_session(current_session_id.clone());
+                            let control_json = dora_messages::encode(&control)?;
                             let control_array = StringArray::from(vec![control_json.as_str()]);
                             node.send_output(
                                 "control".to_string().into(),
@@ -267,12 +284,8 @@ fn send_status(
     session_id: Option<&str>,
     message: &str,
 ) -> Result<()> {
-    let status_output = StatusOutput {
-        status: status.to_string(),
-        session_id: session_id.map(String::from),
-        message: message.to_string(),
-    };
-    let status_json = serde_json::to_string(&status_output)?;
+    let status_output = SessionStatus::new(status, session_id, message);
+    let status_json = dora_messages::encode(&status_output)?;
     let status_array = StringArray::from(vec![status_json.as_str()]);
     node.send_output(
         "status".to_string().into(),
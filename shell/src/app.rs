@@ -18,9 +18,13 @@ use std::{io, thread};
 use colang_core::asset_api::init_asset_api;
 use colang_core::dict_api::init_dict_api;
 use colang_core::learn_api::{init_learn_api, set_learn_api_token};
-use colang_core::models::Preferences;
+use colang_core::models::{Achievement, Preferences};
 use colang_core::routes::{self, paths, get_page_meta, SidebarRoute};
+use colang_core::screens::{AchievementAction, SessionAction};
 use colang_core::screens::chat::chat_screen::ChatScreenWidgetRefExt;
+use colang_core::screens::home::home_screen::HomeScreenWidgetRefExt;
+use colang_core::screens::review::review_screen::ReviewScreenWidgetRefExt;
+use colang_core::screens::scenes::scenes_screen::ScenesAction;
 use colang_core::screens::settings::settings_screen::SettingsScreenWidgetRefExt;
 use colang_core::screens::settings::{SettingsScreenAction, ThemeMode};
 use colang_shell::widgets::sidebar::SidebarWidgetRefExt;
@@ -254,6 +258,53 @@ live_design! {
                     }
                 }
             }
+
+            // Achievement announcement, top center; see show_achievement_toast
+            achievement_toast = <View> {
+                width: 320, height: Fit
+                abs_pos: vec2(540.0, 64.0)
+                visible: false
+                padding: {top: 12, bottom: 12, left: 16, right: 16}
+                flow: Down
+                spacing: 4
+                show_bg: true
+                draw_bg: {
+                    instance dark_mode: 0.0
+                    fn pixel(self) -> vec4 {
+                        let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                        sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 6.0);
+                        let bg = mix((SLATE_50), (SLATE_800), self.dark_mode);
+                        sdf.fill(bg);
+                        sdf.box(0., 0., self.rect_size.x, self.rect_size.y, 6.0);
+                        sdf.stroke((ACCENT_GREEN), 1.5);
+                        return sdf.result;
+                    }
+                }
+
+                toast_title = <Label> {
+                    text: "🏆 获得成就"
+                    draw_text: {
+                        instance dark_mode: 0.0
+                        text_style: <FONT_SEMIBOLD>{ font_size: 12.0 }
+                        fn get_color(self) -> vec4 {
+                            return mix((TEXT_PRIMARY), (TEXT_PRIMARY_DARK), self.dark_mode);
+                        }
+                    }
+                }
+
+                toast_body = <Label> {
+                    width: Fill
+                    text: ""
+                    draw_text: {
+                        instance dark_mode: 0.0
+                        text_style: { font_size: 11.0 }
+                        wrap: Word
+                        fn get_color(self) -> vec4 {
+                            return mix((TEXT_SECONDARY), (TEXT_SECONDARY_DARK), self.dark_mode);
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -325,6 +376,9 @@ pub struct App {
     /// Current route path for navigation
     #[rust]
     current_path: String,
+    /// Hides the achievement toast
+    #[rust]
+    achievement_toast_timer: Timer,
 }
 
 impl LiveHook for App {
@@ -342,7 +396,7 @@ impl LiveHook for App {
         let prefs = Preferences::load();
         self.dark_mode = prefs.dark_mode;
         self.dark_mode_anim = if prefs.dark_mode { 1.0 } else { 0.0 };
-        self.auth_token = prefs.auth_token.clone();
+        self.auth_token = prefs.auth_token().map(str::to_string);
         self.user_name = None;
         self.user_info_rx = None;
         self.user_info_fetching = false;
@@ -359,7 +413,7 @@ impl LiveHook for App {
         // Initialize API clients with backend URL
         init_asset_api(&config.api_url);
         init_dict_api(&config.api_url);
-        init_learn_api(&config.api_url, prefs.auth_token);
+        init_learn_api(&config.api_url, self.auth_token.clone());
     }
 }
 
@@ -432,6 +486,7 @@ impl AppMain for App {
 
         self.poll_desktop_auth(cx);
         self.poll_user_info(cx);
+        self.poll_latency_report(cx);
 
         // Handle hover events
         self.handle_sidebar_hover(cx, event);
@@ -450,6 +505,9 @@ impl AppMain for App {
         self.handle_tab_clicks(cx, &actions);
         self.handle_tab_close_clicks(cx, event);
         self.handle_settings_actions(cx, &actions);
+        self.handle_achievement_actions(cx, event, &actions);
+        self.handle_session_actions(cx, &actions);
+        self.handle_scene_actions(cx, &actions);
     }
 }
 
@@ -493,6 +551,14 @@ impl App {
             },
         );
 
+        let toast_x = ((window_width - 320.0) / 2.0).max(0.0);
+        self.ui.view(ids!(achievement_toast)).apply_over(
+            cx,
+            live! {
+                abs_pos: (dvec2(toast_x, 64.0))
+            },
+        );
+
         self.ui.redraw(cx);
     }
 }
@@ -624,11 +690,11 @@ impl App {
         match result {
             DesktopAuthResult::Success(token) => {
                 self.auth_token = Some(token.clone());
-                println!("[Login] Desktop login succeeded, token: {}", token);
+                println!("[Login] Desktop login succeeded");
                 // Update learn API client with new token
                 set_learn_api_token(Some(token.clone()));
                 let mut prefs = Preferences::load();
-                prefs.auth_token = Some(token);
+                prefs.set_auth_token(Some(token));
                 let _ = prefs.save();
                 // Fetch user info to get username
                 self.fetch_user_info(cx);
@@ -769,7 +835,7 @@ impl App {
                     self.user_name = None;
                     // Update preferences
                     let mut prefs = Preferences::load();
-                    prefs.auth_token = None;
+                    prefs.set_auth_token(None);
                     let _ = prefs.save();
                     set_learn_api_token(None);
                 }
@@ -779,6 +845,15 @@ impl App {
         self.update_login_button_label(cx);
     }
 
+    /// Show the latest turn latencies in the debug panel
+    fn poll_latency_report(&mut self, cx: &mut Cx) {
+        if let Some(report) = colang_core::metrics::take_report() {
+            self.ui
+                .debug_panel(ids!(body.base.content_area.debug_panel))
+                .set_latency_report(cx, &report.to_markdown());
+        }
+    }
+
     fn start_desktop_login(&mut self, cx: &mut Cx) {
         if self.desktop_auth_in_progress {
             return;
@@ -1310,6 +1385,74 @@ impl App {
         }
     }
 
+    /// Announce newly earned achievements from any screen, and hide the
+    /// toast again once it has been shown long enough
+    fn handle_achievement_actions(&mut self, cx: &mut Cx, event: &Event, actions: &[Action]) {
+        if self.achievement_toast_timer.is_event(event).is_some() {
+            self.ui.view(ids!(achievement_toast)).set_visible(cx, false);
+            self.ui.redraw(cx);
+        }
+
+        for action in actions {
+            if let AchievementAction::Earned(earned) = action.as_widget_action().cast() {
+                self.show_achievement_toast(cx, &earned);
+            }
+        }
+    }
+
+    /// Reload the review data once a conversation has been closed, so its
+    /// mistakes show up even if the review screen is already loaded
+    fn handle_session_actions(&mut self, cx: &mut Cx, actions: &[Action]) {
+        for action in actions {
+            if let SessionAction::Ended = action.as_widget_action().cast() {
+                self.ui
+                    .review_screen(ids!(
+                        body.base.content_area.main_content.content.review_screen
+                    ))
+                    .refresh_data(cx);
+            }
+        }
+    }
+
+    /// Open the chat screen on the scene picked in the scene center
+    fn handle_scene_actions(&mut self, cx: &mut Cx, actions: &[Action]) {
+        for action in actions {
+            if let ScenesAction::Selected(scene) = action.as_widget_action().cast() {
+                self.ui
+                    .chat_screen(ids!(
+                        body.base.content_area.main_content.content.chat_screen
+                    ))
+                    .set_scene(Some(scene));
+                self.navigate(cx, paths::CHAT);
+            }
+        }
+    }
+
+    /// Show the achievement toast for a few seconds
+    fn show_achievement_toast(&mut self, cx: &mut Cx, earned: &[Achievement]) {
+        if earned.is_empty() {
+            return;
+        }
+        let body = earned
+            .iter()
+            .map(|a| {
+                a.description_zh
+                    .clone()
+                    .or_else(|| a.description_en.clone())
+                    .unwrap_or_else(|| a.achievement_name.clone())
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.ui
+            .label(ids!(achievement_toast.toast_body))
+            .set_text(cx, &body);
+        self.ui.view(ids!(achievement_toast)).set_visible(cx, true);
+
+        cx.stop_timer(self.achievement_toast_timer);
+        self.achievement_toast_timer = cx.start_timeout(4.0);
+        self.ui.redraw(cx);
+    }
+
     /// Update dark mode animation
     fn update_dark_mode_animation(&mut self, cx: &mut Cx) {
         let elapsed = Cx::time_now() - self.dark_mode_anim_start;
@@ -1379,6 +1522,26 @@ impl App {
             },
         );
 
+        // Apply to achievement toast
+        self.ui.view(ids!(achievement_toast)).apply_over(
+            cx,
+            live! {
+                draw_bg: { dark_mode: (dm) }
+            },
+        );
+        self.ui.label(ids!(achievement_toast.toast_title)).apply_over(
+            cx,
+            live! {
+                draw_text: { dark_mode: (dm) }
+            },
+        );
+        self.ui.label(ids!(achievement_toast.toast_body)).apply_over(
+            cx,
+            live! {
+                draw_text: { dark_mode: (dm) }
+            },
+        );
+
         // Apply to user menu buttons
         self.ui.button(ids!(user_menu.menu_profile_btn)).apply_over(
             cx,
@@ -2287,6 +2450,15 @@ impl App {
                 .stop_timers(cx);
         }
 
+        // Only the scene center starts scene conversations
+        if !path.starts_with(paths::CHAT) {
+            self.ui
+                .chat_screen(ids!(
+                    body.base.content_area.main_content.content.chat_screen
+                ))
+                .set_scene(None);
+        }
+
         // Determine which page to show based on path
         let page_id = match path {
             p if p == paths::HOME => live_id!(home_screen),
@@ -2313,6 +2485,24 @@ impl App {
                 .start_timers(cx);
         }
 
+        // Reload learning stats so sessions finished elsewhere show up
+        if path == paths::HOME {
+            self.ui
+                .home_screen(ids!(
+                    body.base.content_area.main_content.content.home_screen
+                ))
+                .refresh_data(cx);
+        }
+
+        // Reload review data so words practiced or added elsewhere show up
+        if path.starts_with(paths::REVIEW) {
+            self.ui
+                .review_screen(ids!(
+                    body.base.content_area.main_content.content.review_screen
+                ))
+                .refresh_data(cx);
+        }
+
         // Update header title and icon
         if let Some(meta) = get_page_meta(path) {
             self.set_header_page_title(cx, meta.icon, meta.title);
@@ -9,6 +9,7 @@
 //! - **Node Filtering**: Filter by source node (ASR, TTS, LLM, etc.)
 //! - **Search**: Full-text search in log content
 //! - **Copy**: Copy filtered logs to clipboard
+//! - **Latency**: Per-stage pipeline latency (last turn, p50, p95)
 //!
 //! ## Usage
 //!
@@ -201,6 +202,32 @@ live_design! {
                 }
             }
 
+            // Pipeline latency per stage, hidden until a turn is measured
+            latency_container = <RoundedView> {
+                width: Fill, height: Fit
+                visible: false
+                padding: 8
+                draw_bg: {
+                    instance dark_mode: 0.0
+                    border_radius: 6.0
+                    fn get_color(self) -> vec4 {
+                        return mix((SLATE_50), (SLATE_900), self.dark_mode);
+                    }
+                }
+                flow: Down
+
+                latency_content = <Markdown> {
+                    width: Fill, height: Fit
+                    font_size: 9.0
+                    font_color: (GRAY_600)
+                    paragraph_spacing: 2
+
+                    draw_normal: { text_style: <FONT_REGULAR>{ font_size: 9.0 } }
+                    draw_bold: { text_style: <FONT_SEMIBOLD>{ font_size: 9.0 } }
+                    draw_fixed: { text_style: <FONT_REGULAR>{ font_size: 8.0 } }
+                }
+            }
+
             // Log content area
             log_container = <RoundedView> {
                 width: Fill, height: Fill
@@ -489,6 +516,17 @@ impl DebugPanel {
         cx.copy_to_clipboard(&text.join("\n"));
     }
 
+    /// Show the pipeline latency table (markdown)
+    pub fn set_latency_report(&mut self, cx: &mut Cx, report: &str) {
+        self.view
+            .view(ids!(content.latency_container))
+            .set_visible(cx, !report.is_empty());
+        self.view
+            .markdown(ids!(content.latency_container.latency_content))
+            .set_text(cx, report);
+        self.view.redraw(cx);
+    }
+
     /// Update dark mode for the debug panel
     pub fn update_dark_mode(&mut self, cx: &mut Cx, dark_mode: f64) {
         self.dark_mode = dark_mode;
@@ -510,6 +548,9 @@ impl DebugPanel {
         self.view
             .view(ids!(content.log_container))
             .apply_over(cx, live! { draw_bg: { dark_mode: (dark_mode) } });
+        self.view
+            .view(ids!(content.latency_container))
+            .apply_over(cx, live! { draw_bg: { dark_mode: (dark_mode) } });
 
         self.view.redraw(cx);
     }
@@ -533,6 +574,13 @@ impl DebugPanelRef {
         }
     }
 
+    /// Show the pipeline latency table (markdown)
+    pub fn set_latency_report(&self, cx: &mut Cx, report: &str) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_latency_report(cx, report);
+        }
+    }
+
     /// Update dark mode
     pub fn update_dark_mode(&self, cx: &mut Cx, dark_mode: f64) {
         if let Some(mut inner) = self.borrow_mut() {
@@ -129,5 +129,5 @@ pub fn live_design(cx: &mut Cx) {
 
 // Re-export commonly used types
 pub use audio_player::*;
-pub use participant_panel::ParticipantPanel;
+pub use participant_panel::{ParticipantPanel, ParticipantPanelAction};
 pub use router::{Route, Router, RouterAction, RouterRef};
@@ -8,6 +8,7 @@
 //! - **Status Indicator**: Colored dot showing participant state (waiting/speaking/error)
 //! - **Name Label**: Participant name with dark mode support
 //! - **Audio Waveform**: 8-band rainbow equalizer with level bar background
+//! - **Replay Button**: Optional, emits `ParticipantPanelAction::ReplayClicked`
 //!
 //! ## Usage
 //!
@@ -40,6 +41,13 @@
 //! });
 //! ```
 //!
+//! Or use `set_speaking` on the widget ref, e.g. driven by the mic VAD:
+//!
+//! ```rust,ignore
+//! self.ui.participant_panel(ids!(my_participant))
+//!     .set_speaking(cx, audio_manager.is_speaking());
+//! ```
+//!
 //! ### Waveform Levels
 //!
 //! Update the 8 frequency bands (band0-band7) with values from 0.0 to 1.0:
@@ -57,6 +65,21 @@
 //! });
 //! ```
 //!
+//! ### Replay
+//!
+//! The replay button is hidden by default. Show it on panels whose audio can
+//! be replayed (e.g. the teacher) and handle the action in the parent:
+//!
+//! ```rust,ignore
+//! self.ui.participant_panel(ids!(teacher_panel)).set_replay_enabled(cx, true);
+//!
+//! for action in actions {
+//!     if let ParticipantPanelAction::ReplayClicked = action.as_widget_action().cast() {
+//!         // replay the last utterance
+//!     }
+//! }
+//! ```
+//!
 //! ### Dark Mode
 //!
 //! Use the `update_dark_mode` method on the widget ref:
@@ -341,6 +364,19 @@ live_design! {
 
             <View> { width: Fill, height: 1 }
 
+            // Replay last utterance (hidden unless enabled)
+            replay_btn = <Button> {
+                visible: false
+                width: Fit, height: 24
+                padding: { left: 8, right: 8 }
+                text: "重播"
+                draw_text: {
+                    color: (ACCENT_INDIGO)
+                    text_style: <FONT_MEDIUM>{ font_size: 11.0 }
+                }
+                draw_bg: { color: (INDIGO_100) border_radius: 12.0 }
+            }
+
             role_badge = <RoundedView> {
                 width: Fit, height: Fit
                 padding: { left: 10, right: 10, top: 4, bottom: 4 }
@@ -363,15 +399,34 @@ live_design! {
     }
 }
 
+#[derive(Clone, Debug, DefaultNone)]
+pub enum ParticipantPanelAction {
+    None,
+    /// The replay button was clicked
+    ReplayClicked,
+}
+
 #[derive(Live, LiveHook, Widget)]
 pub struct ParticipantPanel {
     #[deref]
     view: View,
+    #[rust]
+    speaking: bool,
 }
 
 impl Widget for ParticipantPanel {
     fn handle_event(&mut self, cx: &mut Cx, event: &Event, scope: &mut Scope) {
         self.view.handle_event(cx, event, scope);
+
+        if let Event::Actions(actions) = event {
+            if self.view.button(ids!(header.replay_btn)).clicked(actions) {
+                cx.widget_action(
+                    self.widget_uid(),
+                    &scope.path,
+                    ParticipantPanelAction::ReplayClicked,
+                );
+            }
+        }
     }
 
     fn draw_walk(&mut self, cx: &mut Cx2d, scope: &mut Scope, walk: Walk) -> DrawStep {
@@ -380,6 +435,41 @@ impl Widget for ParticipantPanel {
 }
 
 impl ParticipantPanelRef {
+    /// Show or clear the "speaking" state on the status indicator
+    pub fn set_speaking(&self, cx: &mut Cx, speaking: bool) {
+        if let Some(mut inner) = self.borrow_mut() {
+            if inner.speaking == speaking {
+                return;
+            }
+            inner.speaking = speaking;
+
+            let status = if speaking { 1.0 } else { 0.0 };
+            inner.view.view(ids!(header.indicator)).apply_over(
+                cx,
+                live! {
+                    draw_bg: { status: (status) }
+                },
+            );
+            inner.view.redraw(cx);
+        }
+    }
+
+    /// Show or hide the replay button
+    pub fn set_replay_enabled(&self, cx: &mut Cx, enabled: bool) {
+        if let Some(inner) = self.borrow_mut() {
+            inner
+                .view
+                .button(ids!(header.replay_btn))
+                .set_visible(cx, enabled);
+            inner.view.redraw(cx);
+        }
+    }
+
+    /// Whether the panel is currently showing the speaking state
+    pub fn is_speaking(&self) -> bool {
+        self.borrow().is_some_and(|inner| inner.speaking)
+    }
+
     /// Update dark mode for this widget
     pub fn update_dark_mode(&self, cx: &mut Cx, dark_mode: f64) {
         if let Some(mut inner) = self.borrow_mut() {